@@ -21,9 +21,11 @@ function init() {
 
     const invoke = window.__TAURI__.invoke;
 
-    let st_bridge;
-    invoke('read_st_bridge', {fileName: '/Users/Kota/rust/stb-viewer/steel_standard_model.stb'}).then((stb) => st_bridge = stb);
-    invoke('members', {stBridge: st_bridge}).then((v) => {console.log(v)});
+    let handle;
+    invoke('open_st_bridge', {fileName: '/Users/Kota/rust/stb-viewer/steel_standard_model.stb'})
+        .then((h) => handle = h)
+        .then(() => invoke('members', {handle: handle}))
+        .then((v) => {console.log(v)});
 
     const points = [];
 
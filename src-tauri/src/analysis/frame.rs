@@ -0,0 +1,1327 @@
+// Matrix code reads best with explicit indices.
+#![allow(clippy::needless_range_loop)]
+
+use crate::connectivity::foundation_node_list;
+use crate::geometry::{member_plane, Node};
+use crate::material::{concrete_grade, section_grade_list, Material, MaterialDatabase};
+use crate::node_merge::find_duplicate_nodes;
+use crate::section_property::steel_section_property;
+use crate::st_bridge::stb_model::stb_members::{JointCondition, StbMember};
+use crate::st_bridge::stb_model::stb_sections::StbSection;
+use crate::st_bridge::StBridge;
+use crate::validation::NODE_TOLERANCE;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::f64::consts::PI;
+
+// Fallback grades when a member's own cannot be resolved.
+const DEFAULT_STEEL: &str = "SN400B";
+const DEFAULT_CONCRETE: &str = "FC24";
+
+// Units are N and mm throughout. Dof order at a node is ux, uy, uz, rx, ry, rz.
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameNode {
+    pub id: u32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+// iy is about the local y axis, which runs along the section outline's x, so it is the strong
+// axis of an H-shape.
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameSection {
+    pub name: String,
+    pub area: f64,
+    pub iy: f64,
+    pub iz: f64,
+    pub j: f64,
+}
+
+// Local x runs from node i to node j; y and z follow the section outline's x and y.
+#[derive(Debug, Clone, Serialize)]
+pub struct FrameElement {
+    pub member_id: u32,
+    pub name: String,
+    pub node_i: u32,
+    pub node_j: u32,
+    pub y_axis: Node,
+    pub z_axis: Node,
+    pub section: FrameSection,
+    pub material: Material,
+    pub release_i: bool,
+    pub release_j: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Support {
+    pub id: u32,
+    pub fixity: [bool; 6],
+}
+
+// Members split at every node lying along them, coincident nodes merged into the lowest id and
+// the foundation-level nodes fixed.
+#[derive(Debug, Serialize)]
+pub struct FrameModel {
+    pub node_list: Vec<FrameNode>,
+    pub element_list: Vec<FrameElement>,
+    pub support_list: Vec<Support>,
+    pub merged_node_map: BTreeMap<u32, u32>,
+    pub warning_list: Vec<String>,
+}
+
+// Global forces and moments on a node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodalLoad {
+    pub id: u32,
+    pub force: [f64; 6],
+}
+
+// Uniform load in global directions per unit length (N/mm) from `start` to `end`, in fractions
+// of the member length from its first node. Without a range it covers the whole member.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberLoad {
+    pub id: u32,
+    pub w: [f64; 3],
+    #[serde(default)]
+    pub start: f64,
+    #[serde(default = "whole_member")]
+    pub end: f64,
+}
+
+fn whole_member() -> f64 {
+    1.0
+}
+
+impl MemberLoad {
+    pub fn uniform(id: u32, w: [f64; 3]) -> MemberLoad {
+        MemberLoad {
+            id,
+            w,
+            start: 0.0,
+            end: 1.0,
+        }
+    }
+
+    // The part of an element the load covers, in fractions of the element, given the element's
+    // range along the member.
+    pub fn on_element(&self, range: (f64, f64)) -> Option<(f64, f64)> {
+        let (s0, s1) = range;
+        let a = self.start.max(s0);
+        let b = self.end.min(s1);
+        if b - a < 1e-9 || s1 - s0 < 1e-9 {
+            return None;
+        }
+        Some(((a - s0) / (s1 - s0), (b - s0) / (s1 - s0)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadCase {
+    pub name: String,
+    pub nodal_load_list: Vec<NodalLoad>,
+    pub member_load_list: Vec<MemberLoad>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeDisplacement {
+    pub id: u32,
+    pub displacement: [f64; 6],
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeReaction {
+    pub id: u32,
+    pub force: [f64; 6],
+}
+
+// Forces acting on the element ends in local axes: N, Vy, Vz, T, My, Mz.
+#[derive(Debug, Serialize)]
+pub struct MemberEndForce {
+    pub member_id: u32,
+    pub node_i: u32,
+    pub node_j: u32,
+    pub start: [f64; 6],
+    pub end: [f64; 6],
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalysisResult {
+    pub load_case: String,
+    pub displacement_list: Vec<NodeDisplacement>,
+    pub reaction_list: Vec<NodeReaction>,
+    pub member_force_list: Vec<MemberEndForce>,
+}
+
+fn rectangle_torsion(a: f64, b: f64) -> f64 {
+    let (a, b) = if a >= b { (a, b) } else { (b, a) };
+    let r = b / a;
+    (1.0 / 3.0 - 0.21 * r * (1.0 - r.powi(4) / 12.0)) * a * b.powi(3)
+}
+
+fn rectangle_section(name: String, width: f64, depth: f64) -> FrameSection {
+    FrameSection {
+        name,
+        area: width * depth,
+        iy: width * depth.powi(3) / 12.0,
+        iz: depth * width.powi(3) / 12.0,
+        j: rectangle_torsion(width, depth),
+    }
+}
+
+// Steel sections use the section calculator; RC sections use the gross concrete section.
+pub fn frame_section(st_bridge: &StBridge, stb_section: &StbSection) -> Option<FrameSection> {
+    if let Some(shape) = stb_section.steel_shape() {
+        let steel = st_bridge.stb_model.stb_sections.stb_sec_steel.get(shape)?;
+        let p = steel_section_property(steel);
+        return Some(FrameSection {
+            name: stb_section.name(),
+            area: p.area,
+            iy: p.ix,
+            iz: p.iy,
+            j: p.j,
+        });
+    }
+
+    match stb_section {
+        StbSection::StbSecBeamRC { stb_sec_figure, .. } => {
+            let (width, depth) = crate::geometry::beam_rc_size(stb_sec_figure)?;
+            Some(rectangle_section(stb_section.name(), width, depth))
+        }
+        StbSection::StbSecColumnRC { stb_sec_figure, .. } => {
+            match (&stb_sec_figure.stb_sec_rect, &stb_sec_figure.stb_sec_circle) {
+                (Some(rect), _) => Some(rectangle_section(
+                    stb_section.name(),
+                    rect.width_x,
+                    rect.width_y,
+                )),
+                (None, Some(circle)) => {
+                    let i = PI * circle.d.powi(4) / 64.0;
+                    Some(FrameSection {
+                        name: stb_section.name(),
+                        area: PI * circle.d.powi(2) / 4.0,
+                        iy: i,
+                        iz: i,
+                        j: 2.0 * i,
+                    })
+                }
+                (None, None) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn member_material(
+    st_bridge: &StBridge,
+    database: &MaterialDatabase,
+    stb_member: &StbMember,
+    stb_section: &StbSection,
+    warning_list: &mut Vec<String>,
+) -> Material {
+    let (grade, default) = if stb_section.steel_shape().is_some() {
+        (
+            section_grade_list(stb_section)
+                .into_iter()
+                .next()
+                .unwrap_or_default(),
+            DEFAULT_STEEL,
+        )
+    } else {
+        (
+            concrete_grade(st_bridge, stb_member, stb_section),
+            DEFAULT_CONCRETE,
+        )
+    };
+
+    match database.get(&grade) {
+        Some(material) => material,
+        None => {
+            warning_list.push(format!(
+                "{} {}: unknown material \"{}\", {} used",
+                stb_member.member_kind(),
+                stb_member.name(),
+                grade,
+                default
+            ));
+            database.get(default).unwrap()
+        }
+    }
+}
+
+fn point_on_segment(p: Node, a: Node, b: Node) -> Option<f64> {
+    let axis = b - a;
+    let length = axis.length();
+    let t = (p - a).dot(axis) / length;
+    if t <= NODE_TOLERANCE || t >= length - NODE_TOLERANCE {
+        return None;
+    }
+    let foot = a + axis * (t / length);
+    if Node::distance(p, foot) < NODE_TOLERANCE {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+impl FrameModel {
+    pub fn new(st_bridge: &StBridge, database: &MaterialDatabase) -> FrameModel {
+        let stb_model = &st_bridge.stb_model;
+        let mut warning_list = Vec::new();
+
+        let mut merged_node_map = BTreeMap::new();
+        for group in find_duplicate_nodes(st_bridge, NODE_TOLERANCE).unwrap() {
+            for duplicate in group.duplicate_id_list {
+                merged_node_map.insert(duplicate, group.id);
+            }
+        }
+        let resolve = |id: u32| *merged_node_map.get(&id).unwrap_or(&id);
+
+        let point = |id: u32| {
+            stb_model
+                .stb_nodes
+                .get(id)
+                .map(|n| Node::new(n.x, n.y, n.z))
+        };
+
+        let mut member_list: Vec<&StbMember> = stb_model
+            .stb_members
+            .iter()
+            .filter(|m| m.is_linear())
+            .collect();
+        member_list.sort_by_key(|m| (m.member_kind(), m.id()));
+
+        let mut element_list: Vec<FrameElement> = Vec::new();
+        let mut used: BTreeSet<u32> = BTreeSet::new();
+
+        // Elements are first built over whole members and split afterwards.
+        for stb_member in member_list {
+            let (node_i, node_j) = match (stb_member.node_i(), stb_member.node_j()) {
+                (Some(i), Some(j)) => (resolve(i), resolve(j)),
+                _ => continue,
+            };
+            let (ni, nj) = match (point(node_i), point(node_j)) {
+                (Some(ni), Some(nj)) => (ni, nj),
+                _ => {
+                    warning_list.push(format!(
+                        "{} {}: missing node, skipped",
+                        stb_member.member_kind(),
+                        stb_member.name()
+                    ));
+                    continue;
+                }
+            };
+            if node_i == node_j || Node::distance(ni, nj) < NODE_TOLERANCE {
+                warning_list.push(format!(
+                    "{} {}: zero length, skipped",
+                    stb_member.member_kind(),
+                    stb_member.name()
+                ));
+                continue;
+            }
+
+            let stb_section = match stb_model.stb_sections.get(stb_member.id_section()) {
+                Some(s) => s,
+                None => {
+                    warning_list.push(format!(
+                        "{} {}: missing section, skipped",
+                        stb_member.member_kind(),
+                        stb_member.name()
+                    ));
+                    continue;
+                }
+            };
+            let section = match frame_section(st_bridge, stb_section) {
+                Some(s) => s,
+                None => {
+                    warning_list.push(format!(
+                        "{} {}: no properties for section {}, skipped",
+                        stb_member.member_kind(),
+                        stb_member.name(),
+                        stb_section.name()
+                    ));
+                    continue;
+                }
+            };
+            let material = member_material(
+                st_bridge,
+                database,
+                stb_member,
+                stb_section,
+                &mut warning_list,
+            );
+
+            let (rotate, release_i, release_j) = match stb_member {
+                StbMember::StbColumn {
+                    rotate,
+                    condition_bottom,
+                    condition_top,
+                    ..
+                }
+                | StbMember::StbPost {
+                    rotate,
+                    condition_bottom,
+                    condition_top,
+                    ..
+                } => (*rotate, condition_bottom, condition_top),
+                StbMember::StbGirder {
+                    rotate,
+                    condition_start,
+                    condition_end,
+                    ..
+                }
+                | StbMember::StbBeam {
+                    rotate,
+                    condition_start,
+                    condition_end,
+                    ..
+                }
+                | StbMember::StbBrace {
+                    rotate,
+                    condition_start,
+                    condition_end,
+                    ..
+                } => (*rotate, condition_start, condition_end),
+                _ => continue,
+            };
+
+            let plane = member_plane(ni, nj, rotate);
+            used.insert(node_i);
+            used.insert(node_j);
+
+            element_list.push(FrameElement {
+                member_id: stb_member.id(),
+                name: stb_member.name(),
+                node_i,
+                node_j,
+                y_axis: plane.x_axis,
+                z_axis: plane.y_axis,
+                section,
+                material,
+                release_i: matches!(release_i, JointCondition::Pin),
+                release_j: matches!(release_j, JointCondition::Pin),
+            });
+        }
+
+        // Split at the ends of other members that frame into the span, such as beams on girders.
+        let node_point: Vec<(u32, Node)> =
+            used.iter().map(|&id| (id, point(id).unwrap())).collect();
+        let mut split_list = Vec::new();
+        for element in element_list {
+            let a = point(element.node_i).unwrap();
+            let b = point(element.node_j).unwrap();
+
+            let mut inner: Vec<(f64, u32)> = node_point
+                .iter()
+                .filter_map(|&(id, p)| point_on_segment(p, a, b).map(|t| (t, id)))
+                .collect();
+            if inner.is_empty() {
+                split_list.push(element);
+                continue;
+            }
+            inner.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+
+            let mut chain = vec![element.node_i];
+            chain.extend(inner.iter().map(|&(_, id)| id));
+            chain.push(element.node_j);
+
+            let count = chain.len() - 1;
+            for k in 0..count {
+                split_list.push(FrameElement {
+                    node_i: chain[k],
+                    node_j: chain[k + 1],
+                    release_i: element.release_i && k == 0,
+                    release_j: element.release_j && k == count - 1,
+                    ..element.clone()
+                });
+            }
+        }
+
+        let node_list: Vec<FrameNode> = node_point
+            .iter()
+            .map(|&(id, p)| FrameNode {
+                id,
+                x: p.x,
+                y: p.y,
+                z: p.z,
+            })
+            .collect();
+
+        let support_list = foundation_node_list(st_bridge)
+            .into_iter()
+            .map(resolve)
+            .filter(|id| used.contains(id))
+            .collect::<BTreeSet<u32>>()
+            .into_iter()
+            .map(|id| Support {
+                id,
+                fixity: [true; 6],
+            })
+            .collect();
+
+        FrameModel {
+            node_list,
+            element_list: split_list,
+            support_list,
+            merged_node_map,
+            warning_list,
+        }
+    }
+
+    pub fn node_id(&self, id: u32) -> u32 {
+        *self.merged_node_map.get(&id).unwrap_or(&id)
+    }
+
+    // The range of each element along its member, in fractions of the member length. A member's
+    // elements follow each other from its first node.
+    pub fn element_range_list(&self) -> Vec<(f64, f64)> {
+        let point: HashMap<u32, Node> = self
+            .node_list
+            .iter()
+            .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+            .collect();
+        let length_list: Vec<f64> = self
+            .element_list
+            .iter()
+            .map(|e| Node::distance(point[&e.node_i], point[&e.node_j]))
+            .collect();
+
+        let mut member_length: HashMap<u32, f64> = HashMap::new();
+        for (e, length) in self.element_list.iter().zip(&length_list) {
+            *member_length.entry(e.member_id).or_insert(0.0) += length;
+        }
+
+        let mut done: HashMap<u32, f64> = HashMap::new();
+        self.element_list
+            .iter()
+            .zip(&length_list)
+            .map(|(e, length)| {
+                let total = member_length[&e.member_id];
+                let start = done.entry(e.member_id).or_insert(0.0);
+                let range = (*start / total, (*start + length) / total);
+                *start += length;
+                range
+            })
+            .collect()
+    }
+
+    pub fn analyze(&self, load_case: &LoadCase) -> Result<AnalysisResult, String> {
+        let node_index: HashMap<u32, usize> = self
+            .node_list
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.id, i))
+            .collect();
+        let node_count = self.node_list.len();
+
+        let element_data: Vec<ElementData> = self
+            .element_list
+            .iter()
+            .map(|e| ElementData::new(self, e, &node_index))
+            .collect();
+
+        // Equivalent nodal loads of the member loads, per element in local axes.
+        let mut member_load: HashMap<u32, Vec<&MemberLoad>> = HashMap::new();
+        for load in &load_case.member_load_list {
+            member_load.entry(load.id).or_default().push(load);
+        }
+        let equivalent_list: Vec<[f64; 12]> = element_data
+            .iter()
+            .zip(&self.element_list)
+            .zip(self.element_range_list())
+            .map(|((data, e), range)| {
+                let mut equivalent = [0.0; 12];
+                for load in member_load.get(&e.member_id).into_iter().flatten() {
+                    if let Some((a, b)) = load.on_element(range) {
+                        let part = data.equivalent_load(load.w, a, b);
+                        for k in 0..12 {
+                            equivalent[k] += part[k];
+                        }
+                    }
+                }
+                equivalent
+            })
+            .collect();
+
+        let mut nodal_load = vec![0.0; node_count * 6];
+        for nodal in &load_case.nodal_load_list {
+            let index = match node_index.get(&self.node_id(nodal.id)) {
+                Some(&i) => i,
+                None => return Err(format!("load on unknown node {}", nodal.id)),
+            };
+            for k in 0..6 {
+                nodal_load[index * 6 + k] += nodal.force[k];
+            }
+        }
+        let mut load = nodal_load.clone();
+        for (data, equivalent) in element_data.iter().zip(&equivalent_list) {
+            let global = data.to_global(equivalent);
+            for (k, &dof) in data.dof_list.iter().enumerate() {
+                load[dof] += global[k];
+            }
+        }
+
+        // Supports plus any dof nothing is stiff against, such as the rotations of a node reached
+        // only by pinned ends. The latter are held at zero rather than reported as a mechanism.
+        let mut restrained = vec![false; node_count * 6];
+        for support in &self.support_list {
+            if let Some(&i) = node_index.get(&support.id) {
+                for k in 0..6 {
+                    restrained[i * 6 + k] |= support.fixity[k];
+                }
+            }
+        }
+        let mut diagonal = vec![0.0; node_count * 6];
+        for data in &element_data {
+            for (k, &dof) in data.dof_list.iter().enumerate() {
+                diagonal[dof] += data.global[k][k];
+            }
+        }
+        let max_translation = (0..node_count * 6)
+            .filter(|d| d % 6 < 3)
+            .map(|d| diagonal[d])
+            .fold(0.0, f64::max);
+        let max_rotation = (0..node_count * 6)
+            .filter(|d| d % 6 >= 3)
+            .map(|d| diagonal[d])
+            .fold(0.0, f64::max);
+        for dof in 0..node_count * 6 {
+            let max = if dof % 6 < 3 {
+                max_translation
+            } else {
+                max_rotation
+            };
+            if diagonal[dof] <= max * 1e-12 {
+                restrained[dof] = true;
+            }
+        }
+
+        // Equations numbered in reverse Cuthill-McKee node order to keep the band narrow.
+        let mut equation = vec![usize::MAX; node_count * 6];
+        let mut equation_count = 0;
+        for i in node_order(node_count, &element_data) {
+            for k in 0..6 {
+                if !restrained[i * 6 + k] {
+                    equation[i * 6 + k] = equation_count;
+                    equation_count += 1;
+                }
+            }
+        }
+
+        let mut band_width = 0;
+        for data in &element_data {
+            let list: Vec<usize> = data
+                .dof_list
+                .iter()
+                .map(|&d| equation[d])
+                .filter(|&e| e != usize::MAX)
+                .collect();
+            if let (Some(min), Some(max)) = (list.iter().min(), list.iter().max()) {
+                band_width = band_width.max(max - min);
+            }
+        }
+
+        let mut matrix = BandMatrix::new(equation_count, band_width);
+        for data in &element_data {
+            for (a, &da) in data.dof_list.iter().enumerate() {
+                let ea = equation[da];
+                if ea == usize::MAX {
+                    continue;
+                }
+                for (b, &db) in data.dof_list.iter().enumerate() {
+                    let eb = equation[db];
+                    if eb == usize::MAX || eb < ea {
+                        continue;
+                    }
+                    matrix.add(ea, eb, data.global[a][b]);
+                }
+            }
+        }
+
+        let mut rhs = vec![0.0; equation_count];
+        for dof in 0..node_count * 6 {
+            if equation[dof] != usize::MAX {
+                rhs[equation[dof]] = load[dof];
+            }
+        }
+
+        if let Err(e) = matrix.factorize() {
+            let dof = equation.iter().position(|&x| x == e).unwrap();
+            return Err(format!(
+                "structure is unstable at node {} ({})",
+                self.node_list[dof / 6].id,
+                ["ux", "uy", "uz", "rx", "ry", "rz"][dof % 6]
+            ));
+        }
+        matrix.solve(&mut rhs);
+
+        let mut displacement = vec![0.0; node_count * 6];
+        for dof in 0..node_count * 6 {
+            if equation[dof] != usize::MAX {
+                displacement[dof] = rhs[equation[dof]];
+            }
+        }
+
+        let mut reaction = vec![0.0; node_count * 6];
+        let mut member_force_list = Vec::new();
+        for ((data, equivalent), element) in element_data
+            .iter()
+            .zip(&equivalent_list)
+            .zip(&self.element_list)
+        {
+            let mut u = [0.0; 12];
+            for (k, &dof) in data.dof_list.iter().enumerate() {
+                u[k] = displacement[dof];
+            }
+            let force = data.end_force(&u, equivalent);
+
+            let global = data.to_global(&force);
+            for (k, &dof) in data.dof_list.iter().enumerate() {
+                reaction[dof] += global[k];
+            }
+
+            let mut start = [0.0; 6];
+            let mut end = [0.0; 6];
+            start.copy_from_slice(&force[0..6]);
+            end.copy_from_slice(&force[6..12]);
+            member_force_list.push(MemberEndForce {
+                member_id: element.member_id,
+                node_i: element.node_i,
+                node_j: element.node_j,
+                start,
+                end,
+            });
+        }
+
+        let mut reaction_list = Vec::new();
+        for support in &self.support_list {
+            let i = node_index[&support.id];
+            let mut force = [0.0; 6];
+            for k in 0..6 {
+                if support.fixity[k] {
+                    force[k] = reaction[i * 6 + k] - nodal_load[i * 6 + k];
+                }
+            }
+            reaction_list.push(NodeReaction {
+                id: support.id,
+                force,
+            });
+        }
+
+        let displacement_list = self
+            .node_list
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let mut d = [0.0; 6];
+                d.copy_from_slice(&displacement[i * 6..i * 6 + 6]);
+                NodeDisplacement {
+                    id: n.id,
+                    displacement: d,
+                }
+            })
+            .collect();
+
+        Ok(AnalysisResult {
+            load_case: load_case.name.clone(),
+            displacement_list,
+            reaction_list,
+            member_force_list,
+        })
+    }
+}
+
+// Element matrices in local and global axes, with the pinned ends condensed out.
+struct ElementData {
+    dof_list: [usize; 12],
+    rotation: [[f64; 3]; 3],
+    full: [[f64; 12]; 12],
+    local: [[f64; 12]; 12],
+    global: [[f64; 12]; 12],
+    length: f64,
+    released: Vec<usize>,
+}
+
+impl ElementData {
+    fn new(model: &FrameModel, e: &FrameElement, node_index: &HashMap<u32, usize>) -> ElementData {
+        let i = node_index[&e.node_i];
+        let j = node_index[&e.node_j];
+        let ni = &model.node_list[i];
+        let nj = &model.node_list[j];
+
+        let axis = Node::new(nj.x - ni.x, nj.y - ni.y, nj.z - ni.z);
+        let length = axis.length();
+        let x = axis.normalize();
+        let rotation = [
+            [x.x, x.y, x.z],
+            [e.y_axis.x, e.y_axis.y, e.y_axis.z],
+            [e.z_axis.x, e.z_axis.y, e.z_axis.z],
+        ];
+
+        let young_modulus = e.material.young_modulus;
+        let shear_modulus = young_modulus / (2.0 * (1.0 + e.material.poisson_ratio));
+        let s = &e.section;
+        let l = length;
+
+        let mut k = [[0.0; 12]; 12];
+        let ea = young_modulus * s.area / l;
+        let gj = shear_modulus * s.j / l;
+        let eiz = young_modulus * s.iz;
+        let eiy = young_modulus * s.iy;
+
+        k[0][0] = ea;
+        k[0][6] = -ea;
+        k[6][6] = ea;
+        k[3][3] = gj;
+        k[3][9] = -gj;
+        k[9][9] = gj;
+
+        k[1][1] = 12.0 * eiz / l.powi(3);
+        k[1][5] = 6.0 * eiz / l.powi(2);
+        k[1][7] = -12.0 * eiz / l.powi(3);
+        k[1][11] = 6.0 * eiz / l.powi(2);
+        k[5][5] = 4.0 * eiz / l;
+        k[5][7] = -6.0 * eiz / l.powi(2);
+        k[5][11] = 2.0 * eiz / l;
+        k[7][7] = 12.0 * eiz / l.powi(3);
+        k[7][11] = -6.0 * eiz / l.powi(2);
+        k[11][11] = 4.0 * eiz / l;
+
+        k[2][2] = 12.0 * eiy / l.powi(3);
+        k[2][4] = -6.0 * eiy / l.powi(2);
+        k[2][8] = -12.0 * eiy / l.powi(3);
+        k[2][10] = -6.0 * eiy / l.powi(2);
+        k[4][4] = 4.0 * eiy / l;
+        k[4][8] = 6.0 * eiy / l.powi(2);
+        k[4][10] = 2.0 * eiy / l;
+        k[8][8] = 12.0 * eiy / l.powi(3);
+        k[8][10] = 6.0 * eiy / l.powi(2);
+        k[10][10] = 4.0 * eiy / l;
+
+        for a in 0..12 {
+            for b in 0..a {
+                k[a][b] = k[b][a];
+            }
+        }
+
+        let mut released = Vec::new();
+        if e.release_i {
+            released.extend(&[4, 5]);
+        }
+        if e.release_j {
+            released.extend(&[10, 11]);
+        }
+        let full = k;
+        condense(&mut k, &mut [0.0; 12], &released);
+
+        let mut data = ElementData {
+            dof_list: [0; 12],
+            rotation,
+            full,
+            local: k,
+            global: [[0.0; 12]; 12],
+            length,
+            released,
+        };
+        for a in 0..6 {
+            data.dof_list[a] = i * 6 + a;
+            data.dof_list[a + 6] = j * 6 + a;
+        }
+
+        // K_global = T^t K_local T, with T the rotation repeated on each 3 x 3 block.
+        let mut kt = [[0.0; 12]; 12];
+        for a in 0..12 {
+            for b in 0..12 {
+                let (bb, bc) = (b / 3, b % 3);
+                kt[a][b] = (0..3)
+                    .map(|m| data.local[a][bb * 3 + m] * data.rotation[m][bc])
+                    .sum();
+            }
+        }
+        for a in 0..12 {
+            for b in 0..12 {
+                let (ab, ac) = (a / 3, a % 3);
+                data.global[a][b] = (0..3)
+                    .map(|m| data.rotation[m][ac] * kt[ab * 3 + m][b])
+                    .sum();
+            }
+        }
+
+        data
+    }
+
+    fn to_local(&self, v: &[f64; 12]) -> [f64; 12] {
+        let mut local = [0.0; 12];
+        for block in 0..4 {
+            for a in 0..3 {
+                local[block * 3 + a] = (0..3).map(|m| self.rotation[a][m] * v[block * 3 + m]).sum();
+            }
+        }
+        local
+    }
+
+    fn to_global(&self, v: &[f64; 12]) -> [f64; 12] {
+        let mut global = [0.0; 12];
+        for block in 0..4 {
+            for a in 0..3 {
+                global[block * 3 + a] =
+                    (0..3).map(|m| self.rotation[m][a] * v[block * 3 + m]).sum();
+            }
+        }
+        global
+    }
+
+    // Nodal loads in local axes equivalent to a uniform global load from a to b, in fractions of
+    // the element, condensed like the matrix. These are the integrals of the load times the linear
+    // axial and cubic bending shape functions.
+    fn equivalent_load(&self, w: [f64; 3], a: f64, b: f64) -> [f64; 12] {
+        let q: Vec<f64> = (0..3)
+            .map(|r| (0..3).map(|m| self.rotation[r][m] * w[m]).sum())
+            .collect();
+        let l = self.length;
+
+        let integral = |f: &dyn Fn(f64) -> f64| l * (f(b) - f(a));
+        let axial_i = integral(&|x| x - x * x / 2.0);
+        let axial_j = integral(&|x| x * x / 2.0);
+        let shear_i = integral(&|x| x - x.powi(3) + x.powi(4) / 2.0);
+        let moment_i = l * integral(&|x| x * x / 2.0 - 2.0 * x.powi(3) / 3.0 + x.powi(4) / 4.0);
+        let shear_j = integral(&|x| x.powi(3) - x.powi(4) / 2.0);
+        let moment_j = l * integral(&|x| -x.powi(3) / 3.0 + x.powi(4) / 4.0);
+
+        let mut e = [0.0; 12];
+        e[0] = q[0] * axial_i;
+        e[6] = q[0] * axial_j;
+        e[1] = q[1] * shear_i;
+        e[5] = q[1] * moment_i;
+        e[7] = q[1] * shear_j;
+        e[11] = q[1] * moment_j;
+        e[2] = q[2] * shear_i;
+        e[4] = -q[2] * moment_i;
+        e[8] = q[2] * shear_j;
+        e[10] = -q[2] * moment_j;
+
+        let mut k = self.full;
+        condense(&mut k, &mut e, &self.released);
+        e
+    }
+
+    fn end_force(&self, u_global: &[f64; 12], equivalent: &[f64; 12]) -> [f64; 12] {
+        let u = self.to_local(u_global);
+        let mut f = [0.0; 12];
+        for a in 0..12 {
+            f[a] = (0..12).map(|b| self.local[a][b] * u[b]).sum::<f64>() - equivalent[a];
+        }
+        f
+    }
+}
+
+// Static condensation of the released dofs one at a time, for the matrix and a load vector.
+fn condense(k: &mut [[f64; 12]; 12], e: &mut [f64; 12], released: &[usize]) {
+    for &r in released {
+        let pivot = k[r][r];
+        if pivot == 0.0 {
+            continue;
+        }
+        let column: Vec<f64> = (0..12).map(|a| k[a][r]).collect();
+        let er = e[r];
+        for a in 0..12 {
+            e[a] -= column[a] * er / pivot;
+            for b in 0..12 {
+                k[a][b] -= column[a] * column[b] / pivot;
+            }
+        }
+        e[r] = 0.0;
+        for a in 0..12 {
+            k[a][r] = 0.0;
+            k[r][a] = 0.0;
+        }
+    }
+}
+
+// Reverse Cuthill-McKee ordering of the node indices.
+fn node_order(node_count: usize, element_data: &[ElementData]) -> Vec<usize> {
+    let mut adjacency: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); node_count];
+    for data in element_data {
+        let i = data.dof_list[0] / 6;
+        let j = data.dof_list[6] / 6;
+        adjacency[i].insert(j);
+        adjacency[j].insert(i);
+    }
+
+    let mut visited = vec![false; node_count];
+    let mut order = Vec::with_capacity(node_count);
+
+    let mut start_list: Vec<usize> = (0..node_count).collect();
+    start_list.sort_by_key(|&i| adjacency[i].len());
+
+    for start in start_list {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            let mut next: Vec<usize> = adjacency[i]
+                .iter()
+                .cloned()
+                .filter(|&n| !visited[n])
+                .collect();
+            next.sort_by_key(|&n| adjacency[n].len());
+            for n in next {
+                visited[n] = true;
+                queue.push_back(n);
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+// Symmetric band matrix holding the upper triangle, factorized in place as U^t U.
+struct BandMatrix {
+    size: usize,
+    band_width: usize,
+    data: Vec<f64>,
+}
+
+impl BandMatrix {
+    fn new(size: usize, band_width: usize) -> BandMatrix {
+        BandMatrix {
+            size,
+            band_width,
+            data: vec![0.0; size * (band_width + 1)],
+        }
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        i * (self.band_width + 1) + (j - i)
+    }
+
+    fn get(&self, i: usize, j: usize) -> f64 {
+        if j < i || j - i > self.band_width {
+            0.0
+        } else {
+            self.data[self.index(i, j)]
+        }
+    }
+
+    fn add(&mut self, i: usize, j: usize, value: f64) {
+        let index = self.index(i, j);
+        self.data[index] += value;
+    }
+
+    // Returns the equation whose pivot vanished when the matrix is not positive definite.
+    fn factorize(&mut self) -> Result<(), usize> {
+        let scale = (0..self.size)
+            .map(|i| self.get(i, i).abs())
+            .fold(0.0, f64::max);
+
+        for i in 0..self.size {
+            let first = i.saturating_sub(self.band_width);
+            let last = (i + self.band_width).min(self.size - 1);
+
+            let mut pivot = self.get(i, i);
+            for m in first..i {
+                pivot -= self.get(m, i).powi(2);
+            }
+            if pivot <= scale * 1e-14 {
+                return Err(i);
+            }
+            let pivot = pivot.sqrt();
+            let index = self.index(i, i);
+            self.data[index] = pivot;
+
+            for j in i + 1..=last {
+                let mut value = self.get(i, j);
+                for m in j.saturating_sub(self.band_width)..i {
+                    value -= self.get(m, i) * self.get(m, j);
+                }
+                let index = self.index(i, j);
+                self.data[index] = value / pivot;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn solve(&self, b: &mut [f64]) {
+        for i in 0..self.size {
+            let first = i.saturating_sub(self.band_width);
+            let mut value = b[i];
+            for m in first..i {
+                value -= self.get(m, i) * b[m];
+            }
+            b[i] = value / self.get(i, i);
+        }
+
+        for i in (0..self.size).rev() {
+            let last = (i + self.band_width).min(self.size - 1);
+            let mut value = b[i];
+            for j in i + 1..=last {
+                value -= self.get(i, j) * b[j];
+            }
+            b[i] = value / self.get(i, i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LENGTH: f64 = 4000.0;
+    const W: f64 = 10.0;
+
+    fn section() -> FrameSection {
+        rectangle_section("test".to_string(), 200.0, 400.0)
+    }
+
+    fn ei() -> f64 {
+        MaterialDatabase::new()
+            .get(DEFAULT_STEEL)
+            .unwrap()
+            .young_modulus
+            * section().iy
+    }
+
+    fn node(id: u32, x: f64, z: f64) -> FrameNode {
+        FrameNode { id, x, y: 0.0, z }
+    }
+
+    // Local y is the global y, so loads along the global z bend about iy.
+    fn element(member_id: u32, node_i: u32, node_j: u32, release_j: bool) -> FrameElement {
+        FrameElement {
+            member_id,
+            name: "test".to_string(),
+            node_i,
+            node_j,
+            y_axis: Node::new(0.0, 1.0, 0.0),
+            z_axis: Node::new(0.0, 0.0, 1.0),
+            section: section(),
+            material: MaterialDatabase::new().get(DEFAULT_STEEL).unwrap(),
+            release_i: false,
+            release_j,
+        }
+    }
+
+    fn model(
+        node_list: Vec<FrameNode>,
+        element_list: Vec<FrameElement>,
+        support_list: Vec<Support>,
+    ) -> FrameModel {
+        FrameModel {
+            node_list,
+            element_list,
+            support_list,
+            merged_node_map: BTreeMap::new(),
+            warning_list: Vec::new(),
+        }
+    }
+
+    fn fixed(id: u32) -> Support {
+        Support {
+            id,
+            fixity: [true; 6],
+        }
+    }
+
+    fn pinned(id: u32) -> Support {
+        Support {
+            id,
+            fixity: [true, true, true, false, false, false],
+        }
+    }
+
+    fn load_case(nodal_load_list: Vec<NodalLoad>, member_load_list: Vec<MemberLoad>) -> LoadCase {
+        LoadCase {
+            name: "test".to_string(),
+            nodal_load_list,
+            member_load_list,
+        }
+    }
+
+    fn reaction(result: &AnalysisResult, id: u32) -> [f64; 6] {
+        result
+            .reaction_list
+            .iter()
+            .find(|r| r.id == id)
+            .unwrap()
+            .force
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-6,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn cantilever_tip_deflection() {
+        let p = 5000.0;
+        let model = model(
+            vec![node(1, 0.0, 0.0), node(2, LENGTH, 0.0)],
+            vec![element(1, 1, 2, false)],
+            vec![fixed(1)],
+        );
+        let load = NodalLoad {
+            id: 2,
+            force: [0.0, 0.0, -p, 0.0, 0.0, 0.0],
+        };
+        let result = model.analyze(&load_case(vec![load], Vec::new())).unwrap();
+
+        let tip = &result.displacement_list[1];
+        assert_eq!(tip.id, 2);
+        assert_close(tip.displacement[2], -p * LENGTH.powi(3) / (3.0 * ei()));
+        assert_close(reaction(&result, 1)[2], p);
+        assert_close(reaction(&result, 1)[4], -p * LENGTH);
+    }
+
+    // Two elements between fixed ends, so the middle node is free and the ends carry wL²/12.
+    #[test]
+    fn fixed_end_moments() {
+        let model = model(
+            vec![
+                node(1, 0.0, 0.0),
+                node(2, LENGTH / 2.0, 0.0),
+                node(3, LENGTH, 0.0),
+            ],
+            vec![element(1, 1, 2, false), element(1, 2, 3, false)],
+            vec![fixed(1), fixed(3)],
+        );
+        let load = MemberLoad::uniform(1, [0.0, 0.0, -W]);
+        let result = model.analyze(&load_case(Vec::new(), vec![load])).unwrap();
+
+        let moment = W * LENGTH.powi(2) / 12.0;
+        assert_close(result.member_force_list[0].start[4].abs(), moment);
+        assert_close(result.member_force_list[1].end[4].abs(), moment);
+        assert_close(
+            result.displacement_list[1].displacement[2],
+            -W * LENGTH.powi(4) / (384.0 * ei()),
+        );
+        assert_close(reaction(&result, 1)[2], W * LENGTH / 2.0);
+        assert_close(reaction(&result, 3)[2], W * LENGTH / 2.0);
+    }
+
+    // A propped cantilever: fixed at the start, released at the end.
+    #[test]
+    fn pinned_end_release() {
+        let model = model(
+            vec![node(1, 0.0, 0.0), node(2, LENGTH, 0.0)],
+            vec![element(1, 1, 2, true)],
+            vec![fixed(1), pinned(2)],
+        );
+        let load = MemberLoad::uniform(1, [0.0, 0.0, -W]);
+        let result = model.analyze(&load_case(Vec::new(), vec![load])).unwrap();
+
+        let force = &result.member_force_list[0];
+        assert_close(force.start[4].abs(), W * LENGTH.powi(2) / 8.0);
+        assert!(force.end[4].abs() < 1e-6);
+        assert_close(reaction(&result, 1)[2], 5.0 * W * LENGTH / 8.0);
+        assert_close(reaction(&result, 2)[2], 3.0 * W * LENGTH / 8.0);
+    }
+
+    // Load on the outer half of a cantilever, on one element and on the second of two.
+    #[test]
+    fn partial_member_load() {
+        let load = MemberLoad {
+            start: 0.5,
+            ..MemberLoad::uniform(1, [0.0, 0.0, -W])
+        };
+
+        let one = model(
+            vec![node(1, 0.0, 0.0), node(2, LENGTH, 0.0)],
+            vec![element(1, 1, 2, false)],
+            vec![fixed(1)],
+        );
+        let one = one
+            .analyze(&load_case(Vec::new(), vec![load.clone()]))
+            .unwrap();
+        assert_close(reaction(&one, 1)[2], W * LENGTH / 2.0);
+        assert_close(reaction(&one, 1)[4], -W * LENGTH / 2.0 * 0.75 * LENGTH);
+
+        let two = model(
+            vec![
+                node(1, 0.0, 0.0),
+                node(2, LENGTH / 2.0, 0.0),
+                node(3, LENGTH, 0.0),
+            ],
+            vec![element(1, 1, 2, false), element(1, 2, 3, false)],
+            vec![fixed(1)],
+        );
+        let two = two.analyze(&load_case(Vec::new(), vec![load])).unwrap();
+        assert_close(
+            one.displacement_list[1].displacement[2],
+            two.displacement_list[2].displacement[2],
+        );
+        assert_close(
+            one.displacement_list[1].displacement[4],
+            two.displacement_list[2].displacement[4],
+        );
+    }
+
+    #[test]
+    fn reactions_balance_load() {
+        let height = 3000.0;
+        let model = model(
+            vec![
+                node(1, 0.0, 0.0),
+                node(2, LENGTH, 0.0),
+                node(3, 0.0, height),
+                node(4, LENGTH, height),
+            ],
+            vec![
+                FrameElement {
+                    y_axis: Node::new(1.0, 0.0, 0.0),
+                    z_axis: Node::new(0.0, 1.0, 0.0),
+                    ..element(1, 1, 3, false)
+                },
+                FrameElement {
+                    y_axis: Node::new(1.0, 0.0, 0.0),
+                    z_axis: Node::new(0.0, 1.0, 0.0),
+                    ..element(2, 2, 4, false)
+                },
+                element(3, 3, 4, false),
+            ],
+            vec![fixed(1), fixed(2)],
+        );
+        let h = 2000.0;
+        let nodal = NodalLoad {
+            id: 3,
+            force: [h, 0.0, 0.0, 0.0, 0.0, 0.0],
+        };
+        let member = MemberLoad::uniform(3, [0.0, 0.0, -W]);
+        let result = model
+            .analyze(&load_case(vec![nodal], vec![member]))
+            .unwrap();
+
+        let total = |k: usize| reaction(&result, 1)[k] + reaction(&result, 2)[k];
+        assert_close(total(0), -h);
+        assert_close(total(2), W * LENGTH);
+        assert!(total(1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn girder_conditions_release_ends() {
+        let file_name = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        );
+        let st_bridge = crate::read_st_bridge(file_name).unwrap();
+        let model = FrameModel::new(&st_bridge, &MaterialDatabase::new());
+
+        let girder = model
+            .element_list
+            .iter()
+            .find(|e| e.member_id == 3)
+            .unwrap();
+        assert!(!girder.release_i);
+        assert!(girder.release_j);
+    }
+}
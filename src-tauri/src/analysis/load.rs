@@ -0,0 +1,737 @@
+use super::frame::{FrameModel, LoadCase, MemberLoad, NodalLoad};
+use crate::geometry::{plate_outline, point_in_polygon, signed_area, Node};
+use crate::material::{concrete_grade, MaterialDatabase, MaterialKind};
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const GRAVITY: f64 = 9.80665;
+
+// Reinforcement adds 1 kN/m3 to the unit weight of plain concrete (AIJ RC standard).
+const REBAR_UNIT_WEIGHT: f64 = 1000.0; // N/m3
+
+// A girder or beam supports a slab edge when both its ends lie this close to the edge line in
+// plan (mm).
+const EDGE_TOLERANCE: f64 = 10.0;
+
+// Sample points along the longer side of a slab when splitting it into tributary areas.
+const SAMPLE_COUNT: usize = 40;
+
+// Steps of the load on each member carrying slab, following the shape of its tributary area.
+const STEP_COUNT: usize = 10;
+
+// Area loads in kN/m2.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloorLoad {
+    pub dead: f64,
+    pub live: f64,
+}
+
+// A slab takes the load of its kind ("NORMAL", "CANTI") if listed, else that of its story, else
+// the default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadSettings {
+    pub default: FloorLoad,
+    pub story_map: HashMap<String, FloorLoad>,
+    pub slab_kind_map: HashMap<String, FloorLoad>,
+}
+
+impl LoadSettings {
+    // Finishes and ceiling, with the office live load for frame design (Enforcement Order
+    // Article 85).
+    pub fn new() -> LoadSettings {
+        LoadSettings {
+            default: FloorLoad {
+                dead: 1.0,
+                live: 1.8,
+            },
+            story_map: HashMap::new(),
+            slab_kind_map: HashMap::new(),
+        }
+    }
+
+    pub fn floor_load(&self, story: Option<&str>, slab_kind: &str) -> &FloorLoad {
+        self.slab_kind_map
+            .get(slab_kind)
+            .or_else(|| story.and_then(|s| self.story_map.get(s)))
+            .unwrap_or(&self.default)
+    }
+}
+
+impl Default for LoadSettings {
+    fn default() -> LoadSettings {
+        LoadSettings::new()
+    }
+}
+
+// Cases "SW" (members and slabs), "DL" (floor dead load) and "LL" (floor live load), acting
+// downwards. Member self-weight is uniform; slab load follows the triangle or trapezoid of each
+// member's tributary area in STEP_COUNT uniform steps.
+#[derive(Debug, Serialize)]
+pub struct GeneratedLoads {
+    pub load_case_list: Vec<LoadCase>,
+    pub warning_list: Vec<String>,
+}
+
+// Weight per unit volume in N/mm3.
+fn unit_weight(material_density: f64, kind: &MaterialKind) -> f64 {
+    let rebar = match kind {
+        MaterialKind::Concrete => REBAR_UNIT_WEIGHT,
+        _ => 0.0,
+    };
+    (material_density * GRAVITY + rebar) * 1e-9
+}
+
+fn segment_distance(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length2 = dx * dx + dy * dy;
+    let t = if length2 > 0.0 {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (fx, fy) = (a.0 + dx * t, a.1 + dy * t);
+    (((p.0 - fx).powi(2) + (p.1 - fy).powi(2)).sqrt(), t)
+}
+
+// A stretch of slab edge carried by an element, from t0 to t1 in fractions of the edge, lying
+// from s0 to s1 in fractions of its member.
+#[derive(Clone)]
+struct EdgeSupport {
+    member_id: u32,
+    t0: f64,
+    t1: f64,
+    s0: f64,
+    s1: f64,
+}
+
+impl EdgeSupport {
+    // The step of the member under the point t of the edge.
+    fn step(&self, t: f64) -> usize {
+        let r = ((t - self.t0) / (self.t1 - self.t0)).clamp(0.0, 1.0);
+        let s = self.s0 + (self.s1 - self.s0) * r;
+        ((s * STEP_COUNT as f64) as usize).min(STEP_COUNT - 1)
+    }
+}
+
+// Splits a slab into the areas carried by each step of each supporting member, in mm2. Every
+// sample point of the slab goes to the nearest supported edge and then to the member covering
+// that point of the edge. Edges and members are matched in plan, so that a slab set off from
+// its girders by its level still finds them; only the members in `support_member_list` are
+// considered. Returns None when no edge is supported.
+fn tributary_area(
+    model: &FrameModel,
+    node_map: &HashMap<u32, Node>,
+    support_member_list: &HashSet<u32>,
+    outline: &[(f64, f64)],
+    holes: &[Vec<(f64, f64)>],
+    corners: &[Node],
+) -> Option<BTreeMap<(u32, usize), f64>> {
+    let count = corners.len();
+    let range_list = model.element_range_list();
+
+    let plan = |p: Node| Node::new(p.x, p.y, 0.0);
+
+    let mut support_list: Vec<Vec<EdgeSupport>> = vec![Vec::new(); count];
+    for (i, supports) in support_list.iter_mut().enumerate() {
+        let a = plan(corners[i]);
+        let b = plan(corners[(i + 1) % count]);
+        let axis = b - a;
+        let length = axis.length();
+        if length < EDGE_TOLERANCE {
+            continue;
+        }
+        let direction = axis.normalize();
+
+        let along = |p: Node| -> Option<f64> {
+            let t = (p - a).dot(direction);
+            let foot = a + direction * t;
+            if Node::distance(p, foot) < EDGE_TOLERANCE {
+                Some(t / length)
+            } else {
+                None
+            }
+        };
+
+        for (element, &(sp, sq)) in model.element_list.iter().zip(&range_list) {
+            if !support_member_list.contains(&element.member_id) {
+                continue;
+            }
+            let (p, q) = match (node_map.get(&element.node_i), node_map.get(&element.node_j)) {
+                (Some(&p), Some(&q)) => (plan(p), plan(q)),
+                _ => continue,
+            };
+            if let (Some(tp), Some(tq)) = (along(p), along(q)) {
+                if (tq - tp).abs() * length < EDGE_TOLERANCE {
+                    continue;
+                }
+                // The member position at t, for the element clipped to the edge.
+                let at = |t: f64| sp + (sq - sp) * (t - tp) / (tq - tp);
+                let (t0, t1) = (tp.min(tq).max(0.0), tp.max(tq).min(1.0));
+                if (t1 - t0) * length > EDGE_TOLERANCE {
+                    supports.push(EdgeSupport {
+                        member_id: element.member_id,
+                        t0,
+                        t1,
+                        s0: at(t0),
+                        s1: at(t1),
+                    });
+                }
+            }
+        }
+    }
+
+    if support_list.iter().all(|s| s.is_empty()) {
+        return None;
+    }
+
+    let (mut u0, mut v0) = (f64::INFINITY, f64::INFINITY);
+    let (mut u1, mut v1) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(u, v) in outline {
+        u0 = u0.min(u);
+        v0 = v0.min(v);
+        u1 = u1.max(u);
+        v1 = v1.max(v);
+    }
+    let h = (u1 - u0).max(v1 - v0) / SAMPLE_COUNT as f64;
+    let nu = ((u1 - u0) / h).ceil() as usize;
+    let nv = ((v1 - v0) / h).ceil() as usize;
+
+    let mut share: BTreeMap<(u32, usize), f64> = BTreeMap::new();
+    let mut sample_count = 0;
+
+    for iu in 0..nu {
+        for iv in 0..nv {
+            let p = (u0 + (iu as f64 + 0.5) * h, v0 + (iv as f64 + 0.5) * h);
+            if !point_in_polygon(p, outline) || holes.iter().any(|hole| point_in_polygon(p, hole)) {
+                continue;
+            }
+
+            let mut nearest: Vec<(f64, usize, f64)> = Vec::new();
+            for (i, supports) in support_list.iter().enumerate() {
+                if supports.is_empty() {
+                    continue;
+                }
+                let (distance, t) = segment_distance(p, outline[i], outline[(i + 1) % count]);
+                nearest.push((distance, i, t));
+            }
+            let min = nearest.iter().map(|n| n.0).fold(f64::INFINITY, f64::min);
+
+            // A sample as near to several edges, such as one on the diagonal of a square panel,
+            // is shared between them.
+            nearest.retain(|n| n.0 - min < 1e-6);
+            let part = 1.0 / nearest.len() as f64;
+            for &(_, edge, t) in &nearest {
+                // The member covering t, or the closest one where the edge has gaps.
+                let support = support_list[edge]
+                    .iter()
+                    .min_by(|a, b| {
+                        let gap = |s: &EdgeSupport| (s.t0 - t).max(t - s.t1).max(0.0);
+                        gap(a).partial_cmp(&gap(b)).unwrap()
+                    })
+                    .unwrap();
+
+                *share
+                    .entry((support.member_id, support.step(t)))
+                    .or_insert(0.0) += part;
+            }
+            sample_count += 1;
+        }
+    }
+
+    if sample_count == 0 {
+        return None;
+    }
+
+    // Scale the sample counts so that the shares add up to the exact net area.
+    let area = signed_area(outline).abs() - holes.iter().map(|h| signed_area(h).abs()).sum::<f64>();
+    for value in share.values_mut() {
+        *value *= area / sample_count as f64;
+    }
+
+    Some(share)
+}
+
+pub fn generate_load_cases(
+    st_bridge: &StBridge,
+    model: &FrameModel,
+    database: &MaterialDatabase,
+    settings: &LoadSettings,
+) -> GeneratedLoads {
+    let stb_model = &st_bridge.stb_model;
+    let mut warning_list = Vec::new();
+
+    let node_map: HashMap<u32, Node> = model
+        .node_list
+        .iter()
+        .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+        .collect();
+
+    // Member lengths over all of their elements, to turn forces into loads per unit length.
+    let mut member_length: BTreeMap<u32, f64> = BTreeMap::new();
+    let mut self_weight: BTreeMap<u32, f64> = BTreeMap::new();
+    for element in &model.element_list {
+        let length = Node::distance(node_map[&element.node_i], node_map[&element.node_j]);
+        *member_length.entry(element.member_id).or_insert(0.0) += length;
+        self_weight.insert(
+            element.member_id,
+            element.section.area * unit_weight(element.material.density, &element.material.kind),
+        );
+    }
+
+    // Total downward slab force in N per member step and per node, for each of SW, DL and LL.
+    let mut member_force: Vec<BTreeMap<(u32, usize), f64>> = vec![BTreeMap::new(); 3];
+    let mut node_force: Vec<BTreeMap<u32, f64>> = vec![BTreeMap::new(); 3];
+
+    let mut slab_list: Vec<&StbMember> = stb_model.stb_members.stb_slabs.values().collect();
+    slab_list.sort_by_key(|m| m.id());
+
+    for slab in slab_list {
+        let (kind_slab, id_section) = match slab {
+            StbMember::StbSlab {
+                kind_slab,
+                id_section,
+                ..
+            } => (kind_slab.to_string(), *id_section),
+            _ => continue,
+        };
+
+        let plate = match plate_outline(st_bridge, slab) {
+            Some(p) => p,
+            None => {
+                warning_list.push(format!("Slab {}: no outline, skipped", slab.name()));
+                continue;
+            }
+        };
+
+        let story = stb_model.story(slab).map(|s| s.name.clone());
+        let floor_load = settings.floor_load(story.as_deref(), &kind_slab);
+
+        // Only the girders and beams of the slab's story can carry it, since members of the
+        // floors above and below share its edges in plan.
+        let support_member_list: HashSet<u32> = stb_model
+            .stb_members
+            .stb_girders
+            .values()
+            .chain(stb_model.stb_members.stb_beams.values())
+            .filter(|m| stb_model.story(m).map(|s| s.name.clone()) == story)
+            .map(|m| m.id())
+            .collect();
+
+        let concrete = stb_model
+            .stb_sections
+            .get(id_section)
+            .map(|section| concrete_grade(st_bridge, slab, section))
+            .and_then(|grade| database.get(&grade))
+            .or_else(|| database.get("FC24"))
+            .unwrap();
+        let thickness = plate.top - plate.bottom;
+
+        // N/mm2 for each case.
+        let pressure = [
+            thickness * unit_weight(concrete.density, &concrete.kind),
+            floor_load.dead * 1e-3,
+            floor_load.live * 1e-3,
+        ];
+
+        let corners: Vec<Node> = plate
+            .outline
+            .iter()
+            .map(|&(u, v)| plate.plane.to_global(u, v, 0.0))
+            .collect();
+
+        match tributary_area(
+            model,
+            &node_map,
+            &support_member_list,
+            &plate.outline,
+            &plate.holes,
+            &corners,
+        ) {
+            Some(share) => {
+                for (key, area) in share {
+                    for k in 0..3 {
+                        *member_force[k].entry(key).or_insert(0.0) += pressure[k] * area;
+                    }
+                }
+            }
+            None => {
+                // Nothing frames the slab, so its load goes straight to the corner nodes.
+                let node_id_list: Vec<u32> = slab
+                    .node_id_list()
+                    .into_iter()
+                    .map(|id| model.node_id(id))
+                    .filter(|id| node_map.contains_key(id))
+                    .collect();
+                if node_id_list.is_empty() {
+                    warning_list.push(format!("Slab {}: not supported, load lost", slab.name()));
+                    continue;
+                }
+                warning_list.push(format!(
+                    "Slab {}: no supporting girder or beam, load applied to its nodes",
+                    slab.name()
+                ));
+
+                let area = signed_area(&plate.outline).abs()
+                    - plate
+                        .holes
+                        .iter()
+                        .map(|h| signed_area(h).abs())
+                        .sum::<f64>();
+                for k in 0..3 {
+                    let force = pressure[k] * area / node_id_list.len() as f64;
+                    for &id in &node_id_list {
+                        *node_force[k].entry(id).or_insert(0.0) += force;
+                    }
+                }
+            }
+        }
+    }
+
+    let step = 1.0 / STEP_COUNT as f64;
+    let load_case_list = ["SW", "DL", "LL"]
+        .iter()
+        .enumerate()
+        .map(|(k, name)| {
+            let mut member_load_list = Vec::new();
+            if k == 0 {
+                for (&id, &w) in &self_weight {
+                    member_load_list.push(MemberLoad::uniform(id, [0.0, 0.0, -w]));
+                }
+            }
+            for (&(id, i), &force) in &member_force[k] {
+                member_load_list.push(MemberLoad {
+                    id,
+                    w: [0.0, 0.0, -force / (member_length[&id] * step)],
+                    start: i as f64 * step,
+                    end: (i + 1) as f64 * step,
+                });
+            }
+
+            LoadCase {
+                name: name.to_string(),
+                nodal_load_list: node_force[k]
+                    .iter()
+                    .map(|(&id, &force)| NodalLoad {
+                        id,
+                        force: [0.0, 0.0, -force, 0.0, 0.0, 0.0],
+                    })
+                    .collect(),
+                member_load_list,
+            }
+        })
+        .collect();
+
+    GeneratedLoads {
+        load_case_list,
+        warning_list,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::frame::{FrameElement, FrameNode, FrameSection};
+
+    fn sample() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn net_area(st_bridge: &StBridge, slab: &StbMember) -> f64 {
+        let plate = plate_outline(st_bridge, slab).unwrap();
+        signed_area(&plate.outline).abs()
+            - plate
+                .holes
+                .iter()
+                .map(|h| signed_area(h).abs())
+                .sum::<f64>()
+    }
+
+    fn member_length(model: &FrameModel) -> HashMap<u32, f64> {
+        let point: HashMap<u32, Node> = model
+            .node_list
+            .iter()
+            .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+            .collect();
+        let mut length = HashMap::new();
+        for e in &model.element_list {
+            *length.entry(e.member_id).or_insert(0.0) +=
+                Node::distance(point[&e.node_i], point[&e.node_j]);
+        }
+        length
+    }
+
+    // Total downward force of a case, split into member and nodal loads.
+    fn total(model: &FrameModel, load_case: &LoadCase) -> (f64, f64) {
+        let length = member_length(model);
+        let member = load_case
+            .member_load_list
+            .iter()
+            .map(|l| -l.w[2] * (l.end - l.start) * length[&l.id])
+            .sum();
+        let nodal = load_case.nodal_load_list.iter().map(|l| -l.force[2]).sum();
+        (member, nodal)
+    }
+
+    fn case<'a>(loads: &'a GeneratedLoads, name: &str) -> &'a LoadCase {
+        loads
+            .load_case_list
+            .iter()
+            .find(|l| l.name == name)
+            .unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn totals_are_slab_area_times_pressure_plus_self_weight() {
+        let st_bridge = sample();
+        let database = MaterialDatabase::new();
+        let model = FrameModel::new(&st_bridge, &database);
+        let settings = LoadSettings::new();
+        let loads = generate_load_cases(&st_bridge, &model, &database, &settings);
+        assert!(loads.warning_list.is_empty(), "{:?}", loads.warning_list);
+
+        let slab_list: Vec<&StbMember> =
+            st_bridge.stb_model.stb_members.stb_slabs.values().collect();
+        assert!(!slab_list.is_empty());
+        let area: f64 = slab_list.iter().map(|s| net_area(&st_bridge, s)).sum();
+
+        let slab_weight: f64 = slab_list
+            .iter()
+            .map(|&slab| {
+                let plate = plate_outline(&st_bridge, slab).unwrap();
+                let section = st_bridge.stb_model.stb_sections.get(slab.id_section());
+                let concrete = section
+                    .and_then(|s| database.get(&concrete_grade(&st_bridge, slab, s)))
+                    .unwrap_or_else(|| database.get("FC24").unwrap());
+                net_area(&st_bridge, slab)
+                    * (plate.top - plate.bottom)
+                    * unit_weight(concrete.density, &concrete.kind)
+            })
+            .sum();
+        let length = member_length(&model);
+        let member_weight: f64 = model
+            .element_list
+            .iter()
+            .map(|e| e.member_id)
+            .collect::<std::collections::BTreeSet<u32>>()
+            .into_iter()
+            .map(|id| {
+                let e = model
+                    .element_list
+                    .iter()
+                    .find(|e| e.member_id == id)
+                    .unwrap();
+                e.section.area * unit_weight(e.material.density, &e.material.kind) * length[&id]
+            })
+            .sum();
+
+        // Every slab is framed, so nothing goes to the nodes.
+        let (sw, sw_nodal) = total(&model, case(&loads, "SW"));
+        let (dl, dl_nodal) = total(&model, case(&loads, "DL"));
+        let (ll, ll_nodal) = total(&model, case(&loads, "LL"));
+        assert_eq!((sw_nodal, dl_nodal, ll_nodal), (0.0, 0.0, 0.0));
+
+        assert_close(sw, member_weight + slab_weight);
+        assert_close(dl, area * settings.default.dead * 1e-3);
+        assert_close(ll, area * settings.default.live * 1e-3);
+    }
+
+    // A square slab on four members, one along each edge, each running from the corner before.
+    fn square_frame(side: f64) -> FrameModel {
+        let corner_list = [(0.0, 0.0), (side, 0.0), (side, side), (0.0, side)];
+        let node_list = corner_list
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| FrameNode {
+                id: i as u32 + 1,
+                x,
+                y,
+                z: 0.0,
+            })
+            .collect();
+        let element_list = (0..4)
+            .map(|i| FrameElement {
+                member_id: i + 1,
+                name: "G1".to_string(),
+                node_i: i + 1,
+                node_j: (i + 1) % 4 + 1,
+                y_axis: Node::new(0.0, 0.0, 1.0),
+                z_axis: Node::new(0.0, 0.0, 1.0),
+                section: FrameSection {
+                    name: "G1".to_string(),
+                    area: 1.0,
+                    iy: 1.0,
+                    iz: 1.0,
+                    j: 1.0,
+                },
+                material: MaterialDatabase::new().get("SN400B").unwrap(),
+                release_i: false,
+                release_j: false,
+            })
+            .collect();
+
+        FrameModel {
+            node_list,
+            element_list,
+            support_list: Vec::new(),
+            merged_node_map: BTreeMap::new(),
+            warning_list: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn square_slab_loads_its_edges_in_triangles() {
+        let side = 6000.0;
+        let model = square_frame(side);
+        let node_map: HashMap<u32, Node> = model
+            .node_list
+            .iter()
+            .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+            .collect();
+        let support_member_list: HashSet<u32> = (1..=4).collect();
+        let outline = vec![(0.0, 0.0), (side, 0.0), (side, side), (0.0, side)];
+        let corners: Vec<Node> = outline.iter().map(|&(x, y)| Node::new(x, y, 0.0)).collect();
+
+        let share = tributary_area(
+            &model,
+            &node_map,
+            &support_member_list,
+            &outline,
+            &[],
+            &corners,
+        )
+        .unwrap();
+
+        // Each member carries a triangle of a quarter of the slab. Its steps hold 1, 3, 5, 7 and
+        // 9 fiftieths of that from either end towards the middle.
+        for member_id in 1..=4 {
+            let step_list: Vec<f64> = (0..STEP_COUNT)
+                .map(|i| share.get(&(member_id, i)).cloned().unwrap_or(0.0))
+                .collect();
+            let quarter = side * side / 4.0;
+            let total: f64 = step_list.iter().sum();
+            assert!((total - quarter).abs() < quarter * 0.01, "{:?}", step_list);
+            for (i, &area) in step_list.iter().enumerate() {
+                let expected = [1.0, 3.0, 5.0, 7.0, 9.0, 9.0, 7.0, 5.0, 3.0, 1.0][i] / 50.0;
+                assert!((area / quarter - expected).abs() < 0.01, "{:?}", step_list);
+            }
+        }
+    }
+
+    // The slab corners lie 100 below the girder ends, as for a slab with its own nodes set down
+    // by its level.
+    #[test]
+    fn slab_edges_are_matched_in_plan() {
+        let side = 6000.0;
+        let model = square_frame(side);
+        let node_map: HashMap<u32, Node> = model
+            .node_list
+            .iter()
+            .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+            .collect();
+        let outline = vec![(0.0, 0.0), (side, 0.0), (side, side), (0.0, side)];
+        let corners: Vec<Node> = outline
+            .iter()
+            .map(|&(x, y)| Node::new(x, y, -100.0))
+            .collect();
+
+        let support_member_list: HashSet<u32> = (1..=4).collect();
+        let share = tributary_area(
+            &model,
+            &node_map,
+            &support_member_list,
+            &outline,
+            &[],
+            &corners,
+        )
+        .unwrap();
+        let total: f64 = share.values().sum();
+        assert!((total - side * side).abs() < side * side * 1e-9);
+        let member_id_list: HashSet<u32> = share.keys().map(|&(id, _)| id).collect();
+        assert_eq!(member_id_list, support_member_list);
+
+        // Members of other stories are left out even where they line up with the edges.
+        assert!(
+            tributary_area(&model, &node_map, &HashSet::new(), &outline, &[], &corners).is_none()
+        );
+    }
+
+    #[test]
+    fn unframed_slabs_load_their_nodes() {
+        let mut st_bridge = sample();
+        st_bridge.stb_model.stb_members.stb_girders.clear();
+        st_bridge.stb_model.stb_members.stb_beams.clear();
+        let database = MaterialDatabase::new();
+        let model = FrameModel::new(&st_bridge, &database);
+        let settings = LoadSettings::new();
+        let loads = generate_load_cases(&st_bridge, &model, &database, &settings);
+
+        // With the girders and beams gone each slab falls back to those of its nodes that are
+        // still in the frame.
+        let mut area = 0.0;
+        let mut applied_count = 0;
+        for slab in st_bridge.stb_model.stb_members.stb_slabs.values() {
+            let framed = slab
+                .node_id_list()
+                .into_iter()
+                .any(|id| model.node_list.iter().any(|n| n.id == model.node_id(id)));
+            if framed {
+                area += net_area(&st_bridge, slab);
+                applied_count += 1;
+            }
+        }
+        assert!(area > 0.0);
+        let warning_count = loads
+            .warning_list
+            .iter()
+            .filter(|w| w.ends_with("no supporting girder or beam, load applied to its nodes"))
+            .count();
+        assert_eq!(warning_count, applied_count);
+
+        let (dl, dl_nodal) = total(&model, case(&loads, "DL"));
+        assert_eq!(dl, 0.0);
+        assert_close(dl_nodal, area * settings.default.dead * 1e-3);
+
+        for load in &case(&loads, "LL").nodal_load_list {
+            assert!(model.node_list.iter().any(|n| n.id == load.id));
+        }
+    }
+
+    #[test]
+    fn floor_load_by_slab_kind_then_story() {
+        let mut settings = LoadSettings::new();
+        settings.story_map.insert(
+            "2F".to_string(),
+            FloorLoad {
+                dead: 2.0,
+                live: 2.9,
+            },
+        );
+        settings.slab_kind_map.insert(
+            "CANTI".to_string(),
+            FloorLoad {
+                dead: 3.0,
+                live: 1.0,
+            },
+        );
+
+        assert_eq!(settings.floor_load(Some("2F"), "NORMAL").dead, 2.0);
+        assert_eq!(settings.floor_load(Some("2F"), "CANTI").dead, 3.0);
+        assert_eq!(settings.floor_load(Some("RF"), "NORMAL").dead, 1.0);
+        assert_eq!(settings.floor_load(None, "NORMAL").live, 1.8);
+    }
+}
@@ -0,0 +1,357 @@
+use app::diff::{Change, ModelDiff};
+use app::drawing::DEFAULT_SCALE;
+use app::export::MeshExportOptions;
+use app::validation::{Issue, Severity, NODE_TOLERANCE};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::exit;
+
+// Exit codes: 0 on success, 1 when a check fails (validation errors or differences found) and 2
+// for usage, read and write errors.
+const EXIT_FAILED: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
+const USAGE: &str = "usage: stb <command> [options]
+
+commands:
+  info <file> [--json]                        version, project and element counts
+  validate <file> [--json]                    check the model, exit 1 on errors
+  convert <file> <output> [--scale <s>] [--story <name>] [--version <v>]
+                                              export by extension: .glb .ifc .obj .stl .tcl .py .json (SAF),
+                                              or ST-Bridge 1.x to .stb .xml, in the file's version by default
+  takeoff <file> [--steel | --concrete] [--json]
+                                              steel and concrete quantities, CSV by default
+  diff <old> <new> [--json] [--tolerance <mm>]
+                                              model changes, exit 1 when there are any
+  render-plan <file> <output> [--story <name>] [--scale <s>]
+                                              framing plans as SVG, or PDF for a .pdf output
+
+exit codes: 0 success, 1 check failed, 2 error";
+
+// Options that take a value; every other option is a flag.
+const VALUE_OPTION_LIST: [&str; 4] = ["--scale", "--story", "--tolerance", "--version"];
+
+struct Args {
+    positional_list: Vec<String>,
+    flag_list: Vec<String>,
+    option_map: BTreeMap<String, String>,
+}
+
+impl Args {
+    fn parse(arg_list: &[String]) -> Result<Args, String> {
+        let mut args = Args {
+            positional_list: Vec::new(),
+            flag_list: Vec::new(),
+            option_map: BTreeMap::new(),
+        };
+        let mut iter = arg_list.iter();
+        while let Some(arg) = iter.next() {
+            if VALUE_OPTION_LIST.contains(&arg.as_str()) {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} needs a value", arg))?;
+                args.option_map.insert(arg.clone(), value.clone());
+            } else if arg.starts_with("--") {
+                args.flag_list.push(arg.clone());
+            } else {
+                args.positional_list.push(arg.clone());
+            }
+        }
+        Ok(args)
+    }
+
+    // Rejects flags and options the command does not know, and a wrong number of files.
+    fn check(&self, positional_count: usize, known_list: &[&str]) -> Result<(), String> {
+        if self.positional_list.len() != positional_count {
+            return Err(format!(
+                "expected {} file argument(s), got {}",
+                positional_count,
+                self.positional_list.len()
+            ));
+        }
+        for name in self.flag_list.iter().chain(self.option_map.keys()) {
+            if !known_list.contains(&name.as_str()) {
+                return Err(format!("unknown option {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flag_list.iter().any(|f| f == name)
+    }
+
+    fn number(&self, name: &str, default: f64) -> Result<f64, String> {
+        match self.option_map.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("{} expects a number, got {}", name, value)),
+            None => Ok(default),
+        }
+    }
+
+    // A drawing or export scale, which has to be a positive number.
+    fn scale(&self, default: f64) -> Result<f64, String> {
+        let scale = self.number("--scale", default)?;
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(format!("--scale must be positive, got {}", scale));
+        }
+        Ok(scale)
+    }
+}
+
+fn main() {
+    let arg_list: Vec<String> = std::env::args().skip(1).collect();
+    if arg_list.is_empty() || arg_list[0] == "--help" || arg_list[0] == "-h" {
+        println!("{}", USAGE);
+        exit(if arg_list.is_empty() { EXIT_ERROR } else { 0 });
+    }
+
+    let result = Args::parse(&arg_list[1..]).and_then(|args| match arg_list[0].as_str() {
+        "info" => info(&args),
+        "validate" => validate(&args),
+        "convert" => convert(&args),
+        "takeoff" => takeoff(&args),
+        "diff" => diff(&args),
+        "render-plan" => render_plan(&args),
+        command => Err(format!("unknown command {}\n\n{}", command, USAGE)),
+    });
+
+    match result {
+        Ok(code) => exit(code),
+        Err(message) => {
+            eprintln!("stb: {}", message);
+            exit(EXIT_ERROR);
+        }
+    }
+}
+
+fn print_json(value: &serde_json::Value) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap());
+}
+
+fn info(args: &Args) -> Result<i32, String> {
+    args.check(1, &["--json"])?;
+    let st_bridge = app::read_st_bridge(&args.positional_list[0])?;
+    let stb_model = &st_bridge.stb_model;
+
+    let mut member_count: BTreeMap<&str, usize> = BTreeMap::new();
+    for m in stb_model.stb_members.iter() {
+        *member_count.entry(m.member_kind()).or_default() += 1;
+    }
+    let stb_sections = &stb_model.stb_sections;
+    let section_count = [
+        &stb_sections.column_rc_map,
+        &stb_sections.column_s_map,
+        &stb_sections.beam_rc_map,
+        &stb_sections.beam_s_map,
+        &stb_sections.slab_rc_map,
+        &stb_sections.brace_s_map,
+        &stb_sections.wall_rc_map,
+    ]
+    .iter()
+    .map(|map| map.len())
+    .sum::<usize>();
+
+    let value = json!({
+        "version": st_bridge.version,
+        "project_name": st_bridge.stb_common.project_name,
+        "node_count": stb_model.stb_nodes.map.len(),
+        "x_axis_count": stb_model.stb_axes.stb_x_axis_list.len(),
+        "y_axis_count": stb_model.stb_axes.stb_y_axis_list.len(),
+        "story_count": stb_model.stb_stories.stb_story_list.len(),
+        "member_count": member_count,
+        "section_count": section_count,
+        "steel_shape_count": stb_sections.stb_sec_steel.children_map.len(),
+    });
+
+    if args.flag("--json") {
+        print_json(&value);
+    } else {
+        println!("version: {}", st_bridge.version);
+        println!("project: {}", st_bridge.stb_common.project_name);
+        println!("nodes: {}", stb_model.stb_nodes.map.len());
+        println!(
+            "axes: {} X, {} Y",
+            stb_model.stb_axes.stb_x_axis_list.len(),
+            stb_model.stb_axes.stb_y_axis_list.len()
+        );
+        println!("stories: {}", stb_model.stb_stories.stb_story_list.len());
+        for (kind, count) in &member_count {
+            println!("{}: {}", kind.to_lowercase(), count);
+        }
+        println!("sections: {}", section_count);
+        println!(
+            "steel shapes: {}",
+            stb_sections.stb_sec_steel.children_map.len()
+        );
+    }
+    Ok(0)
+}
+
+fn validate(args: &Args) -> Result<i32, String> {
+    args.check(1, &["--json"])?;
+    let st_bridge = app::read_st_bridge(&args.positional_list[0])?;
+    let issue_list: Vec<Issue> = app::validation::validate(&st_bridge);
+
+    if args.flag("--json") {
+        print_json(&serde_json::to_value(&issue_list).unwrap());
+    } else {
+        for issue in &issue_list {
+            println!("{:?} {}: {}", issue.severity, issue.rule, issue.message);
+        }
+        let count =
+            |severity: Severity| issue_list.iter().filter(|i| i.severity == severity).count();
+        println!(
+            "{} error(s), {} warning(s), {} info",
+            count(Severity::Error),
+            count(Severity::Warning),
+            count(Severity::Info)
+        );
+    }
+
+    let failed = issue_list.iter().any(|i| i.severity == Severity::Error);
+    Ok(if failed { EXIT_FAILED } else { 0 })
+}
+
+// ST-Bridge output keeps only what the reader understands, so unsupported elements such as
+// footings are dropped on the way.
+fn convert(args: &Args) -> Result<i32, String> {
+    args.check(2, &["--scale", "--story", "--version"])?;
+    let output = &args.positional_list[1];
+    let extension = Path::new(output)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let st_bridge = app::read_st_bridge(&args.positional_list[0])?;
+    let version = match args.option_map.get("--version") {
+        Some(version) => version.clone(),
+        None => st_bridge.version.clone(),
+    };
+    app::st_bridge::check_version(&version)?;
+    let options = MeshExportOptions {
+        scale: args.scale(1.0)?,
+        story: args.option_map.get("--story").cloned(),
+    };
+
+    let written = match extension.as_str() {
+        "glb" => app::export::gltf::write_glb(&st_bridge, output),
+        "ifc" => app::export::ifc::write_ifc(&st_bridge, output),
+        "obj" => app::export::obj::write_obj(&st_bridge, output, &options),
+        "stl" => app::export::stl::write_stl(&st_bridge, output, &options),
+        "tcl" | "py" => app::export::opensees::write_opensees(&st_bridge, output),
+        "json" => app::export::saf::write_saf(&st_bridge, output),
+        "stb" | "xml" => app::export::stb::write_st_bridge(&st_bridge, output, &version),
+        _ => return Err(format!("unknown output format .{}", extension)),
+    };
+    written.map_err(|e| format!("{}: {}", output, e))?;
+    Ok(0)
+}
+
+fn takeoff(args: &Args) -> Result<i32, String> {
+    args.check(1, &["--steel", "--concrete", "--json"])?;
+    let st_bridge = app::read_st_bridge(&args.positional_list[0])?;
+
+    let (steel, concrete) = match (args.flag("--steel"), args.flag("--concrete")) {
+        (true, false) => (true, false),
+        (false, true) => (false, true),
+        _ => (true, true),
+    };
+    let steel = if steel {
+        Some(app::takeoff::steel::steel_takeoff(&st_bridge))
+    } else {
+        None
+    };
+    let concrete = if concrete {
+        Some(app::takeoff::concrete::concrete_takeoff(&st_bridge))
+    } else {
+        None
+    };
+
+    if args.flag("--json") {
+        print_json(&json!({ "steel": steel, "concrete": concrete }));
+    } else {
+        if let Some(t) = &steel {
+            print!("{}", t.to_csv());
+        }
+        if steel.is_some() && concrete.is_some() {
+            println!();
+        }
+        if let Some(t) = &concrete {
+            print!("{}", t.to_csv());
+            for warning in &t.warning_list {
+                eprintln!("stb: warning: {}", warning);
+            }
+        }
+    }
+    Ok(0)
+}
+
+fn diff(args: &Args) -> Result<i32, String> {
+    args.check(2, &["--json", "--tolerance"])?;
+    let old = app::read_st_bridge(&args.positional_list[0])?;
+    let new = app::read_st_bridge(&args.positional_list[1])?;
+    let tolerance = args.number("--tolerance", NODE_TOLERANCE)?;
+    let model_diff: ModelDiff = app::diff::diff_with_tolerance(&old, &new, tolerance)?;
+
+    if args.flag("--json") {
+        println!("{}", model_diff.to_json());
+    } else {
+        let change_list = model_diff
+            .node_list
+            .iter()
+            .chain(&model_diff.member_list)
+            .chain(&model_diff.section_list);
+        for change in change_list {
+            print_change(change);
+        }
+    }
+    Ok(if model_diff.is_empty() {
+        0
+    } else {
+        EXIT_FAILED
+    })
+}
+
+fn print_change(change: &Change) {
+    let id = |id: Option<u32>| id.map(|i| i.to_string()).unwrap_or_default();
+    let id = match (change.old_id, change.new_id) {
+        (Some(a), Some(b)) if a != b => format!("{} -> {}", a, b),
+        (a, b) => id(b.or(a)),
+    };
+    let line = format!(
+        "{:?} {} {} {}",
+        change.kind, change.element, id, change.name
+    );
+    println!("{}", line.trim_end());
+    for field in &change.field_list {
+        println!("    {}: {} -> {}", field.field, field.old, field.new);
+    }
+}
+
+// A single story's plan with --story, every story otherwise.
+fn render_plan(args: &Args) -> Result<i32, String> {
+    args.check(2, &["--story", "--scale"])?;
+    let st_bridge = app::read_st_bridge(&args.positional_list[0])?;
+    let output = &args.positional_list[1];
+    let scale = args.scale(DEFAULT_SCALE)?;
+
+    let written = match args.option_map.get("--story") {
+        Some(name) => {
+            let story = st_bridge
+                .stb_model
+                .stb_stories
+                .stb_story_list
+                .iter()
+                .find(|s| &s.name == name)
+                .ok_or_else(|| format!("no story named {}", name))?;
+            let drawing = app::drawing::plan::framing_plan(&st_bridge, story, scale);
+            app::drawing::write_drawing_list(&[drawing], output)
+        }
+        None => app::drawing::plan::write_framing_plans(&st_bridge, output, scale),
+    };
+    written.map_err(|e| format!("{}: {}", output, e))?;
+    Ok(0)
+}
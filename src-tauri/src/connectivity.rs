@@ -0,0 +1,316 @@
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+// Nodes within this height above the lowest story count as foundation level (mm).
+const FOUNDATION_TOLERANCE: f64 = 1.0;
+
+// Linear members join their two end nodes and slabs and walls join the corners along their
+// outline. A node placed on a girder or beam through id_member joins that member as well, since
+// small beams usually frame into the span rather than the ends. Openings carry no nodes and are
+// left out.
+pub struct ConnectivityGraph {
+    member_map: BTreeMap<u32, Vec<u32>>,
+    neighbour_map: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DisconnectedPart {
+    pub node_id_list: Vec<u32>,
+    pub member_id_list: Vec<u32>,
+}
+
+impl ConnectivityGraph {
+    pub fn new(st_bridge: &StBridge) -> ConnectivityGraph {
+        let mut member_map: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        let mut neighbour_map: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+
+        let mut link = |a: u32, b: u32| {
+            if a != b {
+                neighbour_map.entry(a).or_default().insert(b);
+                neighbour_map.entry(b).or_default().insert(a);
+            }
+        };
+
+        for stb_member in st_bridge.stb_model.stb_members.iter() {
+            let node_id_list = stb_member.node_id_list();
+
+            match stb_member {
+                StbMember::StbSlab { .. } | StbMember::StbWall { .. } => {
+                    for i in 0..node_id_list.len() {
+                        link(node_id_list[i], node_id_list[(i + 1) % node_id_list.len()]);
+                    }
+                }
+                StbMember::StbOpen { .. } => continue,
+                _ => link(node_id_list[0], node_id_list[1]),
+            }
+
+            for id in node_id_list {
+                let list = member_map.entry(id).or_default();
+                if !list.contains(&stb_member.id()) {
+                    list.push(stb_member.id());
+                }
+            }
+        }
+
+        let stb_members = &st_bridge.stb_model.stb_members;
+        for (&id, node) in &st_bridge.stb_model.stb_nodes.map {
+            let host = match node.id_member.and_then(|m| stb_members.get(m)) {
+                Some(m) => m,
+                None => continue,
+            };
+            match (host.node_i(), host.node_j()) {
+                (Some(i), Some(j)) => {
+                    link(id, i);
+                    link(id, j);
+                }
+                _ => continue,
+            }
+
+            let list = member_map.entry(id).or_default();
+            if !list.contains(&host.id()) {
+                list.push(host.id());
+            }
+        }
+
+        for list in member_map.values_mut() {
+            list.sort_unstable();
+        }
+
+        ConnectivityGraph {
+            member_map,
+            neighbour_map,
+        }
+    }
+
+    pub fn members_at(&self, node: u32) -> Vec<u32> {
+        self.member_map.get(&node).cloned().unwrap_or_default()
+    }
+
+    pub fn neighbours(&self, node: u32) -> Vec<u32> {
+        match self.neighbour_map.get(&node) {
+            Some(set) => set.iter().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Every node used by a member, grouped into connected components, largest first.
+    pub fn components(&self) -> Vec<Vec<u32>> {
+        let mut visited: BTreeSet<u32> = BTreeSet::new();
+        let mut component_list = Vec::new();
+
+        for &start in self.member_map.keys() {
+            if visited.contains(&start) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut queue = VecDeque::new();
+            visited.insert(start);
+            queue.push_back(start);
+
+            while let Some(node) = queue.pop_front() {
+                component.push(node);
+                for &next in self.neighbour_map.get(&node).into_iter().flatten() {
+                    if visited.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            component_list.push(component);
+        }
+
+        component_list.sort_by_key(|c| std::cmp::Reverse(c.len()));
+        component_list
+    }
+
+    // Fewest hops from one node to another, both ends included.
+    pub fn path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        if !self.member_map.contains_key(&from) || !self.member_map.contains_key(&to) {
+            return None;
+        }
+
+        let mut previous: BTreeMap<u32, u32> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        previous.insert(from, from);
+        queue.push_back(from);
+
+        while let Some(node) = queue.pop_front() {
+            if node == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while current != from {
+                    current = previous[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for &next in self.neighbour_map.get(&node).into_iter().flatten() {
+                if let std::collections::btree_map::Entry::Vacant(e) = previous.entry(next) {
+                    e.insert(node);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// Nodes at the lowest story, or at the lowest node when the model has no stories.
+pub fn foundation_node_list(st_bridge: &StBridge) -> Vec<u32> {
+    let stb_model = &st_bridge.stb_model;
+
+    let level = stb_model
+        .stb_stories
+        .stb_story_list
+        .iter()
+        .map(|s| s.height)
+        .reduce(f64::min)
+        .or_else(|| {
+            stb_model
+                .stb_nodes
+                .map
+                .values()
+                .map(|n| n.z)
+                .reduce(f64::min)
+        });
+
+    let level = match level {
+        Some(l) => l,
+        None => return Vec::new(),
+    };
+
+    let mut node_id_list: Vec<u32> = stb_model
+        .stb_nodes
+        .map
+        .iter()
+        .filter(|(_, n)| n.z <= level + FOUNDATION_TOLERANCE)
+        .map(|(&id, _)| id)
+        .collect();
+    node_id_list.sort_unstable();
+    node_id_list
+}
+
+// Components that reach no foundation-level node, such as beams left floating by a missing
+// column or by coincident nodes that were never merged.
+pub fn disconnected_parts(st_bridge: &StBridge) -> Vec<DisconnectedPart> {
+    let graph = ConnectivityGraph::new(st_bridge);
+    let foundation: BTreeSet<u32> = foundation_node_list(st_bridge).into_iter().collect();
+
+    graph
+        .components()
+        .into_iter()
+        .filter(|c| !c.iter().any(|id| foundation.contains(id)))
+        .map(|node_id_list| {
+            let member_id_list: BTreeSet<u32> = node_id_list
+                .iter()
+                .flat_map(|&id| graph.members_at(id))
+                .collect();
+            DisconnectedPart {
+                node_id_list,
+                member_id_list: member_id_list.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn sample_is_connected() {
+        let st_bridge = sample();
+        let graph = ConnectivityGraph::new(&st_bridge);
+
+        // Column 1C1 runs from node 9 to node 44.
+        assert!(graph.members_at(9).contains(&1));
+        assert!(graph.neighbours(9).contains(&44));
+        assert_eq!(graph.path(9, 44), Some(vec![9, 44]));
+        assert_eq!(graph.path(9, 9), Some(vec![9]));
+        assert_eq!(graph.path(9, 100_000), None);
+
+        assert_eq!(graph.components().len(), 1);
+        assert!(disconnected_parts(&st_bridge).is_empty());
+    }
+
+    #[test]
+    fn nodes_on_members_join_their_host() {
+        let st_bridge = sample();
+        let graph = ConnectivityGraph::new(&st_bridge);
+        let stb_members = &st_bridge.stb_model.stb_members;
+
+        let mut checked = 0;
+        for (&id, node) in &st_bridge.stb_model.stb_nodes.map {
+            let host = match node.id_member.and_then(|m| stb_members.get(m)) {
+                Some(m) if m.node_i().is_some() => m,
+                _ => continue,
+            };
+            assert!(graph.members_at(id).contains(&host.id()));
+            assert!(graph.neighbours(id).contains(&host.node_i().unwrap()));
+            assert!(graph.neighbours(id).contains(&host.node_j().unwrap()));
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn floating_beam() {
+        let mut st_bridge = sample();
+
+        // Move a beam that hosts no nodes up and away from the rest of the model.
+        let hosts: BTreeSet<u32> = st_bridge
+            .stb_model
+            .stb_nodes
+            .map
+            .values()
+            .filter_map(|n| n.id_member)
+            .collect();
+        let mut beam_id_list: Vec<u32> = st_bridge
+            .stb_model
+            .stb_members
+            .stb_beams
+            .keys()
+            .cloned()
+            .collect();
+        beam_id_list.sort_unstable();
+        let beam_id = *beam_id_list.iter().find(|id| !hosts.contains(id)).unwrap();
+
+        let mut node = st_bridge.stb_model.stb_nodes.get(9).unwrap();
+        node.z = 100_000.0;
+        node.id_member = None;
+        st_bridge.stb_model.stb_nodes.insert(9000, node.clone());
+        node.x += 1000.0;
+        st_bridge.stb_model.stb_nodes.insert(9001, node);
+        if let Some(StbMember::StbBeam {
+            id_node_start,
+            id_node_end,
+            ..
+        }) = st_bridge.stb_model.stb_members.stb_beams.get_mut(&beam_id)
+        {
+            *id_node_start = 9000;
+            *id_node_end = 9001;
+        }
+
+        let part_list = disconnected_parts(&st_bridge);
+
+        assert_eq!(part_list.len(), 1);
+        assert_eq!(part_list[0].node_id_list, [9000, 9001]);
+        assert_eq!(part_list[0].member_id_list, [beam_id]);
+    }
+}
@@ -0,0 +1,696 @@
+use crate::node_merge::check_tolerance;
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::stb_model::stb_nodes::StbNode;
+use crate::st_bridge::stb_model::stb_sections::StbSection;
+use crate::st_bridge::StBridge;
+use crate::validation::NODE_TOLERANCE;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+// Nested fields are flattened to paths such as "stb_sec_figure.stb_sec_rect.width_x".
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+// The element is "Node", the member kind or the section type.
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub element: String,
+    pub name: String,
+    pub old_id: Option<u32>,
+    pub new_id: Option<u32>,
+    pub field_list: Vec<FieldChange>,
+}
+
+impl Change {
+    fn new(kind: ChangeKind, element: &str, name: String) -> Change {
+        Change {
+            kind,
+            element: element.to_string(),
+            name,
+            old_id: None,
+            new_id: None,
+            field_list: Vec::new(),
+        }
+    }
+
+    fn sort_id(&self) -> u32 {
+        self.new_id.or(self.old_id).unwrap_or(0)
+    }
+}
+
+// The id maps pair every matched old id with its new id, whether or not anything changed.
+#[derive(Debug, Serialize)]
+pub struct ModelDiff {
+    pub node_list: Vec<Change>,
+    pub member_list: Vec<Change>,
+    pub section_list: Vec<Change>,
+    pub node_id_map: BTreeMap<u32, u32>,
+    pub member_id_map: BTreeMap<u32, u32>,
+    pub section_id_map: BTreeMap<u32, u32>,
+}
+
+impl ModelDiff {
+    pub fn is_empty(&self) -> bool {
+        self.node_list.is_empty() && self.member_list.is_empty() && self.section_list.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+pub fn diff(old: &StBridge, new: &StBridge) -> ModelDiff {
+    diff_with_tolerance(old, new, NODE_TOLERANCE).unwrap()
+}
+
+// Sections are matched first so that members can compare their section ids through the map.
+// Fails for a tolerance that is not a positive number.
+pub fn diff_with_tolerance(
+    old: &StBridge,
+    new: &StBridge,
+    tolerance: f64,
+) -> Result<ModelDiff, String> {
+    check_tolerance(tolerance)?;
+    let node_id_map = match_nodes(old, new, tolerance);
+    let section_id_map = match_sections(old, new);
+    let member_id_map = match_members(old, new, &node_id_map);
+
+    let id_map = IdMap {
+        node: &node_id_map,
+        member: &member_id_map,
+        section: &section_id_map,
+    };
+
+    let node_list = node_changes(old, new, &node_id_map, tolerance);
+
+    let old_member_map: BTreeMap<u32, &StbMember> = member_map(old);
+    let new_member_map: BTreeMap<u32, &StbMember> = member_map(new);
+    let member_list = changes(
+        &old_member_map,
+        &new_member_map,
+        &member_id_map,
+        |m| (m.member_kind().to_string(), m.name(), flatten_member(m)),
+        &id_map,
+    );
+
+    let old_section_map = section_map(old);
+    let new_section_map = section_map(new);
+    let section_list = changes(
+        &old_section_map,
+        &new_section_map,
+        &section_id_map,
+        |s| (section_type(s), s.name(), flatten_section(s)),
+        &id_map,
+    );
+
+    Ok(ModelDiff {
+        node_list,
+        member_list,
+        section_list,
+        node_id_map,
+        member_id_map,
+        section_id_map,
+    })
+}
+
+struct IdMap<'a> {
+    node: &'a BTreeMap<u32, u32>,
+    member: &'a BTreeMap<u32, u32>,
+    section: &'a BTreeMap<u32, u32>,
+}
+
+impl IdMap<'_> {
+    // Rewrites an old id field into the new numbering, so that renumbering alone is no change.
+    fn translate(&self, field: &str, value: &Value) -> Value {
+        let map = if field.starts_with("id_node_") || field.starts_with("stb_node_id_list.") {
+            self.node
+        } else if field == "id_section" {
+            self.section
+        } else if field == "id_member" {
+            self.member
+        } else {
+            return value.clone();
+        };
+        match value.as_u64().and_then(|id| map.get(&(id as u32))) {
+            Some(&id) => Value::from(id),
+            None => value.clone(),
+        }
+    }
+}
+
+// Matches nodes with the same id at the same place, then nodes at the same place under another
+// id, and finally nodes that kept their id but moved.
+fn match_nodes(old: &StBridge, new: &StBridge, tolerance: f64) -> BTreeMap<u32, u32> {
+    let old_map = &old.stb_model.stb_nodes.map;
+    let new_map = &new.stb_model.stb_nodes.map;
+
+    let mut old_id_list: Vec<u32> = old_map.keys().cloned().collect();
+    old_id_list.sort_unstable();
+
+    let mut id_map = BTreeMap::new();
+    let mut taken: BTreeSet<u32> = BTreeSet::new();
+
+    for &id in &old_id_list {
+        if let Some(node) = new_map.get(&id) {
+            if distance(&old_map[&id], node) <= tolerance {
+                id_map.insert(id, id);
+                taken.insert(id);
+            }
+        }
+    }
+
+    let cell = |v: f64| (v / tolerance).floor() as i64;
+    let mut grid: HashMap<(i64, i64, i64), Vec<u32>> = HashMap::new();
+    for (&id, node) in new_map {
+        if !taken.contains(&id) {
+            grid.entry((cell(node.x), cell(node.y), cell(node.z)))
+                .or_default()
+                .push(id);
+        }
+    }
+
+    for &id in &old_id_list {
+        if id_map.contains_key(&id) {
+            continue;
+        }
+        let node = &old_map[&id];
+        let (cx, cy, cz) = (cell(node.x), cell(node.y), cell(node.z));
+        let mut nearest: Option<(f64, u32)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let key = (
+                        cx.saturating_add(dx),
+                        cy.saturating_add(dy),
+                        cz.saturating_add(dz),
+                    );
+                    for &other in grid.get(&key).into_iter().flatten() {
+                        if taken.contains(&other) {
+                            continue;
+                        }
+                        let d = distance(node, &new_map[&other]);
+                        let closer = match nearest {
+                            Some((n, o)) => (d, other) < (n, o),
+                            None => true,
+                        };
+                        if d <= tolerance && closer {
+                            nearest = Some((d, other));
+                        }
+                    }
+                }
+            }
+        }
+        if let Some((_, other)) = nearest {
+            id_map.insert(id, other);
+            taken.insert(other);
+        }
+    }
+
+    for &id in &old_id_list {
+        if !id_map.contains_key(&id) && new_map.contains_key(&id) && !taken.contains(&id) {
+            id_map.insert(id, id);
+            taken.insert(id);
+        }
+    }
+
+    id_map
+}
+
+// Matches members by id when they also share their end nodes, then by end nodes, and finally by
+// id alone. The kind has to agree in every case.
+fn match_members(
+    old: &StBridge,
+    new: &StBridge,
+    node_id_map: &BTreeMap<u32, u32>,
+) -> BTreeMap<u32, u32> {
+    let old_map = member_map(old);
+    let new_map = member_map(new);
+
+    let old_key = |m: &StbMember| -> Option<(&'static str, Vec<u32>)> {
+        let mut list = m
+            .node_id_list()
+            .iter()
+            .map(|id| node_id_map.get(id).cloned())
+            .collect::<Option<Vec<u32>>>()?;
+        list.sort_unstable();
+        Some((m.member_kind(), list))
+    };
+    let new_key = |m: &StbMember| -> (&'static str, Vec<u32>) {
+        let mut list = m.node_id_list();
+        list.sort_unstable();
+        (m.member_kind(), list)
+    };
+
+    let mut id_map = BTreeMap::new();
+    let mut taken: BTreeSet<u32> = BTreeSet::new();
+
+    for (&id, m) in &old_map {
+        if let Some(n) = new_map.get(&id) {
+            if m.member_kind() == n.member_kind() && old_key(m) == Some(new_key(n)) {
+                id_map.insert(id, id);
+                taken.insert(id);
+            }
+        }
+    }
+
+    // Openings have no nodes, so they can only be matched by id.
+    let mut by_nodes: BTreeMap<(&'static str, Vec<u32>), Vec<u32>> = BTreeMap::new();
+    for (&id, n) in new_map.iter().rev() {
+        if !taken.contains(&id) && !n.node_id_list().is_empty() {
+            by_nodes.entry(new_key(n)).or_default().push(id);
+        }
+    }
+    for (&id, m) in &old_map {
+        if id_map.contains_key(&id) {
+            continue;
+        }
+        if let Some(other) = old_key(m)
+            .and_then(|key| by_nodes.get_mut(&key))
+            .and_then(|list| list.pop())
+        {
+            id_map.insert(id, other);
+            taken.insert(other);
+        }
+    }
+
+    for (&id, m) in &old_map {
+        if id_map.contains_key(&id) || taken.contains(&id) {
+            continue;
+        }
+        if let Some(n) = new_map.get(&id) {
+            if m.member_kind() == n.member_kind() {
+                id_map.insert(id, id);
+                taken.insert(id);
+            }
+        }
+    }
+
+    id_map
+}
+
+// Matches sections by id and name, then by name and floor under another id, and finally by id.
+fn match_sections(old: &StBridge, new: &StBridge) -> BTreeMap<u32, u32> {
+    let old_map = section_map(old);
+    let new_map = section_map(new);
+
+    let key = |s: &StbSection| (section_type(s), s.name(), floor(s));
+
+    let mut id_map = BTreeMap::new();
+    let mut taken: BTreeSet<u32> = BTreeSet::new();
+
+    for (&id, s) in &old_map {
+        if let Some(n) = new_map.get(&id) {
+            if key(s) == key(n) {
+                id_map.insert(id, id);
+                taken.insert(id);
+            }
+        }
+    }
+
+    let mut by_name: BTreeMap<(String, String, String), Vec<u32>> = BTreeMap::new();
+    for (&id, n) in new_map.iter().rev() {
+        if !taken.contains(&id) {
+            by_name.entry(key(n)).or_default().push(id);
+        }
+    }
+    for (&id, s) in &old_map {
+        if id_map.contains_key(&id) {
+            continue;
+        }
+        if let Some(other) = by_name.get_mut(&key(s)).and_then(|list| list.pop()) {
+            id_map.insert(id, other);
+            taken.insert(other);
+        }
+    }
+
+    for (&id, s) in &old_map {
+        if id_map.contains_key(&id) || taken.contains(&id) {
+            continue;
+        }
+        if let Some(n) = new_map.get(&id) {
+            if section_type(s) == section_type(n) {
+                id_map.insert(id, id);
+                taken.insert(id);
+            }
+        }
+    }
+
+    id_map
+}
+
+fn node_changes(
+    old: &StBridge,
+    new: &StBridge,
+    node_id_map: &BTreeMap<u32, u32>,
+    tolerance: f64,
+) -> Vec<Change> {
+    let old_map = &old.stb_model.stb_nodes.map;
+    let new_map = &new.stb_model.stb_nodes.map;
+    let matched: BTreeSet<u32> = node_id_map.values().cloned().collect();
+
+    let mut change_list = Vec::new();
+
+    for (&old_id, &new_id) in node_id_map {
+        let (a, b) = (&old_map[&old_id], &new_map[&new_id]);
+        let mut field_list = Vec::new();
+        if distance(a, b) > tolerance {
+            for (field, u, v) in [("x", a.x, b.x), ("y", a.y, b.y), ("z", a.z, b.z)] {
+                if u != v {
+                    field_list.push(FieldChange {
+                        field: field.to_string(),
+                        old: Value::from(u),
+                        new: Value::from(v),
+                    });
+                }
+            }
+        }
+        let (kind_a, kind_b) = (to_value(&a.kind), to_value(&b.kind));
+        if kind_a != kind_b {
+            field_list.push(FieldChange {
+                field: "kind".to_string(),
+                old: kind_a,
+                new: kind_b,
+            });
+        }
+        if !field_list.is_empty() {
+            let mut change = Change::new(ChangeKind::Modified, "Node", String::new());
+            change.old_id = Some(old_id);
+            change.new_id = Some(new_id);
+            change.field_list = field_list;
+            change_list.push(change);
+        }
+    }
+    for &id in old_map.keys() {
+        if !node_id_map.contains_key(&id) {
+            let mut change = Change::new(ChangeKind::Removed, "Node", String::new());
+            change.old_id = Some(id);
+            change_list.push(change);
+        }
+    }
+    for &id in new_map.keys() {
+        if !matched.contains(&id) {
+            let mut change = Change::new(ChangeKind::Added, "Node", String::new());
+            change.new_id = Some(id);
+            change_list.push(change);
+        }
+    }
+
+    change_list.sort_by_key(|c| (c.sort_id(), c.old_id));
+    change_list
+}
+
+// Compares matched elements field by field, with the old id fields translated to the new numbering.
+fn changes<T>(
+    old_map: &BTreeMap<u32, &T>,
+    new_map: &BTreeMap<u32, &T>,
+    id_map: &BTreeMap<u32, u32>,
+    describe: impl Fn(&T) -> (String, String, BTreeMap<String, Value>),
+    translation: &IdMap,
+) -> Vec<Change> {
+    let matched: BTreeSet<u32> = id_map.values().cloned().collect();
+    let mut change_list = Vec::new();
+
+    for (&old_id, &new_id) in id_map {
+        let (_, _, old_field_map) = describe(old_map[&old_id]);
+        let (element, name, new_field_map) = describe(new_map[&new_id]);
+
+        let field_set: BTreeSet<&String> =
+            old_field_map.keys().chain(new_field_map.keys()).collect();
+        let mut field_list = Vec::new();
+        for field in field_set {
+            let a = old_field_map.get(field).cloned().unwrap_or(Value::Null);
+            let b = new_field_map.get(field).cloned().unwrap_or(Value::Null);
+            if translation.translate(field, &a) != b {
+                field_list.push(FieldChange {
+                    field: field.clone(),
+                    old: a,
+                    new: b,
+                });
+            }
+        }
+        if !field_list.is_empty() {
+            let mut change = Change::new(ChangeKind::Modified, &element, name);
+            change.old_id = Some(old_id);
+            change.new_id = Some(new_id);
+            change.field_list = field_list;
+            change_list.push(change);
+        }
+    }
+    for (&id, &t) in old_map {
+        if !id_map.contains_key(&id) {
+            let (element, name, _) = describe(t);
+            let mut change = Change::new(ChangeKind::Removed, &element, name);
+            change.old_id = Some(id);
+            change_list.push(change);
+        }
+    }
+    for (&id, &t) in new_map {
+        if !matched.contains(&id) {
+            let (element, name, _) = describe(t);
+            let mut change = Change::new(ChangeKind::Added, &element, name);
+            change.new_id = Some(id);
+            change_list.push(change);
+        }
+    }
+
+    change_list.sort_by_key(|c| (c.sort_id(), c.old_id));
+    change_list
+}
+
+fn member_map(st_bridge: &StBridge) -> BTreeMap<u32, &StbMember> {
+    st_bridge
+        .stb_model
+        .stb_members
+        .iter()
+        .map(|m| (m.id(), m))
+        .collect()
+}
+
+fn section_map(st_bridge: &StBridge) -> BTreeMap<u32, &StbSection> {
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    [
+        &stb_sections.column_rc_map,
+        &stb_sections.column_s_map,
+        &stb_sections.beam_rc_map,
+        &stb_sections.beam_s_map,
+        &stb_sections.slab_rc_map,
+        &stb_sections.brace_s_map,
+        &stb_sections.wall_rc_map,
+    ]
+    .iter()
+    .flat_map(|map| map.iter().map(|(&id, s)| (id, s)))
+    .collect()
+}
+
+// The serde tag of an enum, such as "StbSecBeamRC".
+fn variant(value: &Value) -> (String, Value) {
+    match value {
+        Value::Object(map) if map.len() == 1 => {
+            let (tag, inner) = map.iter().next().unwrap();
+            (tag.clone(), inner.clone())
+        }
+        _ => (String::new(), value.clone()),
+    }
+}
+
+fn section_type(stb_section: &StbSection) -> String {
+    variant(&to_value(stb_section)).0
+}
+
+fn floor(stb_section: &StbSection) -> String {
+    let (_, inner) = variant(&to_value(stb_section));
+    inner
+        .get("floor")
+        .and_then(|f| f.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn flatten_member(stb_member: &StbMember) -> BTreeMap<String, Value> {
+    flatten_fields(&variant(&to_value(stb_member)).1)
+}
+
+fn flatten_section(stb_section: &StbSection) -> BTreeMap<String, Value> {
+    flatten_fields(&variant(&to_value(stb_section)).1)
+}
+
+// The element's own id is left out, since the match already pairs old and new ids.
+fn flatten_fields(value: &Value) -> BTreeMap<String, Value> {
+    let mut field_map = BTreeMap::new();
+    flatten("", value, &mut field_map);
+    field_map.remove("id");
+    field_map
+}
+
+fn flatten(path: &str, value: &Value, field_map: &mut BTreeMap<String, Value>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                flatten(&join(key), v, field_map);
+            }
+        }
+        Value::Array(list) => {
+            for (i, v) in list.iter().enumerate() {
+                flatten(&join(&i.to_string()), v, field_map);
+            }
+        }
+        _ => {
+            field_map.insert(path.to_string(), value.clone());
+        }
+    }
+}
+
+fn to_value<T: Serialize>(t: &T) -> Value {
+    serde_json::to_value(t).unwrap()
+}
+
+fn distance(a: &StbNode, b: &StbNode) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn first_beam_id(st_bridge: &StBridge) -> u32 {
+        *st_bridge
+            .stb_model
+            .stb_members
+            .stb_beams
+            .keys()
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn identical_models() {
+        let st_bridge = sample();
+
+        let model_diff = diff(&st_bridge, &st_bridge);
+
+        assert!(model_diff.is_empty());
+        assert_eq!(
+            model_diff.node_id_map.len(),
+            st_bridge.stb_model.stb_nodes.map.len()
+        );
+        assert_eq!(
+            model_diff.member_id_map.len(),
+            st_bridge.stb_model.stb_members.iter().count()
+        );
+        assert!(model_diff.node_id_map.iter().all(|(a, b)| a == b));
+    }
+
+    #[test]
+    fn renumbered_member_is_unchanged() {
+        let old = sample();
+        let mut new = sample();
+        let beam_id = first_beam_id(&new);
+        let mut stb_member = new
+            .stb_model
+            .stb_members
+            .stb_beams
+            .remove(&beam_id)
+            .unwrap();
+        if let StbMember::StbBeam { id, .. } = &mut stb_member {
+            *id = 90000;
+        }
+        new.stb_model
+            .stb_members
+            .stb_beams
+            .insert(90000, stb_member);
+
+        let model_diff = diff(&old, &new);
+
+        assert!(model_diff.is_empty());
+        assert_eq!(model_diff.member_id_map[&beam_id], 90000);
+    }
+
+    #[test]
+    fn node_and_member_changes() {
+        let old = sample();
+        let mut new = sample();
+        let mut node = new.stb_model.stb_nodes.get(9).unwrap();
+        node.x += 500.0;
+        new.stb_model.stb_nodes.insert(9, node.clone());
+        node.z += 100_000.0;
+        new.stb_model.stb_nodes.insert(90000, node);
+        let beam_id = first_beam_id(&new);
+        new.stb_model.stb_members.stb_beams.remove(&beam_id);
+
+        let model_diff = diff(&old, &new);
+
+        let moved = &model_diff.node_list[0];
+        assert_eq!(moved.kind, ChangeKind::Modified);
+        assert_eq!((moved.old_id, moved.new_id), (Some(9), Some(9)));
+        assert_eq!(moved.field_list.len(), 1);
+        assert_eq!(moved.field_list[0].field, "x");
+        assert_eq!(
+            moved.field_list[0].new.as_f64().unwrap() - moved.field_list[0].old.as_f64().unwrap(),
+            500.0
+        );
+
+        let added = model_diff.node_list.last().unwrap();
+        assert_eq!(added.kind, ChangeKind::Added);
+        assert_eq!(added.new_id, Some(90000));
+        assert_eq!(model_diff.node_list.len(), 2);
+
+        assert_eq!(model_diff.member_list.len(), 1);
+        assert_eq!(model_diff.member_list[0].kind, ChangeKind::Removed);
+        assert_eq!(model_diff.member_list[0].old_id, Some(beam_id));
+        assert!(model_diff.section_list.is_empty());
+    }
+
+    #[test]
+    fn modified_section() {
+        let old = sample();
+        let mut new = sample();
+        let stb_sections = &mut new.stb_model.stb_sections;
+        let section_id = *stb_sections.column_s_map.keys().min().unwrap();
+        if let Some(StbSection::StbSecColumnS { floor, .. }) =
+            stb_sections.column_s_map.get_mut(&section_id)
+        {
+            *floor = "9".to_string();
+        }
+
+        let model_diff = diff(&old, &new);
+
+        assert_eq!(model_diff.section_list.len(), 1);
+        let change = &model_diff.section_list[0];
+        assert_eq!(change.kind, ChangeKind::Modified);
+        assert_eq!(change.element, "StbSecColumnS");
+        assert_eq!(change.new_id, Some(section_id));
+        assert_eq!(change.field_list.len(), 1);
+        assert_eq!(change.field_list[0].field, "floor");
+        assert_eq!(change.field_list[0].new, "9");
+        assert!(model_diff.member_list.is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+// A model without a file, such as a merge result, has no file name.
+pub struct Document {
+    pub file_name: Option<String>,
+    pub st_bridge: RwLock<StBridge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocumentInfo {
+    pub handle: u32,
+    pub file_name: Option<String>,
+}
+
+// Open models behind handle ids. The map is locked only to look a document up, so a long command
+// on one model does not hold up the others.
+pub struct DocumentStore {
+    document_map: Mutex<BTreeMap<u32, Arc<Document>>>,
+    next_handle: AtomicU32,
+}
+
+impl DocumentStore {
+    pub fn new() -> DocumentStore {
+        DocumentStore {
+            document_map: Mutex::new(BTreeMap::new()),
+            next_handle: AtomicU32::new(1),
+        }
+    }
+
+    pub fn open(&self, file_name: Option<String>, st_bridge: StBridge) -> u32 {
+        let handle = self.next_handle.fetch_add(1, Ordering::Relaxed);
+        let document = Document {
+            file_name,
+            st_bridge: RwLock::new(st_bridge),
+        };
+        self.document_map
+            .lock()
+            .unwrap()
+            .insert(handle, Arc::new(document));
+        handle
+    }
+
+    pub fn close(&self, handle: u32) -> Result<(), String> {
+        self.document_map
+            .lock()
+            .unwrap()
+            .remove(&handle)
+            .map(|_| ())
+            .ok_or_else(|| unknown_handle(handle))
+    }
+
+    pub fn document_list(&self) -> Vec<DocumentInfo> {
+        self.document_map
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&handle, d)| DocumentInfo {
+                handle,
+                file_name: d.file_name.clone(),
+            })
+            .collect()
+    }
+
+    pub fn get(&self, handle: u32) -> Result<Arc<Document>, String> {
+        self.document_map
+            .lock()
+            .unwrap()
+            .get(&handle)
+            .cloned()
+            .ok_or_else(|| unknown_handle(handle))
+    }
+
+    pub fn read<T>(&self, handle: u32, f: impl FnOnce(&StBridge) -> T) -> Result<T, String> {
+        let document = self.get(handle)?;
+        let st_bridge = document.st_bridge.read().unwrap();
+        Ok(f(&st_bridge))
+    }
+
+    // Several models at once, in the order of the handles. A handle given twice is locked once,
+    // as a second read lock on one document can wait forever behind a queued writer.
+    pub fn read_list<T>(
+        &self,
+        handle_list: &[u32],
+        f: impl FnOnce(&[&StBridge]) -> T,
+    ) -> Result<T, String> {
+        let mut distinct_list = handle_list.to_vec();
+        distinct_list.sort_unstable();
+        distinct_list.dedup();
+        let document_list = distinct_list
+            .iter()
+            .map(|&handle| self.get(handle))
+            .collect::<Result<Vec<_>, _>>()?;
+        let guard_list: Vec<_> = document_list
+            .iter()
+            .map(|d| d.st_bridge.read().unwrap())
+            .collect();
+        let st_bridge_list: Vec<&StBridge> = handle_list
+            .iter()
+            .map(|h| &*guard_list[distinct_list.binary_search(h).unwrap()])
+            .collect();
+        Ok(f(&st_bridge_list))
+    }
+
+    pub fn write<T>(&self, handle: u32, f: impl FnOnce(&mut StBridge) -> T) -> Result<T, String> {
+        let document = self.get(handle)?;
+        let mut st_bridge = document.st_bridge.write().unwrap();
+        Ok(f(&mut st_bridge))
+    }
+}
+
+impl Default for DocumentStore {
+    fn default() -> DocumentStore {
+        DocumentStore::new()
+    }
+}
+
+fn unknown_handle(handle: u32) -> String {
+    format!("no open document with handle {}", handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn node_count(document_store: &DocumentStore, handle: u32) -> Result<usize, String> {
+        document_store.read(handle, |st_bridge| st_bridge.stb_model.stb_nodes.map.len())
+    }
+
+    #[test]
+    fn open_and_close() {
+        let document_store = DocumentStore::new();
+        let a = document_store.open(Some("a.stb".to_string()), sample());
+        let b = document_store.open(None, sample());
+        assert_ne!(a, b);
+
+        let document_list = document_store.document_list();
+        assert_eq!(document_list.len(), 2);
+        assert_eq!(document_list[0].handle, a);
+        assert_eq!(document_list[0].file_name.as_deref(), Some("a.stb"));
+        assert_eq!(document_list[1].file_name, None);
+
+        let count = node_count(&document_store, a).unwrap();
+        assert!(count > 0);
+
+        // Writing to one model leaves the other alone.
+        document_store
+            .write(b, |st_bridge| st_bridge.stb_model.stb_nodes.map.clear())
+            .unwrap();
+        assert_eq!(node_count(&document_store, b), Ok(0));
+        assert_eq!(node_count(&document_store, a), Ok(count));
+
+        assert_eq!(document_store.close(a), Ok(()));
+        assert_eq!(document_store.document_list().len(), 1);
+        assert_eq!(node_count(&document_store, b), Ok(0));
+    }
+
+    #[test]
+    fn unknown_handle() {
+        let document_store = DocumentStore::new();
+        let handle = document_store.open(None, sample());
+        document_store.close(handle).unwrap();
+
+        let message = format!("no open document with handle {}", handle);
+        assert_eq!(node_count(&document_store, handle), Err(message.clone()));
+        assert_eq!(document_store.close(handle), Err(message));
+        assert!(document_store.write(handle + 1, |_| ()).is_err());
+        assert!(document_store.get(handle).is_err());
+
+        // Handles are not reused after a close.
+        assert_ne!(document_store.open(None, sample()), handle);
+    }
+}
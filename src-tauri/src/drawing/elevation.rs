@@ -0,0 +1,294 @@
+use super::{
+    bounds, label, section_name, Anchor, Drawing, Pen, Viewport, BLACK, BUBBLE_RADIUS, GRAY,
+    GRID_EXTENSION, LABEL_TEXT, MARGIN, RED, TITLE_TEXT,
+};
+use crate::geometry::{member_ends, Node};
+use crate::st_bridge::stb_model::stb_axes_and_stories::{StbNodeIdList, StbXAxis, StbYAxis};
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+use std::collections::BTreeSet;
+
+// A member with its ends in the elevation's horizontal coordinate and height.
+type MemberLine<'a> = (&'a StbMember, (f64, f64), (f64, f64));
+
+pub fn axis_elevation_list(st_bridge: &StBridge, scale: f64) -> Vec<Drawing> {
+    let stb_axes = &st_bridge.stb_model.stb_axes;
+    let mut drawing_list: Vec<Drawing> = stb_axes
+        .stb_x_axis_list
+        .iter()
+        .map(|axis| x_axis_elevation(st_bridge, axis, scale))
+        .collect();
+    drawing_list.extend(
+        stb_axes
+            .stb_y_axis_list
+            .iter()
+            .map(|axis| y_axis_elevation(st_bridge, axis, scale)),
+    );
+    drawing_list
+}
+
+pub fn write_axis_elevations(
+    st_bridge: &StBridge,
+    file_name: &str,
+    scale: f64,
+) -> std::io::Result<()> {
+    super::write_drawing_list(&axis_elevation_list(st_bridge, scale), file_name)
+}
+
+// An X axis is seen from the -X side, so Y runs to the right and the Y axes cross it.
+pub fn x_axis_elevation(st_bridge: &StBridge, axis: &StbXAxis, scale: f64) -> Drawing {
+    let crossing_list: Vec<(f64, &str)> = st_bridge
+        .stb_model
+        .stb_axes
+        .stb_y_axis_list
+        .iter()
+        .map(|a| (a.distance, a.name.as_str()))
+        .collect();
+    elevation(
+        st_bridge,
+        &axis.name,
+        &axis.stb_node_id_list,
+        |p| p.y,
+        crossing_list,
+        scale,
+    )
+}
+
+// A Y axis is seen from the -Y side, so X runs to the right and the X axes cross it.
+pub fn y_axis_elevation(st_bridge: &StBridge, axis: &StbYAxis, scale: f64) -> Drawing {
+    let crossing_list: Vec<(f64, &str)> = st_bridge
+        .stb_model
+        .stb_axes
+        .stb_x_axis_list
+        .iter()
+        .map(|a| (a.distance, a.name.as_str()))
+        .collect();
+    elevation(
+        st_bridge,
+        &axis.name,
+        &axis.stb_node_id_list,
+        |p| p.x,
+        crossing_list,
+        scale,
+    )
+}
+
+// Columns, girders and braces with every node on the axis, with the crossing grids below and the
+// story lines to the right.
+fn elevation(
+    st_bridge: &StBridge,
+    name: &str,
+    stb_node_id_list: &StbNodeIdList,
+    horizontal: impl Fn(Node) -> f64,
+    mut crossing_list: Vec<(f64, &str)>,
+    scale: f64,
+) -> Drawing {
+    let stb_model = &st_bridge.stb_model;
+    crossing_list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut story_list: Vec<(f64, &str)> = stb_model
+        .stb_stories
+        .stb_story_list
+        .iter()
+        .map(|s| (s.height, s.name.as_str()))
+        .collect();
+    story_list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let on_axis: BTreeSet<u32> = stb_node_id_list
+        .children
+        .iter()
+        .map(|n| n.id as u32)
+        .collect();
+
+    let mut member_list: Vec<&StbMember> = stb_model
+        .stb_members
+        .iter()
+        .filter(|m| {
+            matches!(
+                m,
+                StbMember::StbColumn { .. }
+                    | StbMember::StbPost { .. }
+                    | StbMember::StbGirder { .. }
+                    | StbMember::StbBrace { .. }
+            ) && m.node_id_list().iter().all(|id| on_axis.contains(id))
+        })
+        .collect();
+    member_list.sort_by_key(|m| m.id());
+
+    let line_list: Vec<MemberLine> = member_list
+        .into_iter()
+        .filter_map(|m| {
+            let (ni, nj) = member_ends(st_bridge, m)?;
+            Some((m, (horizontal(ni), ni.z), (horizontal(nj), nj.z)))
+        })
+        .collect();
+
+    let mut point_list: Vec<(f64, f64)> = Vec::new();
+    for &(_, a, b) in &line_list {
+        point_list.push(a);
+        point_list.push(b);
+    }
+    for &(h, _) in &crossing_list {
+        point_list.extend(story_list.iter().map(|&(z, _)| (h, z)));
+    }
+    let (x0, y0, x1, y1) = bounds(&point_list);
+
+    let view = Viewport {
+        scale,
+        origin: (x0, y0),
+        offset: (MARGIN, MARGIN),
+    };
+    let (left, bottom) = view.to_paper(x0, y0);
+    let (right, top) = view.to_paper(x1, y1);
+
+    let mut drawing = Drawing::new(
+        name,
+        (x1 - x0) / scale + 2.0 * MARGIN,
+        (y1 - y0) / scale + 2.0 * MARGIN,
+    );
+
+    let grid_pen = Pen::new(GRAY, 0.18).dashed();
+    let dimension_pen = Pen::new(BLACK, 0.13);
+
+    for &(h, grid) in &crossing_list {
+        let (px, _) = view.to_paper(h, 0.0);
+        drawing.line((px, bottom - GRID_EXTENSION), (px, top + 4.0), grid_pen);
+        drawing.bubble(
+            (px, bottom - GRID_EXTENSION - BUBBLE_RADIUS),
+            BUBBLE_RADIUS,
+            grid,
+            Pen::new(BLACK, 0.18),
+        );
+    }
+    for pair in crossing_list.windows(2) {
+        let (a, _) = view.to_paper(pair[0].0, 0.0);
+        let (b, _) = view.to_paper(pair[1].0, 0.0);
+        drawing.dimension(
+            (a, bottom - 8.0),
+            (b, bottom - 8.0),
+            pair[1].0 - pair[0].0,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+    if crossing_list.len() > 2 {
+        let first = crossing_list[0].0;
+        let last = crossing_list[crossing_list.len() - 1].0;
+        drawing.dimension(
+            (view.to_paper(first, 0.0).0, bottom - 15.0),
+            (view.to_paper(last, 0.0).0, bottom - 15.0),
+            last - first,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+
+    // Story lines labelled with name and height, with the story heights dimensioned on the left.
+    for &(z, story) in &story_list {
+        let (_, py) = view.to_paper(0.0, z);
+        drawing.line((left - 4.0, py), (right + GRID_EXTENSION, py), grid_pen);
+        drawing.text(
+            (right + 6.0, py + 0.8),
+            &format!("{}  FL {}", story, z.round()),
+            LABEL_TEXT * 1.25,
+            0.0,
+            Anchor::Start,
+            BLACK,
+        );
+    }
+    for pair in story_list.windows(2) {
+        let (_, a) = view.to_paper(0.0, pair[0].0);
+        let (_, b) = view.to_paper(0.0, pair[1].0);
+        drawing.dimension(
+            (left - 8.0, a),
+            (left - 8.0, b),
+            pair[1].0 - pair[0].0,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+
+    for &(m, a, b) in &line_list {
+        let a = view.to_paper(a.0, a.1);
+        let b = view.to_paper(b.0, b.1);
+        let (pen, color) = match m {
+            StbMember::StbBrace { .. } => (Pen::new(RED, 0.25), RED),
+            StbMember::StbPost { .. } => (Pen::new(BLACK, 0.25), BLACK),
+            _ => (Pen::new(BLACK, 0.5), BLACK),
+        };
+        drawing.line(a, b, pen);
+        label(&mut drawing, a, b, &section_name(st_bridge, m), color);
+    }
+
+    // The grid bubbles take the bottom margin, so the title goes on top.
+    let title_height = drawing.height - MARGIN / 2.0;
+    drawing.text(
+        (MARGIN, title_height),
+        &format!("{} axis elevation  1:{}", name, scale.round()),
+        TITLE_TEXT,
+        0.0,
+        Anchor::Start,
+        BLACK,
+    );
+
+    drawing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn one_elevation_per_axis() {
+        let drawing_list = axis_elevation_list(&sample(), 200.0);
+
+        let name_list: Vec<&str> = drawing_list.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(name_list, ["1", "1'", "2", "A", "B", "C", "D", "E"]);
+    }
+
+    #[test]
+    fn x_axis_svg() {
+        let st_bridge = sample();
+        let axis = &st_bridge.stb_model.stb_axes.stb_x_axis_list[0];
+
+        let svg = x_axis_elevation(&st_bridge, axis, 200.0).to_svg();
+
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        // A bubble below each of the 5 crossing Y axes.
+        assert_eq!(
+            document
+                .descendants()
+                .filter(|n| n.has_tag_name("circle"))
+                .count(),
+            5
+        );
+
+        let text_list: Vec<&str> = document
+            .descendants()
+            .filter(|n| n.has_tag_name("text"))
+            .filter_map(|n| n.text())
+            .collect();
+        let count = |text: &str| text_list.iter().filter(|t| **t == text).count();
+        assert!(text_list.contains(&"1 axis elevation  1:200"));
+        for story in [
+            "1  FL 0",
+            "2  FL 4000",
+            "3  FL 7700",
+            "R  FL 11400",
+            "PHR  FL 13950",
+        ] {
+            assert_eq!(count(story), 1, "{}", story);
+        }
+        // Five columns on the axis in each of the three lower stories.
+        assert_eq!(count("C1"), 15);
+        assert_eq!(count("22680"), 1);
+    }
+}
@@ -0,0 +1,607 @@
+pub mod elevation;
+pub mod plan;
+pub mod rc_section;
+
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+// Drawings are laid out on paper in mm with y pointing up, and written to SVG or PDF.
+
+pub const DEFAULT_SCALE: f64 = 200.0;
+
+// Paper sizes in mm.
+const MARGIN: f64 = 40.0;
+const GRID_EXTENSION: f64 = 24.0;
+const BUBBLE_RADIUS: f64 = 4.0;
+const LABEL_TEXT: f64 = 2.0;
+const TITLE_TEXT: f64 = 5.0;
+
+const BLACK: [u8; 3] = [0, 0, 0];
+const GRAY: [u8; 3] = [128, 128, 128];
+const RED: [u8; 3] = [192, 0, 0];
+const BLUE: [u8; 3] = [0, 64, 160];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pen {
+    pub color: [u8; 3],
+    pub width: f64,
+    pub dash: bool,
+    pub fill: Option<[u8; 3]>,
+}
+
+impl Pen {
+    pub fn new(color: [u8; 3], width: f64) -> Pen {
+        Pen {
+            color,
+            width,
+            dash: false,
+            fill: None,
+        }
+    }
+
+    pub fn dashed(self) -> Pen {
+        Pen { dash: true, ..self }
+    }
+
+    pub fn filled(self, fill: [u8; 3]) -> Pen {
+        Pen {
+            fill: Some(fill),
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Shape {
+    Polyline {
+        point_list: Vec<(f64, f64)>,
+        closed: bool,
+    },
+    Circle {
+        center: (f64, f64),
+        radius: f64,
+    },
+    // Angle in degrees counterclockwise, size is the font size in mm.
+    Text {
+        position: (f64, f64),
+        text: String,
+        size: f64,
+        angle: f64,
+        anchor: Anchor,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub shape: Shape,
+    pub pen: Pen,
+}
+
+#[derive(Debug, Clone)]
+pub struct Drawing {
+    pub name: String,
+    pub width: f64,
+    pub height: f64,
+    pub element_list: Vec<Element>,
+}
+
+// Maps model coordinates in mm onto the paper at 1:scale.
+#[derive(Debug, Clone, Copy)]
+pub struct Viewport {
+    pub scale: f64,
+    pub origin: (f64, f64),
+    pub offset: (f64, f64),
+}
+
+impl Viewport {
+    pub fn to_paper(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x - self.origin.0) / self.scale + self.offset.0,
+            (y - self.origin.1) / self.scale + self.offset.1,
+        )
+    }
+}
+
+// Readable text angle along a line from a to b, between -90 and 90 degrees.
+pub fn text_angle(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let mut angle = (b.1 - a.1).atan2(b.0 - a.0).to_degrees();
+    if angle > 90.0 + 1e-6 {
+        angle -= 180.0;
+    } else if angle <= -90.0 + 1e-6 {
+        angle += 180.0;
+    }
+    angle
+}
+
+// Smallest and largest x and y, or all zero for no points.
+pub fn bounds(point_list: &[(f64, f64)]) -> (f64, f64, f64, f64) {
+    if point_list.is_empty() {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+
+    let (mut x0, mut y0) = (f64::INFINITY, f64::INFINITY);
+    let (mut x1, mut y1) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for &(x, y) in point_list {
+        x0 = x0.min(x);
+        y0 = y0.min(y);
+        x1 = x1.max(x);
+        y1 = y1.max(y);
+    }
+    (x0, y0, x1, y1)
+}
+
+fn section_name(st_bridge: &StBridge, stb_member: &StbMember) -> String {
+    st_bridge
+        .stb_model
+        .stb_sections
+        .get(stb_member.id_section())
+        .map(|s| s.name())
+        .unwrap_or_default()
+}
+
+// Text along a line, just above its middle.
+fn label(drawing: &mut Drawing, a: (f64, f64), b: (f64, f64), text: &str, color: [u8; 3]) {
+    let angle = text_angle(a, b);
+    let (nx, ny) = (-angle.to_radians().sin(), angle.to_radians().cos());
+    let gap = LABEL_TEXT * 0.4;
+    drawing.text(
+        ((a.0 + b.0) / 2.0 + nx * gap, (a.1 + b.1) / 2.0 + ny * gap),
+        text,
+        LABEL_TEXT,
+        angle,
+        Anchor::Middle,
+        color,
+    );
+}
+
+impl Drawing {
+    pub fn new(name: &str, width: f64, height: f64) -> Drawing {
+        Drawing {
+            name: name.to_string(),
+            width,
+            height,
+            element_list: Vec::new(),
+        }
+    }
+
+    pub fn line(&mut self, a: (f64, f64), b: (f64, f64), pen: Pen) {
+        self.polyline(vec![a, b], false, pen);
+    }
+
+    pub fn polyline(&mut self, point_list: Vec<(f64, f64)>, closed: bool, pen: Pen) {
+        self.element_list.push(Element {
+            shape: Shape::Polyline { point_list, closed },
+            pen,
+        });
+    }
+
+    pub fn circle(&mut self, center: (f64, f64), radius: f64, pen: Pen) {
+        self.element_list.push(Element {
+            shape: Shape::Circle { center, radius },
+            pen,
+        });
+    }
+
+    pub fn text(
+        &mut self,
+        position: (f64, f64),
+        text: &str,
+        size: f64,
+        angle: f64,
+        anchor: Anchor,
+        color: [u8; 3],
+    ) {
+        self.element_list.push(Element {
+            shape: Shape::Text {
+                position,
+                text: text.to_string(),
+                size,
+                angle,
+                anchor,
+            },
+            pen: Pen::new(color, 0.0),
+        });
+    }
+
+    // Grid line name in a circle.
+    pub fn bubble(&mut self, center: (f64, f64), radius: f64, name: &str, pen: Pen) {
+        self.circle(center, radius, pen);
+        let size = radius * 0.9;
+        self.text(
+            (center.0, center.1 - size * 0.35),
+            name,
+            size,
+            0.0,
+            Anchor::Middle,
+            pen.color,
+        );
+    }
+
+    // Dimension line from a to b with tick marks, labelled with the length in mm above the line.
+    pub fn dimension(&mut self, a: (f64, f64), b: (f64, f64), value: f64, size: f64, pen: Pen) {
+        let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        if length < 1e-6 {
+            return;
+        }
+        let (ux, uy) = ((b.0 - a.0) / length, (b.1 - a.1) / length);
+        let tick = size * 0.5;
+
+        self.line(a, b, pen);
+        for p in [a, b] {
+            // 45 degree slash across the end.
+            let (dx, dy) = ((ux - uy) * tick, (uy + ux) * tick);
+            self.line((p.0 - dx, p.1 - dy), (p.0 + dx, p.1 + dy), pen);
+        }
+
+        let angle = text_angle(a, b);
+        let (nx, ny) = (-angle.to_radians().sin(), angle.to_radians().cos());
+        let gap = size * 0.3;
+        self.text(
+            ((a.0 + b.0) / 2.0 + nx * gap, (a.1 + b.1) / 2.0 + ny * gap),
+            &format!("{}", value.round()),
+            size,
+            angle,
+            Anchor::Middle,
+            pen.color,
+        );
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}mm\" height=\"{h}mm\" \
+             viewBox=\"0 0 {w} {h}\">",
+            w = number(self.width),
+            h = number(self.height)
+        )
+        .unwrap();
+        writeln!(svg, "<title>{}</title>", escape_xml(&self.name)).unwrap();
+        writeln!(
+            svg,
+            "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>",
+            number(self.width),
+            number(self.height)
+        )
+        .unwrap();
+
+        let flip = |p: (f64, f64)| (number(p.0), number(self.height - p.1));
+
+        for element in &self.element_list {
+            let pen = &element.pen;
+            let color = rgb(pen.color);
+            let mut style = format!(
+                "fill=\"{}\" stroke=\"{}\" stroke-width=\"{}\"",
+                pen.fill.map(rgb).unwrap_or_else(|| "none".to_string()),
+                color,
+                number(pen.width)
+            );
+            if pen.dash {
+                write!(
+                    style,
+                    " stroke-dasharray=\"{} {}\"",
+                    number(pen.width * 12.0 + 1.0),
+                    number(pen.width * 6.0 + 0.5)
+                )
+                .unwrap();
+            }
+
+            match &element.shape {
+                Shape::Polyline { point_list, closed } => {
+                    let points: Vec<String> = point_list
+                        .iter()
+                        .map(|&p| {
+                            let (x, y) = flip(p);
+                            format!("{},{}", x, y)
+                        })
+                        .collect();
+                    let tag = if *closed { "polygon" } else { "polyline" };
+                    writeln!(svg, "<{} points=\"{}\" {}/>", tag, points.join(" "), style).unwrap();
+                }
+                Shape::Circle { center, radius } => {
+                    let (x, y) = flip(*center);
+                    writeln!(
+                        svg,
+                        "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" {}/>",
+                        x,
+                        y,
+                        number(*radius),
+                        style
+                    )
+                    .unwrap();
+                }
+                Shape::Text {
+                    position,
+                    text,
+                    size,
+                    angle,
+                    anchor,
+                } => {
+                    let (x, y) = flip(*position);
+                    let anchor = match anchor {
+                        Anchor::Start => "start",
+                        Anchor::Middle => "middle",
+                        Anchor::End => "end",
+                    };
+                    let rotate = if angle.abs() > 1e-6 {
+                        format!(" transform=\"rotate({} {} {})\"", number(-angle), x, y)
+                    } else {
+                        String::new()
+                    };
+                    writeln!(
+                        svg,
+                        "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" \
+                         text-anchor=\"{}\" fill=\"{}\"{}>{}</text>",
+                        x,
+                        y,
+                        number(*size),
+                        anchor,
+                        color,
+                        rotate,
+                        escape_xml(text)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+fn number(v: f64) -> String {
+    // Two decimals are plenty on paper; adding zero prints -0 as 0.
+    let v = (v * 100.0).round() / 100.0 + 0.0;
+    format!("{}", v)
+}
+
+fn rgb(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+pub fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// The PDF uses the built-in Helvetica, which has no Japanese glyphs, so characters outside ASCII
+// are printed as '?'. Use SVG for drawings with Japanese names.
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+fn pdf_color(color: [u8; 3]) -> String {
+    format!(
+        "{} {} {}",
+        number(color[0] as f64 / 255.0),
+        number(color[1] as f64 / 255.0),
+        number(color[2] as f64 / 255.0)
+    )
+}
+
+// Page content in mm, scaled to points by the first transformation.
+fn pdf_content(drawing: &Drawing) -> String {
+    let mut content = String::new();
+    writeln!(content, "{} 0 0 {} 0 0 cm", 72.0 / 25.4, 72.0 / 25.4).unwrap();
+    content += "1 J 1 j\n";
+
+    for element in &drawing.element_list {
+        let pen = &element.pen;
+
+        if let Shape::Text {
+            position,
+            text,
+            size,
+            angle,
+            anchor,
+        } = &element.shape
+        {
+            // Helvetica averages about half an em per character.
+            let width = 0.5 * size * text.chars().count() as f64;
+            let shift = match anchor {
+                Anchor::Start => 0.0,
+                Anchor::Middle => -width / 2.0,
+                Anchor::End => -width,
+            };
+            let (sin, cos) = angle.to_radians().sin_cos();
+            writeln!(
+                content,
+                "{} rg BT /F1 {} Tf {} {} {} {} {} {} Tm ({}) Tj ET",
+                pdf_color(pen.color),
+                number(*size),
+                cos,
+                sin,
+                -sin,
+                cos,
+                number(position.0 + cos * shift),
+                number(position.1 + sin * shift),
+                pdf_text(text)
+            )
+            .unwrap();
+            continue;
+        }
+
+        writeln!(
+            content,
+            "{} RG {} w {}",
+            pdf_color(pen.color),
+            number(pen.width),
+            if pen.dash {
+                format!(
+                    "[{} {}] 0 d",
+                    number(pen.width * 12.0 + 1.0),
+                    number(pen.width * 6.0 + 0.5)
+                )
+            } else {
+                "[] 0 d".to_string()
+            }
+        )
+        .unwrap();
+        if let Some(fill) = pen.fill {
+            writeln!(content, "{} rg", pdf_color(fill)).unwrap();
+        }
+
+        let closed = match &element.shape {
+            Shape::Polyline { point_list, closed } => {
+                for (i, p) in point_list.iter().enumerate() {
+                    let op = if i == 0 { "m" } else { "l" };
+                    writeln!(content, "{} {} {}", number(p.0), number(p.1), op).unwrap();
+                }
+                *closed
+            }
+            Shape::Circle { center, radius } => {
+                // Four cubic Bezier quarter arcs.
+                let k = 0.5523 * radius;
+                let (x, y, r) = (center.0, center.1, *radius);
+                writeln!(content, "{} {} m", number(x + r), number(y)).unwrap();
+                for (p1, p2, p3) in [
+                    ((x + r, y + k), (x + k, y + r), (x, y + r)),
+                    ((x - k, y + r), (x - r, y + k), (x - r, y)),
+                    ((x - r, y - k), (x - k, y - r), (x, y - r)),
+                    ((x + k, y - r), (x + r, y - k), (x + r, y)),
+                ] {
+                    writeln!(
+                        content,
+                        "{} {} {} {} {} {} c",
+                        number(p1.0),
+                        number(p1.1),
+                        number(p2.0),
+                        number(p2.1),
+                        number(p3.0),
+                        number(p3.1)
+                    )
+                    .unwrap();
+                }
+                true
+            }
+            Shape::Text { .. } => unreachable!(),
+        };
+
+        content += match (closed, pen.fill.is_some()) {
+            (true, true) => "b\n",
+            (true, false) => "s\n",
+            (false, true) => "B\n",
+            (false, false) => "S\n",
+        };
+    }
+
+    content
+}
+
+// One page per drawing, sized to the drawing.
+pub fn create_pdf(drawing_list: &[Drawing]) -> Vec<u8> {
+    // Objects 1 to 3 are the catalog, the page tree and the font, then a page and its content
+    // for each drawing.
+    let mut object_list: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..drawing_list.len())
+                .map(|i| format!("{} 0 R", 4 + 2 * i))
+                .collect::<Vec<String>>()
+                .join(" "),
+            drawing_list.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+
+    for (i, drawing) in drawing_list.iter().enumerate() {
+        let content = pdf_content(drawing);
+        object_list.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            number(drawing.width * 72.0 / 25.4),
+            number(drawing.height * 72.0 / 25.4),
+            5 + 2 * i
+        ));
+        object_list.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offset_list = Vec::new();
+    for (i, object) in object_list.iter().enumerate() {
+        offset_list.push(pdf.len());
+        writeln!(pdf, "{} 0 obj\n{}\nendobj", i + 1, object).unwrap();
+    }
+
+    let xref = pdf.len();
+    writeln!(
+        pdf,
+        "xref\n0 {}\n0000000000 65535 f ",
+        object_list.len() + 1
+    )
+    .unwrap();
+    for offset in offset_list {
+        writeln!(pdf, "{:010} 00000 n ", offset).unwrap();
+    }
+    writeln!(
+        pdf,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF",
+        object_list.len() + 1,
+        xref
+    )
+    .unwrap();
+
+    pdf.into_bytes()
+}
+
+// A .pdf file gets every drawing as a page. Otherwise each drawing goes to its own SVG, named
+// after the drawing when there is more than one.
+pub fn write_drawing_list(drawing_list: &[Drawing], file_name: &str) -> std::io::Result<()> {
+    if file_name.to_lowercase().ends_with(".pdf") {
+        let mut f = File::create(file_name)?;
+        return f.write_all(&create_pdf(drawing_list));
+    }
+
+    if drawing_list.len() == 1 {
+        let mut f = File::create(file_name)?;
+        return f.write_all(drawing_list[0].to_svg().as_bytes());
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    for drawing in drawing_list {
+        let name: String = drawing
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut f = File::create(path.with_file_name(format!("{}_{}.svg", stem, name)))?;
+        f.write_all(drawing.to_svg().as_bytes())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,465 @@
+use super::{
+    bounds, label, section_name, text_angle, Anchor, Drawing, Pen, Viewport, BLACK, BLUE,
+    BUBBLE_RADIUS, GRAY, GRID_EXTENSION, LABEL_TEXT, MARGIN, RED, TITLE_TEXT,
+};
+use crate::geometry::{member_ends, member_placement, section_outline};
+use crate::st_bridge::stb_model::stb_axes_and_stories::StbStory;
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+
+// Nodes within this distance of the story height lie on the floor (mm).
+const LEVEL_TOLERANCE: f64 = 1.0;
+
+const SLAB_TEXT: f64 = 2.5;
+const COLUMN_FILL: [u8; 3] = [200, 200, 200];
+
+fn on_level(st_bridge: &StBridge, id: Option<u32>, height: f64) -> bool {
+    id.and_then(|id| st_bridge.stb_model.stb_nodes.get(id))
+        .map(|n| (n.z - height).abs() <= LEVEL_TOLERANCE)
+        .unwrap_or(false)
+}
+
+// Columns below the floor, or above it where nothing stands below, drawn as their section cut at
+// the floor.
+fn column_footprint(
+    st_bridge: &StBridge,
+    stb_member: &StbMember,
+    height: f64,
+) -> Option<Vec<(f64, f64)>> {
+    let stb_section = st_bridge
+        .stb_model
+        .stb_sections
+        .get(stb_member.id_section())?;
+    let (outline, _) = section_outline(st_bridge, stb_section)?;
+    let (plane, length) = member_placement(st_bridge, stb_member)?;
+
+    let w = if on_level(st_bridge, stb_member.node_j(), height) {
+        length
+    } else {
+        0.0
+    };
+    Some(
+        outline
+            .iter()
+            .map(|&(u, v)| {
+                let p = plane.to_global(u, v, w);
+                (p.x, p.y)
+            })
+            .collect(),
+    )
+}
+
+fn polygon_centroid(point_list: &[(f64, f64)]) -> (f64, f64) {
+    let mut area = 0.0;
+    let (mut cx, mut cy) = (0.0, 0.0);
+    for i in 0..point_list.len() {
+        let (x0, y0) = point_list[i];
+        let (x1, y1) = point_list[(i + 1) % point_list.len()];
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        cx += (x0 + x1) * cross;
+        cy += (y0 + y1) * cross;
+    }
+
+    if area.abs() < 1e-9 {
+        let n = point_list.len().max(1) as f64;
+        return (
+            point_list.iter().map(|p| p.0).sum::<f64>() / n,
+            point_list.iter().map(|p| p.1).sum::<f64>() / n,
+        );
+    }
+    (cx / (3.0 * area), cy / (3.0 * area))
+}
+
+pub fn framing_plan_list(st_bridge: &StBridge, scale: f64) -> Vec<Drawing> {
+    st_bridge
+        .stb_model
+        .stb_stories
+        .stb_story_list
+        .iter()
+        .map(|story| framing_plan(st_bridge, story, scale))
+        .collect()
+}
+
+pub fn write_framing_plans(
+    st_bridge: &StBridge,
+    file_name: &str,
+    scale: f64,
+) -> std::io::Result<()> {
+    super::write_drawing_list(&framing_plan_list(st_bridge, scale), file_name)
+}
+
+// The floor at the story height seen from above: grids with their dimensions, girders and beams
+// as center lines, columns as section footprints, braces and slabs.
+pub fn framing_plan(st_bridge: &StBridge, story: &StbStory, scale: f64) -> Drawing {
+    let stb_model = &st_bridge.stb_model;
+    let height = story.height;
+
+    let mut x_axis_list: Vec<(f64, &str)> = stb_model
+        .stb_axes
+        .stb_x_axis_list
+        .iter()
+        .map(|a| (a.distance, a.name.as_str()))
+        .collect();
+    x_axis_list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let mut y_axis_list: Vec<(f64, &str)> = stb_model
+        .stb_axes
+        .stb_y_axis_list
+        .iter()
+        .map(|a| (a.distance, a.name.as_str()))
+        .collect();
+    y_axis_list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut member_list: Vec<&StbMember> = stb_model.stb_members.iter().collect();
+    member_list.sort_by_key(|m| m.id());
+
+    let floor_list: Vec<&StbMember> = member_list
+        .iter()
+        .cloned()
+        .filter(|m| {
+            !matches!(m, StbMember::StbOpen { .. })
+                && m.node_id_list()
+                    .iter()
+                    .all(|&id| on_level(st_bridge, Some(id), height))
+        })
+        .collect();
+
+    // Columns and vertical braces reaching the floor from below, then columns rising from it
+    // where there is nothing below.
+    let mut below: Vec<&StbMember> = Vec::new();
+    let mut above: Vec<&StbMember> = Vec::new();
+    for &m in &member_list {
+        if !m.is_linear() || floor_list.iter().any(|f| f.id() == m.id()) {
+            continue;
+        }
+        if on_level(st_bridge, m.node_j(), height) {
+            below.push(m);
+        } else if on_level(st_bridge, m.node_i(), height) {
+            above.push(m);
+        }
+    }
+    let mut column_list: Vec<&StbMember> = below
+        .iter()
+        .cloned()
+        .filter(|m| matches!(m, StbMember::StbColumn { .. } | StbMember::StbPost { .. }))
+        .collect();
+    for m in above {
+        if matches!(m, StbMember::StbColumn { .. } | StbMember::StbPost { .. })
+            && !column_list.iter().any(|c| c.node_j() == m.node_i())
+        {
+            column_list.push(m);
+        }
+    }
+    let vertical_brace_list: Vec<&StbMember> = below
+        .iter()
+        .cloned()
+        .filter(|m| matches!(m, StbMember::StbBrace { .. }))
+        .collect();
+
+    // Extent of the drawing in model coordinates.
+    let mut point_list: Vec<(f64, f64)> = Vec::new();
+    for m in floor_list
+        .iter()
+        .chain(&column_list)
+        .chain(&vertical_brace_list)
+    {
+        for id in m.node_id_list() {
+            if let Some(n) = stb_model.stb_nodes.get(id) {
+                point_list.push((n.x, n.y));
+            }
+        }
+    }
+    for &(x, _) in &x_axis_list {
+        point_list.extend(y_axis_list.iter().map(|&(y, _)| (x, y)));
+    }
+    let (x0, y0, x1, y1) = bounds(&point_list);
+
+    let width = (x1 - x0) / scale + 2.0 * MARGIN;
+    let paper_height = (y1 - y0) / scale + 2.0 * MARGIN;
+    let view = Viewport {
+        scale,
+        origin: (x0, y0),
+        offset: (MARGIN, MARGIN),
+    };
+    let (left, bottom) = view.to_paper(x0, y0);
+    let (right, top) = view.to_paper(x1, y1);
+
+    let mut drawing = Drawing::new(&story.name, width, paper_height);
+
+    let grid_pen = Pen::new(GRAY, 0.18).dashed();
+    let dimension_pen = Pen::new(BLACK, 0.13);
+    let girder_pen = Pen::new(BLACK, 0.5);
+    let beam_pen = Pen::new(BLACK, 0.25);
+    let brace_pen = Pen::new(RED, 0.25).dashed();
+    let slab_pen = Pen::new(BLUE, 0.13).dashed();
+    let column_pen = Pen::new(BLACK, 0.25).filled(COLUMN_FILL);
+
+    // Grids run past the frame to bubbles at the top and left, with the spans and the overall
+    // length dimensioned between the frame and the bubbles.
+    for &(x, name) in &x_axis_list {
+        let (px, _) = view.to_paper(x, 0.0);
+        drawing.line((px, bottom - 4.0), (px, top + GRID_EXTENSION), grid_pen);
+        drawing.bubble(
+            (px, top + GRID_EXTENSION + BUBBLE_RADIUS),
+            BUBBLE_RADIUS,
+            name,
+            Pen::new(BLACK, 0.18),
+        );
+    }
+    for &(y, name) in &y_axis_list {
+        let (_, py) = view.to_paper(0.0, y);
+        drawing.line((left - GRID_EXTENSION, py), (right + 4.0, py), grid_pen);
+        drawing.bubble(
+            (left - GRID_EXTENSION - BUBBLE_RADIUS, py),
+            BUBBLE_RADIUS,
+            name,
+            Pen::new(BLACK, 0.18),
+        );
+    }
+
+    for pair in x_axis_list.windows(2) {
+        let (a, _) = view.to_paper(pair[0].0, 0.0);
+        let (b, _) = view.to_paper(pair[1].0, 0.0);
+        drawing.dimension(
+            (a, top + 8.0),
+            (b, top + 8.0),
+            pair[1].0 - pair[0].0,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+    if x_axis_list.len() > 2 {
+        let first = x_axis_list[0].0;
+        let last = x_axis_list[x_axis_list.len() - 1].0;
+        drawing.dimension(
+            (view.to_paper(first, 0.0).0, top + 15.0),
+            (view.to_paper(last, 0.0).0, top + 15.0),
+            last - first,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+    for pair in y_axis_list.windows(2) {
+        let (_, a) = view.to_paper(0.0, pair[0].0);
+        let (_, b) = view.to_paper(0.0, pair[1].0);
+        drawing.dimension(
+            (left - 8.0, a),
+            (left - 8.0, b),
+            pair[1].0 - pair[0].0,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+    if y_axis_list.len() > 2 {
+        let first = y_axis_list[0].0;
+        let last = y_axis_list[y_axis_list.len() - 1].0;
+        drawing.dimension(
+            (left - 15.0, view.to_paper(0.0, first).1),
+            (left - 15.0, view.to_paper(0.0, last).1),
+            last - first,
+            LABEL_TEXT,
+            dimension_pen,
+        );
+    }
+
+    // Slabs first so that the framing is drawn over their outlines.
+    for &m in &floor_list {
+        if let StbMember::StbSlab { .. } | StbMember::StbWall { .. } = m {
+            let outline: Vec<(f64, f64)> = m
+                .node_id_list()
+                .into_iter()
+                .filter_map(|id| stb_model.stb_nodes.get(id))
+                .map(|n| view.to_paper(n.x, n.y))
+                .collect();
+            if outline.len() < 3 {
+                continue;
+            }
+            let center = polygon_centroid(&outline);
+            drawing.polyline(outline, true, slab_pen);
+            drawing.text(
+                (center.0, center.1 - SLAB_TEXT * 0.35),
+                &m.name(),
+                SLAB_TEXT,
+                0.0,
+                Anchor::Middle,
+                BLUE,
+            );
+        }
+    }
+
+    for &m in &floor_list {
+        if !m.is_linear() {
+            continue;
+        }
+        let (ni, nj) = match member_ends(st_bridge, m) {
+            Some(ends) => ends,
+            None => continue,
+        };
+        let a = view.to_paper(ni.x, ni.y);
+        let b = view.to_paper(nj.x, nj.y);
+
+        let (pen, color) = match m {
+            StbMember::StbGirder { .. } => (girder_pen, BLACK),
+            StbMember::StbBrace { .. } => (brace_pen, RED),
+            _ => (beam_pen, BLACK),
+        };
+        drawing.line(a, b, pen);
+        label(&mut drawing, a, b, &section_name(st_bridge, m), color);
+    }
+
+    // Vertical braces show as their plan projection with a cross at the middle.
+    for &m in &vertical_brace_list {
+        let (ni, nj) = match member_ends(st_bridge, m) {
+            Some(ends) => ends,
+            None => continue,
+        };
+        let a = view.to_paper(ni.x, ni.y);
+        let b = view.to_paper(nj.x, nj.y);
+        let middle = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let size = 1.5;
+        drawing.line(
+            (middle.0 - size, middle.1 - size),
+            (middle.0 + size, middle.1 + size),
+            Pen::new(RED, 0.25),
+        );
+        drawing.line(
+            (middle.0 - size, middle.1 + size),
+            (middle.0 + size, middle.1 - size),
+            Pen::new(RED, 0.25),
+        );
+
+        if (b.0 - a.0).hypot(b.1 - a.1) > 1e-6 {
+            drawing.line(a, b, brace_pen);
+        }
+
+        // Below the projection, so it does not run into the girder label.
+        let angle = text_angle(a, b);
+        let (px, py) = (angle.to_radians().sin(), -angle.to_radians().cos());
+        drawing.text(
+            (
+                middle.0 + px * (size + LABEL_TEXT),
+                middle.1 + py * (size + LABEL_TEXT),
+            ),
+            &section_name(st_bridge, m),
+            LABEL_TEXT,
+            angle,
+            Anchor::Middle,
+            RED,
+        );
+    }
+
+    for &m in &column_list {
+        let footprint = match column_footprint(st_bridge, m, height) {
+            Some(f) => f,
+            None => continue,
+        };
+        let footprint: Vec<(f64, f64)> = footprint
+            .into_iter()
+            .map(|(x, y)| view.to_paper(x, y))
+            .collect();
+        let right_edge = footprint
+            .iter()
+            .map(|p| p.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let top_edge = footprint
+            .iter()
+            .map(|p| p.1)
+            .fold(f64::NEG_INFINITY, f64::max);
+        drawing.polyline(footprint, true, column_pen);
+        drawing.text(
+            (right_edge + 0.5, top_edge + 0.5),
+            &section_name(st_bridge, m),
+            LABEL_TEXT,
+            0.0,
+            Anchor::Start,
+            BLACK,
+        );
+    }
+
+    drawing.text(
+        (MARGIN, MARGIN / 3.0),
+        &format!(
+            "{} framing plan  FL {}  1:{}",
+            story.name,
+            height.round(),
+            scale.round()
+        ),
+        TITLE_TEXT,
+        0.0,
+        Anchor::Start,
+        BLACK,
+    );
+
+    drawing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn text_list(svg: &str) -> Vec<String> {
+        let document = roxmltree::Document::parse(svg).unwrap();
+        document
+            .descendants()
+            .filter(|n| n.has_tag_name("text"))
+            .map(|n| n.text().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn one_plan_per_story() {
+        let drawing_list = framing_plan_list(&sample(), 200.0);
+
+        let name_list: Vec<&str> = drawing_list.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(name_list, ["1", "2", "3", "R", "PHR"]);
+    }
+
+    #[test]
+    fn second_floor_svg() {
+        let st_bridge = sample();
+        let story = &st_bridge.stb_model.stb_stories.stb_story_list[1];
+
+        let svg = framing_plan(&st_bridge, story, 200.0).to_svg();
+
+        let document = roxmltree::Document::parse(&svg).unwrap();
+        let root = document.root_element();
+        assert_eq!(root.tag_name().name(), "svg");
+        assert!(root.attribute("width").unwrap().ends_with("mm"));
+        // A bubble at the end of each of the 3 X and 5 Y axes.
+        assert_eq!(
+            document
+                .descendants()
+                .filter(|n| n.has_tag_name("circle"))
+                .count(),
+            8
+        );
+
+        let text_list = text_list(&svg);
+        let count = |text: &str| text_list.iter().filter(|t| *t == text).count();
+        assert!(text_list.contains(&"2 framing plan  FL 4000  1:200".to_string()));
+        assert_eq!(count("C1"), 10);
+        assert_eq!(count("G1"), 5);
+        assert_eq!(count("G2"), 8);
+        assert_eq!(count("22680"), 1);
+    }
+
+    #[test]
+    fn scale_sets_the_paper_size() {
+        let st_bridge = sample();
+        let story = &st_bridge.stb_model.stb_stories.stb_story_list[1];
+
+        let small = framing_plan(&st_bridge, story, 200.0);
+        let large = framing_plan(&st_bridge, story, 100.0);
+
+        assert!(large.width > small.width && large.height > small.height);
+        assert_eq!(large.element_list.len(), small.element_list.len());
+    }
+}
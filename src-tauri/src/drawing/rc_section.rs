@@ -0,0 +1,726 @@
+use super::{Anchor, Drawing, Pen, BLACK, BLUE, LABEL_TEXT, TITLE_TEXT};
+use crate::section_property::{
+    bar_diameter, between, rect_column_bar_list, spaced, DEFAULT_COVER, LAYER_SPACING,
+};
+use crate::st_bridge::stb_model::stb_sections::{
+    bar_text, layered_bar_text, pitched_bar_text, BeamBars, StbSecRectColumnSame, StbSection,
+};
+use crate::st_bridge::StBridge;
+
+pub const DEFAULT_SECTION_SCALE: f64 = 10.0;
+
+// Paper space around each section for dimensions on the left and below, the bar list on the
+// right and the position name above (mm).
+const LEFT_SPACE: f64 = 16.0;
+const RIGHT_SPACE: f64 = 48.0;
+const BOTTOM_SPACE: f64 = 14.0;
+const TOP_SPACE: f64 = 10.0;
+const HEADER: f64 = 12.0;
+
+const CONCRETE_FILL: [u8; 3] = [235, 235, 235];
+
+// Nominal diameter of a deformed bar, or the number in its name for sizes not in the table.
+fn diameter(d: &str) -> f64 {
+    bar_diameter(d).unwrap_or_else(|| {
+        d.trim_start_matches(|c: char| !c.is_ascii_digit())
+            .parse()
+            .unwrap_or(10.0)
+    })
+}
+
+// Faces in the order left, right, bottom, top, measured from the concrete to the main bar centers.
+type Covers = [f64; 4];
+
+// One cross-section with its concrete centered at `center` on the paper.
+struct Panel<'a> {
+    drawing: &'a mut Drawing,
+    center: (f64, f64),
+    scale: f64,
+}
+
+impl Panel<'_> {
+    fn to_paper(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.center.0 + x / self.scale,
+            self.center.1 + y / self.scale,
+        )
+    }
+
+    fn rectangle(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, pen: Pen) {
+        let point_list = vec![
+            self.to_paper(x0, y0),
+            self.to_paper(x1, y0),
+            self.to_paper(x1, y1),
+            self.to_paper(x0, y1),
+        ];
+        self.drawing.polyline(point_list, true, pen);
+    }
+
+    fn line(&mut self, a: (f64, f64), b: (f64, f64), d: f64) {
+        let a = self.to_paper(a.0, a.1);
+        let b = self.to_paper(b.0, b.1);
+        self.drawing.line(a, b, Pen::new(BLUE, d / self.scale));
+    }
+
+    fn bar(&mut self, x: f64, y: f64, d: f64) {
+        let p = self.to_paper(x, y);
+        self.drawing
+            .circle(p, d / 2.0 / self.scale, Pen::new(BLACK, 0.1).filled(BLACK));
+    }
+
+    // Concrete outline with its width dimensioned below and its depth on the left.
+    fn concrete(&mut self, width: f64, depth: f64) {
+        self.rectangle(
+            -width / 2.0,
+            -depth / 2.0,
+            width / 2.0,
+            depth / 2.0,
+            Pen::new(BLACK, 0.35).filled(CONCRETE_FILL),
+        );
+
+        let pen = Pen::new(BLACK, 0.13);
+        let (left, bottom) = self.to_paper(-width / 2.0, -depth / 2.0);
+        let (right, top) = self.to_paper(width / 2.0, depth / 2.0);
+        self.drawing.dimension(
+            (left, bottom - 6.0),
+            (right, bottom - 6.0),
+            width,
+            LABEL_TEXT,
+            pen,
+        );
+        self.drawing.dimension(
+            (left - 6.0, bottom),
+            (left - 6.0, top),
+            depth,
+            LABEL_TEXT,
+            pen,
+        );
+    }
+
+    fn title(&mut self, text: &str, depth: f64) {
+        let (x, y) = self.to_paper(0.0, depth / 2.0);
+        self.drawing.text(
+            (x, y + 4.0),
+            text,
+            LABEL_TEXT * 1.4,
+            0.0,
+            Anchor::Middle,
+            BLACK,
+        );
+    }
+
+    // Labelled bar descriptions listed to the right of the section, top down, leaving out those
+    // without bars.
+    fn bar_list(&mut self, width: f64, depth: f64, line_list: &[(&str, String)]) {
+        let (x, y) = self.to_paper(width / 2.0, depth / 2.0);
+        let line_list = line_list.iter().filter(|(_, text)| !text.is_empty());
+        for (i, (label, text)) in line_list.enumerate() {
+            self.drawing.text(
+                (x + 6.0, y - LABEL_TEXT * (1.0 + 1.6 * i as f64)),
+                &format!("{} {}", label, text),
+                LABEL_TEXT,
+                0.0,
+                Anchor::Start,
+                BLACK,
+            );
+        }
+    }
+}
+
+// Main bars along the top and bottom with any 2nd layer one layer spacing further in, web bars in
+// pairs up the sides, a stirrup around the main bars with extra legs between them, and bar
+// spacers across at the web bars.
+#[allow(clippy::too_many_arguments)]
+fn beam_panel(
+    panel: &mut Panel,
+    (width, depth): (f64, f64),
+    cover: Covers,
+    d_main: &str,
+    d_stirrup: &str,
+    d_web: &str,
+    d_bar_spacing: &str,
+    bars: &BeamBars,
+) {
+    panel.concrete(width, depth);
+    panel.title(&bars.position, depth);
+
+    let (dm, ds, dw) = (diameter(d_main), diameter(d_stirrup), diameter(d_web));
+    let x0 = -width / 2.0 + cover[0];
+    let x1 = width / 2.0 - cover[1];
+    let y0 = -depth / 2.0 + cover[2];
+    let y1 = depth / 2.0 - cover[3];
+
+    // Stirrup center line, touching the outside of the main bars.
+    let offset = (dm + ds) / 2.0;
+    let (sx0, sx1, sy0, sy1) = (x0 - offset, x1 + offset, y0 - offset, y1 + offset);
+    if bars.stirrup > 0 {
+        panel.rectangle(sx0, sy0, sx1, sy1, Pen::new(BLUE, ds / panel.scale));
+        for x in between(x0, x1, bars.stirrup.saturating_sub(2)) {
+            panel.line((x, sy0), (x, sy1), ds);
+        }
+    }
+
+    let row_list = between(y0, y1, (bars.web + 1) / 2);
+    let mut web = bars.web;
+    for &y in &row_list {
+        for &x in &[x0, x1] {
+            if web > 0 {
+                panel.bar(x, y, dw);
+                web -= 1;
+            }
+        }
+        if bars.bar_spacing > 0 {
+            panel.line((sx0, y), (sx1, y), diameter(d_bar_spacing));
+        }
+    }
+
+    let spacing = LAYER_SPACING * dm;
+    for (count, y) in [
+        (bars.top_1st, y1),
+        (bars.top_2nd, y1 - spacing),
+        (bars.bottom_1st, y0),
+        (bars.bottom_2nd, y0 + spacing),
+    ] {
+        for x in spaced(x0, x1, count) {
+            panel.bar(x, y, dm);
+        }
+    }
+
+    panel.bar_list(
+        width,
+        depth,
+        &[
+            ("Top", layered_bar_text(bars.top_1st, bars.top_2nd, d_main)),
+            (
+                "Bottom",
+                layered_bar_text(bars.bottom_1st, bars.bottom_2nd, d_main),
+            ),
+            (
+                "Stirrup",
+                pitched_bar_text(bars.stirrup, d_stirrup, bars.pitch_stirrup),
+            ),
+            ("Web", bar_text(bars.web, d_web)),
+            (
+                "Spacer",
+                pitched_bar_text(bars.bar_spacing, d_bar_spacing, bars.pitch_bar_spacing),
+            ),
+        ],
+    );
+}
+
+// The main bars as laid out for section properties, a hoop around them and extra legs at the
+// intermediate bars: legs along X count toward count_band_dir_x.
+fn rect_column_panel(
+    panel: &mut Panel,
+    (width_x, width_y): (f64, f64),
+    cover: Covers,
+    d_main: &str,
+    d_band: &str,
+    bars: &StbSecRectColumnSame,
+) {
+    panel.concrete(width_x, width_y);
+
+    let (dm, db) = (diameter(d_main), diameter(d_band));
+    let x0 = -width_x / 2.0 + cover[0];
+    let x1 = width_x / 2.0 - cover[1];
+    let y0 = -width_y / 2.0 + cover[2];
+    let y1 = width_y / 2.0 - cover[3];
+
+    let offset = (dm + db) / 2.0;
+    let (hx0, hx1, hy0, hy1) = (x0 - offset, x1 + offset, y0 - offset, y1 + offset);
+    panel.rectangle(hx0, hy0, hx1, hy1, Pen::new(BLUE, db / panel.scale));
+
+    let x_list = spaced(x0, x1, bars.count_main_x_1st.max(2));
+    let y_list = spaced(y0, y1, bars.count_main_y_1st.max(2));
+    for leg in between(y0, y1, bars.count_band_dir_x.saturating_sub(2)) {
+        // Snap each leg to the nearest bar row so that it holds the bars.
+        let y = y_list
+            .iter()
+            .cloned()
+            .min_by(|a, b| (a - leg).abs().partial_cmp(&(b - leg).abs()).unwrap())
+            .unwrap_or(leg);
+        panel.line((hx0, y), (hx1, y), db);
+    }
+    for leg in between(x0, x1, bars.count_band_dir_y.saturating_sub(2)) {
+        let x = x_list
+            .iter()
+            .cloned()
+            .min_by(|a, b| (a - leg).abs().partial_cmp(&(b - leg).abs()).unwrap())
+            .unwrap_or(leg);
+        panel.line((x, hy0), (x, hy1), db);
+    }
+
+    for (x, y) in rect_column_bar_list((width_x, width_y), cover, d_main, bars) {
+        panel.bar(x, y, dm);
+    }
+
+    panel.bar_list(
+        width_x,
+        width_y,
+        &[
+            ("Main", bar_text(bars.count_main_total, d_main)),
+            (
+                "Hoop",
+                format!(
+                    "{}x{}-{}@{}",
+                    bars.count_band_dir_x, bars.count_band_dir_y, d_band, bars.pitch_band
+                ),
+            ),
+        ],
+    );
+}
+
+fn circle_column_panel(
+    panel: &mut Panel,
+    d: f64,
+    cover: f64,
+    d_main: &str,
+    d_band: &str,
+    (count_main, count_band, pitch_band): (u32, u32, f64),
+) {
+    let (dm, db) = (diameter(d_main), diameter(d_band));
+    let center = panel.to_paper(0.0, 0.0);
+    panel.drawing.circle(
+        center,
+        d / 2.0 / panel.scale,
+        Pen::new(BLACK, 0.35).filled(CONCRETE_FILL),
+    );
+    let pen = Pen::new(BLACK, 0.13);
+    panel.drawing.dimension(
+        (
+            center.0 - d / 2.0 / panel.scale,
+            center.1 - d / 2.0 / panel.scale - 6.0,
+        ),
+        (
+            center.0 + d / 2.0 / panel.scale,
+            center.1 - d / 2.0 / panel.scale - 6.0,
+        ),
+        d,
+        LABEL_TEXT,
+        pen,
+    );
+
+    let radius = d / 2.0 - cover;
+    panel.drawing.circle(
+        center,
+        (radius + (dm + db) / 2.0) / panel.scale,
+        Pen::new(BLUE, db / panel.scale),
+    );
+    for i in 0..count_main {
+        let angle = std::f64::consts::PI * 2.0 * i as f64 / count_main as f64;
+        panel.bar(radius * angle.cos(), radius * angle.sin(), dm);
+    }
+
+    panel.bar_list(
+        d,
+        d,
+        &[
+            ("Main", bar_text(count_main, d_main)),
+            ("Hoop", pitched_bar_text(count_band, d_band, pitch_band)),
+        ],
+    );
+}
+
+pub fn rc_section_drawing_list(st_bridge: &StBridge, scale: f64) -> Vec<Drawing> {
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    let mut section_list: Vec<&StbSection> = stb_sections
+        .column_rc_map
+        .values()
+        .chain(stb_sections.beam_rc_map.values())
+        .collect();
+    section_list.sort_by_key(|s| s.id());
+
+    section_list
+        .into_iter()
+        .filter_map(|s| rc_section_drawing(s, scale))
+        .collect()
+}
+
+pub fn write_rc_sections(st_bridge: &StBridge, file_name: &str, scale: f64) -> std::io::Result<()> {
+    super::write_drawing_list(&rc_section_drawing_list(st_bridge, scale), file_name)
+}
+
+// The bar arrangement of an RC beam at each of its positions side by side, or of an RC column.
+// Covers are to the main bar centers, as for section properties. None for other sections and for
+// sections without a figure or bar arrangement.
+pub fn rc_section_drawing(stb_section: &StbSection, scale: f64) -> Option<Drawing> {
+    match stb_section {
+        StbSection::StbSecBeamRC {
+            id,
+            name,
+            floor,
+            d_reinforcement_main,
+            d_stirrup,
+            d_reinforcement_web,
+            d_bar_spacing,
+            depth_cover_left,
+            depth_cover_right,
+            depth_cover_top,
+            depth_cover_bottom,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } => {
+            let bars_list = stb_sec_bar_arrangement.beam_bars_list();
+            let size_list: Vec<(f64, f64)> = bars_list
+                .iter()
+                .map(|bars| {
+                    match (
+                        &stb_sec_figure.stb_sec_haunch,
+                        &stb_sec_figure.stb_sec_straight,
+                    ) {
+                        (Some(haunch), _) => Some(haunch.size(&bars.position)),
+                        (None, Some(straight)) => Some((straight.width, straight.depth)),
+                        (None, None) => None,
+                    }
+                })
+                .collect::<Option<Vec<(f64, f64)>>>()?;
+            if size_list.is_empty() {
+                return None;
+            }
+
+            let cover = [
+                depth_cover_left.unwrap_or(DEFAULT_COVER),
+                depth_cover_right.unwrap_or(DEFAULT_COVER),
+                depth_cover_bottom.unwrap_or(DEFAULT_COVER),
+                depth_cover_top.unwrap_or(DEFAULT_COVER),
+            ];
+
+            let depth = size_list.iter().map(|s| s.1).fold(0.0, f64::max) / scale;
+            let panel_width_list: Vec<f64> = size_list
+                .iter()
+                .map(|s| LEFT_SPACE + s.0 / scale + RIGHT_SPACE)
+                .collect();
+            let mut drawing = Drawing::new(
+                &format!("{}_{}", name, id),
+                panel_width_list.iter().sum::<f64>(),
+                HEADER + TOP_SPACE + depth + BOTTOM_SPACE,
+            );
+            header(&mut drawing, name, floor, scale);
+
+            let mut left = 0.0;
+            for ((bars, &size), panel_width) in
+                bars_list.iter().zip(&size_list).zip(panel_width_list)
+            {
+                let mut panel = Panel {
+                    drawing: &mut drawing,
+                    center: (
+                        left + LEFT_SPACE + size.0 / scale / 2.0,
+                        BOTTOM_SPACE + depth / 2.0,
+                    ),
+                    scale,
+                };
+                beam_panel(
+                    &mut panel,
+                    size,
+                    cover,
+                    d_reinforcement_main,
+                    d_stirrup,
+                    d_reinforcement_web,
+                    d_bar_spacing,
+                    bars,
+                );
+                left += panel_width;
+            }
+
+            Some(drawing)
+        }
+        StbSection::StbSecColumnRC {
+            id,
+            name,
+            floor,
+            d_reinforcement_main,
+            d_rein_band,
+            depth_cover_start_x,
+            depth_cover_end_x,
+            depth_cover_start_y,
+            depth_cover_end_y,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } => {
+            let (width, depth) =
+                match (&stb_sec_figure.stb_sec_rect, &stb_sec_figure.stb_sec_circle) {
+                    (Some(rect), _) => (rect.width_x, rect.width_y),
+                    (None, Some(circle)) => (circle.d, circle.d),
+                    (None, None) => return None,
+                };
+
+            let mut drawing = Drawing::new(
+                &format!("{}_{}", name, id),
+                LEFT_SPACE + width / scale + RIGHT_SPACE,
+                HEADER + TOP_SPACE + depth / scale + BOTTOM_SPACE,
+            );
+            header(&mut drawing, name, floor, scale);
+            let mut panel = Panel {
+                drawing: &mut drawing,
+                center: (
+                    LEFT_SPACE + width / scale / 2.0,
+                    BOTTOM_SPACE + depth / scale / 2.0,
+                ),
+                scale,
+            };
+
+            match (
+                &stb_sec_figure.stb_sec_rect,
+                &stb_sec_bar_arrangement.stb_sec_rect_column_same,
+                &stb_sec_bar_arrangement.stb_sec_circle_column_same,
+            ) {
+                (Some(rect), Some(bars), _) => rect_column_panel(
+                    &mut panel,
+                    (rect.width_x, rect.width_y),
+                    [
+                        depth_cover_start_x.unwrap_or(DEFAULT_COVER),
+                        depth_cover_end_x.unwrap_or(DEFAULT_COVER),
+                        depth_cover_start_y.unwrap_or(DEFAULT_COVER),
+                        depth_cover_end_y.unwrap_or(DEFAULT_COVER),
+                    ],
+                    d_reinforcement_main,
+                    d_rein_band,
+                    bars,
+                ),
+                (None, _, Some(bars)) => circle_column_panel(
+                    &mut panel,
+                    width,
+                    depth_cover_start_x.unwrap_or(DEFAULT_COVER),
+                    d_reinforcement_main,
+                    d_rein_band,
+                    (bars.count_main, bars.count_band, bars.pitch_band),
+                ),
+                _ => return None,
+            }
+
+            Some(drawing)
+        }
+        _ => None,
+    }
+}
+
+fn header(drawing: &mut Drawing, name: &str, floor: &str, scale: f64) {
+    let title = if floor.is_empty() {
+        format!("{}  1:{}", name, scale.round())
+    } else {
+        format!("{}  {}F  1:{}", name, floor, scale.round())
+    };
+    let y = drawing.height - HEADER / 2.0 - TITLE_TEXT * 0.35;
+    drawing.text((4.0, y), &title, TITLE_TEXT, 0.0, Anchor::Start, BLACK);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Shape;
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const SCALE: f64 = 10.0;
+
+    fn portal_frame() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        ))
+        .unwrap()
+    }
+
+    fn drawing(name: &str) -> Drawing {
+        rc_section_drawing_list(&portal_frame(), SCALE)
+            .into_iter()
+            .find(|d| d.name == name)
+            .unwrap()
+    }
+
+    // Centers of the bars of diameter d within half a width of a panel center on the paper, in
+    // tenths of mm of the section from that center.
+    fn bar_list(drawing: &Drawing, center: (f64, f64), width: f64, d: &str) -> Vec<(i64, i64)> {
+        let radius = diameter(d) / 2.0 / SCALE;
+        let mut bar_list: Vec<(i64, i64)> = drawing
+            .element_list
+            .iter()
+            .filter(|e| e.pen.fill == Some(BLACK))
+            .filter_map(|e| match e.shape {
+                Shape::Circle {
+                    center: c,
+                    radius: r,
+                } if (r - radius).abs() < 1e-9 => Some(c),
+                _ => None,
+            })
+            .filter(|c| (c.0 - center.0).abs() < width / SCALE / 2.0)
+            .map(|c| {
+                (
+                    ((c.0 - center.0) * SCALE * 10.0).round() as i64,
+                    ((c.1 - center.1) * SCALE * 10.0).round() as i64,
+                )
+            })
+            .collect();
+        bar_list.sort_unstable();
+        bar_list
+    }
+
+    // Bar counts by height in the section.
+    fn row_map(bar_list: &[(i64, i64)]) -> BTreeMap<i64, usize> {
+        let mut row_map = BTreeMap::new();
+        for &(_, y) in bar_list {
+            *row_map.entry(y).or_insert(0) += 1;
+        }
+        row_map
+    }
+
+    // Texts starting from x0 to x1 on the paper, top down.
+    fn text_list(drawing: &Drawing, x0: f64, x1: f64) -> Vec<String> {
+        let mut text_list: Vec<(f64, String)> = drawing
+            .element_list
+            .iter()
+            .filter_map(|e| match &e.shape {
+                Shape::Text { position, text, .. } if position.0 >= x0 && position.0 < x1 => {
+                    Some((-position.1, text.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        text_list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        text_list.into_iter().map(|(_, text)| text).collect()
+    }
+
+    #[test]
+    fn girder_positions_side_by_side() {
+        let drawing = drawing("G1_2");
+        let panel_width = LEFT_SPACE + 400.0 / SCALE + RIGHT_SPACE;
+        assert!((drawing.width - 3.0 * panel_width).abs() < 1e-9);
+
+        // Main bars 300 above and below the middle with any 2nd layer 2.5 D22 further in, and a
+        // pair of web bars at mid-depth 150 to either side.
+        let second = (10.0 * (300.0 - LAYER_SPACING * 22.2)).round() as i64;
+        let position_list = [
+            (
+                "START",
+                vec![(-3000, 3), (second, 2), (3000, 4)],
+                ["Top 4+2-D22", "Bottom 3-D22", "Stirrup 2-D10@100"],
+            ),
+            (
+                "CENTER",
+                vec![(-3000, 3), (-second, 2), (3000, 3)],
+                ["Top 3-D22", "Bottom 3+2-D22", "Stirrup 2-D10@200"],
+            ),
+            (
+                "END",
+                vec![(-3000, 3), (second, 2), (3000, 4)],
+                ["Top 4+2-D22", "Bottom 3-D22", "Stirrup 2-D10@100"],
+            ),
+        ];
+
+        for (i, (position, row_list, [top, bottom, stirrup])) in position_list.iter().enumerate() {
+            let left = i as f64 * panel_width;
+            let center = (
+                left + LEFT_SPACE + 400.0 / SCALE / 2.0,
+                BOTTOM_SPACE + 700.0 / SCALE / 2.0,
+            );
+
+            let main = bar_list(&drawing, center, 400.0, "D22");
+            assert_eq!(
+                row_map(&main),
+                row_list.iter().cloned().collect(),
+                "{}",
+                position
+            );
+            assert_eq!(
+                bar_list(&drawing, center, 400.0, "D10"),
+                [(-1500, 0), (1500, 0)],
+                "{}",
+                position
+            );
+
+            let mut expected = Vec::new();
+            if i == 0 {
+                expected.push("G1  2F  1:10");
+            }
+            expected.extend(&[
+                position,
+                top,
+                bottom,
+                stirrup,
+                "Web 2-D10",
+                "Spacer 1-D10@1000",
+                "700",
+                "400",
+            ]);
+            assert_eq!(text_list(&drawing, left, left + panel_width), expected);
+        }
+    }
+
+    #[test]
+    fn column_with_2nd_layer_bars() {
+        let drawing = drawing("C1_1");
+        let center = (
+            LEFT_SPACE + 600.0 / SCALE / 2.0,
+            BOTTOM_SPACE + 600.0 / SCALE / 2.0,
+        );
+
+        // Four bars a face 250 out from the middle, corners shared, and two 2nd layer bars a face
+        // 2.5 D22 in, spread between the 2nd layer corners: 20 in all.
+        let spacing = LAYER_SPACING * 22.2;
+        let (a, b, c, d) = (
+            2500,
+            (10.0 * (250.0 - spacing)).round() as i64,
+            833,
+            (10.0 * (500.0 - 2.0 * spacing) / 6.0).round() as i64,
+        );
+        let mut expected = Vec::new();
+        for &x in &[-a, -c, c, a] {
+            expected.push((x, -a));
+            expected.push((x, a));
+        }
+        for &y in &[-c, c] {
+            expected.push((-a, y));
+            expected.push((a, y));
+        }
+        for &u in &[-d, d] {
+            expected.extend(&[(-b, u), (b, u), (u, -b), (u, b)]);
+        }
+        expected.sort_unstable();
+        let main = bar_list(&drawing, center, 600.0, "D22");
+        assert_eq!(main, expected);
+
+        // No two bars overlap: centres are at least a bar diameter apart.
+        for (i, p) in main.iter().enumerate() {
+            for q in &main[i + 1..] {
+                let distance = (((p.0 - q.0).pow(2) + (p.1 - q.1).pow(2)) as f64).sqrt();
+                assert!(distance >= 222.0, "{:?} and {:?}", p, q);
+            }
+        }
+
+        assert_eq!(
+            text_list(&drawing, 0.0, drawing.width),
+            [
+                "C1  1F  1:10",
+                "Main 20-D22",
+                "Hoop 2x2-D10@100",
+                "600",
+                "600"
+            ]
+        );
+    }
+
+    #[test]
+    fn other_sections_are_not_drawn() {
+        let st_bridge = portal_frame();
+        let name_list: Vec<String> = rc_section_drawing_list(&st_bridge, SCALE)
+            .into_iter()
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(name_list, ["C1_1", "G1_2"]);
+
+        let brace = st_bridge.stb_model.stb_sections.get(3).unwrap();
+        assert!(rc_section_drawing(brace, SCALE).is_none());
+    }
+
+    #[test]
+    fn spaced_and_between() {
+        assert!(spaced(0.0, 10.0, 0).is_empty());
+        assert_eq!(spaced(0.0, 10.0, 1), [5.0]);
+        assert_eq!(spaced(0.0, 10.0, 3), [0.0, 5.0, 10.0]);
+        assert_eq!(between(0.0, 10.0, 1), [5.0]);
+        assert!(between(0.0, 10.0, 0).is_empty());
+        assert_eq!(diameter("D22"), 22.2);
+        assert_eq!(diameter("X16"), 16.0);
+    }
+}
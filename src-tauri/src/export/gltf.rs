@@ -0,0 +1,278 @@
+use super::structure_kind_color;
+use crate::geometry::{self, Mesh};
+use crate::st_bridge::StBridge;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::prelude::*;
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+pub fn write_glb(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let mut f = File::create(file_name)?;
+    f.write_all(&create_glb(st_bridge))
+}
+
+pub fn create_glb(st_bridge: &StBridge) -> Vec<u8> {
+    let mut mesh_list = geometry::create_member_mesh_list(st_bridge);
+    mesh_list.extend(geometry::create_slab_mesh_list(st_bridge));
+    mesh_list.extend(geometry::create_wall_mesh_list(st_bridge));
+
+    let (document, bin) = create_document(st_bridge, &mesh_list);
+
+    let mut json = serde_json::to_vec(&document).unwrap();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut glb = Vec::with_capacity(total_length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(total_length as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(&json);
+    glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend_from_slice(&bin);
+
+    glb
+}
+
+fn create_document(st_bridge: &StBridge, mesh_list: &[Mesh]) -> (Value, Vec<u8>) {
+    let stb_model = &st_bridge.stb_model;
+
+    let mut bin: Vec<u8> = Vec::new();
+    let mut nodes = Vec::new();
+    let mut meshes = Vec::new();
+    let mut accessors = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut material_kinds: Vec<String> = Vec::new();
+
+    for mesh in mesh_list {
+        let stb_member = match stb_model.stb_members.get(mesh.id) {
+            Some(m) => m,
+            None => continue,
+        };
+
+        let kind_structure = stb_member.kind_structure();
+        let material = match material_kinds.iter().position(|k| *k == kind_structure) {
+            Some(i) => i,
+            None => {
+                material_kinds.push(kind_structure);
+                material_kinds.len() - 1
+            }
+        };
+
+        // ST-Bridge is Z-up in millimetres, glTF is Y-up in metres.
+        let positions: Vec<[f32; 3]> = mesh
+            .vertices
+            .iter()
+            .map(|v| {
+                [
+                    (v.x / 1000.0) as f32,
+                    (v.z / 1000.0) as f32,
+                    (-v.y / 1000.0) as f32,
+                ]
+            })
+            .collect();
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for p in &positions {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+
+        let position_offset = bin.len();
+        for p in &positions {
+            for c in p {
+                bin.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": position_offset,
+            "byteLength": bin.len() - position_offset,
+            "target": ARRAY_BUFFER,
+        }));
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": FLOAT,
+            "count": positions.len(),
+            "type": "VEC3",
+            "min": min,
+            "max": max,
+        }));
+        let position_accessor = accessors.len() - 1;
+
+        let index_offset = bin.len();
+        for triangle in &mesh.triangles {
+            for &i in triangle {
+                bin.extend_from_slice(&(i as u32).to_le_bytes());
+            }
+        }
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": index_offset,
+            "byteLength": bin.len() - index_offset,
+            "target": ELEMENT_ARRAY_BUFFER,
+        }));
+        accessors.push(json!({
+            "bufferView": buffer_views.len() - 1,
+            "componentType": UNSIGNED_INT,
+            "count": mesh.triangles.len() * 3,
+            "type": "SCALAR",
+        }));
+        let index_accessor = accessors.len() - 1;
+
+        let name = format!("{}_{}", stb_member.name(), stb_member.id());
+
+        meshes.push(json!({
+            "name": name,
+            "primitives": [{
+                "attributes": { "POSITION": position_accessor },
+                "indices": index_accessor,
+                "material": material,
+            }],
+        }));
+
+        let section = match stb_model.stb_sections.get(stb_member.id_section()) {
+            Some(s) => Value::String(s.name()),
+            None => Value::Null,
+        };
+        let story = match stb_model.story(stb_member) {
+            Some(s) => Value::String(s.name.clone()),
+            None => Value::Null,
+        };
+
+        nodes.push(json!({
+            "name": name,
+            "mesh": meshes.len() - 1,
+            "extras": {
+                "id": stb_member.id(),
+                "section": section,
+                "story": story,
+            },
+        }));
+    }
+
+    let materials: Vec<Value> = material_kinds
+        .iter()
+        .map(|kind| {
+            json!({
+                "name": kind,
+                "pbrMetallicRoughness": {
+                    "baseColorFactor": structure_kind_color(kind),
+                    "metallicFactor": if kind == "S" { 0.5 } else { 0.0 },
+                    "roughnessFactor": 0.8,
+                },
+                "doubleSided": false,
+            })
+        })
+        .collect();
+
+    let document = json!({
+        "asset": {
+            "version": "2.0",
+            "generator": "stb-viewer",
+        },
+        "scene": 0,
+        "scenes": [{ "nodes": (0..nodes.len()).collect::<Vec<usize>>() }],
+        "nodes": nodes,
+        "meshes": meshes,
+        "materials": materials,
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{ "byteLength": bin.len() }],
+    });
+
+    (document, bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> usize {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+    }
+
+    // The JSON and BIN chunks of a GLB file, checking the header and chunk framing on the way.
+    fn chunks(glb: &[u8]) -> (Value, &[u8]) {
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(u32_at(glb, 4), 2);
+        assert_eq!(u32_at(glb, 8), glb.len());
+
+        let json_length = u32_at(glb, 12);
+        assert_eq!(&glb[16..20], b"JSON");
+        assert_eq!(json_length % 4, 0);
+        let json = serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+
+        let bin_start = 20 + json_length;
+        let bin_length = u32_at(glb, bin_start);
+        assert_eq!(&glb[bin_start + 4..bin_start + 8], b"BIN\0");
+        assert_eq!((bin_start + 8) % 4, 0);
+        assert_eq!(bin_length % 4, 0);
+        assert_eq!(bin_start + 8 + bin_length, glb.len());
+
+        (json, &glb[bin_start + 8..])
+    }
+
+    #[test]
+    fn glb_chunks_are_framed_and_aligned() {
+        let glb = create_glb(&sample());
+        let (json, bin) = chunks(&glb);
+
+        assert_eq!(json["asset"]["version"], "2.0");
+        assert_eq!(json["buffers"][0]["byteLength"], bin.len());
+        for buffer_view in json["bufferViews"].as_array().unwrap() {
+            let offset = buffer_view["byteOffset"].as_u64().unwrap() as usize;
+            let length = buffer_view["byteLength"].as_u64().unwrap() as usize;
+            assert_eq!(offset % 4, 0);
+            assert!(offset + length <= bin.len());
+        }
+    }
+
+    #[test]
+    fn accessors_match_the_meshes() {
+        let st_bridge = sample();
+        let mut mesh_list = geometry::create_member_mesh_list(&st_bridge);
+        mesh_list.extend(geometry::create_slab_mesh_list(&st_bridge));
+        mesh_list.extend(geometry::create_wall_mesh_list(&st_bridge));
+
+        let glb = create_glb(&st_bridge);
+        let (json, _) = chunks(&glb);
+        let meshes = json["meshes"].as_array().unwrap();
+        let accessors = json["accessors"].as_array().unwrap();
+        assert_eq!(meshes.len(), mesh_list.len());
+        assert_eq!(json["nodes"].as_array().unwrap().len(), mesh_list.len());
+
+        for (mesh, gltf_mesh) in mesh_list.iter().zip(meshes) {
+            let primitive = &gltf_mesh["primitives"][0];
+            let position =
+                &accessors[primitive["attributes"]["POSITION"].as_u64().unwrap() as usize];
+            let indices = &accessors[primitive["indices"].as_u64().unwrap() as usize];
+
+            assert_eq!(position["count"], mesh.vertices.len());
+            assert_eq!(position["type"], "VEC3");
+            assert_eq!(indices["count"], mesh.triangles.len() * 3);
+            assert_eq!(indices["type"], "SCALAR");
+        }
+    }
+}
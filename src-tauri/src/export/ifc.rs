@@ -0,0 +1,984 @@
+use crate::geometry::{self, Node, Plane};
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::stb_model::stb_sections::{
+    center_steel_beam, StbSecSteelChildren, StbSection,
+};
+use crate::st_bridge::StBridge;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const GUID_CHARS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_$";
+
+pub fn write_ifc(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let mut f = File::create(file_name)?;
+    f.write_all(create_ifc(st_bridge, file_name).as_bytes())
+}
+
+pub fn create_ifc(st_bridge: &StBridge, file_name: &str) -> String {
+    let mut writer = IfcWriter::new(model_seed(st_bridge));
+    writer.write_model(st_bridge);
+
+    let mut ifc = String::new();
+    ifc.push_str("ISO-10303-21;\n");
+    ifc.push_str("HEADER;\n");
+    ifc.push_str("FILE_DESCRIPTION(('ViewDefinition [ReferenceView_V1.2]'),'2;1');\n");
+    ifc.push_str(&format!(
+        "FILE_NAME({},'{}',(''),(''),'stb-viewer','stb-viewer','');\n",
+        string(file_name),
+        timestamp()
+    ));
+    ifc.push_str("FILE_SCHEMA(('IFC4'));\n");
+    ifc.push_str("ENDSEC;\n");
+    ifc.push_str("DATA;\n");
+    for (i, entity) in writer.entities.iter().enumerate() {
+        ifc.push_str(&format!("#{}={};\n", i + 1, entity));
+    }
+    ifc.push_str("ENDSEC;\n");
+    ifc.push_str("END-ISO-10303-21;\n");
+
+    ifc
+}
+
+struct IfcWriter {
+    entities: Vec<String>,
+    guid_seed: u64,
+    origin: usize,
+    z_axis: usize,
+    x_axis: usize,
+    identity: usize,
+    body_context: usize,
+    footprint_context: usize,
+}
+
+impl IfcWriter {
+    fn new(guid_seed: u64) -> IfcWriter {
+        let mut writer = IfcWriter {
+            entities: Vec::new(),
+            guid_seed,
+            origin: 0,
+            z_axis: 0,
+            x_axis: 0,
+            identity: 0,
+            body_context: 0,
+            footprint_context: 0,
+        };
+
+        writer.origin = writer.add("IFCCARTESIANPOINT((0.,0.,0.))".to_string());
+        writer.z_axis = writer.add("IFCDIRECTION((0.,0.,1.))".to_string());
+        writer.x_axis = writer.add("IFCDIRECTION((1.,0.,0.))".to_string());
+        writer.identity = writer.add(format!(
+            "IFCAXIS2PLACEMENT3D(#{},#{},#{})",
+            writer.origin, writer.z_axis, writer.x_axis
+        ));
+
+        writer
+    }
+
+    fn add(&mut self, entity: String) -> usize {
+        self.entities.push(entity);
+        self.entities.len()
+    }
+
+    // IFC GlobalIds are 128 bits packed into 22 characters of a 64-character alphabet. They are
+    // derived from the model seed, the entity and the ST-Bridge id or name it stands for, so that
+    // an element keeps its id across re-exports of later revisions of the model.
+    fn guid(&self, entity: &str, key: impl std::fmt::Display) -> String {
+        let key = fnv1a(format!("{}:{}", entity, key).as_bytes());
+        let hi = splitmix64(self.guid_seed ^ key);
+        let lo = splitmix64(hi ^ key.rotate_left(32));
+        let n = ((hi as u128) << 64) | lo as u128;
+
+        let mut guid = String::with_capacity(22);
+        guid.push(GUID_CHARS[(n >> 126) as usize] as char);
+        for i in (0..21).rev() {
+            guid.push(GUID_CHARS[((n >> (6 * i)) & 63) as usize] as char);
+        }
+        format!("'{}'", guid)
+    }
+
+    fn point(&mut self, x: f64, y: f64, z: f64) -> usize {
+        self.add(format!(
+            "IFCCARTESIANPOINT(({},{},{}))",
+            real(x),
+            real(y),
+            real(z)
+        ))
+    }
+
+    fn point_2d(&mut self, x: f64, y: f64) -> usize {
+        self.add(format!("IFCCARTESIANPOINT(({},{}))", real(x), real(y)))
+    }
+
+    fn direction(&mut self, d: Node) -> usize {
+        self.add(format!(
+            "IFCDIRECTION(({},{},{}))",
+            real(d.x),
+            real(d.y),
+            real(d.z)
+        ))
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)]) -> usize {
+        let mut ids: Vec<usize> = points.iter().map(|&(x, y)| self.point_2d(x, y)).collect();
+        ids.push(ids[0]);
+        self.add(format!("IFCPOLYLINE({})", refs(&ids)))
+    }
+
+    fn local_placement(&mut self, relative_to: usize, plane: &Plane, elevation: f64) -> usize {
+        let location = self.point(plane.origin.x, plane.origin.y, plane.origin.z - elevation);
+        let axis = self.direction(plane.normal);
+        let ref_direction = self.direction(plane.x_axis);
+        let placement = self.add(format!(
+            "IFCAXIS2PLACEMENT3D(#{},#{},#{})",
+            location, axis, ref_direction
+        ));
+        self.add(format!(
+            "IFCLOCALPLACEMENT(#{},#{})",
+            relative_to, placement
+        ))
+    }
+
+    fn write_model(&mut self, st_bridge: &StBridge) {
+        let stb_model = &st_bridge.stb_model;
+
+        let length_unit = self.add("IFCSIUNIT(*,.LENGTHUNIT.,.MILLI.,.METRE.)".to_string());
+        let area_unit = self.add("IFCSIUNIT(*,.AREAUNIT.,.MILLI.,.SQUARE_METRE.)".to_string());
+        let volume_unit = self.add("IFCSIUNIT(*,.VOLUMEUNIT.,.MILLI.,.CUBIC_METRE.)".to_string());
+        let angle_unit = self.add("IFCSIUNIT(*,.PLANEANGLEUNIT.,$,.RADIAN.)".to_string());
+        let units = self.add(format!(
+            "IFCUNITASSIGNMENT({})",
+            refs(&[length_unit, area_unit, volume_unit, angle_unit])
+        ));
+
+        let context = self.add(format!(
+            "IFCGEOMETRICREPRESENTATIONCONTEXT($,'Model',3,1.E-05,#{},$)",
+            self.identity
+        ));
+        self.body_context = self.add(format!(
+            "IFCGEOMETRICREPRESENTATIONSUBCONTEXT('Body','Model',*,*,*,*,#{},$,.MODEL_VIEW.,$)",
+            context
+        ));
+        self.footprint_context = self.add(format!(
+            "IFCGEOMETRICREPRESENTATIONSUBCONTEXT('FootPrint','Model',*,*,*,*,#{},$,.PLAN_VIEW.,$)",
+            context
+        ));
+
+        let project_name = if st_bridge.stb_common.project_name.is_empty() {
+            "ST-Bridge".to_string()
+        } else {
+            st_bridge.stb_common.project_name.clone()
+        };
+        let guid = self.guid("IFCPROJECT", "");
+        let project = self.add(format!(
+            "IFCPROJECT({},$,{},$,$,$,$,(#{}),#{})",
+            guid,
+            string(&project_name),
+            context,
+            units
+        ));
+
+        let site_placement = self.add(format!("IFCLOCALPLACEMENT($,#{})", self.identity));
+        let guid = self.guid("IFCSITE", "");
+        let site = self.add(format!(
+            "IFCSITE({},$,'Site',$,$,#{},$,$,.ELEMENT.,$,$,$,$,$)",
+            guid, site_placement
+        ));
+
+        let building_placement = self.add(format!(
+            "IFCLOCALPLACEMENT(#{},#{})",
+            site_placement, self.identity
+        ));
+        let guid = self.guid("IFCBUILDING", "");
+        let building = self.add(format!(
+            "IFCBUILDING({},$,{},$,$,#{},$,$,.ELEMENT.,$,$,$)",
+            guid,
+            string(&project_name),
+            building_placement
+        ));
+
+        let guid = self.guid("IFCRELAGGREGATES", "IFCPROJECT");
+        self.add(format!(
+            "IFCRELAGGREGATES({},$,$,$,#{},(#{}))",
+            guid, project, site
+        ));
+        let guid = self.guid("IFCRELAGGREGATES", "IFCSITE");
+        self.add(format!(
+            "IFCRELAGGREGATES({},$,$,$,#{},(#{}))",
+            guid, site, building
+        ));
+
+        let mut storey_list = Vec::new();
+        for stb_story in &stb_model.stb_stories.stb_story_list {
+            let location = self.point(0.0, 0.0, stb_story.height);
+            let axis_placement = self.add(format!(
+                "IFCAXIS2PLACEMENT3D(#{},#{},#{})",
+                location, self.z_axis, self.x_axis
+            ));
+            let placement = self.add(format!(
+                "IFCLOCALPLACEMENT(#{},#{})",
+                building_placement, axis_placement
+            ));
+            let guid = self.guid("IFCBUILDINGSTOREY", stb_story.id);
+            let storey = self.add(format!(
+                "IFCBUILDINGSTOREY({},$,{},$,$,#{},$,$,.ELEMENT.,{})",
+                guid,
+                string(&stb_story.name),
+                placement,
+                real(stb_story.height)
+            ));
+            storey_list.push((stb_story.id, storey, placement, stb_story.height));
+        }
+
+        if !storey_list.is_empty() {
+            let storeys: Vec<usize> = storey_list.iter().map(|s| s.1).collect();
+            let guid = self.guid("IFCRELAGGREGATES", "IFCBUILDING");
+            self.add(format!(
+                "IFCRELAGGREGATES({},$,$,$,#{},{})",
+                guid,
+                building,
+                refs(&storeys)
+            ));
+        }
+
+        let mut building_elements = Vec::new();
+        if let Some(grid) = self.write_grid(st_bridge, building_placement) {
+            building_elements.push(grid);
+        }
+
+        let mut storey_elements: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut section_elements: HashMap<u32, Vec<usize>> = HashMap::new();
+        let mut material_elements: HashMap<(String, String), Vec<usize>> = HashMap::new();
+        let mut profile_cache: HashMap<u32, usize> = HashMap::new();
+
+        let mut stb_member_list: Vec<&StbMember> = stb_model.stb_members.iter().collect();
+        stb_member_list.sort_by_key(|m| m.id());
+
+        for stb_member in stb_member_list {
+            let stb_section = match stb_model.stb_sections.get(stb_member.id_section()) {
+                Some(s) => s,
+                None => continue,
+            };
+
+            let (storey, storey_placement, elevation) = match stb_model
+                .story(stb_member)
+                .and_then(|s| storey_list.iter().find(|t| t.0 == s.id))
+            {
+                Some(&(_, storey, placement, height)) => (Some(storey), placement, height),
+                None => (None, building_placement, 0.0),
+            };
+
+            let element = match self.write_element(
+                st_bridge,
+                stb_member,
+                stb_section,
+                storey_placement,
+                elevation,
+                &mut profile_cache,
+            ) {
+                Some(e) => e,
+                None => continue,
+            };
+
+            match storey {
+                Some(storey) => storey_elements.entry(storey).or_default().push(element),
+                None => building_elements.push(element),
+            }
+            section_elements
+                .entry(stb_section.id())
+                .or_default()
+                .push(element);
+            if let Some(material) = material_name(st_bridge, stb_member, stb_section) {
+                material_elements.entry(material).or_default().push(element);
+            }
+        }
+
+        if !building_elements.is_empty() {
+            let guid = self.guid("IFCRELCONTAINEDINSPATIALSTRUCTURE", "IFCBUILDING");
+            self.add(format!(
+                "IFCRELCONTAINEDINSPATIALSTRUCTURE({},$,$,$,{},#{})",
+                guid,
+                refs(&building_elements),
+                building
+            ));
+        }
+
+        for (id, storey, _, _) in &storey_list {
+            if let Some(elements) = storey_elements.get(storey) {
+                let guid = self.guid("IFCRELCONTAINEDINSPATIALSTRUCTURE", id);
+                self.add(format!(
+                    "IFCRELCONTAINEDINSPATIALSTRUCTURE({},$,$,$,{},#{})",
+                    guid,
+                    refs(elements),
+                    storey
+                ));
+            }
+        }
+
+        let mut section_ids: Vec<&u32> = section_elements.keys().collect();
+        section_ids.sort();
+        for id in section_ids {
+            let stb_section = stb_model.stb_sections.get(*id).unwrap();
+            self.write_section_property_set(st_bridge, stb_section, &section_elements[id]);
+        }
+
+        let mut materials: Vec<&(String, String)> = material_elements.keys().collect();
+        materials.sort();
+        for material in materials {
+            self.write_material(&material.0, &material.1, &material_elements[material]);
+        }
+    }
+
+    fn write_grid(&mut self, st_bridge: &StBridge, building_placement: usize) -> Option<usize> {
+        let stb_axes = &st_bridge.stb_model.stb_axes;
+        if stb_axes.stb_x_axis_list.is_empty() || stb_axes.stb_y_axis_list.is_empty() {
+            return None;
+        }
+
+        let range = |distances: Vec<f64>| {
+            let min = distances.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = distances.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let margin = ((max - min) * 0.1).max(1000.0);
+            (min - margin, max + margin)
+        };
+        let (x_min, x_max) = range(
+            stb_axes
+                .stb_x_axis_list
+                .iter()
+                .map(|a| a.distance)
+                .collect(),
+        );
+        let (y_min, y_max) = range(
+            stb_axes
+                .stb_y_axis_list
+                .iter()
+                .map(|a| a.distance)
+                .collect(),
+        );
+
+        let mut curves = Vec::new();
+
+        let mut u_axes = Vec::new();
+        for stb_x_axis in &stb_axes.stb_x_axis_list {
+            let start = self.point_2d(stb_x_axis.distance, y_min);
+            let end = self.point_2d(stb_x_axis.distance, y_max);
+            let curve = self.add(format!("IFCPOLYLINE((#{},#{}))", start, end));
+            curves.push(curve);
+            u_axes.push(self.add(format!(
+                "IFCGRIDAXIS({},#{},.T.)",
+                string(&stb_x_axis.name),
+                curve
+            )));
+        }
+
+        let mut v_axes = Vec::new();
+        for stb_y_axis in &stb_axes.stb_y_axis_list {
+            let start = self.point_2d(x_min, stb_y_axis.distance);
+            let end = self.point_2d(x_max, stb_y_axis.distance);
+            let curve = self.add(format!("IFCPOLYLINE((#{},#{}))", start, end));
+            curves.push(curve);
+            v_axes.push(self.add(format!(
+                "IFCGRIDAXIS({},#{},.T.)",
+                string(&stb_y_axis.name),
+                curve
+            )));
+        }
+
+        let curve_set = self.add(format!("IFCGEOMETRICCURVESET({})", refs(&curves)));
+        let representation = self.add(format!(
+            "IFCSHAPEREPRESENTATION(#{},'FootPrint','GeometricCurveSet',(#{}))",
+            self.footprint_context, curve_set
+        ));
+        let shape = self.add(format!(
+            "IFCPRODUCTDEFINITIONSHAPE($,$,(#{}))",
+            representation
+        ));
+        let placement = self.add(format!(
+            "IFCLOCALPLACEMENT(#{},#{})",
+            building_placement, self.identity
+        ));
+        let guid = self.guid("IFCGRID", "");
+
+        Some(self.add(format!(
+            "IFCGRID({},$,'Grid',$,$,#{},#{},{},{},$,.RECTANGULAR.)",
+            guid,
+            placement,
+            shape,
+            refs(&u_axes),
+            refs(&v_axes)
+        )))
+    }
+
+    fn write_element(
+        &mut self,
+        st_bridge: &StBridge,
+        stb_member: &StbMember,
+        stb_section: &StbSection,
+        storey_placement: usize,
+        elevation: f64,
+        profile_cache: &mut HashMap<u32, usize>,
+    ) -> Option<usize> {
+        let (profile, plane, depth) = if stb_member.is_linear() {
+            let (plane, length) = geometry::member_placement(st_bridge, stb_member)?;
+            let profile = match profile_cache.get(&stb_section.id()) {
+                Some(&p) => p,
+                None => {
+                    let p = self.write_section_profile(st_bridge, stb_section)?;
+                    profile_cache.insert(stb_section.id(), p);
+                    p
+                }
+            };
+            (profile, plane, length)
+        } else {
+            let plate = geometry::plate_outline(st_bridge, stb_member)?;
+            let profile =
+                self.write_arbitrary_profile(&stb_section.name(), &plate.outline, &plate.holes);
+            let mut plane = plate.plane;
+            plane.origin = plane.origin + plane.normal * plate.bottom;
+            (profile, plane, plate.top - plate.bottom)
+        };
+
+        let placement = self.local_placement(storey_placement, &plane, elevation);
+        let solid = self.add(format!(
+            "IFCEXTRUDEDAREASOLID(#{},#{},#{},{})",
+            profile,
+            self.identity,
+            self.z_axis,
+            real(depth)
+        ));
+        let representation = self.add(format!(
+            "IFCSHAPEREPRESENTATION(#{},'Body','SweptSolid',(#{}))",
+            self.body_context, solid
+        ));
+        let shape = self.add(format!(
+            "IFCPRODUCTDEFINITIONSHAPE($,$,(#{}))",
+            representation
+        ));
+
+        let (entity, predefined_type) = match stb_member {
+            StbMember::StbColumn { .. } => ("IFCCOLUMN", ".COLUMN."),
+            StbMember::StbPost { .. } => ("IFCMEMBER", ".POST."),
+            StbMember::StbGirder { .. } | StbMember::StbBeam { .. } => ("IFCBEAM", ".BEAM."),
+            StbMember::StbBrace { .. } => ("IFCMEMBER", ".BRACE."),
+            StbMember::StbSlab { .. } => ("IFCSLAB", ".FLOOR."),
+            StbMember::StbWall { .. } => ("IFCWALL", ".SOLIDWALL."),
+            StbMember::StbOpen { .. } => return None,
+        };
+
+        let guid = self.guid(entity, stb_member.id());
+        Some(self.add(format!(
+            "{}({},$,{},$,{},#{},#{},'{}',{})",
+            entity,
+            guid,
+            string(&stb_member.name()),
+            string(&stb_section.name()),
+            placement,
+            shape,
+            stb_member.id(),
+            predefined_type
+        )))
+    }
+
+    fn write_section_profile(
+        &mut self,
+        st_bridge: &StBridge,
+        stb_section: &StbSection,
+    ) -> Option<usize> {
+        let stb_sec_steel = &st_bridge.stb_model.stb_sections.stb_sec_steel;
+
+        if let Some(children) = stb_section
+            .steel_shape()
+            .and_then(|shape| stb_sec_steel.get(shape))
+        {
+            let name = string(&children.name());
+            let profile = match *children {
+                StbSecSteelChildren::StbSecRollH {
+                    a, b, t1, t2, r, ..
+                } => format!(
+                    "IFCISHAPEPROFILEDEF(.AREA.,{},$,{},{},{},{},{},$,$)",
+                    name,
+                    real(b),
+                    real(a),
+                    real(t1),
+                    real(t2),
+                    real(r)
+                ),
+                StbSecSteelChildren::StbSecBuildH { a, b, t1, t2, .. } => format!(
+                    "IFCISHAPEPROFILEDEF(.AREA.,{},$,{},{},{},{},$,$,$)",
+                    name,
+                    real(b),
+                    real(a),
+                    real(t1),
+                    real(t2)
+                ),
+                StbSecSteelChildren::StbSecRollBox { a, b, t, r, .. } => format!(
+                    "IFCRECTANGLEHOLLOWPROFILEDEF(.AREA.,{},$,{},{},{},{},{})",
+                    name,
+                    real(b),
+                    real(a),
+                    real(t),
+                    real((r - t).max(0.0)),
+                    real(r)
+                ),
+                StbSecSteelChildren::StbSecBuildBox { a, b, t1, t2, .. }
+                    if (t1 - t2).abs() < 1e-9 =>
+                {
+                    format!(
+                        "IFCRECTANGLEHOLLOWPROFILEDEF(.AREA.,{},$,{},{},{},$,$)",
+                        name,
+                        real(b),
+                        real(a),
+                        real(t1)
+                    )
+                }
+                StbSecSteelChildren::StbSecPipe { d, t, .. } => format!(
+                    "IFCCIRCLEHOLLOWPROFILEDEF(.AREA.,{},$,{},{})",
+                    name,
+                    real(d / 2.0),
+                    real(t)
+                ),
+                StbSecSteelChildren::StbSecRollL {
+                    a, b, t1, r1, r2, ..
+                } => format!(
+                    "IFCLSHAPEPROFILEDEF(.AREA.,{},$,{},{},{},{},{},$)",
+                    name,
+                    real(a),
+                    real(b),
+                    real(t1),
+                    real(r1),
+                    real(r2)
+                ),
+                _ => {
+                    return Some(self.write_arbitrary_profile(
+                        &children.name(),
+                        &children.shape(),
+                        &children.holes(),
+                    ))
+                }
+            };
+            return Some(self.add(profile));
+        }
+
+        match stb_section {
+            StbSection::StbSecBeamRC { stb_sec_figure, .. } => {
+                let (width, depth) = geometry::beam_rc_size(stb_sec_figure)?;
+                Some(self.add(format!(
+                    "IFCRECTANGLEPROFILEDEF(.AREA.,{},$,{},{})",
+                    string(&stb_section.name()),
+                    real(width),
+                    real(depth)
+                )))
+            }
+            _ => {
+                let (outline, holes) = geometry::section_outline(st_bridge, stb_section)?;
+                Some(self.write_arbitrary_profile(&stb_section.name(), &outline, &holes))
+            }
+        }
+    }
+
+    fn write_arbitrary_profile(
+        &mut self,
+        name: &str,
+        outline: &[(f64, f64)],
+        holes: &[Vec<(f64, f64)>],
+    ) -> usize {
+        let outer = self.polyline(outline);
+
+        if holes.is_empty() {
+            return self.add(format!(
+                "IFCARBITRARYCLOSEDPROFILEDEF(.AREA.,{},#{})",
+                string(name),
+                outer
+            ));
+        }
+
+        let inner: Vec<usize> = holes.iter().map(|h| self.polyline(h)).collect();
+        self.add(format!(
+            "IFCARBITRARYPROFILEDEFWITHVOIDS(.AREA.,{},#{},{})",
+            string(name),
+            outer,
+            refs(&inner)
+        ))
+    }
+
+    fn write_section_property_set(
+        &mut self,
+        st_bridge: &StBridge,
+        stb_section: &StbSection,
+        elements: &[usize],
+    ) {
+        let mut properties = vec![self.add(format!(
+            "IFCPROPERTYSINGLEVALUE('SectionName',$,IFCLABEL({}),$)",
+            string(&stb_section.name())
+        ))];
+
+        if let Some(shape) = stb_section.steel_shape() {
+            properties.push(self.add(format!(
+                "IFCPROPERTYSINGLEVALUE('Shape',$,IFCLABEL({}),$)",
+                string(shape)
+            )));
+        }
+
+        if let Some((outline, holes)) = geometry::section_outline(st_bridge, stb_section) {
+            let area = geometry::signed_area(&outline).abs()
+                - holes
+                    .iter()
+                    .map(|h| geometry::signed_area(h).abs())
+                    .sum::<f64>();
+            properties.push(self.add(format!(
+                "IFCPROPERTYSINGLEVALUE('CrossSectionArea',$,IFCAREAMEASURE({}),$)",
+                real(area)
+            )));
+        }
+
+        let guid = self.guid("IFCPROPERTYSET", stb_section.id());
+        let property_set = self.add(format!(
+            "IFCPROPERTYSET({},$,'Pset_StbSection',$,{})",
+            guid,
+            refs(&properties)
+        ));
+        let guid = self.guid("IFCRELDEFINESBYPROPERTIES", stb_section.id());
+        self.add(format!(
+            "IFCRELDEFINESBYPROPERTIES({},$,$,$,{},#{})",
+            guid,
+            refs(elements),
+            property_set
+        ));
+    }
+
+    fn write_material(&mut self, name: &str, category: &str, elements: &[usize]) {
+        let material = self.add(format!(
+            "IFCMATERIAL({},$,{})",
+            string(name),
+            string(category)
+        ));
+
+        let grade = self.add(format!(
+            "IFCPROPERTYSINGLEVALUE('Grade',$,IFCLABEL({}),$)",
+            string(name)
+        ));
+        self.add(format!(
+            "IFCMATERIALPROPERTIES('Pset_StbMaterial',$,(#{}),#{})",
+            grade, material
+        ));
+
+        let guid = self.guid("IFCRELASSOCIATESMATERIAL", format!("{}:{}", category, name));
+        self.add(format!(
+            "IFCRELASSOCIATESMATERIAL({},$,$,$,{},#{})",
+            guid,
+            refs(elements),
+            material
+        ));
+    }
+}
+
+fn material_name(
+    st_bridge: &StBridge,
+    stb_member: &StbMember,
+    stb_section: &StbSection,
+) -> Option<(String, String)> {
+    let story_concrete = || {
+        st_bridge
+            .stb_model
+            .story(stb_member)
+            .map(|s| s.concrete_strength.clone())
+    };
+
+    let (name, category) = match stb_section {
+        StbSection::StbSecColumnS {
+            stb_sec_steel_column,
+            ..
+        } => (Some(stb_sec_steel_column.strength_main.clone()), "Steel"),
+        StbSection::StbSecBeamS {
+            stb_sec_steel_beam_list,
+            ..
+        } => (
+            Some(
+                center_steel_beam(stb_sec_steel_beam_list)
+                    .strength_main
+                    .clone(),
+            ),
+            "Steel",
+        ),
+        StbSection::StbSecBraceS {
+            stb_sec_steel_brace,
+            ..
+        } => (Some(stb_sec_steel_brace.strength_main.clone()), "Steel"),
+        StbSection::StbSecColumnRC {
+            strength_concrete, ..
+        }
+        | StbSection::StbSecBeamRC {
+            strength_concrete, ..
+        } => (
+            strength_concrete.clone().or_else(story_concrete),
+            "Concrete",
+        ),
+        StbSection::StbSecSlabRC {
+            strength_concrete, ..
+        }
+        | StbSection::StbSecWallRC {
+            strength_concrete, ..
+        } => (
+            Some(strength_concrete.clone())
+                .filter(|s| !s.is_empty())
+                .or_else(story_concrete),
+            "Concrete",
+        ),
+    };
+
+    name.filter(|n| !n.is_empty())
+        .map(|n| (n, category.to_string()))
+}
+
+fn refs(ids: &[usize]) -> String {
+    let list: Vec<String> = ids.iter().map(|id| format!("#{}", id)).collect();
+    format!("({})", list.join(","))
+}
+
+fn real(v: f64) -> String {
+    let s = format!("{}", v);
+    if s.contains('.') {
+        s
+    } else {
+        s + "."
+    }
+}
+
+// STEP strings are ASCII; anything else goes through the \X2\ UTF-16 escape.
+fn string(s: &str) -> String {
+    let mut encoded = String::from("'");
+    let mut wide = Vec::new();
+
+    let flush = |encoded: &mut String, wide: &mut Vec<u16>| {
+        if !wide.is_empty() {
+            encoded.push_str("\\X2\\");
+            for u in wide.iter() {
+                encoded.push_str(&format!("{:04X}", u));
+            }
+            encoded.push_str("\\X0\\");
+            wide.clear();
+        }
+    };
+
+    for c in s.chars() {
+        if c.is_ascii() && !c.is_ascii_control() {
+            flush(&mut encoded, &mut wide);
+            match c {
+                '\'' => encoded.push_str("''"),
+                '\\' => encoded.push_str("\\\\"),
+                _ => encoded.push(c),
+            }
+        } else {
+            let mut buf = [0u16; 2];
+            wide.extend_from_slice(c.encode_utf16(&mut buf));
+        }
+    }
+    flush(&mut encoded, &mut wide);
+
+    encoded.push('\'');
+    encoded
+}
+
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    // Civil date from days since the epoch (Howard Hinnant's algorithm).
+    let days = secs.div_euclid(86400);
+    let rem = secs.rem_euclid(86400);
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+// Sets the model's GlobalIds apart from another model's: the project name and the grid and story
+// layout, which identify the building and rarely change between revisions.
+fn model_seed(st_bridge: &StBridge) -> u64 {
+    let stb_model = &st_bridge.stb_model;
+    let mut key = st_bridge.stb_common.project_name.clone();
+    for axis in &stb_model.stb_axes.stb_x_axis_list {
+        key.push_str(&format!(";X{}={}", axis.name, axis.distance));
+    }
+    for axis in &stb_model.stb_axes.stb_y_axis_list {
+        key.push_str(&format!(";Y{}={}", axis.name, axis.distance));
+    }
+    for stb_story in &stb_model.stb_stories.stb_story_list {
+        key.push_str(&format!(";S{}={}", stb_story.name, stb_story.height));
+    }
+    fnv1a(key.as_bytes())
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn count(ifc: &str, entity: &str) -> usize {
+        ifc.matches(&format!("={}(", entity)).count()
+    }
+
+    #[test]
+    fn exports_every_member_of_the_sample() {
+        let ifc = create_ifc(&sample(), "sample.ifc");
+
+        assert!(ifc.starts_with("ISO-10303-21;\n"));
+        assert!(ifc.ends_with("END-ISO-10303-21;\n"));
+        assert_eq!(count(&ifc, "IFCBUILDINGSTOREY"), 5);
+        assert_eq!(count(&ifc, "IFCCOLUMN"), 34);
+        // Girders and beams, then braces and posts.
+        assert_eq!(count(&ifc, "IFCBEAM"), 74 + 91);
+        assert_eq!(count(&ifc, "IFCMEMBER"), 4 + 17);
+        assert_eq!(count(&ifc, "IFCSLAB"), 90);
+    }
+
+    #[test]
+    fn references_point_at_earlier_entities() {
+        let ifc = create_ifc(&sample(), "sample.ifc");
+
+        for line in ifc.lines().filter(|l| l.starts_with('#')) {
+            let (id, body) = line.split_at(line.find('=').unwrap());
+            let id: usize = id[1..].parse().unwrap();
+            for reference in body.split('#').skip(1) {
+                let digits: String = reference
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                let reference: usize = digits.parse().unwrap();
+                assert!(reference < id, "#{} refers to #{}", id, reference);
+            }
+        }
+    }
+
+    #[test]
+    fn guids_are_stable_between_exports() {
+        let st_bridge = sample();
+        let guids = |ifc: String| -> Vec<String> {
+            ifc.lines()
+                .filter_map(|l| l.split('\'').nth(1).map(|s| s.to_string()))
+                .filter(|s| s.len() == 22)
+                .collect()
+        };
+
+        let first = guids(create_ifc(&st_bridge, "a.ifc"));
+        let second = guids(create_ifc(&st_bridge, "b.ifc"));
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn guids_are_unique() {
+        let ifc = create_ifc(&sample(), "sample.ifc");
+        let guid_list: Vec<&str> = ifc
+            .lines()
+            .filter_map(|l| l.split('\'').nth(1))
+            .filter(|s| s.len() == 22)
+            .collect();
+        let guid_set: std::collections::HashSet<&&str> = guid_list.iter().collect();
+
+        assert_eq!(guid_set.len(), guid_list.len());
+    }
+
+    #[test]
+    fn inserting_a_member_keeps_the_other_guids() {
+        use crate::st_bridge::stb_model::stb_members::{ColumnStructureKind, JointCondition};
+
+        // ST-Bridge member id to GlobalId, from the tag at the end of each element.
+        let member_guids = |st_bridge: &StBridge| -> HashMap<String, String> {
+            create_ifc(st_bridge, "portal_frame.ifc")
+                .lines()
+                .filter(|l| {
+                    ["IFCCOLUMN(", "IFCBEAM(", "IFCMEMBER(", "IFCSLAB("]
+                        .iter()
+                        .any(|e| l.contains(e))
+                })
+                .map(|l| {
+                    let field_list: Vec<&str> = l.split(',').collect();
+                    let guid = l.split('\'').nth(1).unwrap().to_string();
+                    (field_list[field_list.len() - 2].to_string(), guid)
+                })
+                .collect()
+        };
+        let mut st_bridge = crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        ))
+        .unwrap();
+        let before = member_guids(&st_bridge);
+
+        // A column sorted ahead of every other member.
+        st_bridge.stb_model.stb_members.stb_columns.insert(
+            0,
+            StbMember::StbColumn {
+                id: 0,
+                name: "C2".to_string(),
+                id_node_bottom: 1,
+                id_node_top: 5,
+                rotate: 0.0,
+                id_section: 1,
+                kind_structure: ColumnStructureKind::RC,
+                offset_x: 0.0,
+                offset_y: 0.0,
+                condition_bottom: JointCondition::Fix,
+                condition_top: JointCondition::Fix,
+            },
+        );
+        let after = member_guids(&st_bridge);
+
+        assert_eq!(before.len(), 5);
+        assert_eq!(after.len(), 6);
+        for (id, guid) in &before {
+            assert_eq!(&after[id], guid, "member {}", id);
+        }
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        assert_eq!(string("it's"), "'it''s'");
+        assert_eq!(string("a\\b"), "'a\\\\b'");
+        assert_eq!(string("柱C1"), "'\\X2\\67F1\\X0\\C1'");
+    }
+}
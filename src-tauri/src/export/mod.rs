@@ -0,0 +1,64 @@
+pub mod gltf;
+pub mod ifc;
+pub mod obj;
+pub mod opensees;
+pub mod saf;
+pub mod stb;
+pub mod stl;
+
+use crate::geometry::{self, Mesh};
+use crate::st_bridge::StBridge;
+
+pub struct MeshExportOptions {
+    pub scale: f64,
+    pub story: Option<String>,
+}
+
+impl MeshExportOptions {
+    pub fn new() -> MeshExportOptions {
+        MeshExportOptions {
+            scale: 1.0,
+            story: None,
+        }
+    }
+}
+
+impl Default for MeshExportOptions {
+    fn default() -> MeshExportOptions {
+        MeshExportOptions::new()
+    }
+}
+
+fn structure_kind_color(kind_structure: &str) -> [f64; 4] {
+    match kind_structure {
+        "S" => [0.55, 0.60, 0.70, 1.0],
+        "RC" => [0.78, 0.77, 0.74, 1.0],
+        "SRC" => [0.80, 0.58, 0.45, 1.0],
+        "CFT" => [0.45, 0.68, 0.62, 1.0],
+        _ => [0.60, 0.60, 0.60, 1.0],
+    }
+}
+
+// Member, slab and wall meshes of the model, keeping only those on the requested story.
+fn filtered_mesh_list(st_bridge: &StBridge, story: Option<&str>) -> Vec<Mesh> {
+    let stb_model = &st_bridge.stb_model;
+
+    let mut mesh_list = geometry::create_member_mesh_list(st_bridge);
+    mesh_list.extend(geometry::create_slab_mesh_list(st_bridge));
+    mesh_list.extend(geometry::create_wall_mesh_list(st_bridge));
+
+    match story {
+        Some(story) => mesh_list
+            .into_iter()
+            .filter(|mesh| {
+                stb_model
+                    .stb_members
+                    .get(mesh.id)
+                    .and_then(|m| stb_model.story(m))
+                    .map(|s| s.name.as_str())
+                    == Some(story)
+            })
+            .collect(),
+        None => mesh_list,
+    }
+}
@@ -0,0 +1,195 @@
+use super::{filtered_mesh_list, structure_kind_color, MeshExportOptions};
+use crate::st_bridge::StBridge;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+// Writes file_name and a material library next to it with the same stem and a .mtl extension.
+pub fn write_obj(
+    st_bridge: &StBridge,
+    file_name: &str,
+    options: &MeshExportOptions,
+) -> std::io::Result<()> {
+    let mtl_path = Path::new(file_name).with_extension("mtl");
+    let mtl_name = mtl_path.file_name().unwrap().to_string_lossy().to_string();
+
+    let (obj, mtl) = create_obj(st_bridge, &mtl_name, options);
+
+    let mut f = File::create(file_name)?;
+    f.write_all(obj.as_bytes())?;
+    let mut f = File::create(mtl_path)?;
+    f.write_all(mtl.as_bytes())
+}
+
+pub fn create_obj(
+    st_bridge: &StBridge,
+    mtl_name: &str,
+    options: &MeshExportOptions,
+) -> (String, String) {
+    let stb_model = &st_bridge.stb_model;
+    let mesh_list = filtered_mesh_list(st_bridge, options.story.as_deref());
+
+    let mut obj = String::new();
+    let mut material_kinds: Vec<String> = Vec::new();
+    let mut vertex_offset = 1;
+
+    writeln!(obj, "# stb-viewer").unwrap();
+    writeln!(obj, "mtllib {}", mtl_name).unwrap();
+
+    for mesh in &mesh_list {
+        let stb_member = match stb_model.stb_members.get(mesh.id) {
+            Some(m) => m,
+            None => continue,
+        };
+
+        let kind_structure = stb_member.kind_structure();
+        if !material_kinds.contains(&kind_structure) {
+            material_kinds.push(kind_structure.clone());
+        }
+
+        writeln!(obj, "g {}_{}", stb_member.name(), stb_member.id()).unwrap();
+        writeln!(obj, "usemtl {}", kind_structure).unwrap();
+
+        // OBJ readers assume Y-up, so Z-up model coordinates are turned as in the glTF export.
+        for v in &mesh.vertices {
+            writeln!(
+                obj,
+                "v {} {} {}",
+                v.x * options.scale,
+                v.z * options.scale,
+                -v.y * options.scale
+            )
+            .unwrap();
+        }
+
+        for [a, b, c] in &mesh.triangles {
+            writeln!(
+                obj,
+                "f {} {} {}",
+                a + vertex_offset,
+                b + vertex_offset,
+                c + vertex_offset
+            )
+            .unwrap();
+        }
+
+        vertex_offset += mesh.vertices.len();
+    }
+
+    let mut mtl = String::new();
+    for kind in &material_kinds {
+        let [r, g, b, a] = structure_kind_color(kind);
+        writeln!(mtl, "newmtl {}", kind).unwrap();
+        writeln!(mtl, "Ka {} {} {}", r, g, b).unwrap();
+        writeln!(mtl, "Kd {} {} {}", r, g, b).unwrap();
+        writeln!(mtl, "Ks 0 0 0").unwrap();
+        writeln!(mtl, "d {}", a).unwrap();
+        writeln!(mtl, "illum 1").unwrap();
+        writeln!(mtl).unwrap();
+    }
+
+    (obj, mtl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn options(scale: f64, story: Option<&str>) -> MeshExportOptions {
+        MeshExportOptions {
+            scale,
+            story: story.map(|s| s.to_string()),
+        }
+    }
+
+    fn lines<'a>(obj: &'a str, prefix: &str) -> Vec<&'a str> {
+        obj.lines().filter_map(|l| l.strip_prefix(prefix)).collect()
+    }
+
+    fn vertex_list(obj: &str) -> Vec<[f64; 3]> {
+        lines(obj, "v ")
+            .iter()
+            .map(|l| {
+                let c: Vec<f64> = l.split(' ').map(|c| c.parse().unwrap()).collect();
+                [c[0], c[1], c[2]]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_match_the_meshes() {
+        let st_bridge = sample();
+        let mesh_list = filtered_mesh_list(&st_bridge, None);
+        let (obj, _) = create_obj(&st_bridge, "sample.mtl", &options(1.0, None));
+
+        let vertex_count: usize = mesh_list.iter().map(|m| m.vertices.len()).sum();
+        let triangle_count: usize = mesh_list.iter().map(|m| m.triangles.len()).sum();
+        assert_eq!(lines(&obj, "v ").len(), vertex_count);
+        assert_eq!(lines(&obj, "f ").len(), triangle_count);
+        assert_eq!(lines(&obj, "g ").len(), mesh_list.len());
+
+        // Face indices are 1-based and refer to vertices written so far.
+        let mut written = 0;
+        for line in obj.lines() {
+            if line.starts_with("v ") {
+                written += 1;
+            } else if let Some(face) = line.strip_prefix("f ") {
+                for index in face.split(' ') {
+                    let index: usize = index.parse().unwrap();
+                    assert!(index >= 1 && index <= written, "{}", line);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn materials_are_defined_in_the_library() {
+        let (obj, mtl) = create_obj(&sample(), "sample.mtl", &options(1.0, None));
+
+        assert_eq!(lines(&obj, "mtllib "), vec!["sample.mtl"]);
+        let defined = lines(&mtl, "newmtl ");
+        let mut used = lines(&obj, "usemtl ");
+        used.sort_unstable();
+        used.dedup();
+        assert!(!used.is_empty());
+        assert_eq!(used.len(), defined.len());
+        for name in used {
+            assert!(defined.contains(&name), "{} is not defined", name);
+        }
+    }
+
+    #[test]
+    fn scale_and_story_filter() {
+        let st_bridge = sample();
+        let (millimetre, _) = create_obj(&st_bridge, "a.mtl", &options(1.0, None));
+        let (metre, _) = create_obj(&st_bridge, "a.mtl", &options(0.001, None));
+        for (p, q) in vertex_list(&millimetre).iter().zip(vertex_list(&metre)) {
+            for k in 0..3 {
+                assert!((p[k] * 0.001 - q[k]).abs() < 1e-9);
+            }
+        }
+
+        let total = lines(&millimetre, "f ").len();
+        let story = &st_bridge.stb_model.stb_stories.stb_story_list[1];
+        let (obj, _) = create_obj(&st_bridge, "a.mtl", &options(1.0, Some(&story.name)));
+        let count = lines(&obj, "f ").len();
+        assert!(count > 0 && count < total);
+
+        // Face indices restart with the filtered vertex list.
+        let vertex_count = lines(&obj, "v ").len();
+        assert!(lines(&obj, "f ")
+            .iter()
+            .flat_map(|f| f.split(' '))
+            .all(|i| i.parse::<usize>().unwrap() <= vertex_count));
+    }
+}
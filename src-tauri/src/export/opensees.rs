@@ -0,0 +1,491 @@
+use crate::analysis::frame::{FrameModel, LoadCase, MemberLoad};
+use crate::analysis::load::{generate_load_cases, LoadSettings};
+use crate::geometry::Node;
+use crate::material::{MaterialDatabase, MaterialKind};
+use crate::st_bridge::StBridge;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::prelude::*;
+
+const GRAVITY: f64 = 9806.65; // mm/s2
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpenSeesFormat {
+    Tcl,
+    Python,
+}
+
+enum Arg {
+    Text(String),
+    Number(f64),
+    Integer(usize),
+}
+
+fn text(s: &str) -> Arg {
+    Arg::Text(s.to_string())
+}
+
+// One OpenSees command in either syntax.
+fn command(format: OpenSeesFormat, name: &str, arg_list: &[Arg]) -> String {
+    let arg_list: Vec<String> = arg_list
+        .iter()
+        .map(|a| match a {
+            Arg::Text(s) if format == OpenSeesFormat::Python => format!("'{}'", s),
+            Arg::Text(s) => s.clone(),
+            // Adding zero prints -0 as 0.
+            Arg::Number(v) => format!("{}", v + 0.0),
+            Arg::Integer(v) => format!("{}", v),
+        })
+        .collect();
+
+    match format {
+        OpenSeesFormat::Tcl => {
+            let mut line = name.to_string();
+            for a in arg_list {
+                line += " ";
+                line += &a;
+            }
+            line + "\n"
+        }
+        OpenSeesFormat::Python => format!("ops.{}({})\n", name, arg_list.join(", ")),
+    }
+}
+
+// Python for .py files, Tcl otherwise.
+pub fn write_opensees(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let format = if file_name.to_lowercase().ends_with(".py") {
+        OpenSeesFormat::Python
+    } else {
+        OpenSeesFormat::Tcl
+    };
+
+    let mut f = File::create(file_name)?;
+    f.write_all(create_opensees(st_bridge, format).as_bytes())
+}
+
+// The model of the frame solver in N, mm and s, so masses are in t. Elements are elastic with
+// uniaxial materials defined alongside for switching to fiber sections. Masses are lumped from
+// the self-weight case and the generated load cases become load patterns.
+pub fn create_opensees(st_bridge: &StBridge, format: OpenSeesFormat) -> String {
+    let database = MaterialDatabase::new();
+    let model = FrameModel::new(st_bridge, &database);
+    let loads = generate_load_cases(st_bridge, &model, &database, &LoadSettings::new());
+
+    let node_map: HashMap<u32, Node> = model
+        .node_list
+        .iter()
+        .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+        .collect();
+
+    let mut script = String::new();
+    writeln!(script, "# stb-viewer: units N, mm, s").unwrap();
+    for warning in model.warning_list.iter().chain(&loads.warning_list) {
+        writeln!(script, "# warning: {}", warning).unwrap();
+    }
+
+    match format {
+        OpenSeesFormat::Tcl => script += "wipe\n",
+        OpenSeesFormat::Python => script += "import openseespy.opensees as ops\n\nops.wipe()\n",
+    }
+    script += &command(
+        format,
+        "model",
+        &[
+            text("basic"),
+            text("-ndm"),
+            Arg::Integer(3),
+            text("-ndf"),
+            Arg::Integer(6),
+        ],
+    );
+
+    script += "\n# nodes\n";
+    for n in &model.node_list {
+        script += &command(
+            format,
+            "node",
+            &[
+                Arg::Integer(n.id as usize),
+                Arg::Number(n.x),
+                Arg::Number(n.y),
+                Arg::Number(n.z),
+            ],
+        );
+    }
+
+    script += "\n# supports\n";
+    for support in &model.support_list {
+        let mut arg_list = vec![Arg::Integer(support.id as usize)];
+        arg_list.extend(support.fixity.iter().map(|&f| Arg::Integer(f as usize)));
+        script += &command(format, "fix", &arg_list);
+    }
+
+    if let Some(self_weight) = loads.load_case_list.iter().find(|l| l.name == "SW") {
+        script += "\n# masses from self-weight\n";
+        for (id, m) in lumped_mass(&model, &node_map, self_weight) {
+            script += &command(
+                format,
+                "mass",
+                &[
+                    Arg::Integer(id as usize),
+                    Arg::Number(m),
+                    Arg::Number(m),
+                    Arg::Number(m),
+                    Arg::Number(0.0),
+                    Arg::Number(0.0),
+                    Arg::Number(0.0),
+                ],
+            );
+        }
+    }
+
+    script += "\n# materials\n";
+    let mut material_tag: BTreeMap<String, usize> = BTreeMap::new();
+    for element in &model.element_list {
+        let material = &element.material;
+        if material_tag.contains_key(&material.name) {
+            continue;
+        }
+        let tag = material_tag.len() + 1;
+        material_tag.insert(material.name.clone(), tag);
+
+        writeln!(script, "# {}", material.name).unwrap();
+        script += &match material.kind {
+            MaterialKind::Concrete => command(
+                format,
+                "uniaxialMaterial",
+                &[
+                    text("Concrete01"),
+                    Arg::Integer(tag),
+                    Arg::Number(-material.f),
+                    Arg::Number(-0.002),
+                    Arg::Number(-0.2 * material.f),
+                    Arg::Number(-0.0035),
+                ],
+            ),
+            _ => command(
+                format,
+                "uniaxialMaterial",
+                &[
+                    text("Steel02"),
+                    Arg::Integer(tag),
+                    Arg::Number(material.f),
+                    Arg::Number(material.young_modulus),
+                    Arg::Number(0.01),
+                    Arg::Number(18.0),
+                    Arg::Number(0.925),
+                    Arg::Number(0.15),
+                ],
+            ),
+        };
+    }
+
+    script += "\n# sections\n";
+    let mut section_tag: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for element in &model.element_list {
+        let key = (element.section.name.clone(), element.material.name.clone());
+        if section_tag.contains_key(&key) {
+            continue;
+        }
+        let tag = section_tag.len() + 1;
+        section_tag.insert(key, tag);
+
+        let s = &element.section;
+        let m = &element.material;
+        writeln!(script, "# {} {}", s.name, m.name).unwrap();
+        script += &command(
+            format,
+            "section",
+            &[
+                text("Elastic"),
+                Arg::Integer(tag),
+                Arg::Number(m.young_modulus),
+                Arg::Number(s.area),
+                Arg::Number(s.iz),
+                Arg::Number(s.iy),
+                Arg::Number(m.young_modulus / (2.0 * (1.0 + m.poisson_ratio))),
+                Arg::Number(s.j),
+            ],
+        );
+    }
+
+    // One transformation per element, with vecxz along the element's local z.
+    script += "\n# elements\n";
+    for (i, element) in model.element_list.iter().enumerate() {
+        let tag = i + 1;
+        let z = element.z_axis;
+        writeln!(script, "# {} {}", element.name, element.member_id).unwrap();
+        script += &command(
+            format,
+            "geomTransf",
+            &[
+                text("Linear"),
+                Arg::Integer(tag),
+                Arg::Number(z.x),
+                Arg::Number(z.y),
+                Arg::Number(z.z),
+            ],
+        );
+
+        let section = section_tag[&(element.section.name.clone(), element.material.name.clone())];
+        let release = match (element.release_i, element.release_j) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (false, true) => 2,
+            (true, true) => 3,
+        };
+        let mut arg_list = vec![
+            text("elasticBeamColumn"),
+            Arg::Integer(tag),
+            Arg::Integer(element.node_i as usize),
+            Arg::Integer(element.node_j as usize),
+            Arg::Integer(section),
+            Arg::Integer(tag),
+        ];
+        if release != 0 {
+            arg_list.extend(vec![
+                text("-releasez"),
+                Arg::Integer(release),
+                text("-releasey"),
+                Arg::Integer(release),
+            ]);
+        }
+        script += &command(format, "element", &arg_list);
+    }
+
+    for (i, load_case) in loads.load_case_list.iter().enumerate() {
+        let tag = i + 1;
+        writeln!(script, "\n# load case {}", load_case.name).unwrap();
+        script += &command(format, "timeSeries", &[text("Linear"), Arg::Integer(tag)]);
+        script += &load_pattern(format, &model, tag, load_case);
+    }
+
+    script
+}
+
+fn load_pattern(
+    format: OpenSeesFormat,
+    model: &FrameModel,
+    tag: usize,
+    load_case: &LoadCase,
+) -> String {
+    let mut pattern = match format {
+        OpenSeesFormat::Tcl => format!("pattern Plain {} {} {{\n", tag, tag),
+        OpenSeesFormat::Python => command(
+            format,
+            "pattern",
+            &[text("Plain"), Arg::Integer(tag), Arg::Integer(tag)],
+        ),
+    };
+    let indent = match format {
+        OpenSeesFormat::Tcl => "    ",
+        OpenSeesFormat::Python => "",
+    };
+
+    for nodal in &load_case.nodal_load_list {
+        let mut arg_list = vec![Arg::Integer(model.node_id(nodal.id) as usize)];
+        arg_list.extend(nodal.force.iter().map(|&f| Arg::Number(f)));
+        pattern += indent;
+        pattern += &command(format, "load", &arg_list);
+    }
+
+    // beamUniform takes Wy, Wz and Wx in local axes, element by element, followed by aOverL and
+    // bOverL when the load covers only part of the element.
+    let mut member_load: HashMap<u32, Vec<&MemberLoad>> = HashMap::new();
+    for load in &load_case.member_load_list {
+        member_load.entry(load.id).or_default().push(load);
+    }
+    for (i, (element, &range)) in model
+        .element_list
+        .iter()
+        .zip(&model.element_range_list())
+        .enumerate()
+    {
+        let load_list = match member_load.get(&element.member_id) {
+            Some(l) => l,
+            None => continue,
+        };
+        let ni = model
+            .node_list
+            .iter()
+            .find(|n| n.id == element.node_i)
+            .unwrap();
+        let nj = model
+            .node_list
+            .iter()
+            .find(|n| n.id == element.node_j)
+            .unwrap();
+        let x = Node::new(nj.x - ni.x, nj.y - ni.y, nj.z - ni.z).normalize();
+
+        for load in load_list {
+            let (a, b) = match load.on_element(range) {
+                Some(r) => r,
+                None => continue,
+            };
+            let w = Node::new(load.w[0], load.w[1], load.w[2]);
+            let mut arg_list = vec![
+                text("-ele"),
+                Arg::Integer(i + 1),
+                text("-type"),
+                text("-beamUniform"),
+                Arg::Number(w.dot(element.y_axis)),
+                Arg::Number(w.dot(element.z_axis)),
+                Arg::Number(w.dot(x)),
+            ];
+            if a > 0.0 || b < 1.0 {
+                arg_list.push(Arg::Number(a));
+                arg_list.push(Arg::Number(b));
+            }
+            pattern += indent;
+            pattern += &command(format, "eleLoad", &arg_list);
+        }
+    }
+
+    if format == OpenSeesFormat::Tcl {
+        pattern += "}\n";
+    }
+    pattern
+}
+
+// Each element's share of the case is split between its ends like the reactions of a simple
+// beam; nodal loads stay where they are.
+fn lumped_mass(
+    model: &FrameModel,
+    node_map: &HashMap<u32, Node>,
+    self_weight: &LoadCase,
+) -> BTreeMap<u32, f64> {
+    let mut mass: BTreeMap<u32, f64> = BTreeMap::new();
+
+    for nodal in &self_weight.nodal_load_list {
+        *mass.entry(model.node_id(nodal.id)).or_insert(0.0) += -nodal.force[2] / GRAVITY;
+    }
+
+    for (element, &range) in model.element_list.iter().zip(&model.element_range_list()) {
+        let length = Node::distance(node_map[&element.node_i], node_map[&element.node_j]);
+        for load in &self_weight.member_load_list {
+            if load.id != element.member_id {
+                continue;
+            }
+            if let Some((a, b)) = load.on_element(range) {
+                let m = -load.w[2] * (b - a) * length / GRAVITY;
+                let centre = (a + b) / 2.0;
+                *mass.entry(element.node_i).or_insert(0.0) += m * (1.0 - centre);
+                *mass.entry(element.node_j).or_insert(0.0) += m * centre;
+            }
+        }
+    }
+
+    mass
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn count(script: &str, prefix: &str) -> usize {
+        script.lines().filter(|l| l.starts_with(prefix)).count()
+    }
+
+    #[test]
+    fn tcl_matches_the_frame_model() {
+        let st_bridge = sample();
+        let model = FrameModel::new(&st_bridge, &MaterialDatabase::new());
+
+        let script = create_opensees(&st_bridge, OpenSeesFormat::Tcl);
+
+        assert!(script.contains("\nmodel basic -ndm 3 -ndf 6\n"));
+        assert_eq!(count(&script, "node "), model.node_list.len());
+        assert_eq!(count(&script, "fix "), model.support_list.len());
+        assert_eq!(
+            count(&script, "geomTransf Linear "),
+            model.element_list.len()
+        );
+        assert_eq!(
+            count(&script, "element elasticBeamColumn "),
+            model.element_list.len()
+        );
+        assert_eq!(count(&script, "pattern Plain "), count(&script, "}"));
+        assert!(count(&script, "pattern Plain ") > 0);
+    }
+
+    #[test]
+    fn python_uses_the_same_commands() {
+        let st_bridge = sample();
+
+        let tcl = create_opensees(&st_bridge, OpenSeesFormat::Tcl);
+        let python = create_opensees(&st_bridge, OpenSeesFormat::Python);
+
+        assert!(python.starts_with("# stb-viewer"));
+        assert!(python.contains("import openseespy.opensees as ops\n"));
+        for line in python.lines() {
+            assert!(
+                line.is_empty()
+                    || line.starts_with('#')
+                    || line.starts_with("ops.")
+                    || line.starts_with("import "),
+                "{}",
+                line
+            );
+        }
+        assert_eq!(count(&python, "ops.node("), count(&tcl, "node "));
+        assert_eq!(
+            count(&python, "ops.element('elasticBeamColumn', "),
+            count(&tcl, "element ")
+        );
+        assert_eq!(count(&python, "ops.eleLoad("), count(&tcl, "    eleLoad "));
+        assert_eq!(count(&python, "ops.load("), count(&tcl, "    load "));
+    }
+
+    #[test]
+    fn masses_add_up_to_the_self_weight() {
+        let st_bridge = sample();
+        let database = MaterialDatabase::new();
+        let model = FrameModel::new(&st_bridge, &database);
+        let loads = generate_load_cases(&st_bridge, &model, &database, &LoadSettings::new());
+        let self_weight = loads
+            .load_case_list
+            .iter()
+            .find(|l| l.name == "SW")
+            .unwrap();
+
+        let node_map: HashMap<u32, Node> = model
+            .node_list
+            .iter()
+            .map(|n| (n.id, Node::new(n.x, n.y, n.z)))
+            .collect();
+        let mut weight: f64 = self_weight
+            .nodal_load_list
+            .iter()
+            .map(|l| -l.force[2])
+            .sum();
+        for (element, &range) in model.element_list.iter().zip(&model.element_range_list()) {
+            let length = Node::distance(node_map[&element.node_i], node_map[&element.node_j]);
+            for l in &self_weight.member_load_list {
+                if l.id != element.member_id {
+                    continue;
+                }
+                if let Some((a, b)) = l.on_element(range) {
+                    weight += -l.w[2] * (b - a) * length;
+                }
+            }
+        }
+
+        let mass: f64 = create_opensees(&st_bridge, OpenSeesFormat::Tcl)
+            .lines()
+            .filter(|l| l.starts_with("mass "))
+            .map(|l| l.split(' ').nth(2).unwrap().parse::<f64>().unwrap())
+            .sum();
+
+        assert!(weight > 0.0);
+        assert!((mass - weight / GRAVITY).abs() < 1e-6 * mass);
+    }
+}
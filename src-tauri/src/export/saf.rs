@@ -0,0 +1,456 @@
+use crate::analysis::frame::frame_section;
+use crate::material::{concrete_grade, section_grade_list, MaterialDatabase, MaterialKind};
+use crate::st_bridge::stb_model::stb_members::{JointCondition, StbMember};
+use crate::st_bridge::stb_model::stb_sections::{StbSecSteelChildren, StbSection};
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::prelude::*;
+
+// One worksheet of the Structural Analysis Format, kept as columns and rows so that the layout
+// matches the xlsx template. Lengths are in m and stresses in MPa as SAF expects.
+#[derive(Debug, Serialize)]
+pub struct SafSheet {
+    pub name: String,
+    pub column_list: Vec<String>,
+    pub row_list: Vec<Vec<Value>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SafDocument {
+    pub sheet_list: Vec<SafSheet>,
+}
+
+impl SafSheet {
+    fn new(name: &str, column_list: &[&str]) -> SafSheet {
+        SafSheet {
+            name: name.to_string(),
+            column_list: column_list.iter().map(|c| c.to_string()).collect(),
+            row_list: Vec::new(),
+        }
+    }
+}
+
+impl SafDocument {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+pub fn write_saf(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let mut f = File::create(file_name)?;
+    f.write_all(create_saf(st_bridge).to_json().as_bytes())
+}
+
+fn node_name(id: u32) -> String {
+    format!("N{}", id)
+}
+
+fn member_name(stb_member: &StbMember) -> String {
+    format!("{}_{}", stb_member.name(), stb_member.id())
+}
+
+fn sorted_members(st_bridge: &StBridge) -> Vec<&StbMember> {
+    let mut member_list: Vec<&StbMember> = st_bridge.stb_model.stb_members.iter().collect();
+    member_list.sort_by_key(|m| m.id());
+    member_list
+}
+
+// Shape, parameters in mm and profile name for the SAF parametric cross-section.
+fn section_shape(
+    st_bridge: &StBridge,
+    stb_section: &StbSection,
+) -> Option<(String, String, String)> {
+    let join = |values: &[f64]| {
+        values
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join(";")
+    };
+
+    if let Some(shape) = stb_section.steel_shape() {
+        let steel = st_bridge.stb_model.stb_sections.stb_sec_steel.get(shape)?;
+        let (kind, parameters) = match *steel {
+            StbSecSteelChildren::StbSecRollH { a, b, t1, t2, .. }
+            | StbSecSteelChildren::StbSecBuildH { a, b, t1, t2, .. } => {
+                ("I section", join(&[a, b, t1, t2]))
+            }
+            StbSecSteelChildren::StbSecRollBox { a, b, t, .. } => {
+                ("Rectangular hollow section", join(&[a, b, t]))
+            }
+            StbSecSteelChildren::StbSecBuildBox { a, b, t1, t2, .. } => {
+                ("Rectangular hollow section", join(&[a, b, t1, t2]))
+            }
+            StbSecSteelChildren::StbSecPipe { d, t, .. } => {
+                ("Circular hollow section", join(&[d, t]))
+            }
+            StbSecSteelChildren::StbSecRollL { a, b, t1, t2, .. } => {
+                ("L section", join(&[a, b, t1, t2]))
+            }
+        };
+        return Some((kind.to_string(), parameters, shape.clone()));
+    }
+
+    match stb_section {
+        StbSection::StbSecBeamRC { stb_sec_figure, .. } => {
+            let (width, depth) = crate::geometry::beam_rc_size(stb_sec_figure)?;
+            Some((
+                "Rectangle".to_string(),
+                join(&[depth, width]),
+                String::new(),
+            ))
+        }
+        StbSection::StbSecColumnRC { stb_sec_figure, .. } => {
+            match (&stb_sec_figure.stb_sec_rect, &stb_sec_figure.stb_sec_circle) {
+                (Some(rect), _) => Some((
+                    "Rectangle".to_string(),
+                    join(&[rect.width_y, rect.width_x]),
+                    String::new(),
+                )),
+                (None, Some(circle)) => {
+                    Some(("Circle".to_string(), join(&[circle.d]), String::new()))
+                }
+                (None, None) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Steel sections take their main strength; concrete sections their own strength or, failing
+// that, the story concrete of the first member using them.
+fn section_material(st_bridge: &StBridge, stb_section: &StbSection) -> String {
+    if stb_section.steel_shape().is_some() {
+        return section_grade_list(stb_section)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+    }
+
+    sorted_members(st_bridge)
+        .into_iter()
+        .find(|m| m.id_section() == stb_section.id())
+        .map(|m| concrete_grade(st_bridge, m, stb_section))
+        .unwrap_or_default()
+}
+
+pub fn create_saf(st_bridge: &StBridge) -> SafDocument {
+    let stb_model = &st_bridge.stb_model;
+    let database = MaterialDatabase::new();
+
+    let mut material = SafSheet::new(
+        "StructuralMaterial",
+        &[
+            "Name",
+            "Type",
+            "Quality",
+            "Unit mass [kg/m3]",
+            "E modulus [MPa]",
+            "G modulus [MPa]",
+            "Poisson Coefficient",
+            "Thermal expansion [1/K]",
+        ],
+    );
+    for m in database.report(st_bridge).material_list {
+        let kind = match m.kind {
+            MaterialKind::Steel => "Steel",
+            MaterialKind::Concrete => "Concrete",
+            MaterialKind::Rebar => "Reinforcement",
+        };
+        material.row_list.push(vec![
+            json!(m.name),
+            json!(kind),
+            json!(m.name),
+            json!(m.density),
+            json!(m.young_modulus),
+            json!(m.young_modulus / (2.0 * (1.0 + m.poisson_ratio))),
+            json!(m.poisson_ratio),
+            json!(m.thermal_coefficient),
+        ]);
+    }
+
+    let mut cross_section = SafSheet::new(
+        "StructuralCrossSection",
+        &[
+            "Name",
+            "Material",
+            "Cross-section type",
+            "Shape",
+            "Parameters [mm]",
+            "Profile",
+            "A [m2]",
+            "Iy [m4]",
+            "Iz [m4]",
+            "It [m4]",
+        ],
+    );
+
+    // Section names repeat across floors and kinds, so the repeats get their id appended.
+    let stb_sections = &stb_model.stb_sections;
+    let mut section_list: Vec<&StbSection> = stb_sections
+        .column_rc_map
+        .values()
+        .chain(stb_sections.column_s_map.values())
+        .chain(stb_sections.beam_rc_map.values())
+        .chain(stb_sections.beam_s_map.values())
+        .chain(stb_sections.brace_s_map.values())
+        .collect();
+    section_list.sort_by_key(|s| s.id());
+
+    let mut section_name: BTreeMap<u32, String> = BTreeMap::new();
+    let mut used_name: BTreeSet<String> = BTreeSet::new();
+    for stb_section in section_list {
+        let mut name = stb_section.name();
+        if !used_name.insert(name.clone()) {
+            name = format!("{}_{}", name, stb_section.id());
+            used_name.insert(name.clone());
+        }
+        section_name.insert(stb_section.id(), name.clone());
+
+        let (shape, parameters, profile) =
+            section_shape(st_bridge, stb_section).unwrap_or_default();
+        let (area, iy, iz, it) = match frame_section(st_bridge, stb_section) {
+            Some(s) => (s.area * 1e-6, s.iy * 1e-12, s.iz * 1e-12, s.j * 1e-12),
+            None => (0.0, 0.0, 0.0, 0.0),
+        };
+
+        cross_section.row_list.push(vec![
+            json!(name),
+            json!(section_material(st_bridge, stb_section)),
+            json!("Parametric"),
+            json!(shape),
+            json!(parameters),
+            json!(profile),
+            json!(area),
+            json!(iy),
+            json!(iz),
+            json!(it),
+        ]);
+    }
+
+    let mut storey = SafSheet::new("StructuralStorey", &["Name", "Height level [m]"]);
+    for s in &stb_model.stb_stories.stb_story_list {
+        storey
+            .row_list
+            .push(vec![json!(s.name), json!(s.height / 1000.0)]);
+    }
+
+    let mut point = SafSheet::new(
+        "StructuralPointConnection",
+        &[
+            "Name",
+            "Coordinate X [m]",
+            "Coordinate Y [m]",
+            "Coordinate Z [m]",
+        ],
+    );
+    let mut node_id_list: Vec<&u32> = stb_model.stb_nodes.map.keys().collect();
+    node_id_list.sort();
+    for id in node_id_list {
+        let n = &stb_model.stb_nodes.map[id];
+        point.row_list.push(vec![
+            json!(node_name(*id)),
+            json!(n.x / 1000.0),
+            json!(n.y / 1000.0),
+            json!(n.z / 1000.0),
+        ]);
+    }
+
+    let mut curve = SafSheet::new(
+        "StructuralCurveMember",
+        &[
+            "Name",
+            "Type",
+            "Cross section",
+            "Nodes",
+            "Segments",
+            "Begin node",
+            "End node",
+            "Length [m]",
+            "Behaviour",
+            "System line",
+            "LCS Rotation [deg]",
+            "Layer",
+            "Storey",
+        ],
+    );
+    let mut surface = SafSheet::new(
+        "StructuralSurfaceMember",
+        &[
+            "Name",
+            "Type",
+            "Material",
+            "Thickness type",
+            "Thickness [mm]",
+            "System plane at",
+            "Nodes",
+            "Edges",
+            "Layer",
+            "Storey",
+        ],
+    );
+
+    for stb_member in sorted_members(st_bridge) {
+        let story = stb_model
+            .story(stb_member)
+            .map(|s| s.name.clone())
+            .unwrap_or_default();
+
+        if stb_member.is_linear() {
+            let (kind, rotate, behaviour) = match stb_member {
+                StbMember::StbColumn { rotate, .. } => ("Column", *rotate, "Standard"),
+                StbMember::StbPost { rotate, .. } => ("Secondary column", *rotate, "Standard"),
+                StbMember::StbGirder { rotate, .. } | StbMember::StbBeam { rotate, .. } => {
+                    ("Beam", *rotate, "Standard")
+                }
+                StbMember::StbBrace {
+                    rotate,
+                    condition_start,
+                    condition_end,
+                    ..
+                } => {
+                    let behaviour = match (condition_start, condition_end) {
+                        (JointCondition::Pin, JointCondition::Pin) => "Axial force only",
+                        _ => "Standard",
+                    };
+                    ("Wall bracing", *rotate, behaviour)
+                }
+                _ => continue,
+            };
+
+            // Girders and beams hang from their nodes, so their system line is the top.
+            let system_line = match stb_member {
+                StbMember::StbGirder { .. } | StbMember::StbBeam { .. } => "Top",
+                _ => "Centre",
+            };
+
+            let (node_i, node_j) = match (stb_member.node_i(), stb_member.node_j()) {
+                (Some(i), Some(j)) => (i, j),
+                _ => continue,
+            };
+            let length = match (
+                stb_model.stb_nodes.get(node_i),
+                stb_model.stb_nodes.get(node_j),
+            ) {
+                (Some(a), Some(b)) => {
+                    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+                        / 1000.0
+                }
+                _ => 0.0,
+            };
+
+            let begin = node_name(node_i);
+            let end = node_name(node_j);
+            curve.row_list.push(vec![
+                json!(member_name(stb_member)),
+                json!(kind),
+                json!(section_name
+                    .get(&stb_member.id_section())
+                    .cloned()
+                    .unwrap_or_default()),
+                json!(format!("{};{}", begin, end)),
+                json!("Line"),
+                json!(begin),
+                json!(end),
+                json!(length),
+                json!(behaviour),
+                json!(system_line),
+                json!(rotate),
+                json!(stb_member.member_kind()),
+                json!(story),
+            ]);
+            continue;
+        }
+
+        let stb_section = match stb_sections.get(stb_member.id_section()) {
+            Some(s) => s,
+            None => continue,
+        };
+        let (kind, thickness, plane) = match (stb_member, stb_section) {
+            (StbMember::StbSlab { .. }, StbSection::StbSecSlabRC { stb_sec_figure, .. }) => {
+                ("Plate", stb_sec_figure.stb_sec_straight.depth, "Top")
+            }
+            (StbMember::StbWall { .. }, StbSection::StbSecWallRC { stb_sec_figure, .. }) => {
+                ("Wall", stb_sec_figure.stb_sec_straight.t, "Centre")
+            }
+            _ => continue,
+        };
+
+        let node_id_list = stb_member.node_id_list();
+        surface.row_list.push(vec![
+            json!(member_name(stb_member)),
+            json!(kind),
+            json!(concrete_grade(st_bridge, stb_member, stb_section)),
+            json!("Constant"),
+            json!(thickness),
+            json!(plane),
+            json!(node_id_list
+                .iter()
+                .map(|&id| node_name(id))
+                .collect::<Vec<String>>()
+                .join(";")),
+            json!(vec!["Line"; node_id_list.len()].join(";")),
+            json!(stb_member.member_kind()),
+            json!(story),
+        ]);
+    }
+
+    SafDocument {
+        sheet_list: vec![material, cross_section, storey, point, curve, surface],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Numbers only have to agree closely, as the last digits of the material formulas can
+    // differ between platforms.
+    fn assert_same(actual: &Value, expected: &Value, path: &str) {
+        match (actual, expected) {
+            (Value::Number(a), Value::Number(b)) => {
+                let (a, b) = (a.as_f64().unwrap(), b.as_f64().unwrap());
+                assert!(
+                    (a - b).abs() <= b.abs().max(1.0) * 1e-9,
+                    "{}: {} != {}",
+                    path,
+                    a,
+                    b
+                );
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                assert_eq!(a.len(), b.len(), "{}: length", path);
+                for (k, (x, y)) in a.iter().zip(b).enumerate() {
+                    assert_same(x, y, &format!("{}[{}]", path, k));
+                }
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                assert_eq!(
+                    a.keys().collect::<Vec<_>>(),
+                    b.keys().collect::<Vec<_>>(),
+                    "{}: keys",
+                    path
+                );
+                for (key, x) in a {
+                    assert_same(x, &b[key], &format!("{}.{}", path, key));
+                }
+            }
+            _ => assert_eq!(actual, expected, "{}", path),
+        }
+    }
+
+    #[test]
+    fn portal_frame_matches_golden_file() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/");
+        let st_bridge = crate::read_st_bridge(&format!("{}portal_frame.stb", fixture)).unwrap();
+        let expected =
+            std::fs::read_to_string(format!("{}portal_frame_saf.json", fixture)).unwrap();
+
+        let actual: Value = serde_json::from_str(&create_saf(&st_bridge).to_json()).unwrap();
+        let expected: Value = serde_json::from_str(&expected).unwrap();
+        assert_same(&actual, &expected, "saf");
+    }
+}
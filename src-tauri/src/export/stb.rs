@@ -0,0 +1,871 @@
+use crate::drawing::escape_xml;
+use crate::st_bridge::stb_model::stb_axes_and_stories::StbNodeIdList;
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::stb_model::stb_nodes::StbNode;
+use crate::st_bridge::stb_model::stb_sections::{StbSecSteelChildren, StbSection};
+use crate::st_bridge::StBridge;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::prelude::*;
+
+// Writes the model in the ST-Bridge 1.x layout the reader understands, under the given version.
+// Elements the reader skips, such as footings and SRC sections, are not in the model and so are
+// not written.
+pub fn write_st_bridge(
+    st_bridge: &StBridge,
+    file_name: &str,
+    version: &str,
+) -> std::io::Result<()> {
+    let mut f = File::create(file_name)?;
+    f.write_all(create_st_bridge(st_bridge, version).as_bytes())
+}
+
+pub fn create_st_bridge(st_bridge: &StBridge, version: &str) -> String {
+    let root = Element::new("ST_BRIDGE")
+        .attribute("version", version)
+        .child(stb_common(st_bridge))
+        .child(stb_model(st_bridge))
+        .child(stb_extensions(st_bridge));
+
+    let mut stb = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    root.write(0, &mut stb);
+    stb
+}
+
+struct Element {
+    tag: &'static str,
+    attribute_list: Vec<(&'static str, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn new(tag: &'static str) -> Element {
+        Element {
+            tag,
+            attribute_list: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn attribute(mut self, key: &'static str, value: impl Display) -> Element {
+        self.attribute_list.push((key, value.to_string()));
+        self
+    }
+
+    fn optional(self, key: &'static str, value: Option<impl Display>) -> Element {
+        match value {
+            Some(value) => self.attribute(key, value),
+            None => self,
+        }
+    }
+
+    fn flag(self, key: &'static str, value: bool) -> Element {
+        self.attribute(key, if value { "TRUE" } else { "FALSE" })
+    }
+
+    // Bar counts of optional layers are left out when there are none, as the reader expects.
+    fn count(self, key: &'static str, value: u32) -> Element {
+        self.optional(key, Some(value).filter(|&v| v != 0))
+    }
+
+    fn child(mut self, element: Element) -> Element {
+        self.children.push(element);
+        self
+    }
+
+    fn children(mut self, element_list: impl IntoIterator<Item = Element>) -> Element {
+        self.children.extend(element_list);
+        self
+    }
+
+    fn write(&self, depth: usize, stb: &mut String) {
+        let indent = "    ".repeat(depth);
+        stb.push_str(&format!("{}<{}", indent, self.tag));
+        for (key, value) in &self.attribute_list {
+            stb.push_str(&format!(" {}=\"{}\"", key, escape_xml(value)));
+        }
+        if self.children.is_empty() {
+            stb.push_str("/>\n");
+            return;
+        }
+        stb.push_str(">\n");
+        for child in &self.children {
+            child.write(depth + 1, stb);
+        }
+        stb.push_str(&format!("{}</{}>\n", indent, self.tag));
+    }
+}
+
+// Elements in id order, so that writing the same model twice gives the same file.
+fn sorted<T>(map: &HashMap<u32, T>) -> Vec<&T> {
+    map.iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect()
+}
+
+fn stb_common(st_bridge: &StBridge) -> Element {
+    let stb_common = &st_bridge.stb_common;
+    let strength_map: BTreeMap<&String, &String> =
+        stb_common.stb_reinforcement_strength_list.iter().collect();
+
+    Element::new("StbCommon")
+        .attribute("project_name", &stb_common.project_name)
+        .child(Element::new("StbReinforcement_Strength_List").children(
+            strength_map.into_iter().map(|(d, sd)| {
+                Element::new("StbReinforcement_Strength")
+                    .attribute("D", d)
+                    .attribute("SD", sd)
+            }),
+        ))
+}
+
+fn stb_model(st_bridge: &StBridge) -> Element {
+    let stb_model = &st_bridge.stb_model;
+
+    let node_map: BTreeMap<&u32, &StbNode> = stb_model.stb_nodes.map.iter().collect();
+    let stb_nodes = node_map.into_iter().map(|(id, node)| {
+        Element::new("StbNode")
+            .attribute("id", id)
+            .attribute("x", node.x)
+            .attribute("y", node.y)
+            .attribute("z", node.z)
+            .attribute("kind", &node.kind)
+            .optional("id_member", node.id_member)
+    });
+
+    let stb_axes = &stb_model.stb_axes;
+    let x_axis_list = stb_axes.stb_x_axis_list.iter().map(|axis| {
+        Element::new("StbX_Axis")
+            .attribute("id", axis.id)
+            .attribute("name", &axis.name)
+            .attribute("distance", axis.distance)
+            .child(stb_node_id_list(&axis.stb_node_id_list))
+    });
+    let y_axis_list = stb_axes.stb_y_axis_list.iter().map(|axis| {
+        Element::new("StbY_Axis")
+            .attribute("id", axis.id)
+            .attribute("name", &axis.name)
+            .attribute("distance", axis.distance)
+            .child(stb_node_id_list(&axis.stb_node_id_list))
+    });
+
+    let story_list = stb_model.stb_stories.stb_story_list.iter().map(|story| {
+        Element::new("StbStory")
+            .attribute("id", story.id)
+            .attribute("name", &story.name)
+            .attribute("height", story.height)
+            .attribute("kind", &story.kind)
+            .attribute("concrete_strength", &story.concrete_strength)
+            .child(stb_node_id_list(&story.stb_node_id_list))
+    });
+
+    Element::new("StbModel")
+        .child(Element::new("StbNodes").children(stb_nodes))
+        .child(
+            Element::new("StbAxes")
+                .children(x_axis_list)
+                .children(y_axis_list),
+        )
+        .child(Element::new("StbStories").children(story_list))
+        .child(stb_members(st_bridge))
+        .child(stb_sections(st_bridge))
+}
+
+fn stb_node_id_list(stb_node_id_list: &StbNodeIdList) -> Element {
+    Element::new("StbNodeid_List").children(
+        stb_node_id_list
+            .children
+            .iter()
+            .map(|n| Element::new("StbNodeid").attribute("id", n.id)),
+    )
+}
+
+fn stb_members(st_bridge: &StBridge) -> Element {
+    let stb_members = &st_bridge.stb_model.stb_members;
+    let group_list = [
+        ("StbColumns", &stb_members.stb_columns),
+        ("StbPosts", &stb_members.stb_posts),
+        ("StbGirders", &stb_members.stb_girders),
+        ("StbBeams", &stb_members.stb_beams),
+        ("StbBraces", &stb_members.stb_braces),
+        ("StbSlabs", &stb_members.stb_slabs),
+        ("StbWalls", &stb_members.stb_walls),
+        ("StbOpens", &stb_members.stb_opens),
+    ];
+
+    Element::new("StbMembers").children(
+        group_list
+            .iter()
+            .filter(|(_, map)| !map.is_empty())
+            .map(|(tag, map)| Element::new(tag).children(sorted(map).into_iter().map(stb_member))),
+    )
+}
+
+fn stb_member(stb_member: &StbMember) -> Element {
+    match stb_member {
+        StbMember::StbColumn {
+            id,
+            name,
+            id_node_bottom,
+            id_node_top,
+            rotate,
+            id_section,
+            kind_structure,
+            offset_x,
+            offset_y,
+            condition_bottom,
+            condition_top,
+        } => Element::new("StbColumn")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("idNode_bottom", id_node_bottom)
+            .attribute("idNode_top", id_node_top)
+            .attribute("rotate", rotate)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .attribute("offset_X", offset_x)
+            .attribute("offset_Y", offset_y)
+            .attribute("condition_bottom", condition_bottom)
+            .attribute("condition_top", condition_top),
+        StbMember::StbPost {
+            id,
+            name,
+            id_node_bottom,
+            id_node_top,
+            rotate,
+            id_section,
+            kind_structure,
+            offset_x,
+            offset_y,
+            offset_bottom_x,
+            offset_bottom_y,
+            offset_bottom_z,
+            offset_top_x,
+            offset_top_y,
+            offset_top_z,
+            condition_bottom,
+            condition_top,
+        } => Element::new("StbPost")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("idNode_bottom", id_node_bottom)
+            .attribute("idNode_top", id_node_top)
+            .attribute("rotate", rotate)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .attribute("offset_X", offset_x)
+            .attribute("offset_Y", offset_y)
+            .attribute("offset_bottom_X", offset_bottom_x)
+            .attribute("offset_bottom_Y", offset_bottom_y)
+            .attribute("offset_bottom_Z", offset_bottom_z)
+            .attribute("offset_top_X", offset_top_x)
+            .attribute("offset_top_Y", offset_top_y)
+            .attribute("offset_top_Z", offset_top_z)
+            .attribute("condition_bottom", condition_bottom)
+            .attribute("condition_top", condition_top),
+        StbMember::StbGirder {
+            id,
+            name,
+            id_node_start,
+            id_node_end,
+            rotate,
+            id_section,
+            kind_structure,
+            is_foundation,
+            offset,
+            level,
+            type_haunch_h,
+            condition_start,
+            condition_end,
+        } => Element::new("StbGirder")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("idNode_start", id_node_start)
+            .attribute("idNode_end", id_node_end)
+            .attribute("rotate", rotate)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .flag("isFoundation", *is_foundation)
+            .attribute("offset", offset)
+            .attribute("level", level)
+            .optional("type_haunch_H", type_haunch_h.as_ref())
+            .attribute("condition_start", condition_start)
+            .attribute("condition_end", condition_end),
+        StbMember::StbBeam {
+            id,
+            name,
+            id_node_start,
+            id_node_end,
+            rotate,
+            id_section,
+            kind_structure,
+            is_foundation,
+            offset,
+            level,
+            condition_start,
+            condition_end,
+        } => Element::new("StbBeam")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("idNode_start", id_node_start)
+            .attribute("idNode_end", id_node_end)
+            .attribute("rotate", rotate)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .flag("isFoundation", *is_foundation)
+            .attribute("offset", offset)
+            .attribute("level", level)
+            .attribute("condition_start", condition_start)
+            .attribute("condition_end", condition_end),
+        StbMember::StbBrace {
+            id,
+            name,
+            id_node_start,
+            id_node_end,
+            rotate,
+            id_section,
+            kind_structure,
+            offset_start_x,
+            offset_start_y,
+            offset_start_z,
+            offset_end_x,
+            offset_end_y,
+            offset_end_z,
+            condition_start,
+            condition_end,
+        } => Element::new("StbBrace")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("idNode_start", id_node_start)
+            .attribute("idNode_end", id_node_end)
+            .attribute("rotate", rotate)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .attribute("offset_start_X", offset_start_x)
+            .attribute("offset_start_Y", offset_start_y)
+            .attribute("offset_start_Z", offset_start_z)
+            .attribute("offset_end_X", offset_end_x)
+            .attribute("offset_end_Y", offset_end_y)
+            .attribute("offset_end_Z", offset_end_z)
+            .attribute("condition_start", condition_start)
+            .attribute("condition_end", condition_end),
+        StbMember::StbSlab {
+            id,
+            name,
+            id_section,
+            kind_structure,
+            kind_slab,
+            level,
+            is_foundation,
+            stb_node_id_list: node_id_list,
+        } => Element::new("StbSlab")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .attribute("kind_slab", kind_slab)
+            .attribute("level", level)
+            .flag("isFoundation", *is_foundation)
+            .child(stb_node_id_list(node_id_list)),
+        StbMember::StbWall {
+            id,
+            name,
+            id_section,
+            kind_structure,
+            stb_node_id_list: node_id_list,
+        } => Element::new("StbWall")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("id_section", id_section)
+            .attribute("kind_structure", kind_structure)
+            .child(stb_node_id_list(node_id_list)),
+        StbMember::StbOpen {
+            id,
+            name,
+            id_section,
+            id_member,
+            kind_member,
+            position_x,
+            position_y,
+            length_x,
+            length_y,
+            rotate,
+        } => Element::new("StbOpen")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("id_section", id_section)
+            .attribute("id_member", id_member)
+            .attribute("kind_member", kind_member)
+            .attribute("position_X", position_x)
+            .attribute("position_Y", position_y)
+            .attribute("length_X", length_x)
+            .attribute("length_Y", length_y)
+            .attribute("rotate", rotate),
+    }
+}
+
+fn stb_sections(st_bridge: &StBridge) -> Element {
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    let map_list = [
+        &stb_sections.column_rc_map,
+        &stb_sections.column_s_map,
+        &stb_sections.beam_rc_map,
+        &stb_sections.beam_s_map,
+        &stb_sections.brace_s_map,
+        &stb_sections.slab_rc_map,
+        &stb_sections.wall_rc_map,
+    ];
+
+    let steel_map: BTreeMap<&String, &StbSecSteelChildren> =
+        stb_sections.stb_sec_steel.children_map.iter().collect();
+
+    Element::new("StbSections")
+        .children(map_list.iter().flat_map(|map| sorted(map)).map(stb_section))
+        .child(Element::new("StbSecSteel").children(steel_map.into_values().map(stb_sec_steel)))
+}
+
+fn stb_section(stb_section: &StbSection) -> Element {
+    match stb_section {
+        StbSection::StbSecColumnRC {
+            id,
+            name,
+            floor,
+            kind_column,
+            d_reinforcement_main,
+            d_rein_band,
+            strength_concrete,
+            strength_reinforcement_main,
+            strength_rein_band,
+            depth_cover_start_x,
+            depth_cover_end_x,
+            depth_cover_start_y,
+            depth_cover_end_y,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+        } => {
+            let figure = Element::new("StbSecFigure")
+                .children(stb_sec_figure.stb_sec_rect.as_ref().map(|rect| {
+                    Element::new("StbSecRect")
+                        .attribute("width_X", rect.width_x)
+                        .attribute("width_Y", rect.width_y)
+                }))
+                .children(
+                    stb_sec_figure
+                        .stb_sec_circle
+                        .as_ref()
+                        .map(|circle| Element::new("StbSecCircle").attribute("D", circle.d)),
+                );
+            let bar_arrangement = Element::new("StbSecBar_Arrangement")
+                .children(
+                    stb_sec_bar_arrangement
+                        .stb_sec_rect_column_same
+                        .as_ref()
+                        .map(|bar| {
+                            Element::new("StbSecRect_Column_Same")
+                                .attribute("count_main_X_1st", bar.count_main_x_1st)
+                                .attribute("count_main_Y_1st", bar.count_main_y_1st)
+                                .count("count_main_X_2nd", bar.count_main_x_2nd)
+                                .count("count_main_Y_2nd", bar.count_main_y_2nd)
+                                .attribute("count_main_total", bar.count_main_total)
+                                .attribute("count_band_dir_X", bar.count_band_dir_x)
+                                .attribute("count_band_dir_Y", bar.count_band_dir_y)
+                                .attribute("pitch_band", bar.pitch_band)
+                        }),
+                )
+                .children(
+                    stb_sec_bar_arrangement
+                        .stb_sec_circle_column_same
+                        .as_ref()
+                        .map(|bar| {
+                            Element::new("StbSecCircle_Column_Same")
+                                .attribute("count_main", bar.count_main)
+                                .attribute("count_band", bar.count_band)
+                                .attribute("pitch_band", bar.pitch_band)
+                        }),
+                );
+
+            Element::new("StbSecColumn_RC")
+                .attribute("id", id)
+                .attribute("name", name)
+                .attribute("floor", floor)
+                .attribute("kind_column", kind_column)
+                .attribute("D_reinforcement_main", d_reinforcement_main)
+                .attribute("D_rein_band", d_rein_band)
+                .optional("strength_concrete", strength_concrete.as_ref())
+                .attribute("strength_reinforcement_main", strength_reinforcement_main)
+                .attribute("strength_rein_band", strength_rein_band)
+                .optional("depth_cover_start_X", *depth_cover_start_x)
+                .optional("depth_cover_end_X", *depth_cover_end_x)
+                .optional("depth_cover_start_Y", *depth_cover_start_y)
+                .optional("depth_cover_end_Y", *depth_cover_end_y)
+                .child(figure)
+                .child(bar_arrangement)
+        }
+        StbSection::StbSecColumnS {
+            id,
+            name,
+            floor,
+            kind_column,
+            direction,
+            base_type,
+            stb_sec_steel_column: steel,
+        } => Element::new("StbSecColumn_S")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("floor", floor)
+            .attribute("kind_column", kind_column)
+            .flag("direction", *direction)
+            .attribute("base_type", base_type)
+            .child(
+                Element::new("StbSecSteelColumn")
+                    .attribute("pos", &steel.pos)
+                    .attribute("shape", &steel.shape)
+                    .attribute("strength_main", &steel.strength_main)
+                    .attribute("strength_web", &steel.strength_web),
+            ),
+        StbSection::StbSecBeamRC {
+            id,
+            name,
+            floor,
+            kind_beam,
+            is_foundation,
+            is_canti,
+            d_reinforcement_main,
+            d_stirrup,
+            d_reinforcement_web,
+            d_bar_spacing,
+            strength_concrete,
+            strength_reinforcement_main,
+            strength_reinforcement_2nd_main,
+            strength_stirrup,
+            strength_reinforcement_web,
+            strength_bar_spacing,
+            depth_cover_left,
+            depth_cover_right,
+            depth_cover_top,
+            depth_cover_bottom,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+        } => {
+            let figure = Element::new("StbSecFigure")
+                .children(stb_sec_figure.stb_sec_haunch.as_ref().map(|haunch| {
+                    Element::new("StbSecHaunch")
+                        .attribute("width_start", haunch.width_start)
+                        .attribute("depth_start", haunch.depth_start)
+                        .attribute("width_center", haunch.width_center)
+                        .attribute("depth_center", haunch.depth_center)
+                        .attribute("width_end", haunch.width_end)
+                        .attribute("depth_end", haunch.depth_end)
+                }))
+                .children(stb_sec_figure.stb_sec_straight.as_ref().map(|straight| {
+                    Element::new("StbSecStraight")
+                        .attribute("width", straight.width)
+                        .attribute("depth", straight.depth)
+                }));
+
+            let start_center_end_list = stb_sec_bar_arrangement
+                .stb_sec_beam_start_center_end_section_list
+                .iter()
+                .flatten()
+                .map(|bar| {
+                    Element::new("StbSecBeam_Start_Center_End_Section")
+                        .attribute("pos", &bar.pos)
+                        .attribute("count_main_top_1st", bar.count_main_top_1st)
+                        .attribute("count_main_bottom_1st", bar.count_main_bottom_1st)
+                        .count("count_main_top_2nd", bar.count_main_top_2nd)
+                        .count("count_main_bottom_2nd", bar.count_main_bottom_2nd)
+                        .attribute("count_stirrup", bar.count_stirrup)
+                        .attribute("pitch_stirrup", bar.pitch_stirrup)
+                        .attribute("count_web", bar.count_web)
+                        .attribute("count_bar_spacing", bar.count_bar_spacing)
+                        .attribute("pitch_bar_spacing", bar.pitch_bar_spacing)
+                });
+            let same = stb_sec_bar_arrangement
+                .stb_sec_beam_same_section
+                .as_ref()
+                .map(|bar| {
+                    Element::new("StbSecBeam_Same_Section")
+                        .attribute("count_main_top_1st", bar.count_main_top_1st)
+                        .attribute("count_main_bottom_1st", bar.count_main_bottom_1st)
+                        .count("count_main_top_2nd", bar.count_main_top_2nd)
+                        .count("count_main_bottom_2nd", bar.count_main_bottom_2nd)
+                        .attribute("count_stirrup", bar.count_stirrup)
+                        .attribute("pitch_stirrup", bar.pitch_stirrup)
+                        .attribute("count_web", bar.count_web)
+                        .attribute("count_bar_spacing", bar.count_bar_spacing)
+                        .attribute("pitch_bar_spacing", bar.pitch_bar_spacing)
+                });
+            let bar_arrangement = Element::new("StbSecBar_Arrangement")
+                .children(start_center_end_list)
+                .children(same);
+
+            Element::new("StbSecBeam_RC")
+                .attribute("id", id)
+                .attribute("name", name)
+                .attribute("floor", floor)
+                .attribute("kind_beam", kind_beam)
+                .flag("isFoundation", *is_foundation)
+                .flag("isCanti", *is_canti)
+                .attribute("D_reinforcement_main", d_reinforcement_main)
+                .attribute("D_stirrup", d_stirrup)
+                .attribute("D_reinforcement_web", d_reinforcement_web)
+                .attribute("D_bar_spacing", d_bar_spacing)
+                .optional("strength_concrete", strength_concrete.as_ref())
+                .attribute("strength_reinforcement_main", strength_reinforcement_main)
+                .optional(
+                    "strength_reinforcement_2nd_main",
+                    strength_reinforcement_2nd_main.as_ref(),
+                )
+                .attribute("strength_stirrup", strength_stirrup)
+                .attribute("strength_reinforcement_web", strength_reinforcement_web)
+                .attribute("strength_bar_spacing", strength_bar_spacing)
+                .optional("depth_cover_left", *depth_cover_left)
+                .optional("depth_cover_right", *depth_cover_right)
+                .optional("depth_cover_top", *depth_cover_top)
+                .optional("depth_cover_bottom", *depth_cover_bottom)
+                .child(figure)
+                .child(bar_arrangement)
+        }
+        StbSection::StbSecBeamS {
+            id,
+            name,
+            floor,
+            kind_beam,
+            is_canti,
+            stb_sec_steel_beam_list,
+        } => {
+            let mut element = Element::new("StbSecBeam_S")
+                .attribute("id", id)
+                .attribute("name", name)
+                .attribute("floor", floor)
+                .attribute("kind_beam", kind_beam)
+                .flag("isCanti", *is_canti);
+            for steel in stb_sec_steel_beam_list {
+                element = element.child(
+                    Element::new("StbSecSteelBeam")
+                        .attribute("pos", &steel.pos)
+                        .attribute("shape", &steel.shape)
+                        .attribute("strength_main", &steel.strength_main)
+                        .attribute("strength_web", &steel.strength_web),
+                );
+            }
+            element
+        }
+        StbSection::StbSecSlabRC {
+            id,
+            name,
+            is_foundation,
+            is_canti,
+            strength_concrete,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+        } => Element::new("StbSecSlab_RC")
+            .attribute("id", id)
+            .attribute("name", name)
+            .flag("isFoundation", *is_foundation)
+            .flag("isCanti", *is_canti)
+            .attribute("strength_concrete", strength_concrete)
+            .child(
+                Element::new("StbSecFigure").child(
+                    Element::new("StbSecStraight")
+                        .attribute("depth", stb_sec_figure.stb_sec_straight.depth),
+                ),
+            )
+            .child(
+                Element::new("StbSecBar_Arrangement").children(
+                    stb_sec_bar_arrangement
+                        .stb_sec_1way_slab_1_list
+                        .iter()
+                        .map(|bar| {
+                            Element::new("StbSec1Way_Slab_1")
+                                .attribute("pos", &bar.pos)
+                                .attribute("strength", &bar.strength)
+                                .attribute("D", &bar.d)
+                                .attribute("pitch", bar.pitch)
+                        }),
+                ),
+            ),
+        StbSection::StbSecBraceS {
+            id,
+            name,
+            floor,
+            kind_brace,
+            stb_sec_steel_brace: steel,
+        } => Element::new("StbSecBrace_S")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("floor", floor)
+            .attribute("kind_brace", kind_brace)
+            .child(
+                Element::new("StbSecSteelBrace")
+                    .attribute("pos", &steel.pos)
+                    .attribute("shape", &steel.shape)
+                    .attribute("strength_main", &steel.strength_main)
+                    .attribute("strength_web", &steel.strength_web),
+            ),
+        StbSection::StbSecWallRC {
+            id,
+            name,
+            strength_concrete,
+            stb_sec_figure,
+        } => Element::new("StbSecWall_RC")
+            .attribute("id", id)
+            .attribute("name", name)
+            .attribute("strength_concrete", strength_concrete)
+            .child(Element::new("StbSecFigure").child(
+                Element::new("StbSecStraight").attribute("t", stb_sec_figure.stb_sec_straight.t),
+            )),
+    }
+}
+
+fn stb_sec_steel(steel: &StbSecSteelChildren) -> Element {
+    match steel {
+        StbSecSteelChildren::StbSecRollH {
+            name,
+            sec_type,
+            a,
+            b,
+            t1,
+            t2,
+            r,
+        } => Element::new("StbSecRoll-H")
+            .attribute("name", name)
+            .attribute("type", sec_type)
+            .attribute("A", a)
+            .attribute("B", b)
+            .attribute("t1", t1)
+            .attribute("t2", t2)
+            .attribute("r", r),
+        StbSecSteelChildren::StbSecBuildH { name, a, b, t1, t2 } => Element::new("StbSecBuild-H")
+            .attribute("name", name)
+            .attribute("A", a)
+            .attribute("B", b)
+            .attribute("t1", t1)
+            .attribute("t2", t2),
+        StbSecSteelChildren::StbSecRollBox {
+            name,
+            sec_type,
+            a,
+            b,
+            t,
+            r,
+        } => Element::new("StbSecRoll-BOX")
+            .attribute("name", name)
+            .attribute("type", sec_type)
+            .attribute("A", a)
+            .attribute("B", b)
+            .attribute("t", t)
+            .attribute("R", r),
+        StbSecSteelChildren::StbSecBuildBox { name, a, b, t1, t2 } => {
+            Element::new("StbSecBuild-BOX")
+                .attribute("name", name)
+                .attribute("A", a)
+                .attribute("B", b)
+                .attribute("t1", t1)
+                .attribute("t2", t2)
+        }
+        StbSecSteelChildren::StbSecPipe { name, d, t } => Element::new("StbSecPipe")
+            .attribute("name", name)
+            .attribute("D", d)
+            .attribute("t", t),
+        StbSecSteelChildren::StbSecRollL {
+            name,
+            sec_type,
+            a,
+            b,
+            t1,
+            t2,
+            r1,
+            r2,
+            side,
+        } => Element::new("StbSecRoll-L")
+            .attribute("name", name)
+            .attribute("type", sec_type)
+            .attribute("A", a)
+            .attribute("B", b)
+            .attribute("t1", t1)
+            .attribute("t2", t2)
+            .attribute("r1", r1)
+            .attribute("r2", r2)
+            .flag("side", *side),
+    }
+}
+
+fn stb_extensions(st_bridge: &StBridge) -> Element {
+    Element::new("StbExtensions").children(st_bridge.stb_extensions.stb_extension_list.iter().map(
+        |extension| {
+            Element::new("StbExtension")
+                .attribute("identifier", &extension.identifier)
+                .attribute("description", &extension.description)
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::st_bridge::stb_model::stb_sections::{StbSecSteelBeam, StbSecSteelBeamPosition};
+
+    #[test]
+    fn portal_frame_round_trips() {
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        );
+        let st_bridge = crate::read_st_bridge(fixture).unwrap();
+
+        let file_name = std::env::temp_dir().join("portal_frame_round_trip.stb");
+        let file_name = file_name.to_str().unwrap();
+        write_st_bridge(&st_bridge, file_name, "1.3.00").unwrap();
+        let written = crate::read_st_bridge(file_name).unwrap();
+        std::fs::remove_file(file_name).unwrap();
+
+        assert_eq!(written.version, "1.3.00");
+        let to_value = |s: &StBridge| {
+            let mut value = serde_json::to_value(s).unwrap();
+            value["version"] = serde_json::Value::Null;
+            value
+        };
+        assert_eq!(to_value(&written), to_value(&st_bridge));
+    }
+
+    #[test]
+    fn haunched_steel_beam_round_trips() {
+        let mut st_bridge = crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap();
+        let steel = |pos: StbSecSteelBeamPosition, shape: &str| StbSecSteelBeam {
+            pos,
+            shape: shape.to_string(),
+            strength_main: "SN400".to_string(),
+            strength_web: "SN400".to_string(),
+        };
+        if let Some(StbSection::StbSecBeamS {
+            stb_sec_steel_beam_list,
+            ..
+        }) = st_bridge.stb_model.stb_sections.beam_s_map.get_mut(&5)
+        {
+            *stb_sec_steel_beam_list = vec![
+                steel(StbSecSteelBeamPosition::Haunch, "H-700x300x13x24x28"),
+                steel(StbSecSteelBeamPosition::Center, "H-588x300x12x20x28"),
+            ];
+        }
+
+        let file_name = std::env::temp_dir().join("haunched_steel_beam.stb");
+        let file_name = file_name.to_str().unwrap();
+        write_st_bridge(&st_bridge, file_name, "1.4.00").unwrap();
+        let written = crate::read_st_bridge(file_name).unwrap();
+        std::fs::remove_file(file_name).unwrap();
+
+        let stb_section = written.stb_model.stb_sections.get(5).unwrap();
+        assert_eq!(
+            serde_json::to_value(stb_section).unwrap(),
+            serde_json::to_value(st_bridge.stb_model.stb_sections.get(5).unwrap()).unwrap()
+        );
+        assert_eq!(stb_section.steel_shape().unwrap(), "H-588x300x12x20x28");
+    }
+}
@@ -0,0 +1,166 @@
+use super::{filtered_mesh_list, MeshExportOptions};
+use crate::st_bridge::StBridge;
+use std::fs::File;
+use std::io::prelude::*;
+
+pub fn write_stl(
+    st_bridge: &StBridge,
+    file_name: &str,
+    options: &MeshExportOptions,
+) -> std::io::Result<()> {
+    let mut f = File::create(file_name)?;
+    f.write_all(&create_stl(st_bridge, options))
+}
+
+// Binary STL keeps the model's Z-up axes, as slicers expect. Every mesh is a closed solid with
+// outward facing triangles, so the result is watertight member by member.
+pub fn create_stl(st_bridge: &StBridge, options: &MeshExportOptions) -> Vec<u8> {
+    let mesh_list = filtered_mesh_list(st_bridge, options.story.as_deref());
+    let triangle_count: usize = mesh_list.iter().map(|m| m.triangles.len()).sum();
+
+    let mut stl = Vec::with_capacity(84 + triangle_count * 50);
+
+    let mut header = [0u8; 80];
+    let title = b"stb-viewer";
+    header[..title.len()].copy_from_slice(title);
+    stl.extend_from_slice(&header);
+    stl.extend_from_slice(&(triangle_count as u32).to_le_bytes());
+
+    for mesh in &mesh_list {
+        for triangle in &mesh.triangles {
+            let [a, b, c] = triangle.map(|i| mesh.vertices[i] * options.scale);
+            let cross = (b - a).cross(c - a);
+            let normal = if cross.length() > 0.0 {
+                cross.normalize()
+            } else {
+                cross
+            };
+
+            for v in [normal, a, b, c] {
+                for component in [v.x, v.y, v.z] {
+                    stl.extend_from_slice(&(component as f32).to_le_bytes());
+                }
+            }
+            stl.extend_from_slice(&0u16.to_le_bytes());
+        }
+    }
+
+    stl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+    use std::collections::HashMap;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn options(scale: f64, story: Option<&str>) -> MeshExportOptions {
+        MeshExportOptions {
+            scale,
+            story: story.map(|s| s.to_string()),
+        }
+    }
+
+    // The triangle count from the header and the vertices of each triangle.
+    fn parse(stl: &[u8]) -> (usize, Vec<[[f32; 3]; 3]>) {
+        let count = u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize;
+        let float = |offset: usize| f32::from_le_bytes(stl[offset..offset + 4].try_into().unwrap());
+        let triangle_list = (84..stl.len())
+            .step_by(50)
+            .map(|start| {
+                // Each record is a normal, three vertices and a 2-byte attribute count.
+                let vertex = |i: usize| {
+                    let offset = start + 12 + i * 12;
+                    [float(offset), float(offset + 4), float(offset + 8)]
+                };
+                [vertex(0), vertex(1), vertex(2)]
+            })
+            .collect();
+        (count, triangle_list)
+    }
+
+    #[test]
+    fn size_matches_the_triangle_count() {
+        let stl = create_stl(&sample(), &options(1.0, None));
+        let (count, triangle_list) = parse(&stl);
+
+        assert!(count > 0);
+        assert_eq!(stl.len(), 84 + 50 * count);
+        assert_eq!(triangle_list.len(), count);
+    }
+
+    // Each mesh is written as a run of triangles, in which every edge must be shared by exactly
+    // two triangles running it in opposite directions.
+    #[test]
+    fn every_member_is_watertight() {
+        let st_bridge = sample();
+        let options = options(1.0, None);
+        let mesh_list = filtered_mesh_list(&st_bridge, None);
+        let (_, triangle_list) = parse(&create_stl(&st_bridge, &options));
+
+        let key = |v: [f32; 3]| v.map(|c| (c * 100.0).round() as i64);
+        let mut start = 0;
+        for mesh in &mesh_list {
+            let end = start + mesh.triangles.len();
+            let mut edge_count: HashMap<([i64; 3], [i64; 3]), i32> = HashMap::new();
+            for triangle in &triangle_list[start..end] {
+                for k in 0..3 {
+                    let (a, b) = (key(triangle[k]), key(triangle[(k + 1) % 3]));
+                    *edge_count.entry((a, b)).or_default() += 1;
+                }
+            }
+            for (&(a, b), &count) in &edge_count {
+                assert_eq!(count, 1, "mesh {} repeats an edge", mesh.id);
+                assert_eq!(
+                    edge_count.get(&(b, a)),
+                    Some(&1),
+                    "mesh {} has an open edge",
+                    mesh.id
+                );
+            }
+            start = end;
+        }
+        assert_eq!(start, triangle_list.len());
+    }
+
+    #[test]
+    fn scale_multiplies_coordinates() {
+        let st_bridge = sample();
+        let (_, millimetre) = parse(&create_stl(&st_bridge, &options(1.0, None)));
+        let (_, metre) = parse(&create_stl(&st_bridge, &options(0.001, None)));
+
+        assert_eq!(millimetre.len(), metre.len());
+        for (a, b) in millimetre.iter().zip(&metre) {
+            for (p, q) in a.iter().zip(b) {
+                for k in 0..3 {
+                    assert!((p[k] * 0.001 - q[k]).abs() <= 1e-3 + q[k].abs() * 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn story_filter_splits_the_model() {
+        let st_bridge = sample();
+        let (total, _) = parse(&create_stl(&st_bridge, &options(1.0, None)));
+
+        let mut story_total = 0;
+        for story in &st_bridge.stb_model.stb_stories.stb_story_list {
+            let (count, _) = parse(&create_stl(&st_bridge, &options(1.0, Some(&story.name))));
+            assert!(count < total);
+            story_total += count;
+        }
+        assert_eq!(story_total, total);
+
+        let stl = create_stl(&st_bridge, &options(1.0, Some("no such story")));
+        assert_eq!(stl.len(), 84);
+    }
+}
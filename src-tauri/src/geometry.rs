@@ -1,5 +1,11 @@
+use crate::st_bridge::stb_model::stb_axes_and_stories::StbNodeIdList;
+use crate::st_bridge::stb_model::stb_members::{OpenMemberKind, StbMember};
+use crate::st_bridge::stb_model::stb_sections::{StbSecFigureBeam, StbSection};
 use crate::st_bridge::*;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Sub};
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Node {
     pub x: f64,
     pub y: f64,
@@ -14,6 +20,47 @@ impl Node {
     pub fn distance(ni: Node, nj: Node) -> f64 {
         ((ni.x - nj.x).powi(2) + (ni.y - nj.y).powi(2) + (ni.z - nj.z).powi(2)).sqrt()
     }
+
+    pub fn dot(&self, other: Node) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: Node) -> Node {
+        Node::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Node {
+        *self * (1.0 / self.length())
+    }
+}
+
+impl Add for Node {
+    type Output = Node;
+    fn add(self, other: Node) -> Node {
+        Node::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Node {
+    type Output = Node;
+    fn sub(self, other: Node) -> Node {
+        Node::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Node {
+    type Output = Node;
+    fn mul(self, s: f64) -> Node {
+        Node::new(self.x * s, self.y * s, self.z * s)
+    }
 }
 
 pub struct Shape {
@@ -63,3 +110,762 @@ pub fn create_geometry_list(st_bridge: StBridge) -> Vec<ExtrudeGeometry> {
 
     geometry_list
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Mesh {
+    pub id: u32,
+    pub vertices: Vec<Node>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+#[derive(Clone, Copy)]
+pub struct Plane {
+    pub origin: Node,
+    pub x_axis: Node,
+    pub y_axis: Node,
+    pub normal: Node,
+}
+
+impl Plane {
+    pub fn new(origin: Node, x_axis: Node, normal: Node) -> Plane {
+        let normal = normal.normalize();
+        let x_axis = (x_axis - normal * x_axis.dot(normal)).normalize();
+        let y_axis = normal.cross(x_axis);
+
+        Plane {
+            origin,
+            x_axis,
+            y_axis,
+            normal,
+        }
+    }
+
+    // Newell's method, so that concave and slightly warped outlines still get a stable normal.
+    pub fn from_points(points: &[Node]) -> Plane {
+        let mut normal = Node::new(0.0, 0.0, 0.0);
+
+        for i in 0..points.len() {
+            let p = points[i];
+            let q = points[(i + 1) % points.len()];
+            normal.x += (p.y - q.y) * (p.z + q.z);
+            normal.y += (p.z - q.z) * (p.x + q.x);
+            normal.z += (p.x - q.x) * (p.y + q.y);
+        }
+
+        Plane::new(points[0], points[1] - points[0], normal)
+    }
+
+    pub fn flip(&self) -> Plane {
+        Plane::new(self.origin, self.x_axis, self.normal * -1.0)
+    }
+
+    pub fn to_local(&self, p: Node) -> (f64, f64) {
+        let d = p - self.origin;
+        (d.dot(self.x_axis), d.dot(self.y_axis))
+    }
+
+    pub fn to_global(&self, u: f64, v: f64, w: f64) -> Node {
+        self.origin + self.x_axis * u + self.y_axis * v + self.normal * w
+    }
+}
+
+pub fn signed_area(polygon: &[(f64, f64)]) -> f64 {
+    let mut area = 0.0;
+
+    for i in 0..polygon.len() {
+        let (x1, y1) = polygon[i];
+        let (x2, y2) = polygon[(i + 1) % polygon.len()];
+        area += x1 * y2 - x2 * y1;
+    }
+
+    area / 2.0
+}
+
+// Even-odd ray casting.
+pub fn point_in_polygon(p: (f64, f64), polygon: &[(f64, f64)]) -> bool {
+    let mut inside = false;
+
+    for i in 0..polygon.len() {
+        let (x1, y1) = polygon[i];
+        let (x2, y2) = polygon[(i + 1) % polygon.len()];
+        if (y1 > p.1) != (y2 > p.1) && p.0 < x1 + (p.1 - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+// Ear clipping with holes bridged into the outline. The returned indices refer to the outline
+// followed by each hole in order, and every triangle is counter-clockwise.
+pub fn triangulate(outline: &[(f64, f64)], holes: &[Vec<(f64, f64)>]) -> Vec<[usize; 3]> {
+    let mut points = outline.to_vec();
+
+    let mut ring: Vec<usize> = (0..outline.len()).collect();
+    if signed_area(outline) < 0.0 {
+        ring.reverse();
+    }
+
+    let mut hole_rings = Vec::new();
+    for hole in holes {
+        let offset = points.len();
+        points.extend(hole.iter().cloned());
+        let mut hole_ring: Vec<usize> = (offset..offset + hole.len()).collect();
+        if signed_area(hole) > 0.0 {
+            hole_ring.reverse();
+        }
+        hole_rings.push(hole_ring);
+    }
+
+    let max_x = |r: &Vec<usize>| {
+        r.iter()
+            .map(|&i| points[i].0)
+            .fold(f64::NEG_INFINITY, f64::max)
+    };
+    hole_rings.sort_by(|a, b| max_x(b).partial_cmp(&max_x(a)).unwrap());
+
+    for k in 0..hole_rings.len() {
+        ring = bridge_hole(&points, &ring, &hole_rings[k], &hole_rings[k + 1..]);
+    }
+
+    clip_ears(&points, ring)
+}
+
+fn bridge_hole(
+    points: &[(f64, f64)],
+    ring: &[usize],
+    hole: &[usize],
+    rest: &[Vec<usize>],
+) -> Vec<usize> {
+    let hi = (0..hole.len())
+        .max_by(|&a, &b| points[hole[a]].0.partial_cmp(&points[hole[b]].0).unwrap())
+        .unwrap();
+    let h = points[hole[hi]];
+
+    let rings = std::iter::once(ring)
+        .chain(std::iter::once(hole))
+        .chain(rest.iter().map(|r| r.as_slice()));
+
+    let mut edges = Vec::new();
+    for r in rings {
+        for i in 0..r.len() {
+            edges.push((points[r[i]], points[r[(i + 1) % r.len()]]));
+        }
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    for (oi, &o) in ring.iter().enumerate() {
+        let p = points[o];
+        let d = (p.0 - h.0).powi(2) + (p.1 - h.1).powi(2);
+        if let Some((_, best_d)) = best {
+            if d >= best_d {
+                continue;
+            }
+        }
+        if edges.iter().all(|&(a, b)| !segments_cross(h, p, a, b)) {
+            best = Some((oi, d));
+        }
+    }
+
+    let oi = match best {
+        Some((oi, _)) => oi,
+        None => 0,
+    };
+
+    let mut bridged = Vec::with_capacity(ring.len() + hole.len() + 2);
+    bridged.extend_from_slice(&ring[..=oi]);
+    bridged.extend_from_slice(&hole[hi..]);
+    bridged.extend_from_slice(&hole[..=hi]);
+    bridged.extend_from_slice(&ring[oi..]);
+    bridged
+}
+
+fn segments_cross(p1: (f64, f64), p2: (f64, f64), q1: (f64, f64), q2: (f64, f64)) -> bool {
+    let same = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+    if same(p1, q1) || same(p1, q2) || same(p2, q1) || same(p2, q2) {
+        return false;
+    }
+
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+
+    ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+fn clip_ears(points: &[(f64, f64)], mut ring: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::new();
+
+    while ring.len() > 3 {
+        let n = ring.len();
+        let mut ear = None;
+        let mut degenerate = None;
+
+        for i in 0..n {
+            let (a, b, c) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+            let (pa, pb, pc) = (points[a], points[b], points[c]);
+            let area = cross(pa, pb, pc);
+
+            if area.abs() < 1e-9 {
+                degenerate.get_or_insert(i);
+                continue;
+            }
+            if area < 0.0 {
+                continue;
+            }
+
+            let blocked = ring.iter().any(|&k| {
+                let p = points[k];
+                p != pa && p != pb && p != pc && in_triangle(p, pa, pb, pc)
+            });
+
+            if !blocked {
+                ear = Some(i);
+                break;
+            }
+        }
+
+        match (ear, degenerate) {
+            (Some(i), _) => {
+                triangles.push([ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]]);
+                ring.remove(i);
+            }
+            (None, Some(i)) => {
+                ring.remove(i);
+            }
+            (None, None) => {
+                // Self-intersecting input; fan out the rest rather than loop forever.
+                for i in 1..n - 1 {
+                    triangles.push([ring[0], ring[i], ring[i + 1]]);
+                }
+                return triangles;
+            }
+        }
+    }
+
+    if ring.len() == 3 && cross(points[ring[0]], points[ring[1]], points[ring[2]]).abs() >= 1e-9 {
+        triangles.push([ring[0], ring[1], ring[2]]);
+    }
+
+    triangles
+}
+
+fn in_triangle(p: (f64, f64), a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+// Extrudes a planar outline with holes between `bottom` and `top`, measured along the plane
+// normal. Vertices are shared between caps and sides so that the result is closed.
+pub fn extrude_polygon(
+    id: u32,
+    plane: &Plane,
+    outline: &[(f64, f64)],
+    holes: &[Vec<(f64, f64)>],
+    bottom: f64,
+    top: f64,
+) -> Mesh {
+    let mut rings = vec![outline.to_vec()];
+    if signed_area(outline) < 0.0 {
+        rings[0].reverse();
+    }
+    for hole in holes {
+        let mut hole = hole.clone();
+        if signed_area(&hole) > 0.0 {
+            hole.reverse();
+        }
+        rings.push(hole);
+    }
+
+    let points: Vec<(f64, f64)> = rings.iter().flatten().cloned().collect();
+    let count = points.len();
+
+    let mut vertices = Vec::with_capacity(count * 2);
+    for &(u, v) in &points {
+        vertices.push(plane.to_global(u, v, top));
+    }
+    for &(u, v) in &points {
+        vertices.push(plane.to_global(u, v, bottom));
+    }
+
+    let mut triangles = Vec::new();
+    for [a, b, c] in triangulate(&rings[0], &rings[1..]) {
+        triangles.push([a, b, c]);
+        triangles.push([count + a, count + c, count + b]);
+    }
+
+    let mut offset = 0;
+    for ring in &rings {
+        for i in 0..ring.len() {
+            let a = offset + i;
+            let b = offset + (i + 1) % ring.len();
+            triangles.push([count + a, count + b, b]);
+            triangles.push([count + a, b, a]);
+        }
+        offset += ring.len();
+    }
+
+    Mesh {
+        id,
+        vertices,
+        triangles,
+    }
+}
+
+fn polygon_nodes(st_bridge: &StBridge, stb_node_id_list: &StbNodeIdList) -> Option<Vec<Node>> {
+    let mut nodes = Vec::new();
+
+    for stb_node_id in &stb_node_id_list.children {
+        let stb_node = st_bridge.stb_model.stb_nodes.get(stb_node_id.id as u32)?;
+        nodes.push(Node::new(stb_node.x, stb_node.y, stb_node.z));
+    }
+
+    if nodes.len() < 3 {
+        return None;
+    }
+
+    Some(nodes)
+}
+
+fn open_outline_list(
+    st_bridge: &StBridge,
+    id_member: u32,
+    kind: OpenMemberKind,
+) -> Vec<Vec<(f64, f64)>> {
+    let mut outline_list = Vec::new();
+
+    for stb_open in st_bridge.stb_model.stb_members.stb_opens.values() {
+        if let StbMember::StbOpen {
+            id_member: id,
+            kind_member,
+            position_x,
+            position_y,
+            length_x,
+            length_y,
+            rotate,
+            ..
+        } = stb_open
+        {
+            if *id != id_member || *kind_member != kind {
+                continue;
+            }
+
+            let (sin, cos) = rotate.to_radians().sin_cos();
+            let corners = [
+                (0.0, 0.0),
+                (*length_x, 0.0),
+                (*length_x, *length_y),
+                (0.0, *length_y),
+            ];
+
+            outline_list.push(
+                corners
+                    .iter()
+                    .map(|&(u, v)| {
+                        (
+                            position_x + u * cos - v * sin,
+                            position_y + u * sin + v * cos,
+                        )
+                    })
+                    .collect(),
+            );
+        }
+    }
+
+    outline_list
+}
+
+pub struct PlateOutline {
+    pub plane: Plane,
+    pub outline: Vec<(f64, f64)>,
+    pub holes: Vec<Vec<(f64, f64)>>,
+    pub bottom: f64,
+    pub top: f64,
+}
+
+pub fn plate_outline(st_bridge: &StBridge, stb_member: &StbMember) -> Option<PlateOutline> {
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+
+    match stb_member {
+        StbMember::StbSlab {
+            id,
+            id_section,
+            level,
+            stb_node_id_list,
+            ..
+        } => {
+            let depth = match stb_sections.slab_rc_map.get(id_section) {
+                Some(StbSection::StbSecSlabRC { stb_sec_figure, .. }) => {
+                    stb_sec_figure.stb_sec_straight.depth
+                }
+                _ => return None,
+            };
+
+            let nodes = polygon_nodes(st_bridge, stb_node_id_list)?;
+
+            let mut plane = Plane::from_points(&nodes);
+            if plane.normal.z < 0.0 {
+                plane = plane.flip();
+            }
+
+            Some(PlateOutline {
+                outline: nodes.iter().map(|&n| plane.to_local(n)).collect(),
+                holes: open_outline_list(st_bridge, *id, OpenMemberKind::Slab),
+                plane,
+                bottom: level - depth,
+                top: *level,
+            })
+        }
+        StbMember::StbWall {
+            id,
+            id_section,
+            stb_node_id_list,
+            ..
+        } => {
+            let t = match stb_sections.wall_rc_map.get(id_section) {
+                Some(StbSection::StbSecWallRC { stb_sec_figure, .. }) => {
+                    stb_sec_figure.stb_sec_straight.t
+                }
+                _ => return None,
+            };
+
+            let nodes = polygon_nodes(st_bridge, stb_node_id_list)?;
+
+            // Keep the local y axis pointing up so that opening positions read as (along, up).
+            let mut plane = Plane::from_points(&nodes);
+            if plane.y_axis.z < 0.0 {
+                plane = plane.flip();
+            }
+
+            Some(PlateOutline {
+                outline: nodes.iter().map(|&n| plane.to_local(n)).collect(),
+                holes: open_outline_list(st_bridge, *id, OpenMemberKind::Wall),
+                plane,
+                bottom: -t / 2.0,
+                top: t / 2.0,
+            })
+        }
+        _ => None,
+    }
+}
+
+pub fn create_plate_mesh(st_bridge: &StBridge, stb_member: &StbMember) -> Option<Mesh> {
+    let plate = plate_outline(st_bridge, stb_member)?;
+
+    Some(extrude_polygon(
+        stb_member.id(),
+        &plate.plane,
+        &plate.outline,
+        &plate.holes,
+        plate.bottom,
+        plate.top,
+    ))
+}
+
+pub fn create_slab_mesh_list(st_bridge: &StBridge) -> Vec<Mesh> {
+    st_bridge
+        .stb_model
+        .stb_members
+        .stb_slabs
+        .values()
+        .filter_map(|m| create_plate_mesh(st_bridge, m))
+        .collect()
+}
+
+pub fn create_wall_mesh_list(st_bridge: &StBridge) -> Vec<Mesh> {
+    st_bridge
+        .stb_model
+        .stb_members
+        .stb_walls
+        .values()
+        .filter_map(|m| create_plate_mesh(st_bridge, m))
+        .collect()
+}
+
+// Local frame of a linear member: the normal runs from node i to node j, the section y axis
+// points up (or along global Y for vertical members), and `rotate` turns the section about the
+// member axis.
+pub fn member_plane(ni: Node, nj: Node, rotate: f64) -> Plane {
+    let axis = (nj - ni).normalize();
+
+    let x_axis = if axis.z.abs() > 0.999 {
+        Node::new(1.0, 0.0, 0.0)
+    } else {
+        let up = Node::new(0.0, 0.0, 1.0);
+        (up - axis * up.dot(axis)).normalize().cross(axis)
+    };
+
+    let (sin, cos) = rotate.to_radians().sin_cos();
+    let y_axis = axis.cross(x_axis);
+
+    Plane::new(ni, x_axis * cos + y_axis * sin, axis)
+}
+
+pub type SectionOutline = (Vec<(f64, f64)>, Vec<Vec<(f64, f64)>>);
+
+pub fn section_outline(st_bridge: &StBridge, stb_section: &StbSection) -> Option<SectionOutline> {
+    if let Some(shape) = stb_section.steel_shape() {
+        let children = st_bridge.stb_model.stb_sections.stb_sec_steel.get(shape)?;
+        return Some((children.shape(), children.holes()));
+    }
+
+    match stb_section {
+        StbSection::StbSecBeamRC { stb_sec_figure, .. } => {
+            let (width, depth) = beam_rc_size(stb_sec_figure)?;
+            let outline = vec![
+                (-width / 2.0, -depth / 2.0),
+                (width / 2.0, -depth / 2.0),
+                (width / 2.0, depth / 2.0),
+                (-width / 2.0, depth / 2.0),
+            ];
+            Some((outline, Vec::new()))
+        }
+        StbSection::StbSecColumnRC { stb_sec_figure, .. } => {
+            Some((stb_sec_figure.shape()?, Vec::new()))
+        }
+        _ => None,
+    }
+}
+
+pub fn beam_rc_size(stb_sec_figure: &StbSecFigureBeam) -> Option<(f64, f64)> {
+    match (
+        &stb_sec_figure.stb_sec_straight,
+        &stb_sec_figure.stb_sec_haunch,
+    ) {
+        (Some(straight), _) => Some((straight.width, straight.depth)),
+        (None, Some(haunch)) => Some((haunch.width_center, haunch.depth_center)),
+        (None, None) => None,
+    }
+}
+
+pub fn member_ends(st_bridge: &StBridge, stb_member: &StbMember) -> Option<(Node, Node)> {
+    let stb_nodes = &st_bridge.stb_model.stb_nodes;
+    let ni = stb_nodes.get(stb_member.node_i()?)?;
+    let nj = stb_nodes.get(stb_member.node_j()?)?;
+    let ni = Node::new(ni.x, ni.y, ni.z);
+    let nj = Node::new(nj.x, nj.y, nj.z);
+
+    let ends = match *stb_member {
+        StbMember::StbColumn {
+            offset_x, offset_y, ..
+        } => {
+            let offset = Node::new(offset_x, offset_y, 0.0);
+            (ni + offset, nj + offset)
+        }
+        StbMember::StbPost {
+            offset_x,
+            offset_y,
+            offset_bottom_x,
+            offset_bottom_y,
+            offset_bottom_z,
+            offset_top_x,
+            offset_top_y,
+            offset_top_z,
+            ..
+        } => (
+            ni + Node::new(
+                offset_x + offset_bottom_x,
+                offset_y + offset_bottom_y,
+                offset_bottom_z,
+            ),
+            nj + Node::new(
+                offset_x + offset_top_x,
+                offset_y + offset_top_y,
+                offset_top_z,
+            ),
+        ),
+        StbMember::StbBrace {
+            offset_start_x,
+            offset_start_y,
+            offset_start_z,
+            offset_end_x,
+            offset_end_y,
+            offset_end_z,
+            ..
+        } => (
+            ni + Node::new(offset_start_x, offset_start_y, offset_start_z),
+            nj + Node::new(offset_end_x, offset_end_y, offset_end_z),
+        ),
+        StbMember::StbGirder { offset, level, .. } | StbMember::StbBeam { offset, level, .. } => {
+            let plane = member_plane(ni, nj, 0.0);
+            let shift = plane.x_axis * offset + Node::new(0.0, 0.0, level);
+            (ni + shift, nj + shift)
+        }
+        _ => return None,
+    };
+
+    Some(ends)
+}
+
+// Placement of the section outline: the plane origin sits at the section origin at node i and the
+// normal runs along the member for `length`.
+pub fn member_placement(st_bridge: &StBridge, stb_member: &StbMember) -> Option<(Plane, f64)> {
+    if !stb_member.is_linear() {
+        return None;
+    }
+
+    let (ni, nj) = member_ends(st_bridge, stb_member)?;
+    let length = Node::distance(ni, nj);
+    if length < 1e-6 {
+        return None;
+    }
+
+    let rotate = match *stb_member {
+        StbMember::StbColumn { rotate, .. }
+        | StbMember::StbPost { rotate, .. }
+        | StbMember::StbGirder { rotate, .. }
+        | StbMember::StbBeam { rotate, .. }
+        | StbMember::StbBrace { rotate, .. } => rotate,
+        _ => 0.0,
+    };
+
+    let mut plane = member_plane(ni, nj, rotate);
+
+    // Girders and beams hang from their nodes, which sit on the top of the member.
+    if let StbMember::StbGirder { .. } | StbMember::StbBeam { .. } = stb_member {
+        let stb_section = st_bridge
+            .stb_model
+            .stb_sections
+            .get(stb_member.id_section())?;
+        let (outline, _) = section_outline(st_bridge, stb_section)?;
+        let top = outline
+            .iter()
+            .map(|p| p.1)
+            .fold(f64::NEG_INFINITY, f64::max);
+        plane.origin = plane.origin - plane.y_axis * top;
+    }
+
+    Some((plane, length))
+}
+
+pub fn create_member_mesh(st_bridge: &StBridge, stb_member: &StbMember) -> Option<Mesh> {
+    let stb_section = st_bridge
+        .stb_model
+        .stb_sections
+        .get(stb_member.id_section())?;
+    let (outline, holes) = section_outline(st_bridge, stb_section)?;
+    let (plane, length) = member_placement(st_bridge, stb_member)?;
+
+    Some(extrude_polygon(
+        stb_member.id(),
+        &plane,
+        &outline,
+        &holes,
+        0.0,
+        length,
+    ))
+}
+
+pub fn create_member_mesh_list(st_bridge: &StBridge) -> Vec<Mesh> {
+    st_bridge
+        .stb_model
+        .stb_members
+        .iter()
+        .filter_map(|m| create_member_mesh(st_bridge, m))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle_area(points: &[(f64, f64)], triangle: [usize; 3]) -> f64 {
+        cross(
+            points[triangle[0]],
+            points[triangle[1]],
+            points[triangle[2]],
+        ) / 2.0
+    }
+
+    fn assert_covers(points: &[(f64, f64)], triangles: &[[usize; 3]], area: f64) {
+        let mut total = 0.0;
+        for &triangle in triangles {
+            let a = triangle_area(points, triangle);
+            assert!(a > 0.0, "{:?} is not counter-clockwise", triangle);
+            total += a;
+        }
+        assert!((total - area).abs() < 1e-6, "{} != {}", total, area);
+    }
+
+    #[test]
+    fn concave_outline() {
+        // L-shape given clockwise, so the ring has to be reversed first.
+        let outline = vec![
+            (0.0, 0.0),
+            (0.0, 6.0),
+            (3.0, 6.0),
+            (3.0, 3.0),
+            (6.0, 3.0),
+            (6.0, 0.0),
+        ];
+
+        let triangles = triangulate(&outline, &[]);
+
+        assert_eq!(triangles.len(), outline.len() - 2);
+        assert_covers(&outline, &triangles, 27.0);
+    }
+
+    #[test]
+    fn outline_with_opening() {
+        let outline = vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let opening = vec![(4.0, 4.0), (6.0, 4.0), (6.0, 6.0), (4.0, 6.0)];
+
+        let triangles = triangulate(&outline, std::slice::from_ref(&opening));
+
+        let points: Vec<(f64, f64)> = outline.iter().chain(&opening).cloned().collect();
+        assert_eq!(triangles.len(), 8);
+        assert_covers(&points, &triangles, 96.0);
+    }
+
+    #[test]
+    fn extruded_polygon_is_closed() {
+        let outline = vec![
+            (0.0, 0.0),
+            (6.0, 0.0),
+            (6.0, 3.0),
+            (3.0, 3.0),
+            (3.0, 6.0),
+            (0.0, 6.0),
+        ];
+        let opening = vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)];
+        let plane = Plane::new(
+            Node::new(0.0, 0.0, 0.0),
+            Node::new(1.0, 0.0, 0.0),
+            Node::new(0.0, 0.0, 1.0),
+        );
+
+        let mesh = extrude_polygon(1, &plane, &outline, &[opening], -0.5, 0.0);
+
+        // Every edge is shared by exactly one other triangle running the opposite way.
+        let mut edges = Vec::new();
+        for t in &mesh.triangles {
+            for k in 0..3 {
+                edges.push((t[k], t[(k + 1) % 3]));
+            }
+        }
+        for &(a, b) in &edges {
+            assert_eq!(edges.iter().filter(|&&e| e == (a, b)).count(), 1);
+            assert_eq!(edges.iter().filter(|&&e| e == (b, a)).count(), 1);
+        }
+
+        // Divergence theorem; positive only when the faces point outwards.
+        let volume: f64 = mesh
+            .triangles
+            .iter()
+            .map(|t| {
+                let (a, b, c) = (
+                    mesh.vertices[t[0]],
+                    mesh.vertices[t[1]],
+                    mesh.vertices[t[2]],
+                );
+                a.dot(b.cross(c)) / 6.0
+            })
+            .sum();
+        assert!((volume - 26.0 * 0.5).abs() < 1e-6, "{}", volume);
+    }
+}
@@ -0,0 +1,1533 @@
+use super::step::{parse_step, StepEntity, StepValue};
+use crate::geometry::{member_plane, Node, Plane};
+use crate::node_merge::check_tolerance;
+use crate::st_bridge::stb_common::StbCommon;
+use crate::st_bridge::stb_extensions::StbExtensions;
+use crate::st_bridge::stb_model::stb_axes_and_stories::*;
+use crate::st_bridge::stb_model::stb_members::*;
+use crate::st_bridge::stb_model::stb_nodes::*;
+use crate::st_bridge::stb_model::stb_sections::*;
+use crate::st_bridge::stb_model::StbModel;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+pub const DEFAULT_TOLERANCE: f64 = 10.0;
+const MAX_BEAM_LEVEL: f64 = 1000.0;
+
+#[derive(Serialize)]
+pub struct IfcImport {
+    pub st_bridge: StBridge,
+    pub warnings: Vec<String>,
+}
+
+pub fn read_ifc(file_name: &str) -> Result<IfcImport, String> {
+    let contents =
+        std::fs::read_to_string(file_name).map_err(|e| format!("{}: {}", file_name, e))?;
+    import_ifc(&contents, DEFAULT_TOLERANCE)
+}
+
+pub fn import_ifc(contents: &str, tolerance: f64) -> Result<IfcImport, String> {
+    check_tolerance(tolerance)?;
+    let entities = parse_step(contents)?;
+    let mut importer = Importer::new(&entities, tolerance);
+    importer.import();
+    Ok(importer.finish())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ElementKind {
+    Column,
+    Post,
+    Girder,
+    Beam,
+    Brace,
+    Slab,
+}
+
+enum Profile {
+    Steel(StbSecSteelChildren),
+    Rectangle(f64, f64),
+    Polygon(Vec<(f64, f64)>),
+}
+
+struct Extrusion {
+    profile_id: u64,
+    profile: Profile,
+    outline: Vec<(f64, f64)>,
+    transform: Plane,
+    direction: Node,
+    depth: f64,
+}
+
+struct PendingBeam {
+    id: u32,
+    name: String,
+    pi: Node,
+    pj: Node,
+    rotate: f64,
+    profile_id: u64,
+    profile: Profile,
+    material: String,
+    storey: Option<u64>,
+}
+
+struct Importer<'a> {
+    entities: &'a HashMap<u64, StepEntity>,
+    tolerance: f64,
+    scale: f64,
+    warnings: Vec<String>,
+    stb_nodes: StbNodes,
+    node_grid: HashMap<(i64, i64, i64), Vec<u32>>,
+    stb_members: StbMembers,
+    stb_sections: StbSections,
+    section_ids: HashMap<(u64, u8), u32>,
+    pending_beams: Vec<PendingBeam>,
+    column_nodes: HashSet<u32>,
+    storey_nodes: HashMap<u64, HashSet<u32>>,
+    next_member_id: u32,
+    next_section_id: u32,
+}
+
+impl<'a> Importer<'a> {
+    fn new(entities: &'a HashMap<u64, StepEntity>, tolerance: f64) -> Importer<'a> {
+        Importer {
+            entities,
+            tolerance,
+            scale: 1.0,
+            warnings: Vec::new(),
+            stb_nodes: StbNodes::new(),
+            node_grid: HashMap::new(),
+            stb_members: StbMembers::new(),
+            stb_sections: StbSections::new(),
+            section_ids: HashMap::new(),
+            pending_beams: Vec::new(),
+            column_nodes: HashSet::new(),
+            storey_nodes: HashMap::new(),
+            next_member_id: 1,
+            next_section_id: 1,
+        }
+    }
+
+    fn entity(&self, value: &StepValue) -> Option<&'a StepEntity> {
+        value.as_id().and_then(|id| self.entities.get(&id))
+    }
+
+    fn sorted_ids(&self, names: &[&str]) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .entities
+            .iter()
+            .filter(|(_, e)| names.contains(&e.name.as_str()))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn import(&mut self) {
+        self.scale = self.length_unit_scale();
+
+        let containment = self.containment();
+        let materials = self.materials();
+
+        let element_names = [
+            "IFCCOLUMN",
+            "IFCCOLUMNSTANDARDCASE",
+            "IFCBEAM",
+            "IFCBEAMSTANDARDCASE",
+            "IFCMEMBER",
+            "IFCMEMBERSTANDARDCASE",
+            "IFCSLAB",
+            "IFCSLABSTANDARDCASE",
+            "IFCSLABELEMENTEDCASE",
+        ];
+
+        for id in self.sorted_ids(&element_names) {
+            let entity = &self.entities[&id];
+            let name = entity.arg(2).as_str().unwrap_or("").to_string();
+            let material = materials.get(&id).cloned().unwrap_or_default();
+
+            let extrusion = match self.element_extrusion(entity) {
+                Some(e) => e,
+                None => {
+                    self.warnings.push(format!(
+                        "#{} {} '{}': no extruded body found",
+                        id, entity.name, name
+                    ));
+                    continue;
+                }
+            };
+
+            let kind = match self.element_kind(entity, &extrusion) {
+                Some(k) => k,
+                None => continue,
+            };
+
+            let has_voids = self
+                .entities
+                .get(&extrusion.profile_id)
+                .map(|p| p.name == "IFCARBITRARYPROFILEDEFWITHVOIDS")
+                .unwrap_or(false);
+            if has_voids {
+                self.warnings.push(format!(
+                    "#{} {} '{}': profile voids are not imported",
+                    id, entity.name, name
+                ));
+            }
+
+            let storey = containment.get(&id).cloned();
+            match kind {
+                ElementKind::Slab => self.add_slab(id, &name, &extrusion, &material, storey),
+                _ => self.add_linear(id, &name, kind, extrusion, &material, storey),
+            }
+        }
+
+        self.add_pending_beams();
+    }
+
+    fn finish(self) -> IfcImport {
+        let project_name = self
+            .sorted_ids(&["IFCPROJECT"])
+            .first()
+            .and_then(|id| self.entities[id].arg(2).as_str())
+            .unwrap_or("")
+            .to_string();
+
+        let mut stb_stories = StbStories::new();
+        let mut storey_ids = self.sorted_ids(&["IFCBUILDINGSTOREY"]);
+        storey_ids.sort_by(|a, b| {
+            self.storey_elevation(*a)
+                .partial_cmp(&self.storey_elevation(*b))
+                .unwrap()
+        });
+
+        for (i, storey) in storey_ids.iter().enumerate() {
+            let entity = &self.entities[storey];
+            let mut children: Vec<u32> = self
+                .storey_nodes
+                .get(storey)
+                .map(|s| s.iter().cloned().collect())
+                .unwrap_or_default();
+            children.sort_unstable();
+
+            stb_stories.stb_story_list.push(StbStory {
+                id: i as i32 + 1,
+                name: entity.arg(2).as_str().unwrap_or("").to_string(),
+                height: self.storey_elevation(*storey),
+                kind: StbStoryKind::General,
+                concrete_strength: String::new(),
+                stb_node_id_list: StbNodeIdList {
+                    children: children
+                        .into_iter()
+                        .map(|id| StbNodeId { id: id as i32 })
+                        .collect(),
+                },
+            });
+        }
+
+        let mut stb_common = StbCommon::new();
+        stb_common.project_name = project_name;
+
+        IfcImport {
+            st_bridge: StBridge {
+                version: "1.4.00".to_string(),
+                stb_common,
+                stb_model: StbModel {
+                    stb_nodes: self.stb_nodes,
+                    stb_axes: StbAxes::new(),
+                    stb_stories,
+                    stb_members: self.stb_members,
+                    stb_sections: self.stb_sections,
+                },
+                stb_extensions: StbExtensions {
+                    stb_extension_list: Vec::new(),
+                },
+            },
+            warnings: self.warnings,
+        }
+    }
+
+    fn length_unit_scale(&self) -> f64 {
+        for id in self.sorted_ids(&["IFCUNITASSIGNMENT"]) {
+            for unit in self.entities[&id].arg(0).as_list() {
+                let unit = match self.entity(unit) {
+                    Some(u) => u,
+                    None => continue,
+                };
+                if unit.arg(1).as_str() != Some("LENGTHUNIT") {
+                    continue;
+                }
+                return self.unit_scale(unit).unwrap_or(1.0);
+            }
+        }
+        1.0
+    }
+
+    // Millimetres per model length unit.
+    fn unit_scale(&self, unit: &StepEntity) -> Option<f64> {
+        match unit.name.as_str() {
+            "IFCSIUNIT" => {
+                let prefix = match unit.arg(2).as_str() {
+                    Some("KILO") => 1e3,
+                    Some("DECI") => 1e-1,
+                    Some("CENTI") => 1e-2,
+                    Some("MILLI") => 1e-3,
+                    _ => 1.0,
+                };
+                Some(prefix * 1000.0)
+            }
+            "IFCCONVERSIONBASEDUNIT" => {
+                let measure = self.entity(unit.arg(3))?;
+                let value = measure.arg(0).as_f64()?;
+                let base = self.entity(measure.arg(1))?;
+                Some(value * self.unit_scale(base)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn containment(&self) -> HashMap<u64, u64> {
+        let mut containment = HashMap::new();
+
+        for id in self.sorted_ids(&["IFCRELCONTAINEDINSPATIALSTRUCTURE"]) {
+            let rel = &self.entities[&id];
+            let structure = match rel.arg(5).as_id() {
+                Some(s) => s,
+                None => continue,
+            };
+            if self.entities.get(&structure).map(|e| e.name.as_str()) != Some("IFCBUILDINGSTOREY") {
+                continue;
+            }
+            for element in rel.arg(4).as_list() {
+                if let Some(element) = element.as_id() {
+                    containment.insert(element, structure);
+                }
+            }
+        }
+
+        containment
+    }
+
+    fn materials(&self) -> HashMap<u64, String> {
+        let mut materials = HashMap::new();
+
+        for id in self.sorted_ids(&["IFCRELASSOCIATESMATERIAL"]) {
+            let rel = &self.entities[&id];
+            let name = match self.material_name(rel.arg(5), 0) {
+                Some(n) => n,
+                None => continue,
+            };
+            for object in rel.arg(4).as_list() {
+                if let Some(object) = object.as_id() {
+                    materials.insert(object, name.clone());
+                }
+            }
+        }
+
+        materials
+    }
+
+    fn material_name(&self, value: &StepValue, depth: usize) -> Option<String> {
+        let entity = self.entity(value)?;
+        if depth > 8 {
+            return None;
+        }
+
+        match entity.name.as_str() {
+            "IFCMATERIAL" => entity.arg(0).as_str().map(|s| s.to_string()),
+            "IFCMATERIALPROFILESETUSAGE" | "IFCMATERIALLAYERSETUSAGE" => {
+                self.material_name(entity.arg(0), depth + 1)
+            }
+            "IFCMATERIALPROFILESET" => entity
+                .arg(2)
+                .as_list()
+                .iter()
+                .find_map(|p| self.material_name(p, depth + 1)),
+            "IFCMATERIALPROFILE" => self.material_name(entity.arg(2), depth + 1),
+            "IFCMATERIALLAYERSET" => entity
+                .arg(0)
+                .as_list()
+                .iter()
+                .find_map(|l| self.material_name(l, depth + 1)),
+            "IFCMATERIALLAYER" => self.material_name(entity.arg(0), depth + 1),
+            "IFCMATERIALLIST" => entity
+                .arg(0)
+                .as_list()
+                .iter()
+                .find_map(|m| self.material_name(m, depth + 1)),
+            _ => None,
+        }
+    }
+
+    fn storey_elevation(&self, storey: u64) -> f64 {
+        let entity = &self.entities[&storey];
+        match entity.arg(9).as_f64() {
+            Some(elevation) => elevation * self.scale,
+            None => self.placement(entity.arg(5), 0).origin.z,
+        }
+    }
+
+    fn point(&self, value: &StepValue) -> Option<Node> {
+        let entity = self.entity(value)?;
+        let c: Vec<f64> = entity
+            .arg(0)
+            .as_list()
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .collect();
+        Some(Node::new(
+            c.first().cloned().unwrap_or(0.0) * self.scale,
+            c.get(1).cloned().unwrap_or(0.0) * self.scale,
+            c.get(2).cloned().unwrap_or(0.0) * self.scale,
+        ))
+    }
+
+    fn direction(&self, value: &StepValue, default: Node) -> Node {
+        let entity = match self.entity(value) {
+            Some(e) => e,
+            None => return default,
+        };
+        let c: Vec<f64> = entity
+            .arg(0)
+            .as_list()
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .collect();
+        Node::new(
+            c.first().cloned().unwrap_or(0.0),
+            c.get(1).cloned().unwrap_or(0.0),
+            c.get(2).cloned().unwrap_or(0.0),
+        )
+    }
+
+    fn axis_placement(&self, value: &StepValue) -> Plane {
+        let identity = Plane::new(
+            Node::new(0.0, 0.0, 0.0),
+            Node::new(1.0, 0.0, 0.0),
+            Node::new(0.0, 0.0, 1.0),
+        );
+
+        let entity = match self.entity(value) {
+            Some(e) => e,
+            None => return identity,
+        };
+
+        let origin = self
+            .point(entity.arg(0))
+            .unwrap_or_else(|| Node::new(0.0, 0.0, 0.0));
+
+        match entity.name.as_str() {
+            "IFCAXIS2PLACEMENT3D" => Plane::new(
+                origin,
+                self.direction(entity.arg(2), Node::new(1.0, 0.0, 0.0)),
+                self.direction(entity.arg(1), Node::new(0.0, 0.0, 1.0)),
+            ),
+            "IFCAXIS2PLACEMENT2D" => Plane::new(
+                origin,
+                self.direction(entity.arg(1), Node::new(1.0, 0.0, 0.0)),
+                Node::new(0.0, 0.0, 1.0),
+            ),
+            _ => identity,
+        }
+    }
+
+    fn placement(&self, value: &StepValue, depth: usize) -> Plane {
+        let entity = match self.entity(value) {
+            Some(e) if e.name == "IFCLOCALPLACEMENT" && depth < 32 => e,
+            _ => return self.axis_placement(&StepValue::Null),
+        };
+
+        let relative = self.axis_placement(entity.arg(1));
+        match entity.arg(0) {
+            StepValue::Ref(_) => compose(&self.placement(entity.arg(0), depth + 1), &relative),
+            _ => relative,
+        }
+    }
+
+    fn element_extrusion(&self, element: &StepEntity) -> Option<Extrusion> {
+        let placement = self.placement(element.arg(5), 0);
+        let shape = self.entity(element.arg(6))?;
+
+        let mut representations: Vec<&StepEntity> = shape
+            .arg(2)
+            .as_list()
+            .iter()
+            .filter_map(|r| self.entity(r))
+            .collect();
+        representations.sort_by_key(|r| r.arg(1).as_str() != Some("Body"));
+
+        representations
+            .iter()
+            .flat_map(|r| r.arg(3).as_list().iter())
+            .find_map(|item| self.item_extrusion(item, &placement, 0))
+    }
+
+    fn item_extrusion(&self, value: &StepValue, parent: &Plane, depth: usize) -> Option<Extrusion> {
+        let item = self.entity(value)?;
+        if depth > 8 {
+            return None;
+        }
+
+        match item.name.as_str() {
+            "IFCEXTRUDEDAREASOLID" => {
+                let profile_id = item.arg(0).as_id()?;
+                let (profile, outline) = self.profile(self.entity(item.arg(0))?)?;
+                Some(Extrusion {
+                    profile_id,
+                    profile,
+                    outline,
+                    transform: compose(parent, &self.axis_placement(item.arg(1))),
+                    direction: self
+                        .direction(item.arg(2), Node::new(0.0, 0.0, 1.0))
+                        .normalize(),
+                    depth: item.arg(3).as_f64()? * self.scale,
+                })
+            }
+            "IFCBOOLEANCLIPPINGRESULT" | "IFCBOOLEANRESULT" => {
+                self.item_extrusion(item.arg(1), parent, depth + 1)
+            }
+            "IFCMAPPEDITEM" => {
+                let map = self.entity(item.arg(0))?;
+                let origin = compose(parent, &self.axis_placement(map.arg(0)));
+                let representation = self.entity(map.arg(1))?;
+                representation
+                    .arg(3)
+                    .as_list()
+                    .iter()
+                    .find_map(|i| self.item_extrusion(i, &origin, depth + 1))
+            }
+            _ => None,
+        }
+    }
+
+    // Returns the profile and its outline in the extrusion's coordinates (profile position applied).
+    fn profile(&self, entity: &StepEntity) -> Option<(Profile, Vec<(f64, f64)>)> {
+        let s = self.scale;
+        let dim = |i: usize| entity.arg(i).as_f64().map(|v| v * s);
+        let name = entity.arg(1).as_str().unwrap_or("").to_string();
+        let position = self.axis_placement(entity.arg(2));
+
+        let profile = match entity.name.as_str() {
+            "IFCISHAPEPROFILEDEF" => {
+                let (b, a, t1, t2) = (dim(3)?, dim(4)?, dim(5)?, dim(6)?);
+                let steel = match dim(7) {
+                    Some(r) if r > 0.0 => StbSecSteelChildren::StbSecRollH {
+                        name: steel_name(&name, || format!("H-{}x{}x{}x{}", a, b, t1, t2)),
+                        sec_type: StbSecRollHType::H,
+                        a,
+                        b,
+                        t1,
+                        t2,
+                        r,
+                    },
+                    _ => StbSecSteelChildren::StbSecBuildH {
+                        name: steel_name(&name, || format!("BH-{}x{}x{}x{}", a, b, t1, t2)),
+                        a,
+                        b,
+                        t1,
+                        t2,
+                    },
+                };
+                Profile::Steel(steel)
+            }
+            "IFCRECTANGLEHOLLOWPROFILEDEF" => {
+                let (b, a, t) = (dim(3)?, dim(4)?, dim(5)?);
+                let steel = match dim(7) {
+                    Some(r) if r > 0.0 => StbSecSteelChildren::StbSecRollBox {
+                        name: steel_name(&name, || format!("□-{}x{}x{}", a, b, t)),
+                        sec_type: StbSecRollBoxType::Else,
+                        a,
+                        b,
+                        t,
+                        r,
+                    },
+                    _ => StbSecSteelChildren::StbSecBuildBox {
+                        name: steel_name(&name, || format!("□-{}x{}x{}x{}", a, b, t, t)),
+                        a,
+                        b,
+                        t1: t,
+                        t2: t,
+                    },
+                };
+                Profile::Steel(steel)
+            }
+            "IFCCIRCLEHOLLOWPROFILEDEF" => {
+                let (d, t) = (dim(3)? * 2.0, dim(4)?);
+                Profile::Steel(StbSecSteelChildren::StbSecPipe {
+                    name: steel_name(&name, || format!("○-{}x{}", d, t)),
+                    d,
+                    t,
+                })
+            }
+            "IFCLSHAPEPROFILEDEF" => {
+                let a = dim(3)?;
+                let b = dim(4).unwrap_or(a);
+                let t = dim(5)?;
+                Profile::Steel(StbSecSteelChildren::StbSecRollL {
+                    name: steel_name(&name, || format!("L-{}x{}x{}", a, b, t)),
+                    sec_type: StbSecRollLType::L,
+                    a,
+                    b,
+                    t1: t,
+                    t2: t,
+                    r1: dim(6).unwrap_or(0.0),
+                    r2: dim(7).unwrap_or(0.0),
+                    side: true,
+                })
+            }
+            "IFCRECTANGLEPROFILEDEF" => Profile::Rectangle(dim(3)?, dim(4)?),
+            "IFCARBITRARYCLOSEDPROFILEDEF" | "IFCARBITRARYPROFILEDEFWITHVOIDS" => {
+                let points = self.curve_points(self.entity(entity.arg(2))?)?;
+                return Some((Profile::Polygon(points.clone()), points));
+            }
+            _ => return None,
+        };
+
+        let local = match &profile {
+            Profile::Steel(steel) => steel.shape(),
+            Profile::Rectangle(x, y) => vec![
+                (-x / 2.0, -y / 2.0),
+                (x / 2.0, -y / 2.0),
+                (x / 2.0, y / 2.0),
+                (-x / 2.0, y / 2.0),
+            ],
+            Profile::Polygon(points) => points.clone(),
+        };
+
+        let outline = local
+            .iter()
+            .map(|&(u, v)| {
+                let p = position.to_global(u, v, 0.0);
+                (p.x, p.y)
+            })
+            .collect();
+
+        Some((profile, outline))
+    }
+
+    fn curve_points(&self, curve: &StepEntity) -> Option<Vec<(f64, f64)>> {
+        let mut points: Vec<(f64, f64)> = match curve.name.as_str() {
+            "IFCPOLYLINE" => curve
+                .arg(0)
+                .as_list()
+                .iter()
+                .filter_map(|p| self.point(p))
+                .map(|p| (p.x, p.y))
+                .collect(),
+            "IFCINDEXEDPOLYCURVE" => {
+                let list = self.entity(curve.arg(0))?;
+                list.arg(0)
+                    .as_list()
+                    .iter()
+                    .map(|p| {
+                        let c = p.as_list();
+                        (
+                            c.first().and_then(|v| v.as_f64()).unwrap_or(0.0) * self.scale,
+                            c.get(1).and_then(|v| v.as_f64()).unwrap_or(0.0) * self.scale,
+                        )
+                    })
+                    .collect()
+            }
+            _ => return None,
+        };
+
+        if points.len() > 1 {
+            let (first, last) = (points[0], points[points.len() - 1]);
+            if (first.0 - last.0).abs() < 1e-6 && (first.1 - last.1).abs() < 1e-6 {
+                points.pop();
+            }
+        }
+
+        if points.len() < 3 {
+            return None;
+        }
+
+        Some(points)
+    }
+
+    fn element_kind(&mut self, entity: &StepEntity, extrusion: &Extrusion) -> Option<ElementKind> {
+        let axis = rotate(&extrusion.transform, extrusion.direction);
+        let vertical = axis.z.abs() > 0.99;
+        let horizontal = axis.z.abs() < 0.01;
+        let predefined_type = entity.arg(8).as_str().unwrap_or("");
+
+        let kind = match entity.name.as_str() {
+            "IFCCOLUMN" | "IFCCOLUMNSTANDARDCASE" => ElementKind::Column,
+            "IFCBEAM" | "IFCBEAMSTANDARDCASE" => ElementKind::Beam,
+            "IFCSLAB" | "IFCSLABSTANDARDCASE" | "IFCSLABELEMENTEDCASE" => ElementKind::Slab,
+            _ => match predefined_type {
+                "BRACE" => ElementKind::Brace,
+                "POST" | "STUD" | "MULLION" => ElementKind::Post,
+                _ if vertical => ElementKind::Post,
+                _ if horizontal => ElementKind::Beam,
+                _ => ElementKind::Brace,
+            },
+        };
+
+        let supported = match (&extrusion.profile, kind) {
+            (Profile::Polygon(_), ElementKind::Slab) => true,
+            (Profile::Rectangle(_, _), ElementKind::Slab) => true,
+            (Profile::Steel(_), ElementKind::Slab) => false,
+            (Profile::Steel(_), _) => true,
+            (Profile::Rectangle(_, _), ElementKind::Beam) => true,
+            (Profile::Rectangle(_, _), ElementKind::Column) => true,
+            _ => false,
+        };
+
+        if !supported {
+            self.warnings.push(format!(
+                "{} '{}': profile has no ST-Bridge section equivalent",
+                entity.name,
+                entity.arg(2).as_str().unwrap_or("")
+            ));
+            return None;
+        }
+
+        Some(kind)
+    }
+
+    fn node(&mut self, p: Node) -> u32 {
+        let cell = |v: f64| (v / self.tolerance).floor() as i64;
+        let key = (cell(p.x), cell(p.y), cell(p.z));
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbour = (
+                        key.0.saturating_add(dx),
+                        key.1.saturating_add(dy),
+                        key.2.saturating_add(dz),
+                    );
+                    if let Some(ids) = self.node_grid.get(&neighbour) {
+                        for id in ids {
+                            let n = &self.stb_nodes.map[id];
+                            if Node::distance(Node::new(n.x, n.y, n.z), p) <= self.tolerance {
+                                return *id;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let id = self.stb_nodes.map.len() as u32 + 1;
+        self.stb_nodes.insert(
+            id,
+            StbNode {
+                x: round(p.x),
+                y: round(p.y),
+                z: round(p.z),
+                kind: StbNodeKind::Other,
+                id_member: None,
+            },
+        );
+        self.node_grid.entry(key).or_default().push(id);
+        id
+    }
+
+    fn add_linear(
+        &mut self,
+        element_id: u64,
+        name: &str,
+        kind: ElementKind,
+        extrusion: Extrusion,
+        material: &str,
+        storey: Option<u64>,
+    ) {
+        let (min_x, max_x, min_y, max_y) = bounds(&extrusion.outline);
+        let reference_y = match kind {
+            ElementKind::Beam => max_y,
+            _ => (min_y + max_y) / 2.0,
+        };
+        let start = Node::new((min_x + max_x) / 2.0, reference_y, 0.0);
+        let end = start + extrusion.direction * extrusion.depth;
+
+        let mut pi = extrusion.transform.to_global(start.x, start.y, start.z);
+        let mut pj = extrusion.transform.to_global(end.x, end.y, end.z);
+        if matches!(kind, ElementKind::Column | ElementKind::Post) && pi.z > pj.z {
+            std::mem::swap(&mut pi, &mut pj);
+        }
+
+        if Node::distance(pi, pj) < self.tolerance {
+            self.warnings
+                .push(format!("#{} '{}': zero-length axis", element_id, name));
+            return;
+        }
+
+        let profile_x = rotate(&extrusion.transform, Node::new(1.0, 0.0, 0.0));
+        let frame = member_plane(pi, pj, 0.0);
+        let rotate = round(
+            profile_x
+                .dot(frame.y_axis)
+                .atan2(profile_x.dot(frame.x_axis))
+                .to_degrees(),
+        );
+
+        let id = self.next_member_id;
+        self.next_member_id += 1;
+
+        if kind == ElementKind::Beam {
+            self.pending_beams.push(PendingBeam {
+                id,
+                name: name.to_string(),
+                pi,
+                pj,
+                rotate,
+                profile_id: extrusion.profile_id,
+                profile: extrusion.profile,
+                material: material.to_string(),
+                storey,
+            });
+            return;
+        }
+
+        let (id_section, column_structure) = match extrusion.profile {
+            Profile::Steel(steel) => (
+                self.steel_section(extrusion.profile_id, kind, steel, material),
+                ColumnStructureKind::S,
+            ),
+            Profile::Rectangle(width_x, width_y) => (
+                self.column_rc_section(extrusion.profile_id, width_x, width_y, material),
+                ColumnStructureKind::RC,
+            ),
+            Profile::Polygon(_) => return,
+        };
+        let ni = self.node(pi);
+        let nj = self.node(pj);
+        self.add_to_storey(storey, &[if pi.z <= pj.z { ni } else { nj }]);
+
+        match kind {
+            ElementKind::Column => {
+                self.column_nodes.insert(ni);
+                self.column_nodes.insert(nj);
+                self.stb_members.stb_columns.insert(
+                    id,
+                    StbMember::StbColumn {
+                        id,
+                        name: name.to_string(),
+                        id_node_bottom: ni,
+                        id_node_top: nj,
+                        rotate,
+                        id_section,
+                        kind_structure: column_structure,
+                        offset_x: 0.0,
+                        offset_y: 0.0,
+                        condition_bottom: JointCondition::Fix,
+                        condition_top: JointCondition::Fix,
+                    },
+                );
+            }
+            ElementKind::Post => {
+                self.stb_members.stb_posts.insert(
+                    id,
+                    StbMember::StbPost {
+                        id,
+                        name: name.to_string(),
+                        id_node_bottom: ni,
+                        id_node_top: nj,
+                        rotate,
+                        id_section,
+                        kind_structure: ColumnStructureKind::S,
+                        offset_x: 0.0,
+                        offset_y: 0.0,
+                        offset_bottom_x: 0.0,
+                        offset_bottom_y: 0.0,
+                        offset_bottom_z: 0.0,
+                        offset_top_x: 0.0,
+                        offset_top_y: 0.0,
+                        offset_top_z: 0.0,
+                        condition_bottom: JointCondition::Pin,
+                        condition_top: JointCondition::Pin,
+                    },
+                );
+            }
+            _ => {
+                self.stb_members.stb_braces.insert(
+                    id,
+                    StbMember::StbBrace {
+                        id,
+                        name: name.to_string(),
+                        id_node_start: ni,
+                        id_node_end: nj,
+                        rotate,
+                        id_section,
+                        kind_structure: BraceStructureKind::S,
+                        offset_start_x: 0.0,
+                        offset_start_y: 0.0,
+                        offset_start_z: 0.0,
+                        offset_end_x: 0.0,
+                        offset_end_y: 0.0,
+                        offset_end_z: 0.0,
+                        condition_start: JointCondition::Pin,
+                        condition_end: JointCondition::Pin,
+                    },
+                );
+            }
+        }
+    }
+
+    // Beams wait until every column is in, so that those framing into columns at both ends can
+    // become girders.
+    fn add_pending_beams(&mut self) {
+        let pending_beams = std::mem::take(&mut self.pending_beams);
+
+        for beam in pending_beams {
+            let (ni, nj, level) = self.beam_nodes(beam.pi, beam.pj);
+            self.add_to_storey(beam.storey, &[ni, nj]);
+
+            let is_girder = self.column_nodes.contains(&ni) && self.column_nodes.contains(&nj);
+            let kind = if is_girder {
+                ElementKind::Girder
+            } else {
+                ElementKind::Beam
+            };
+
+            let (id_section, kind_structure) = match beam.profile {
+                Profile::Steel(steel) => (
+                    self.beam_s_section(beam.profile_id, kind, steel, &beam.material),
+                    GirderStructureKind::S,
+                ),
+                Profile::Rectangle(width, depth) => (
+                    self.beam_rc_section(beam.profile_id, kind, width, depth, &beam.material),
+                    GirderStructureKind::RC,
+                ),
+                Profile::Polygon(_) => continue,
+            };
+
+            if is_girder {
+                self.stb_members.stb_girders.insert(
+                    beam.id,
+                    StbMember::StbGirder {
+                        id: beam.id,
+                        name: beam.name,
+                        id_node_start: ni,
+                        id_node_end: nj,
+                        rotate: beam.rotate,
+                        id_section,
+                        kind_structure,
+                        is_foundation: false,
+                        offset: 0.0,
+                        level,
+                        type_haunch_h: None,
+                        condition_start: JointCondition::Fix,
+                        condition_end: JointCondition::Fix,
+                    },
+                );
+            } else {
+                self.stb_members.stb_beams.insert(
+                    beam.id,
+                    StbMember::StbBeam {
+                        id: beam.id,
+                        name: beam.name,
+                        id_node_start: ni,
+                        id_node_end: nj,
+                        rotate: beam.rotate,
+                        id_section,
+                        kind_structure,
+                        is_foundation: false,
+                        offset: 0.0,
+                        level,
+                        condition_start: JointCondition::Fix,
+                        condition_end: JointCondition::Fix,
+                    },
+                );
+            }
+        }
+    }
+
+    // A beam end that lies on a column axis close to a column node frames into that node, with
+    // the height difference kept as the beam level.
+    fn beam_nodes(&mut self, pi: Node, pj: Node) -> (u32, u32, f64) {
+        if let (Some((ni, li)), Some((nj, lj))) =
+            (self.column_node_near(pi), self.column_node_near(pj))
+        {
+            if (li - lj).abs() <= self.tolerance {
+                return (ni, nj, round((li + lj) / 2.0));
+            }
+        }
+
+        (self.node(pi), self.node(pj), 0.0)
+    }
+
+    fn column_node_near(&self, p: Node) -> Option<(u32, f64)> {
+        let mut column_nodes: Vec<&u32> = self.column_nodes.iter().collect();
+        column_nodes.sort_unstable();
+
+        column_nodes
+            .into_iter()
+            .filter_map(|id| {
+                let n = &self.stb_nodes.map[id];
+                let horizontal = ((n.x - p.x).powi(2) + (n.y - p.y).powi(2)).sqrt();
+                let level = p.z - n.z;
+                if horizontal <= self.tolerance && level.abs() <= MAX_BEAM_LEVEL {
+                    Some((*id, level))
+                } else {
+                    None
+                }
+            })
+            .min_by(|a, b| a.1.abs().partial_cmp(&b.1.abs()).unwrap())
+    }
+
+    fn add_to_storey(&mut self, storey: Option<u64>, node_ids: &[u32]) {
+        if let Some(storey) = storey {
+            self.storey_nodes
+                .entry(storey)
+                .or_default()
+                .extend(node_ids.iter().cloned());
+        }
+    }
+
+    fn add_slab(
+        &mut self,
+        element_id: u64,
+        name: &str,
+        extrusion: &Extrusion,
+        material: &str,
+        storey: Option<u64>,
+    ) {
+        let offset = extrusion.direction * extrusion.depth;
+        let face = |dz: Node| -> Vec<Node> {
+            extrusion
+                .outline
+                .iter()
+                .map(|&(u, v)| {
+                    let p = Node::new(u, v, 0.0) + dz;
+                    extrusion.transform.to_global(p.x, p.y, p.z)
+                })
+                .collect()
+        };
+        let bottom = face(Node::new(0.0, 0.0, 0.0));
+        let top = face(offset);
+        let mean_z = |f: &Vec<Node>| f.iter().map(|p| p.z).sum::<f64>() / f.len() as f64;
+        let top = if mean_z(&top) >= mean_z(&bottom) {
+            top
+        } else {
+            bottom
+        };
+
+        let mut node_ids: Vec<u32> = Vec::new();
+        for p in top {
+            let id = self.node(p);
+            if node_ids.last() != Some(&id) && node_ids.first() != Some(&id) {
+                node_ids.push(id);
+            }
+        }
+        if node_ids.len() < 3 {
+            self.warnings.push(format!(
+                "#{} '{}': degenerate slab outline",
+                element_id, name
+            ));
+            return;
+        }
+
+        let key = (extrusion.profile_id, ElementKind::Slab as u8);
+        let id_section = match self.section_ids.get(&key) {
+            Some(&id) => id,
+            None => {
+                let id = self.next_section_id;
+                self.next_section_id += 1;
+                self.stb_sections.slab_rc_map.insert(
+                    id,
+                    StbSection::StbSecSlabRC {
+                        id,
+                        name: format!("S{}", id),
+                        is_foundation: false,
+                        is_canti: false,
+                        strength_concrete: material.to_string(),
+                        stb_sec_figure: StbSecFigureSlab {
+                            stb_sec_straight: StbSecStraightSlab {
+                                depth: round(extrusion.depth),
+                            },
+                        },
+                        stb_sec_bar_arrangement: StbSecBarArrangementSlab {
+                            stb_sec_1way_slab_1_list: Vec::new(),
+                        },
+                    },
+                );
+                self.section_ids.insert(key, id);
+                id
+            }
+        };
+
+        let id = self.next_member_id;
+        self.next_member_id += 1;
+        self.stb_members.stb_slabs.insert(
+            id,
+            StbMember::StbSlab {
+                id,
+                name: name.to_string(),
+                id_section,
+                kind_structure: SlabStructureKind::RC,
+                kind_slab: SlabKind::Normal,
+                level: 0.0,
+                is_foundation: false,
+                stb_node_id_list: StbNodeIdList {
+                    children: node_ids
+                        .iter()
+                        .map(|&id| StbNodeId { id: id as i32 })
+                        .collect(),
+                },
+            },
+        );
+
+        self.add_to_storey(storey, &node_ids);
+    }
+
+    fn steel_section(
+        &mut self,
+        profile_id: u64,
+        kind: ElementKind,
+        steel: StbSecSteelChildren,
+        material: &str,
+    ) -> u32 {
+        let key = (profile_id, kind as u8);
+        if let Some(&id) = self.section_ids.get(&key) {
+            return id;
+        }
+
+        let id = self.next_section_id;
+        self.next_section_id += 1;
+        let shape = self.insert_steel(steel);
+        let name = format!("{}{}", section_prefix(kind), id);
+
+        let stb_section = match kind {
+            ElementKind::Column | ElementKind::Post => StbSection::StbSecColumnS {
+                id,
+                name,
+                floor: String::new(),
+                kind_column: if kind == ElementKind::Column {
+                    ColumnKind::Column
+                } else {
+                    ColumnKind::Post
+                },
+                direction: true,
+                base_type: SteelBaseType::Null,
+                stb_sec_steel_column: StbSecSteelColumn {
+                    pos: StbSecSteelColumnPosition::All,
+                    shape,
+                    strength_main: material.to_string(),
+                    strength_web: material.to_string(),
+                },
+            },
+            _ => StbSection::StbSecBraceS {
+                id,
+                name,
+                floor: String::new(),
+                kind_brace: BraceKind::Vertical,
+                stb_sec_steel_brace: StbSecSteelBrace {
+                    pos: StbSecSteelBraceSPosition::All,
+                    shape,
+                    strength_main: material.to_string(),
+                    strength_web: material.to_string(),
+                },
+            },
+        };
+
+        match stb_section {
+            StbSection::StbSecColumnS { .. } => {
+                self.stb_sections.column_s_map.insert(id, stb_section)
+            }
+            _ => self.stb_sections.brace_s_map.insert(id, stb_section),
+        };
+        self.section_ids.insert(key, id);
+        id
+    }
+
+    fn beam_s_section(
+        &mut self,
+        profile_id: u64,
+        kind: ElementKind,
+        steel: StbSecSteelChildren,
+        material: &str,
+    ) -> u32 {
+        let key = (profile_id, kind as u8);
+        if let Some(&id) = self.section_ids.get(&key) {
+            return id;
+        }
+
+        let id = self.next_section_id;
+        self.next_section_id += 1;
+        let shape = self.insert_steel(steel);
+
+        self.stb_sections.beam_s_map.insert(
+            id,
+            StbSection::StbSecBeamS {
+                id,
+                name: format!("{}{}", section_prefix(kind), id),
+                floor: String::new(),
+                kind_beam: if kind == ElementKind::Girder {
+                    BeamKind::Girder
+                } else {
+                    BeamKind::Beam
+                },
+                is_canti: false,
+                stb_sec_steel_beam_list: vec![StbSecSteelBeam {
+                    pos: StbSecSteelBeamPosition::All,
+                    shape,
+                    strength_main: material.to_string(),
+                    strength_web: material.to_string(),
+                }],
+            },
+        );
+        self.section_ids.insert(key, id);
+        id
+    }
+
+    fn beam_rc_section(
+        &mut self,
+        profile_id: u64,
+        kind: ElementKind,
+        width: f64,
+        depth: f64,
+        material: &str,
+    ) -> u32 {
+        let key = (profile_id, kind as u8);
+        if let Some(&id) = self.section_ids.get(&key) {
+            return id;
+        }
+
+        let id = self.next_section_id;
+        self.next_section_id += 1;
+
+        self.stb_sections.beam_rc_map.insert(
+            id,
+            StbSection::StbSecBeamRC {
+                id,
+                name: format!("{}{}", section_prefix(kind), id),
+                floor: String::new(),
+                kind_beam: if kind == ElementKind::Girder {
+                    BeamKind::Girder
+                } else {
+                    BeamKind::Beam
+                },
+                is_foundation: false,
+                is_canti: false,
+                d_reinforcement_main: String::new(),
+                d_stirrup: String::new(),
+                d_reinforcement_web: String::new(),
+                d_bar_spacing: String::new(),
+                strength_concrete: Some(material.to_string()).filter(|m| !m.is_empty()),
+                strength_reinforcement_main: String::new(),
+                strength_reinforcement_2nd_main: None,
+                strength_stirrup: String::new(),
+                strength_reinforcement_web: String::new(),
+                strength_bar_spacing: String::new(),
+                depth_cover_left: None,
+                depth_cover_right: None,
+                depth_cover_top: None,
+                depth_cover_bottom: None,
+                stb_sec_figure: StbSecFigureBeam {
+                    stb_sec_haunch: None,
+                    stb_sec_straight: Some(StbSecStraightBeam {
+                        width: round(width),
+                        depth: round(depth),
+                    }),
+                },
+                stb_sec_bar_arrangement: StbSecBarArrangementBeam {
+                    stb_sec_beam_start_center_end_section_list: None,
+                    stb_sec_beam_same_section: None,
+                },
+            },
+        );
+        self.section_ids.insert(key, id);
+        id
+    }
+
+    // Bar arrangements are not in the IFC geometry, so RC sections get the outline only.
+    fn column_rc_section(
+        &mut self,
+        profile_id: u64,
+        width_x: f64,
+        width_y: f64,
+        material: &str,
+    ) -> u32 {
+        let key = (profile_id, ElementKind::Column as u8);
+        if let Some(&id) = self.section_ids.get(&key) {
+            return id;
+        }
+
+        let id = self.next_section_id;
+        self.next_section_id += 1;
+
+        self.stb_sections.column_rc_map.insert(
+            id,
+            StbSection::StbSecColumnRC {
+                id,
+                name: format!("{}{}", section_prefix(ElementKind::Column), id),
+                floor: String::new(),
+                kind_column: ColumnKind::Column,
+                d_reinforcement_main: String::new(),
+                d_rein_band: String::new(),
+                strength_concrete: Some(material.to_string()).filter(|m| !m.is_empty()),
+                strength_reinforcement_main: String::new(),
+                strength_rein_band: String::new(),
+                depth_cover_start_x: None,
+                depth_cover_end_x: None,
+                depth_cover_start_y: None,
+                depth_cover_end_y: None,
+                stb_sec_figure: StbSecFigureColumn {
+                    stb_sec_rect: Some(StbSecRect {
+                        width_x: round(width_x),
+                        width_y: round(width_y),
+                    }),
+                    stb_sec_circle: None,
+                },
+                stb_sec_bar_arrangement: StbSecBarArrangementColumn {
+                    stb_sec_rect_column_same: None,
+                    stb_sec_circle_column_same: None,
+                },
+            },
+        );
+        self.section_ids.insert(key, id);
+        id
+    }
+
+    // Profiles sharing a name but not their dimensions are kept apart by a numbered suffix.
+    // Returns the name the shape was stored under.
+    fn insert_steel(&mut self, mut steel: StbSecSteelChildren) -> String {
+        let children_map = &mut self.stb_sections.stb_sec_steel.children_map;
+        let name = steel.name();
+        let mut index = 1;
+        loop {
+            match children_map.get(&steel.name()) {
+                None => break,
+                Some(existing) if *existing == steel => return steel.name(),
+                Some(_) => {
+                    index += 1;
+                    steel.set_name(format!("{}-{}", name, index));
+                }
+            }
+        }
+
+        let shape = steel.name();
+        if index > 1 {
+            self.warnings.push(format!(
+                "steel profile '{}' has differing dimensions, renamed to '{}'",
+                name, shape
+            ));
+        }
+        children_map.insert(shape.clone(), steel);
+        shape
+    }
+}
+
+fn section_prefix(kind: ElementKind) -> &'static str {
+    match kind {
+        ElementKind::Column => "C",
+        ElementKind::Post => "P",
+        ElementKind::Girder => "G",
+        ElementKind::Beam => "B",
+        ElementKind::Brace => "V",
+        ElementKind::Slab => "S",
+    }
+}
+
+fn steel_name<F: Fn() -> String>(name: &str, generate: F) -> String {
+    if name.trim().is_empty() {
+        generate()
+    } else {
+        name.to_string()
+    }
+}
+
+fn compose(parent: &Plane, child: &Plane) -> Plane {
+    Plane::new(
+        parent.to_global(child.origin.x, child.origin.y, child.origin.z),
+        rotate(parent, child.x_axis),
+        rotate(parent, child.normal),
+    )
+}
+
+fn rotate(plane: &Plane, v: Node) -> Node {
+    plane.x_axis * v.x + plane.y_axis * v.y + plane.normal * v.z
+}
+
+fn bounds(points: &[(f64, f64)]) -> (f64, f64, f64, f64) {
+    points.iter().fold(
+        (
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(x0, x1, y0, y1), &(x, y)| (x0.min(x), x1.max(x), y0.min(y), y1.max(y)),
+    )
+}
+
+fn round(v: f64) -> f64 {
+    let r = (v * 1e6).round() / 1e6;
+    if r == 0.0 {
+        0.0
+    } else {
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::ifc::create_ifc;
+    use crate::read_st_bridge;
+
+    fn count(st_bridge: &StBridge) -> HashMap<&'static str, usize> {
+        let mut counts = HashMap::new();
+        for stb_member in st_bridge.stb_model.stb_members.iter() {
+            let kind = match stb_member {
+                StbMember::StbColumn { .. } => "column",
+                StbMember::StbPost { .. } => "post",
+                StbMember::StbGirder { .. } | StbMember::StbBeam { .. } => "beam",
+                StbMember::StbBrace { .. } => "brace",
+                StbMember::StbSlab { .. } => "slab",
+                _ => "other",
+            };
+            *counts.entry(kind).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    // Column offsets are folded into the imported node positions.
+    fn column_ends(st_bridge: &StBridge) -> Vec<(i64, i64, i64, i64)> {
+        let stb_nodes = &st_bridge.stb_model.stb_nodes;
+        let mut ends: Vec<_> = st_bridge
+            .stb_model
+            .stb_members
+            .iter()
+            .filter_map(|m| match *m {
+                StbMember::StbColumn {
+                    id_node_bottom,
+                    id_node_top,
+                    offset_x,
+                    offset_y,
+                    ..
+                } => {
+                    let bottom = stb_nodes.get(id_node_bottom)?;
+                    let top = stb_nodes.get(id_node_top)?;
+                    Some((
+                        (bottom.x + offset_x).round() as i64,
+                        (bottom.y + offset_y).round() as i64,
+                        bottom.z.round() as i64,
+                        top.z.round() as i64,
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+        ends.sort_unstable();
+        ends
+    }
+
+    #[test]
+    fn sample_round_trips_through_ifc() {
+        let st_bridge = read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap();
+
+        let import = import_ifc(&create_ifc(&st_bridge, "sample.ifc"), DEFAULT_TOLERANCE).unwrap();
+
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+        assert_eq!(count(&import.st_bridge), count(&st_bridge));
+        assert_eq!(
+            import.st_bridge.stb_model.stb_stories.stb_story_list.len(),
+            st_bridge.stb_model.stb_stories.stb_story_list.len()
+        );
+        assert_eq!(column_ends(&import.st_bridge), column_ends(&st_bridge));
+    }
+
+    #[test]
+    fn rejects_non_positive_tolerance() {
+        for tolerance in [0.0, -1.0, f64::NAN] {
+            assert!(import_ifc("ISO-10303-21;\nDATA;\nENDSEC;\n", tolerance).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_step() {
+        assert!(import_ifc("", DEFAULT_TOLERANCE).is_err());
+        assert!(import_ifc("<ST_BRIDGE/>", DEFAULT_TOLERANCE).is_err());
+        assert!(import_ifc("ISO-10303-21;\nHEADER;\nENDSEC;\n", DEFAULT_TOLERANCE).is_err());
+    }
+
+    // A 600x500 rectangle extruded 3000 upwards as a column.
+    #[test]
+    fn rectangular_column_becomes_rc() {
+        let contents = "ISO-10303-21;
+DATA;
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#4=IFCDIRECTION((0.,0.,1.));
+#5=IFCAXIS2PLACEMENT3D(#1,$,$);
+#6=IFCLOCALPLACEMENT($,#5);
+#7=IFCRECTANGLEPROFILEDEF(.AREA.,'R600x500',$,600.,500.);
+#11=IFCEXTRUDEDAREASOLID(#7,#5,#4,3000.);
+#12=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#11));
+#13=IFCPRODUCTDEFINITIONSHAPE($,$,(#12));
+#14=IFCCOLUMN('column',$,'C1',$,$,#6,#13,$,.COLUMN.);
+ENDSEC;
+";
+        let import = import_ifc(contents, DEFAULT_TOLERANCE).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+        let stb_model = &import.st_bridge.stb_model;
+        let stb_column = stb_model.stb_members.stb_columns.values().next().unwrap();
+        assert_eq!(stb_column.kind_structure(), "RC");
+        match stb_model.stb_sections.get(stb_column.id_section()) {
+            Some(StbSection::StbSecColumnRC { stb_sec_figure, .. }) => {
+                let rect = stb_sec_figure.stb_sec_rect.as_ref().unwrap();
+                assert_eq!((rect.width_x, rect.width_y), (600.0, 500.0));
+            }
+            _ => panic!("expected an RC column section"),
+        }
+    }
+
+    // One rectangle profile extruded as a beam along X and as a slab upwards.
+    #[test]
+    fn beam_and_slab_sharing_a_profile_get_their_own_sections() {
+        let contents = "ISO-10303-21;
+DATA;
+#1=IFCCARTESIANPOINT((0.,0.,0.));
+#2=IFCDIRECTION((1.,0.,0.));
+#3=IFCDIRECTION((0.,1.,0.));
+#4=IFCDIRECTION((0.,0.,1.));
+#5=IFCAXIS2PLACEMENT3D(#1,$,$);
+#6=IFCLOCALPLACEMENT($,#5);
+#7=IFCRECTANGLEPROFILEDEF(.AREA.,'R300x600',$,300.,600.);
+#10=IFCAXIS2PLACEMENT3D(#1,#2,#3);
+#11=IFCEXTRUDEDAREASOLID(#7,#10,#4,6000.);
+#12=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#11));
+#13=IFCPRODUCTDEFINITIONSHAPE($,$,(#12));
+#14=IFCBEAM('beam',$,'B1',$,$,#6,#13,$,.BEAM.);
+#20=IFCEXTRUDEDAREASOLID(#7,#5,#4,150.);
+#21=IFCSHAPEREPRESENTATION($,'Body','SweptSolid',(#20));
+#22=IFCPRODUCTDEFINITIONSHAPE($,$,(#21));
+#23=IFCSLAB('slab',$,'S1',$,$,#6,#22,$,.FLOOR.);
+ENDSEC;
+";
+        let import = import_ifc(contents, DEFAULT_TOLERANCE).unwrap();
+        assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+        let stb_model = &import.st_bridge.stb_model;
+        assert_eq!(stb_model.stb_sections.beam_rc_map.len(), 1);
+        assert_eq!(stb_model.stb_sections.slab_rc_map.len(), 1);
+
+        let id_section = |kind: &str| {
+            stb_model
+                .stb_members
+                .iter()
+                .find(|m| m.member_kind() == kind)
+                .map(|m| m.id_section())
+                .unwrap()
+        };
+        let beam_section = id_section("Beam");
+        let slab_section = id_section("Slab");
+        assert_ne!(beam_section, slab_section);
+        assert!(stb_model
+            .stb_sections
+            .beam_rc_map
+            .contains_key(&beam_section));
+        assert!(stb_model
+            .stb_sections
+            .slab_rc_map
+            .contains_key(&slab_section));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod ifc;
+pub mod step;
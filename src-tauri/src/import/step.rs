@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub enum StepValue {
+    Null,
+    Derived,
+    Ref(u64),
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Enum(String),
+    List(Vec<StepValue>),
+    Typed(String, Vec<StepValue>),
+}
+
+impl StepValue {
+    pub fn as_id(&self) -> Option<u64> {
+        match *self {
+            StepValue::Ref(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            StepValue::Real(v) => Some(*v),
+            StepValue::Integer(v) => Some(*v as f64),
+            StepValue::Typed(_, args) if args.len() == 1 => args[0].as_f64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            StepValue::String(s) | StepValue::Enum(s) => Some(s),
+            StepValue::Typed(_, args) if args.len() == 1 => args[0].as_str(),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> &[StepValue] {
+        match self {
+            StepValue::List(list) => list,
+            _ => &[],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StepEntity {
+    pub name: String,
+    pub args: Vec<StepValue>,
+}
+
+impl StepEntity {
+    pub fn arg(&self, i: usize) -> &StepValue {
+        self.args.get(i).unwrap_or(&StepValue::Null)
+    }
+}
+
+// Parses the DATA section of an ISO 10303-21 file. Complex (multi-type) instances and anything
+// that does not parse are skipped, since callers only look for a handful of entity types.
+pub fn parse_step(contents: &str) -> Result<HashMap<u64, StepEntity>, String> {
+    let mut entities = HashMap::new();
+
+    if !contents.trim_start().starts_with("ISO-10303-21;") {
+        return Err("not an ISO 10303-21 (STEP) file".to_string());
+    }
+    let data = match contents.find("DATA;") {
+        Some(i) => &contents[i + 5..],
+        None => return Err("STEP file has no DATA section".to_string()),
+    };
+
+    for statement in split_statements(data) {
+        let statement = statement.trim();
+        if !statement.starts_with('#') {
+            continue;
+        }
+
+        let eq = match statement.find('=') {
+            Some(i) => i,
+            None => continue,
+        };
+        let id = match statement[1..eq].trim().parse::<u64>() {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+
+        let mut parser = Parser::new(statement[eq + 1..].trim());
+        if let Some(StepValue::Typed(name, args)) = parser.value() {
+            entities.insert(id, StepEntity { name, args });
+        }
+    }
+
+    Ok(entities)
+}
+
+fn split_statements(data: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut in_string = false;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_string = !in_string;
+                current.push(c);
+            }
+            '/' if !in_string && chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            ';' if !in_string => {
+                if current.trim() == "ENDSEC" {
+                    break;
+                }
+                statements.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+
+    statements
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Parser {
+        Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn value(&mut self) -> Option<StepValue> {
+        self.skip_whitespace();
+
+        match self.peek()? {
+            '$' => {
+                self.pos += 1;
+                Some(StepValue::Null)
+            }
+            '*' => {
+                self.pos += 1;
+                Some(StepValue::Derived)
+            }
+            '#' => {
+                self.pos += 1;
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                digits.parse().ok().map(StepValue::Ref)
+            }
+            '\'' => self.string().map(StepValue::String),
+            '"' => {
+                self.pos += 1;
+                let s = self.take_while(|c| c != '"');
+                self.pos += 1;
+                Some(StepValue::String(s))
+            }
+            '.' => {
+                self.pos += 1;
+                let s = self.take_while(|c| c != '.');
+                self.pos += 1;
+                Some(StepValue::Enum(s))
+            }
+            '(' => self.list().map(StepValue::List),
+            c if c == '-' || c == '+' || c.is_ascii_digit() => self.number(),
+            c if c.is_ascii_alphabetic() => {
+                let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                self.skip_whitespace();
+                if self.peek() == Some('(') {
+                    let args = self.list()?;
+                    Some(StepValue::Typed(name.to_uppercase(), args))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn list(&mut self) -> Option<Vec<StepValue>> {
+        self.pos += 1;
+        let mut list = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                ')' => {
+                    self.pos += 1;
+                    return Some(list);
+                }
+                ',' => self.pos += 1,
+                _ => list.push(self.value()?),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<StepValue> {
+        let s = self.take_while(|c| {
+            c.is_ascii_digit() || c == '.' || c == '-' || c == '+' || c == 'E' || c == 'e'
+        });
+
+        if s.contains('.') || s.contains('E') || s.contains('e') {
+            let normalized = if s.ends_with('.') {
+                format!("{}0", s)
+            } else {
+                s.replace(".E", ".0E")
+            };
+            normalized.parse().ok().map(StepValue::Real)
+        } else {
+            s.parse().ok().map(StepValue::Integer)
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.pos += 1;
+        let mut raw = String::new();
+
+        loop {
+            let c = self.peek()?;
+            self.pos += 1;
+            if c == '\'' {
+                if self.peek() == Some('\'') {
+                    raw.push('\'');
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            } else {
+                raw.push(c);
+            }
+        }
+
+        Some(decode_string(&raw))
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if f(c) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+}
+
+// Undoes the \X2\...\X0\ (UTF-16), \X\hh (Latin-1) and \\ escapes of STEP strings.
+fn decode_string(raw: &str) -> String {
+    let mut decoded = String::new();
+    let mut rest = raw;
+
+    while let Some(i) = rest.find('\\') {
+        decoded.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        if let Some(body) = rest.strip_prefix("\\X2\\") {
+            let end = body.find("\\X0\\").unwrap_or(body.len());
+            let units: Vec<u16> = body.as_bytes()[..end]
+                .chunks(4)
+                .filter_map(|c| std::str::from_utf8(c).ok())
+                .filter_map(|h| u16::from_str_radix(h, 16).ok())
+                .collect();
+            decoded.push_str(&String::from_utf16_lossy(&units));
+            rest = &body[(end + 4).min(body.len())..];
+        } else if let Some(body) = rest.strip_prefix("\\X\\") {
+            let code = body.get(..2).and_then(|h| u8::from_str_radix(h, 16).ok());
+            if let Some(code) = code {
+                decoded.push(code as char);
+                rest = &body[2..];
+            } else {
+                rest = body;
+            }
+        } else if let Some(body) = rest.strip_prefix("\\\\") {
+            decoded.push('\\');
+            rest = body;
+        } else {
+            decoded.push('\\');
+            rest = &rest[1..];
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_value(source: &str) -> Option<StepValue> {
+        Parser::new(source).value()
+    }
+
+    #[test]
+    fn parses_entities_of_the_data_section() {
+        let contents = "ISO-10303-21;
+HEADER;
+FILE_NAME('header.ifc','',(''),(''),'','','');
+ENDSEC;
+DATA;
+#1=IFCCARTESIANPOINT((0.,1.5,-2.E3));
+/* a comment; with a semicolon */
+#2 = IFCDIRECTION((1.,0.,0.));
+#3=IFCWALL('guid',$,'it''s; here',*,.T.,(#1,#2));
+#4=(IFCLENGTHMEASURE(1.) IFCREAL(2.));
+ENDSEC;
+#5=IFCDIRECTION((0.,0.,1.));
+END-ISO-10303-21;
+";
+        let entities = parse_step(contents).unwrap();
+
+        // The complex instance #4 and anything after ENDSEC are skipped.
+        let mut id_list: Vec<u64> = entities.keys().cloned().collect();
+        id_list.sort_unstable();
+        assert_eq!(id_list, vec![1, 2, 3]);
+
+        let point = &entities[&1];
+        assert_eq!(point.name, "IFCCARTESIANPOINT");
+        let c: Vec<f64> = point
+            .arg(0)
+            .as_list()
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .collect();
+        assert_eq!(c, vec![0.0, 1.5, -2000.0]);
+
+        let wall = &entities[&3];
+        assert_eq!(wall.arg(0).as_str(), Some("guid"));
+        assert!(matches!(wall.arg(1), StepValue::Null));
+        assert_eq!(wall.arg(2).as_str(), Some("it's; here"));
+        assert!(matches!(wall.arg(3), StepValue::Derived));
+        assert_eq!(wall.arg(4).as_str(), Some("T"));
+        let id_list: Vec<u64> = wall
+            .arg(5)
+            .as_list()
+            .iter()
+            .filter_map(|v| v.as_id())
+            .collect();
+        assert_eq!(id_list, vec![1, 2]);
+        assert!(matches!(wall.arg(9), StepValue::Null));
+    }
+
+    #[test]
+    fn no_data_section() {
+        assert!(parse_step("ISO-10303-21;\nHEADER;\nENDSEC;\n").is_err());
+        assert!(parse_step("ISO-10303-21;\nDATA;\nENDSEC;\n")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn not_a_step_file() {
+        assert!(parse_step("").is_err());
+        assert!(parse_step("<?xml version=\"1.0\"?>\n<ST_BRIDGE/>\n").is_err());
+        assert!(parse_step("DATA;\n#1=IFCDIRECTION((1.,0.,0.));\nENDSEC;\n").is_err());
+    }
+
+    #[test]
+    fn numbers() {
+        assert!(matches!(parse_value("42"), Some(StepValue::Integer(42))));
+        assert!(matches!(parse_value("-7"), Some(StepValue::Integer(-7))));
+        assert_eq!(parse_value("3.").unwrap().as_f64(), Some(3.0));
+        assert_eq!(parse_value("1.E-3").unwrap().as_f64(), Some(0.001));
+        assert_eq!(parse_value("+2.5e2").unwrap().as_f64(), Some(250.0));
+    }
+
+    #[test]
+    fn typed_values_unwrap_a_single_argument() {
+        let value = parse_value("ifcLengthMeasure(250.)").unwrap();
+        assert!(matches!(&value, StepValue::Typed(name, _) if name == "IFCLENGTHMEASURE"));
+        assert_eq!(value.as_f64(), Some(250.0));
+        assert_eq!(parse_value("IFCLABEL('C1')").unwrap().as_str(), Some("C1"));
+
+        // A bare name without arguments is not a value.
+        assert!(parse_value("IFCLABEL").is_none());
+    }
+
+    #[test]
+    fn strings_are_decoded() {
+        assert_eq!(decode_string("\\X2\\67F1\\X0\\C1"), "柱C1");
+        assert_eq!(decode_string("caf\\X\\E9"), "café");
+        assert_eq!(decode_string("a\\\\b"), "a\\b");
+        assert_eq!(decode_string("trailing\\"), "trailing\\");
+        assert_eq!(parse_value("\"0A1B\"").unwrap().as_str(), Some("0A1B"));
+    }
+}
@@ -12,42 +12,63 @@ use crate::st_bridge::stb_model::stb_sections::*;
 use crate::st_bridge::stb_model::StbModel;
 use crate::st_bridge::StBridge;
 
+pub mod analysis;
+pub mod connectivity;
+pub mod diff;
+pub mod document;
+pub mod drawing;
+pub mod export;
 pub mod geometry;
+pub mod import;
 pub mod material;
+pub mod merge;
+pub mod node_merge;
+pub mod schedule;
+pub mod section_property;
 pub mod st_bridge;
+pub mod takeoff;
+pub mod validation;
 
-pub fn read_st_bridge(file_name: &str) -> StBridge {
-    let contents = get_contents(file_name);
+// Errors name the file, and for a malformed model the element and line at fault.
+pub fn read_st_bridge(file_name: &str) -> Result<StBridge, String> {
+    let contents = get_contents(file_name).map_err(|e| format!("{}: {}", file_name, e))?;
 
-    let document = roxmltree::Document::parse(&contents).unwrap();
+    let document =
+        roxmltree::Document::parse(&contents).map_err(|e| format!("{}: {}", file_name, e))?;
 
-    let root_node = document.root_element();
+    extract_st_bridge(document.root_element()).map_err(|e| format!("{}: {}", file_name, e))
+}
+
+fn extract_st_bridge(root_node: roxmltree::Node) -> Result<StBridge, String> {
+    if root_node.tag_name().name() != "ST_BRIDGE" {
+        return Err(element_error(root_node, "not an ST_BRIDGE document"));
+    }
 
-    let version = root_node.attribute("version").unwrap().to_string();
+    let version: String = parse_attribute("version", root_node)?;
+    crate::st_bridge::check_version(&version).map_err(|e| element_error(root_node, &e))?;
 
-    let stb_common = extract_stb_common(root_node);
+    let stb_common = extract_stb_common(root_node)?;
 
-    let stb_model = extract_stb_model(root_node);
+    let stb_model = extract_stb_model(root_node)?;
 
-    let stb_extensions = extract_stb_extensions(root_node);
+    let stb_extensions = extract_stb_extensions(root_node)?;
 
-    StBridge {
+    Ok(StBridge {
         version,
         stb_common,
         stb_model,
         stb_extensions,
-    }
+    })
 }
 
-pub fn get_contents(filename: &str) -> String {
-    let mut f = File::open(filename).expect("File not found.");
+pub fn get_contents(filename: &str) -> std::io::Result<String> {
+    let mut f = File::open(filename)?;
 
     let mut contents = String::new();
 
-    f.read_to_string(&mut contents)
-        .expect("something went wrong reading the file");
+    f.read_to_string(&mut contents)?;
 
-    return contents;
+    Ok(contents)
 }
 
 pub fn extract_node<'a>(
@@ -67,93 +88,94 @@ pub fn extract_node<'a>(
     None
 }
 
-pub fn extract_stb_common<'a>(root_node: roxmltree::Node<'a, 'a>) -> StbCommon {
-    let stb_common_node = extract_node("StbCommon", root_node).unwrap();
+pub fn extract_stb_common<'a>(root_node: roxmltree::Node<'a, 'a>) -> Result<StbCommon, String> {
+    let stb_common_node = child_node("StbCommon", root_node)?;
     let stb_reinforcement_strength_list =
-        extract_node("StbReinforcement_Strength_List", stb_common_node).unwrap();
+        child_node("StbReinforcement_Strength_List", stb_common_node)?;
     let mut stb_common = StbCommon::new();
 
+    if let Some(project_name) = stb_common_node.attribute("project_name") {
+        stb_common.project_name = project_name.to_string();
+    }
+
     for node in stb_reinforcement_strength_list
         .children()
         .filter(|n| n.is_element())
     {
-        let d = node.attribute("D").unwrap().to_string();
-        let sd = node.attribute("SD").unwrap().to_string();
+        let d = parse_attribute("D", node)?;
+        let sd = parse_attribute("SD", node)?;
         stb_common.stb_reinforcement_strength_list.insert(d, sd);
     }
 
-    return stb_common;
+    Ok(stb_common)
 }
 
-pub fn extract_stb_model(root_node: roxmltree::Node) -> StbModel {
-    let stb_model_node = extract_node("StbModel", root_node).unwrap();
+pub fn extract_stb_model(root_node: roxmltree::Node) -> Result<StbModel, String> {
+    let stb_model_node = child_node("StbModel", root_node)?;
 
-    let stb_nodes = extract_stb_nodes(stb_model_node);
+    let stb_nodes = extract_stb_nodes(stb_model_node)?;
 
-    let stb_axes = extract_stb_axes(stb_model_node);
+    let stb_axes = extract_stb_axes(stb_model_node)?;
 
-    let stb_stories = extract_stb_stories(stb_model_node);
+    let stb_stories = extract_stb_stories(stb_model_node)?;
 
-    let stb_members = extract_stb_members(stb_model_node);
+    let stb_members = extract_stb_members(stb_model_node)?;
 
-    let stb_sections = extract_stb_sections(stb_model_node);
+    let stb_sections = extract_stb_sections(stb_model_node)?;
 
-    StbModel {
+    Ok(StbModel {
         stb_nodes,
         stb_axes,
         stb_stories,
         stb_members,
         stb_sections,
-    }
+    })
 }
 
-fn extract_stb_nodes(stb_model_node: roxmltree::Node) -> StbNodes {
-    let stb_nodes_node = extract_node("StbNodes", stb_model_node).unwrap();
+fn extract_stb_nodes(stb_model_node: roxmltree::Node) -> Result<StbNodes, String> {
+    let stb_nodes_node = child_node("StbNodes", stb_model_node)?;
 
     let mut stb_nodes = StbNodes::new();
 
     for node in stb_nodes_node.children().filter(|n| n.is_element()) {
-        let id_member = match node.attribute("id_member") {
-            Some(s) => Some(s.parse::<u32>().unwrap()),
-            None => None,
-        };
+        let id_member = parse_optional_attribute("id_member", node)?;
 
         stb_nodes.insert(
-            parse_attribute("id", node).unwrap(),
+            parse_attribute("id", node)?,
             StbNode {
-                x: parse_attribute("x", node).unwrap(),
-                y: parse_attribute("y", node).unwrap(),
-                z: parse_attribute("z", node).unwrap(),
-                kind: parse_enum_attribute("kind", node).unwrap(),
+                x: parse_attribute("x", node)?,
+                y: parse_attribute("y", node)?,
+                z: parse_attribute("z", node)?,
+                kind: parse_enum_attribute("kind", node)?,
                 id_member,
             },
         );
     }
 
-    stb_nodes
+    Ok(stb_nodes)
 }
 
-fn extract_stb_axes(stb_model_node: roxmltree::Node) -> StbAxes {
-    let stb_axes_node = extract_node("StbAxes", stb_model_node).unwrap();
+fn extract_stb_axes(stb_model_node: roxmltree::Node) -> Result<StbAxes, String> {
+    let stb_axes_node = child_node("StbAxes", stb_model_node)?;
 
     let mut stb_axes = StbAxes::new();
 
     for node in stb_axes_node.children().filter(|n| n.is_element()) {
-        let stb_node_id_list_node = extract_node("StbNodeid_List", node).unwrap();
+        let stb_node_id_list_node = child_node("StbNodeid_List", node)?;
 
         let mut stb_node_id_list = Vec::new();
 
         for children in stb_node_id_list_node.children().filter(|n| n.is_element()) {
-            let id = parse_attribute("id", children).unwrap();
+            let id = parse_attribute("id", children)?;
             stb_node_id_list.push(StbNodeId { id });
         }
 
         match node.tag_name().name() {
             "StbX_Axis" => {
                 stb_axes.stb_x_axis_list.push(StbXAxis {
-                    id: parse_attribute("id", node).unwrap(),
-                    name: parse_attribute("name", node).unwrap(),
-                    distance: parse_attribute("distance", node).unwrap(),
+                    id: parse_attribute("id", node)?,
+                    name: parse_attribute("name", node)?,
+                    distance: parse_attribute("distance", node)?,
                     stb_node_id_list: StbNodeIdList {
                         children: stb_node_id_list,
                     },
@@ -161,55 +183,55 @@ fn extract_stb_axes(stb_model_node: roxmltree::Node) -> StbAxes {
             }
             "StbY_Axis" => {
                 stb_axes.stb_y_axis_list.push(StbYAxis {
-                    id: parse_attribute("id", node).unwrap(),
-                    name: parse_attribute("name", node).unwrap(),
-                    distance: parse_attribute("distance", node).unwrap(),
+                    id: parse_attribute("id", node)?,
+                    name: parse_attribute("name", node)?,
+                    distance: parse_attribute("distance", node)?,
                     stb_node_id_list: StbNodeIdList {
                         children: stb_node_id_list,
                     },
                 });
             }
             _ => {
-                panic!("Tag name {} is invalid.", node.tag_name().name());
+                return Err(element_error(node, "unknown axis"));
             }
         }
     }
 
-    stb_axes
+    Ok(stb_axes)
 }
 
-fn extract_stb_stories(stb_model_node: roxmltree::Node) -> StbStories {
-    let stb_stories_node = extract_node("StbStories", stb_model_node).unwrap();
+fn extract_stb_stories(stb_model_node: roxmltree::Node) -> Result<StbStories, String> {
+    let stb_stories_node = child_node("StbStories", stb_model_node)?;
 
     let mut stb_stories = StbStories::new();
 
     for node in stb_stories_node.children().filter(|n| n.is_element()) {
-        let stb_node_id_list_node = extract_node("StbNodeid_List", node).unwrap();
+        let stb_node_id_list_node = child_node("StbNodeid_List", node)?;
 
         let mut stb_node_id_list = Vec::new();
 
         for children in stb_node_id_list_node.children().filter(|n| n.is_element()) {
-            let id = parse_attribute("id", children).unwrap();
+            let id = parse_attribute("id", children)?;
             stb_node_id_list.push(StbNodeId { id });
         }
 
         stb_stories.stb_story_list.push(StbStory {
-            id: parse_attribute("id", node).unwrap(),
-            name: parse_attribute("name", node).unwrap(),
-            height: parse_attribute("height", node).unwrap(),
-            kind: StbStoryKind::from_str(node.attribute("kind").unwrap()).unwrap(),
-            concrete_strength: node.attribute("concrete_strength").unwrap().to_string(),
+            id: parse_attribute("id", node)?,
+            name: parse_attribute("name", node)?,
+            height: parse_attribute("height", node)?,
+            kind: parse_enum_attribute("kind", node)?,
+            concrete_strength: parse_attribute("concrete_strength", node)?,
             stb_node_id_list: StbNodeIdList {
                 children: stb_node_id_list,
             },
         });
     }
 
-    stb_stories
+    Ok(stb_stories)
 }
 
-fn extract_stb_members(stb_model_node: roxmltree::Node) -> StbMembers {
-    let stb_members_node = extract_node("StbMembers", stb_model_node).unwrap();
+fn extract_stb_members(stb_model_node: roxmltree::Node) -> Result<StbMembers, String> {
+    let stb_members_node = child_node("StbMembers", stb_model_node)?;
 
     let mut stb_members = StbMembers::new();
 
@@ -217,192 +239,256 @@ fn extract_stb_members(stb_model_node: roxmltree::Node) -> StbMembers {
         let tag_name = node.tag_name().name();
 
         match tag_name {
-            "StbColumns" => stb_members.stb_columns = extract_stb_columns(node),
-            "StbPosts" => stb_members.stb_posts = extract_stb_posts(node),
-            "StbGirders" => stb_members.stb_girders = extract_stb_girders(node),
-            "StbBeams" => stb_members.stb_beams = extract_stb_beams(node),
-            "StbBraces" => stb_members.stb_braces = extract_stb_braces(node),
-            "StbSlabs" => stb_members.stb_slabs = extract_stb_slabs(node),
-            "StbWalls" => {}
+            "StbColumns" => stb_members.stb_columns = extract_stb_columns(node)?,
+            "StbPosts" => stb_members.stb_posts = extract_stb_posts(node)?,
+            "StbGirders" => stb_members.stb_girders = extract_stb_girders(node)?,
+            "StbBeams" => stb_members.stb_beams = extract_stb_beams(node)?,
+            "StbBraces" => stb_members.stb_braces = extract_stb_braces(node)?,
+            "StbSlabs" => stb_members.stb_slabs = extract_stb_slabs(node)?,
+            "StbWalls" => stb_members.stb_walls = extract_stb_walls(node)?,
             "StbFootings" => {}
             "StbStripFootings" => {}
             "StbPiles" => {}
             "StbFoundationColumns" => {}
             "StbParapets" => {}
-            "StbOpens" => {}
+            "StbOpens" => stb_members.stb_opens = extract_stb_opens(node)?,
             _ => {}
         };
     }
 
-    stb_members
+    Ok(stb_members)
 }
 
-fn extract_stb_columns(stb_columns_node: roxmltree::Node) -> HashMap<u32, StbMember> {
+fn extract_stb_columns(
+    stb_columns_node: roxmltree::Node,
+) -> Result<HashMap<u32, StbMember>, String> {
     let mut stb_column_map: HashMap<u32, StbMember> = HashMap::new();
 
     for node in stb_columns_node.children().filter(|n| n.is_element()) {
-        let id = parse_attribute("id", node).unwrap();
+        let id = parse_attribute("id", node)?;
         stb_column_map.insert(
             id,
             StbMember::StbColumn {
                 id,
-                name: node.attribute("name").unwrap().to_string(),
-                id_node_bottom: parse_attribute("idNode_bottom", node).unwrap(),
-                id_node_top: parse_attribute("idNode_top", node).unwrap(),
-                rotate: parse_attribute("rotate", node).unwrap(),
-                id_section: parse_attribute("id_section", node).unwrap(),
-                kind_structure: parse_enum_attribute("kind_structure", node).unwrap(),
-                offset_x: parse_attribute("offset_X", node).unwrap(),
-                offset_y: parse_attribute("offset_Y", node).unwrap(),
-                condition_bottom: parse_enum_attribute("condition_bottom", node).unwrap(),
-                condition_top: parse_enum_attribute("condition_top", node).unwrap(),
+                name: parse_attribute("name", node)?,
+                id_node_bottom: parse_attribute("idNode_bottom", node)?,
+                id_node_top: parse_attribute("idNode_top", node)?,
+                rotate: parse_attribute("rotate", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                offset_x: parse_attribute("offset_X", node)?,
+                offset_y: parse_attribute("offset_Y", node)?,
+                condition_bottom: parse_enum_attribute("condition_bottom", node)?,
+                condition_top: parse_enum_attribute("condition_top", node)?,
             },
         );
     }
 
-    stb_column_map
+    Ok(stb_column_map)
 }
 
-fn extract_stb_posts(stb_posts_node: roxmltree::Node) -> HashMap<u32, StbMember> {
+fn extract_stb_posts(stb_posts_node: roxmltree::Node) -> Result<HashMap<u32, StbMember>, String> {
     let mut stb_post_map: HashMap<u32, StbMember> = HashMap::new();
 
     for node in stb_posts_node.children().filter(|n| n.is_element()) {
-        let id = parse_attribute("id", node).unwrap();
+        let id = parse_attribute("id", node)?;
         stb_post_map.insert(
             id,
             StbMember::StbPost {
                 id,
-                name: node.attribute("name").unwrap().to_string(),
-                id_node_bottom: parse_attribute("idNode_bottom", node).unwrap(),
-                id_node_top: parse_attribute("idNode_top", node).unwrap(),
-                rotate: parse_attribute("rotate", node).unwrap(),
-                id_section: parse_attribute("id_section", node).unwrap(),
-                kind_structure: parse_enum_attribute("kind_structure", node).unwrap(),
-                offset_x: parse_attribute("offset_X", node).unwrap(),
-                offset_y: parse_attribute("offset_Y", node).unwrap(),
-                offset_bottom_x: parse_attribute("offset_bottom_X", node).unwrap(),
-                offset_bottom_y: parse_attribute("offset_bottom_Y", node).unwrap(),
-                offset_bottom_z: parse_attribute("offset_bottom_Z", node).unwrap(),
-                offset_top_x: parse_attribute("offset_top_X", node).unwrap(),
-                offset_top_y: parse_attribute("offset_top_Y", node).unwrap(),
-                offset_top_z: parse_attribute("offset_top_Z", node).unwrap(),
-                condition_bottom: parse_enum_attribute("condition_bottom", node).unwrap(),
-                condition_top: parse_enum_attribute("condition_top", node).unwrap(),
+                name: parse_attribute("name", node)?,
+                id_node_bottom: parse_attribute("idNode_bottom", node)?,
+                id_node_top: parse_attribute("idNode_top", node)?,
+                rotate: parse_attribute("rotate", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                offset_x: parse_attribute("offset_X", node)?,
+                offset_y: parse_attribute("offset_Y", node)?,
+                offset_bottom_x: parse_attribute("offset_bottom_X", node)?,
+                offset_bottom_y: parse_attribute("offset_bottom_Y", node)?,
+                offset_bottom_z: parse_attribute("offset_bottom_Z", node)?,
+                offset_top_x: parse_attribute("offset_top_X", node)?,
+                offset_top_y: parse_attribute("offset_top_Y", node)?,
+                offset_top_z: parse_attribute("offset_top_Z", node)?,
+                condition_bottom: parse_enum_attribute("condition_bottom", node)?,
+                condition_top: parse_enum_attribute("condition_top", node)?,
             },
         );
     }
 
-    stb_post_map
+    Ok(stb_post_map)
 }
 
-fn extract_stb_girders(stb_girders_node: roxmltree::Node) -> HashMap<u32, StbMember> {
+fn extract_stb_girders(
+    stb_girders_node: roxmltree::Node,
+) -> Result<HashMap<u32, StbMember>, String> {
     let mut stb_girder_map: HashMap<u32, StbMember> = HashMap::new();
 
     for node in stb_girders_node.children().filter(|n| n.is_element()) {
-        let id = parse_attribute("id", node).unwrap();
+        let id = parse_attribute("id", node)?;
         stb_girder_map.insert(
             id,
             StbMember::StbGirder {
                 id,
-                name: node.attribute("name").unwrap().to_string(),
-                id_node_start: parse_attribute("idNode_start", node).unwrap(),
-                id_node_end: parse_attribute("idNode_end", node).unwrap(),
-                rotate: parse_attribute("rotate", node).unwrap(),
-                id_section: parse_attribute("id_section", node).unwrap(),
-                kind_structure: parse_enum_attribute("kind_structure", node).unwrap(),
-                is_foundation: parse_attribute("isFoundation", node).unwrap(),
-                offset: parse_attribute("offset", node).unwrap(),
-                level: parse_attribute("level", node).unwrap(),
-                type_haunch_h: match node.attribute("type_haunch_H") {
-                    Some(s) => Some(HaunchType::from_str(s).unwrap()),
-                    None => None,
-                },
+                name: parse_attribute("name", node)?,
+                id_node_start: parse_attribute("idNode_start", node)?,
+                id_node_end: parse_attribute("idNode_end", node)?,
+                rotate: parse_attribute("rotate", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                is_foundation: parse_attribute("isFoundation", node)?,
+                offset: parse_attribute("offset", node)?,
+                level: parse_attribute("level", node)?,
+                type_haunch_h: parse_optional_attribute("type_haunch_H", node)?,
+                condition_start: parse_condition_attribute("condition_start", node)?,
+                condition_end: parse_condition_attribute("condition_end", node)?,
             },
         );
     }
 
-    stb_girder_map
+    Ok(stb_girder_map)
 }
 
-fn extract_stb_beams(stb_beams_node: roxmltree::Node) -> HashMap<u32, StbMember> {
+fn extract_stb_beams(stb_beams_node: roxmltree::Node) -> Result<HashMap<u32, StbMember>, String> {
     let mut stb_beam_map: HashMap<u32, StbMember> = HashMap::new();
 
     for node in stb_beams_node.children().filter(|n| n.is_element()) {
-        let id = parse_attribute("id", node).unwrap();
+        let id = parse_attribute("id", node)?;
         stb_beam_map.insert(
             id,
             StbMember::StbBeam {
                 id,
-                name: node.attribute("name").unwrap().to_string(),
-                id_node_start: parse_attribute("idNode_start", node).unwrap(),
-                id_node_end: parse_attribute("idNode_end", node).unwrap(),
-                rotate: parse_attribute("rotate", node).unwrap(),
-                id_section: parse_attribute("id_section", node).unwrap(),
-                kind_structure: parse_enum_attribute("kind_structure", node).unwrap(),
-                is_foundation: parse_attribute("isFoundation", node).unwrap(),
-                offset: parse_attribute("offset", node).unwrap(),
-                level: parse_attribute("level", node).unwrap(),
+                name: parse_attribute("name", node)?,
+                id_node_start: parse_attribute("idNode_start", node)?,
+                id_node_end: parse_attribute("idNode_end", node)?,
+                rotate: parse_attribute("rotate", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                is_foundation: parse_attribute("isFoundation", node)?,
+                offset: parse_attribute("offset", node)?,
+                level: parse_attribute("level", node)?,
+                condition_start: parse_condition_attribute("condition_start", node)?,
+                condition_end: parse_condition_attribute("condition_end", node)?,
             },
         );
     }
 
-    stb_beam_map
+    Ok(stb_beam_map)
 }
 
-fn extract_stb_braces(stb_braces_node: roxmltree::Node) -> HashMap<u32, StbMember> {
+fn extract_stb_braces(stb_braces_node: roxmltree::Node) -> Result<HashMap<u32, StbMember>, String> {
     let mut stb_brace_map: HashMap<u32, StbMember> = HashMap::new();
 
     for node in stb_braces_node.children().filter(|n| n.is_element()) {
-        let id = parse_attribute("id", node).unwrap();
+        let id = parse_attribute("id", node)?;
         stb_brace_map.insert(
             id,
             StbMember::StbBrace {
                 id,
-                name: node.attribute("name").unwrap().to_string(),
-                id_node_start: parse_attribute("idNode_start", node).unwrap(),
-                id_node_end: parse_attribute("idNode_end", node).unwrap(),
-                rotate: parse_attribute("rotate", node).unwrap(),
-                id_section: parse_attribute("id_section", node).unwrap(),
-                kind_structure: parse_enum_attribute("kind_structure", node).unwrap(),
-                offset_start_x: parse_attribute("offset_start_X", node).unwrap(),
-                offset_start_y: parse_attribute("offset_start_Y", node).unwrap(),
-                offset_start_z: parse_attribute("offset_start_Z", node).unwrap(),
-                offset_end_x: parse_attribute("offset_end_X", node).unwrap(),
-                offset_end_y: parse_attribute("offset_end_Y", node).unwrap(),
-                offset_end_z: parse_attribute("offset_end_Z", node).unwrap(),
-                condition_start: parse_enum_attribute("condition_start", node).unwrap(),
-                condition_end: parse_enum_attribute("condition_end", node).unwrap(),
+                name: parse_attribute("name", node)?,
+                id_node_start: parse_attribute("idNode_start", node)?,
+                id_node_end: parse_attribute("idNode_end", node)?,
+                rotate: parse_attribute("rotate", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                offset_start_x: parse_attribute("offset_start_X", node)?,
+                offset_start_y: parse_attribute("offset_start_Y", node)?,
+                offset_start_z: parse_attribute("offset_start_Z", node)?,
+                offset_end_x: parse_attribute("offset_end_X", node)?,
+                offset_end_y: parse_attribute("offset_end_Y", node)?,
+                offset_end_z: parse_attribute("offset_end_Z", node)?,
+                condition_start: parse_enum_attribute("condition_start", node)?,
+                condition_end: parse_enum_attribute("condition_end", node)?,
             },
         );
     }
 
-    stb_brace_map
+    Ok(stb_brace_map)
 }
 
-fn extract_stb_slabs(stb_slabs_node: roxmltree::Node) -> HashMap<u32, StbMember> {
+fn extract_stb_slabs(stb_slabs_node: roxmltree::Node) -> Result<HashMap<u32, StbMember>, String> {
     let mut stb_slab_map: HashMap<u32, StbMember> = HashMap::new();
 
     for node in stb_slabs_node.children().filter(|n| n.is_element()) {
-        let id = parse_attribute("id", node).unwrap();
+        let id = parse_attribute("id", node)?;
         stb_slab_map.insert(
             id,
             StbMember::StbSlab {
                 id,
-                name: node.attribute("name").unwrap().to_string(),
-                id_section: parse_attribute("id_section", node).unwrap(),
-                kind_structure: parse_enum_attribute("kind_structure", node).unwrap(),
-                kind_slab: parse_enum_attribute("kind_slab", node).unwrap(),
-                level: parse_attribute("level", node).unwrap(),
-                is_foundation: parse_attribute("isFoundation", node).unwrap(),
+                name: parse_attribute("name", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                kind_slab: parse_enum_attribute("kind_slab", node)?,
+                level: parse_attribute("level", node)?,
+                is_foundation: parse_attribute("isFoundation", node)?,
+                stb_node_id_list: extract_stb_node_id_list(node)?,
+            },
+        );
+    }
+
+    Ok(stb_slab_map)
+}
+
+fn extract_stb_walls(stb_walls_node: roxmltree::Node) -> Result<HashMap<u32, StbMember>, String> {
+    let mut stb_wall_map: HashMap<u32, StbMember> = HashMap::new();
+
+    for node in stb_walls_node.children().filter(|n| n.is_element()) {
+        let id = parse_attribute("id", node)?;
+        stb_wall_map.insert(
+            id,
+            StbMember::StbWall {
+                id,
+                name: parse_attribute("name", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                kind_structure: parse_enum_attribute("kind_structure", node)?,
+                stb_node_id_list: extract_stb_node_id_list(node)?,
+            },
+        );
+    }
+
+    Ok(stb_wall_map)
+}
+
+fn extract_stb_opens(stb_opens_node: roxmltree::Node) -> Result<HashMap<u32, StbMember>, String> {
+    let mut stb_open_map: HashMap<u32, StbMember> = HashMap::new();
+
+    for node in stb_opens_node.children().filter(|n| n.is_element()) {
+        let id = parse_attribute("id", node)?;
+        stb_open_map.insert(
+            id,
+            StbMember::StbOpen {
+                id,
+                name: parse_attribute("name", node)?,
+                id_section: parse_attribute("id_section", node)?,
+                id_member: parse_attribute("id_member", node)?,
+                kind_member: parse_enum_attribute("kind_member", node)?,
+                position_x: parse_attribute("position_X", node)?,
+                position_y: parse_attribute("position_Y", node)?,
+                length_x: parse_attribute("length_X", node)?,
+                length_y: parse_attribute("length_Y", node)?,
+                rotate: parse_attribute("rotate", node)?,
             },
         );
     }
 
-    stb_slab_map
+    Ok(stb_open_map)
+}
+
+fn extract_stb_node_id_list(node: roxmltree::Node) -> Result<StbNodeIdList, String> {
+    let stb_node_id_list_node = child_node("StbNodeid_List", node)?;
+
+    let mut children = Vec::new();
+
+    for child in stb_node_id_list_node.children().filter(|n| n.is_element()) {
+        let id = parse_attribute("id", child)?;
+        children.push(StbNodeId { id });
+    }
+
+    Ok(StbNodeIdList { children })
 }
 
-fn extract_stb_sections(stb_model_node: roxmltree::Node) -> StbSections {
-    let stb_sections_node = extract_node("StbSections", stb_model_node).unwrap();
+fn extract_stb_sections(stb_model_node: roxmltree::Node) -> Result<StbSections, String> {
+    let stb_sections_node = child_node("StbSections", stb_model_node)?;
 
     let mut stb_sections = StbSections::new();
 
@@ -410,16 +496,21 @@ fn extract_stb_sections(stb_model_node: roxmltree::Node) -> StbSections {
         let tag_name = node.tag_name().name();
 
         if tag_name == "StbSecSteel" {
-            stb_sections.stb_sec_steel = extract_stb_sec_steel(node);
+            stb_sections.stb_sec_steel = extract_stb_sec_steel(node)?;
             continue;
         }
 
         assert_ne!(tag_name, "StbSecSteel");
 
         match tag_name {
-            "StbSecColumn_RC" => {}
+            "StbSecColumn_RC" => {
+                let stb_section = extract_stb_sec_column_rc(node)?;
+                stb_sections
+                    .column_rc_map
+                    .insert(stb_section.id(), stb_section);
+            }
             "StbSecColumn_S" => {
-                let stb_section = extract_stb_sec_column_s(node);
+                let stb_section = extract_stb_sec_column_s(node)?;
                 stb_sections
                     .column_s_map
                     .insert(stb_section.id(), stb_section);
@@ -427,33 +518,38 @@ fn extract_stb_sections(stb_model_node: roxmltree::Node) -> StbSections {
             "StbSecColumn_SRC" => {}
             "StbSecColumn_CFT" => {}
             "StbSecBeam_RC" => {
-                let stb_section = extract_stb_sec_beam_rc(node);
+                let stb_section = extract_stb_sec_beam_rc(node)?;
                 stb_sections
                     .beam_rc_map
                     .insert(stb_section.id(), stb_section);
             }
             "StbSecBeam_S" => {
-                let stb_section = extract_stb_sec_beam_s(node);
+                let stb_section = extract_stb_sec_beam_s(node)?;
                 stb_sections
                     .beam_s_map
                     .insert(stb_section.id(), stb_section);
             }
             "StbSecBeam_SRC" => {}
             "StbSecBrace_S" => {
-                let stb_section = extract_stb_sec_brace_s(node);
+                let stb_section = extract_stb_sec_brace_s(node)?;
                 stb_sections
                     .brace_s_map
                     .insert(stb_section.id(), stb_section);
             }
             "StbSecSlab_RC" => {
-                let stb_section = extract_stb_sec_slab_rc(node);
+                let stb_section = extract_stb_sec_slab_rc(node)?;
                 stb_sections
                     .slab_rc_map
                     .insert(stb_section.id(), stb_section);
             }
             "StbSecSlabDeck" => {}
             "StbSecSlabPrecast" => {}
-            "StbSecWall_RC" => {}
+            "StbSecWall_RC" => {
+                let stb_section = extract_stb_sec_wall_rc(node)?;
+                stb_sections
+                    .wall_rc_map
+                    .insert(stb_section.id(), stb_section);
+            }
             "StbSecFoundation_RC" => {}
             "StbSecPile_RC" => {}
             "StbSecPile_S" => {}
@@ -465,215 +561,288 @@ fn extract_stb_sections(stb_model_node: roxmltree::Node) -> StbSections {
         };
     }
 
-    stb_sections
-}
-
-fn extract_stb_sec_column_s(node: roxmltree::Node) -> StbSection {
-    let stb_sec_steel_column_node = extract_node("StbSecSteelColumn", node).unwrap();
-
-    StbSection::StbSecColumnS {
-        id: parse_attribute("id", node).unwrap(),
-        name: parse_attribute("name", node).unwrap(),
-        floor: parse_attribute("floor", node).unwrap(),
-        kind_column: parse_enum_attribute("kind_column", node).unwrap(),
-        direction: parse_attribute("direction", node).unwrap(),
-        base_type: parse_enum_attribute("base_type", node).unwrap(),
-        stb_sec_steel_column: StbSecSteelColumn {
-            pos: parse_enum_attribute("pos", stb_sec_steel_column_node).unwrap(),
-            shape: parse_enum_attribute("shape", stb_sec_steel_column_node).unwrap(),
-            strength_main: parse_attribute("strength_web", stb_sec_steel_column_node).unwrap(),
-            strength_web: parse_attribute("strength_web", stb_sec_steel_column_node).unwrap(),
-        },
-    }
+    Ok(stb_sections)
 }
 
-fn extract_stb_sec_beam_rc(node: roxmltree::Node) -> StbSection {
-    let stb_sec_figure_node = extract_node("StbSecFigure", node).unwrap();
-
-    let stb_sec_figure = extract_stb_sec_figure_beam(stb_sec_figure_node);
+fn extract_stb_sec_column_rc(node: roxmltree::Node) -> Result<StbSection, String> {
+    let stb_sec_figure_node = child_node("StbSecFigure", node)?;
 
-    let stb_sec_bar_arrangement_node = extract_node("StbSecBar_Arrangement", node).unwrap();
-
-    let stb_sec_bar_arrangement = extract_stb_sec_bar_arrangement(stb_sec_bar_arrangement_node);
-
-    StbSection::StbSecBeamRC {
-        id: parse_attribute("id", node).unwrap(),
-        name: parse_attribute("name", node).unwrap(),
-        floor: parse_attribute("name", node).unwrap(),
-        kind_beam: parse_enum_attribute("kind_beam", node).unwrap(),
-        is_foundation: parse_attribute("isFoundation", node).unwrap(),
-        is_canti: parse_attribute("isCanti", node).unwrap(),
-        d_reinforcement_main: parse_attribute("D_reinforcement_main", node).unwrap(),
-        d_stirrup: parse_attribute("D_stirrup", node).unwrap(),
-        d_reinforcement_web: parse_attribute("D_reinforcement_web", node).unwrap(),
-        d_bar_spacing: parse_attribute("D_bar_spacing", node).unwrap(),
-        strength_concrete: match node.attribute("strength_concrete") {
-            Some(s) => Some(s.to_string()),
-            None => None,
-        },
-        strength_reinforcement_main: parse_attribute("strength_reinforcement_main", node).unwrap(),
-        strength_reinforcement_2nd_main: match node.attribute("strength_reinforcement_2nd_main") {
-            Some(s) => Some(s.to_string()),
+    let stb_sec_figure = StbSecFigureColumn {
+        stb_sec_rect: match extract_node("StbSecRect", stb_sec_figure_node) {
+            Some(n) => Some(StbSecRect {
+                width_x: parse_attribute("width_X", n)?,
+                width_y: parse_attribute("width_Y", n)?,
+            }),
             None => None,
         },
-        strength_stirrup: parse_attribute("strength_stirrup", node).unwrap(),
-        strength_reinforcement_web: parse_attribute("strength_reinforcement_web", node).unwrap(),
-        strength_bar_spacing: parse_attribute("strength_bar_spacing", node).unwrap(),
-        depth_cover_left: match node.attribute("depth_cover_left") {
-            Some(s) => Some(s.parse().unwrap()),
+        stb_sec_circle: match extract_node("StbSecCircle", stb_sec_figure_node) {
+            Some(n) => Some(StbSecCircle {
+                d: parse_attribute("D", n)?,
+            }),
             None => None,
         },
-        depth_cover_right: match node.attribute("depth_cover_right") {
-            Some(s) => Some(s.parse().unwrap()),
+    };
+
+    let stb_sec_bar_arrangement_node = child_node("StbSecBar_Arrangement", node)?;
+
+    let circle_same_node = extract_node("StbSecCircle_Column_Same", stb_sec_bar_arrangement_node)
+        .or_else(|| extract_node("StbSecCircle_Same", stb_sec_bar_arrangement_node));
+
+    let stb_sec_bar_arrangement = StbSecBarArrangementColumn {
+        stb_sec_rect_column_same: match extract_node(
+            "StbSecRect_Column_Same",
+            stb_sec_bar_arrangement_node,
+        ) {
+            Some(n) => Some(StbSecRectColumnSame {
+                count_main_x_1st: parse_attribute("count_main_X_1st", n)?,
+                count_main_y_1st: parse_attribute("count_main_Y_1st", n)?,
+                count_main_x_2nd: parse_count_attribute("count_main_X_2nd", n)?,
+                count_main_y_2nd: parse_count_attribute("count_main_Y_2nd", n)?,
+                count_main_total: parse_attribute("count_main_total", n)?,
+                count_band_dir_x: parse_attribute("count_band_dir_X", n)?,
+                count_band_dir_y: parse_attribute("count_band_dir_Y", n)?,
+                pitch_band: parse_attribute("pitch_band", n)?,
+            }),
             None => None,
         },
-        depth_cover_top: match node.attribute("depth_cover_top") {
-            Some(s) => Some(s.parse().unwrap()),
+        stb_sec_circle_column_same: match circle_same_node {
+            Some(n) => Some(StbSecCircleColumnSame {
+                count_main: parse_attribute("count_main", n)?,
+                count_band: parse_attribute("count_band", n)?,
+                pitch_band: parse_attribute("pitch_band", n)?,
+            }),
             None => None,
         },
-        depth_cover_bottom: match node.attribute("depth_cover_bottom") {
-            Some(s) => Some(s.parse().unwrap()),
-            None => None,
+    };
+
+    Ok(StbSection::StbSecColumnRC {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        floor: parse_attribute("floor", node)?,
+        kind_column: parse_enum_attribute("kind_column", node)?,
+        d_reinforcement_main: parse_attribute("D_reinforcement_main", node)?,
+        d_rein_band: parse_attribute("D_rein_band", node)?,
+        strength_concrete: parse_optional_attribute("strength_concrete", node)?,
+        strength_reinforcement_main: parse_attribute("strength_reinforcement_main", node)?,
+        strength_rein_band: parse_attribute("strength_rein_band", node)?,
+        depth_cover_start_x: parse_optional_attribute("depth_cover_start_X", node)?,
+        depth_cover_end_x: parse_optional_attribute("depth_cover_end_X", node)?,
+        depth_cover_start_y: parse_optional_attribute("depth_cover_start_Y", node)?,
+        depth_cover_end_y: parse_optional_attribute("depth_cover_end_Y", node)?,
+        stb_sec_figure,
+        stb_sec_bar_arrangement,
+    })
+}
+
+fn extract_stb_sec_column_s(node: roxmltree::Node) -> Result<StbSection, String> {
+    let stb_sec_steel_column_node = child_node("StbSecSteelColumn", node)?;
+
+    Ok(StbSection::StbSecColumnS {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        floor: parse_attribute("floor", node)?,
+        kind_column: parse_enum_attribute("kind_column", node)?,
+        direction: parse_attribute("direction", node)?,
+        base_type: parse_enum_attribute("base_type", node)?,
+        stb_sec_steel_column: StbSecSteelColumn {
+            pos: parse_enum_attribute("pos", stb_sec_steel_column_node)?,
+            shape: parse_attribute("shape", stb_sec_steel_column_node)?,
+            strength_main: parse_attribute("strength_main", stb_sec_steel_column_node)?,
+            strength_web: parse_attribute("strength_web", stb_sec_steel_column_node)?,
         },
+    })
+}
+
+fn extract_stb_sec_beam_rc(node: roxmltree::Node) -> Result<StbSection, String> {
+    let stb_sec_figure_node = child_node("StbSecFigure", node)?;
+
+    let stb_sec_figure = extract_stb_sec_figure_beam(stb_sec_figure_node)?;
+
+    let stb_sec_bar_arrangement_node = child_node("StbSecBar_Arrangement", node)?;
+
+    let stb_sec_bar_arrangement = extract_stb_sec_bar_arrangement(stb_sec_bar_arrangement_node)?;
+
+    Ok(StbSection::StbSecBeamRC {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        floor: parse_attribute("floor", node)?,
+        kind_beam: parse_enum_attribute("kind_beam", node)?,
+        is_foundation: parse_attribute("isFoundation", node)?,
+        is_canti: parse_attribute("isCanti", node)?,
+        d_reinforcement_main: parse_attribute("D_reinforcement_main", node)?,
+        d_stirrup: parse_attribute("D_stirrup", node)?,
+        d_reinforcement_web: parse_attribute("D_reinforcement_web", node)?,
+        d_bar_spacing: parse_attribute("D_bar_spacing", node)?,
+        strength_concrete: parse_optional_attribute("strength_concrete", node)?,
+        strength_reinforcement_main: parse_attribute("strength_reinforcement_main", node)?,
+        strength_reinforcement_2nd_main: parse_optional_attribute(
+            "strength_reinforcement_2nd_main",
+            node,
+        )?,
+        strength_stirrup: parse_attribute("strength_stirrup", node)?,
+        strength_reinforcement_web: parse_attribute("strength_reinforcement_web", node)?,
+        strength_bar_spacing: parse_attribute("strength_bar_spacing", node)?,
+        depth_cover_left: parse_optional_attribute("depth_cover_left", node)?,
+        depth_cover_right: parse_optional_attribute("depth_cover_right", node)?,
+        depth_cover_top: parse_optional_attribute("depth_cover_top", node)?,
+        depth_cover_bottom: parse_optional_attribute("depth_cover_bottom", node)?,
         stb_sec_figure,
         stb_sec_bar_arrangement,
-    }
+    })
 }
 
-fn extract_stb_sec_bar_arrangement(node: roxmltree::Node) -> StbSecBarArrangementBeam {
-    let stb_sec_beam_start_center_end_section_list = match extract_node("", node) {
-        Some(_) => {
-            let mut list = Vec::new();
-            for children in node.children().filter(|n| n.is_element()) {
-                list.push(extract_stb_sec_beam_start_center_end_section(children));
+fn extract_stb_sec_bar_arrangement(
+    node: roxmltree::Node,
+) -> Result<StbSecBarArrangementBeam, String> {
+    let stb_sec_beam_start_center_end_section_list =
+        match extract_node("StbSecBeam_Start_Center_End_Section", node) {
+            Some(_) => {
+                let mut list = Vec::new();
+                for children in node
+                    .children()
+                    .filter(|n| n.tag_name().name() == "StbSecBeam_Start_Center_End_Section")
+                {
+                    list.push(extract_stb_sec_beam_start_center_end_section(children)?);
+                }
+                Some(list)
             }
-            Some(list)
-        }
-        None => None,
-    };
+            None => None,
+        };
 
-    let stb_sec_beam_same_section = match extract_node("", node) {
-        Some(n) => Some(extract_stb_sec_beam_same_section(n)),
+    let stb_sec_beam_same_section = match extract_node("StbSecBeam_Same_Section", node) {
+        Some(n) => Some(extract_stb_sec_beam_same_section(n)?),
         None => None,
     };
 
-    StbSecBarArrangementBeam {
+    Ok(StbSecBarArrangementBeam {
         stb_sec_beam_start_center_end_section_list,
         stb_sec_beam_same_section,
-    }
+    })
 }
 
 fn extract_stb_sec_beam_start_center_end_section(
     node: roxmltree::Node,
-) -> StbSecBeamStartCenterEndSection {
-    StbSecBeamStartCenterEndSection {
-        pos: parse_enum_attribute("pos", node).unwrap(),
-        count_main_top_1st: parse_attribute("count_main_top_1st", node).unwrap(),
-        count_main_bottom_1st: parse_attribute("count_main_bottom_1st", node).unwrap(),
-        count_stirrup: parse_attribute("count_stirrup", node).unwrap(),
-        pitch_stirrup: parse_attribute("pitch_stirrup", node).unwrap(),
-        count_web: parse_attribute("count_web", node).unwrap(),
-        count_bar_spacing: parse_attribute("count_bar_spacing", node).unwrap(),
-        pitch_bar_spacing: parse_attribute("pitch_bar_spacing", node).unwrap(),
-    }
+) -> Result<StbSecBeamStartCenterEndSection, String> {
+    Ok(StbSecBeamStartCenterEndSection {
+        pos: parse_enum_attribute("pos", node)?,
+        count_main_top_1st: parse_attribute("count_main_top_1st", node)?,
+        count_main_bottom_1st: parse_attribute("count_main_bottom_1st", node)?,
+        count_main_top_2nd: parse_count_attribute("count_main_top_2nd", node)?,
+        count_main_bottom_2nd: parse_count_attribute("count_main_bottom_2nd", node)?,
+        count_stirrup: parse_attribute("count_stirrup", node)?,
+        pitch_stirrup: parse_attribute("pitch_stirrup", node)?,
+        count_web: parse_attribute("count_web", node)?,
+        count_bar_spacing: parse_attribute("count_bar_spacing", node)?,
+        pitch_bar_spacing: parse_attribute("pitch_bar_spacing", node)?,
+    })
 }
 
-fn extract_stb_sec_beam_same_section(node: roxmltree::Node) -> StbSecBeamSameSection {
-    StbSecBeamSameSection {
-        count_main_top_1st: parse_attribute("count_main_top_1st", node).unwrap(),
-        count_main_bottom_1st: parse_attribute("count_main_bottom_1st", node).unwrap(),
-        count_stirrup: parse_attribute("count_stirrup", node).unwrap(),
-        pitch_stirrup: parse_attribute("pitch_stirrup", node).unwrap(),
-        count_web: parse_attribute("count_web", node).unwrap(),
-        count_bar_spacing: parse_attribute("count_bar_spacing", node).unwrap(),
-        pitch_bar_spacing: parse_attribute("pitch_bar_spacing", node).unwrap(),
-    }
+fn extract_stb_sec_beam_same_section(
+    node: roxmltree::Node,
+) -> Result<StbSecBeamSameSection, String> {
+    Ok(StbSecBeamSameSection {
+        count_main_top_1st: parse_attribute("count_main_top_1st", node)?,
+        count_main_bottom_1st: parse_attribute("count_main_bottom_1st", node)?,
+        count_main_top_2nd: parse_count_attribute("count_main_top_2nd", node)?,
+        count_main_bottom_2nd: parse_count_attribute("count_main_bottom_2nd", node)?,
+        count_stirrup: parse_attribute("count_stirrup", node)?,
+        pitch_stirrup: parse_attribute("pitch_stirrup", node)?,
+        count_web: parse_attribute("count_web", node)?,
+        count_bar_spacing: parse_attribute("count_bar_spacing", node)?,
+        pitch_bar_spacing: parse_attribute("pitch_bar_spacing", node)?,
+    })
 }
 
-fn extract_stb_sec_figure_beam(node: roxmltree::Node) -> StbSecFigureBeam {
+fn extract_stb_sec_figure_beam(node: roxmltree::Node) -> Result<StbSecFigureBeam, String> {
     let stb_sec_haunch = match extract_node("StbSecHaunch", node) {
-        Some(n) => Some(extract_stb_sec_haunch(n)),
+        Some(n) => Some(extract_stb_sec_haunch(n)?),
         None => None,
     };
 
     let stb_sec_straight = match extract_node("StbSecStraight", node) {
-        Some(n) => Some(extract_stb_sec_straight(n)),
+        Some(n) => Some(extract_stb_sec_straight(n)?),
         None => None,
     };
 
-    StbSecFigureBeam {
+    Ok(StbSecFigureBeam {
         stb_sec_haunch,
         stb_sec_straight,
-    }
+    })
 }
 
-fn extract_stb_sec_haunch(node: roxmltree::Node) -> StbSecHaunch {
-    StbSecHaunch {
-        width_start: parse_attribute("width_start", node).unwrap(),
-        depth_start: parse_attribute("depth_start", node).unwrap(),
-        width_center: parse_attribute("width_center", node).unwrap(),
-        depth_center: parse_attribute("depth_center", node).unwrap(),
-        width_end: parse_attribute("width_end", node).unwrap(),
-        depth_end: parse_attribute("depth_end", node).unwrap(),
-    }
+fn extract_stb_sec_haunch(node: roxmltree::Node) -> Result<StbSecHaunch, String> {
+    Ok(StbSecHaunch {
+        width_start: parse_attribute("width_start", node)?,
+        depth_start: parse_attribute("depth_start", node)?,
+        width_center: parse_attribute("width_center", node)?,
+        depth_center: parse_attribute("depth_center", node)?,
+        width_end: parse_attribute("width_end", node)?,
+        depth_end: parse_attribute("depth_end", node)?,
+    })
 }
 
-fn extract_stb_sec_straight(node: roxmltree::Node) -> StbSecStraightBeam {
-    StbSecStraightBeam {
-        depth: parse_attribute("depth", node).unwrap(),
-    }
+fn extract_stb_sec_straight(node: roxmltree::Node) -> Result<StbSecStraightBeam, String> {
+    Ok(StbSecStraightBeam {
+        width: parse_attribute("width", node)?,
+        depth: parse_attribute("depth", node)?,
+    })
 }
 
-fn extract_stb_sec_beam_s(node: roxmltree::Node) -> StbSection {
-    let stb_sec_steel_beam_node = extract_node("StbSecSteelBeam", node).unwrap();
-    let stb_sec_steel_beam = extract_stb_sec_steel_beam(stb_sec_steel_beam_node);
-
-    StbSection::StbSecBeamS {
-        id: parse_attribute("id", node).unwrap(),
-        name: parse_attribute("name", node).unwrap(),
-        floor: parse_attribute("floor", node).unwrap(),
-        kind_beam: parse_enum_attribute("kind_beam", node).unwrap(),
-        is_canti: parse_attribute("isCanti", node).unwrap(),
-        stb_sec_steel_beam,
-    }
+fn extract_stb_sec_beam_s(node: roxmltree::Node) -> Result<StbSection, String> {
+    // One shape throughout, or one for each position of a haunched beam.
+    child_node("StbSecSteelBeam", node)?;
+    let stb_sec_steel_beam_list = node
+        .children()
+        .filter(|n| n.has_tag_name("StbSecSteelBeam"))
+        .map(extract_stb_sec_steel_beam)
+        .collect::<Result<Vec<StbSecSteelBeam>, String>>()?;
+
+    Ok(StbSection::StbSecBeamS {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        floor: parse_attribute("floor", node)?,
+        kind_beam: parse_enum_attribute("kind_beam", node)?,
+        is_canti: parse_attribute("isCanti", node)?,
+        stb_sec_steel_beam_list,
+    })
 }
 
-fn extract_stb_sec_steel_beam(node: roxmltree::Node) -> StbSecSteelBeam {
-    StbSecSteelBeam {
-        pos: parse_enum_attribute("pos", node).unwrap(),
-        shape: parse_attribute("shape", node).unwrap(),
-        strength_main: parse_attribute("strength_main", node).unwrap(),
-        strength_web: parse_attribute("strength_web", node).unwrap(),
-    }
+fn extract_stb_sec_steel_beam(node: roxmltree::Node) -> Result<StbSecSteelBeam, String> {
+    Ok(StbSecSteelBeam {
+        pos: parse_enum_attribute("pos", node)?,
+        shape: parse_attribute("shape", node)?,
+        strength_main: parse_attribute("strength_main", node)?,
+        strength_web: parse_attribute("strength_web", node)?,
+    })
 }
 
-fn extract_stb_sec_brace_s(node: roxmltree::Node) -> StbSection {
-    let stb_sec_steel_brace_node = extract_node("StbSecSteelBrace", node).unwrap();
+fn extract_stb_sec_brace_s(node: roxmltree::Node) -> Result<StbSection, String> {
+    let stb_sec_steel_brace_node = child_node("StbSecSteelBrace", node)?;
     let stb_sec_steel_brace = StbSecSteelBrace {
-        pos: parse_enum_attribute("pos", stb_sec_steel_brace_node).unwrap(),
-        shape: parse_attribute("shape", stb_sec_steel_brace_node).unwrap(),
-        strength_main: parse_attribute("strength_main", stb_sec_steel_brace_node).unwrap(),
-        strength_web: parse_attribute("strength_web", stb_sec_steel_brace_node).unwrap(),
+        pos: parse_enum_attribute("pos", stb_sec_steel_brace_node)?,
+        shape: parse_attribute("shape", stb_sec_steel_brace_node)?,
+        strength_main: parse_attribute("strength_main", stb_sec_steel_brace_node)?,
+        strength_web: parse_attribute("strength_web", stb_sec_steel_brace_node)?,
     };
 
-    StbSection::StbSecBraceS {
-        id: parse_attribute("id", node).unwrap(),
-        name: parse_attribute("name", node).unwrap(),
-        floor: parse_attribute("floor", node).unwrap(),
-        kind_brace: parse_enum_attribute("kind_brace", node).unwrap(),
+    Ok(StbSection::StbSecBraceS {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        floor: parse_attribute("floor", node)?,
+        kind_brace: parse_enum_attribute("kind_brace", node)?,
         stb_sec_steel_brace,
-    }
+    })
 }
 
-fn extract_stb_sec_slab_rc(node: roxmltree::Node) -> StbSection {
-    let stb_sec_figure_node = extract_node("StbSecFigure", node).unwrap();
-    let stb_sec_straight_node = extract_node("StbSecStraight", stb_sec_figure_node).unwrap();
+fn extract_stb_sec_slab_rc(node: roxmltree::Node) -> Result<StbSection, String> {
+    let stb_sec_figure_node = child_node("StbSecFigure", node)?;
+    let stb_sec_straight_node = child_node("StbSecStraight", stb_sec_figure_node)?;
     let stb_sec_straight = StbSecStraightSlab {
-        depth: parse_attribute("depth", stb_sec_straight_node).unwrap(),
+        depth: parse_attribute("depth", stb_sec_straight_node)?,
     };
     let stb_sec_figure = StbSecFigureSlab { stb_sec_straight };
 
-    let stb_sec_bar_arrangement_node = extract_node("StbSecBar_Arrangement", node).unwrap();
+    let stb_sec_bar_arrangement_node = child_node("StbSecBar_Arrangement", node)?;
     let mut stb_sec_1way_slab_1_list = Vec::new();
 
     for children in stb_sec_bar_arrangement_node
@@ -681,10 +850,10 @@ fn extract_stb_sec_slab_rc(node: roxmltree::Node) -> StbSection {
         .filter(|n| n.is_element())
     {
         stb_sec_1way_slab_1_list.push(StbSec1WaySlab1 {
-            pos: parse_enum_attribute("pos", children).unwrap(),
-            strength: parse_attribute("strength", children).unwrap(),
-            d: parse_attribute("D", children).unwrap(),
-            pitch: parse_attribute("pitch", children).unwrap(),
+            pos: parse_enum_attribute("pos", children)?,
+            strength: parse_attribute("strength", children)?,
+            d: parse_attribute("D", children)?,
+            pitch: parse_attribute("pitch", children)?,
         });
     }
 
@@ -692,32 +861,48 @@ fn extract_stb_sec_slab_rc(node: roxmltree::Node) -> StbSection {
         stb_sec_1way_slab_1_list,
     };
 
-    StbSection::StbSecSlabRC {
-        id: parse_attribute("id", node).unwrap(),
-        name: parse_attribute("name", node).unwrap(),
-        is_foundation: parse_attribute("isFoundation", node).unwrap(),
-        is_canti: parse_attribute("isCanti", node).unwrap(),
-        strength_concrete: parse_attribute("strength_concrete", node).unwrap(),
+    Ok(StbSection::StbSecSlabRC {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        is_foundation: parse_attribute("isFoundation", node)?,
+        is_canti: parse_attribute("isCanti", node)?,
+        strength_concrete: parse_attribute("strength_concrete", node)?,
         stb_sec_figure,
         stb_sec_bar_arrangement,
-    }
+    })
+}
+
+fn extract_stb_sec_wall_rc(node: roxmltree::Node) -> Result<StbSection, String> {
+    let stb_sec_figure_node = child_node("StbSecFigure", node)?;
+    let stb_sec_straight_node = child_node("StbSecStraight", stb_sec_figure_node)?;
+    let stb_sec_straight = StbSecStraightWall {
+        t: parse_attribute("t", stb_sec_straight_node)?,
+    };
+    let stb_sec_figure = StbSecFigureWall { stb_sec_straight };
+
+    Ok(StbSection::StbSecWallRC {
+        id: parse_attribute("id", node)?,
+        name: parse_attribute("name", node)?,
+        strength_concrete: parse_attribute("strength_concrete", node)?,
+        stb_sec_figure,
+    })
 }
 
-fn extract_stb_sec_steel(stb_sec_steel_node: roxmltree::Node) -> StbSecSteel {
+fn extract_stb_sec_steel(stb_sec_steel_node: roxmltree::Node) -> Result<StbSecSteel, String> {
     let mut stb_sec_steel = StbSecSteel::new();
 
     for node in stb_sec_steel_node.children().filter(|n| n.is_element()) {
         let tag_name = node.tag_name().name();
 
         let stb_sec_steel_children = match tag_name {
-            "StbSecRoll-H" => Some(extract_stb_sec_roll_h(node)),
-            "StbSecBuild-H" => Some(extract_stb_sec_build_h(node)),
-            "StbSecRoll-BOX" => Some(extract_stb_sec_roll_box(node)),
-            "StbSecBuild-BOX" => Some(extract_stb_sec_build_box(node)),
-            "StbSecPipe" => Some(extract_stb_sec_pipe(node)),
+            "StbSecRoll-H" => Some(extract_stb_sec_roll_h(node)?),
+            "StbSecBuild-H" => Some(extract_stb_sec_build_h(node)?),
+            "StbSecRoll-BOX" => Some(extract_stb_sec_roll_box(node)?),
+            "StbSecBuild-BOX" => Some(extract_stb_sec_build_box(node)?),
+            "StbSecPipe" => Some(extract_stb_sec_pipe(node)?),
             "StbSecRoll-T" => None,
             "StbSecRoll-C" => None,
-            "StbSecRoll-L" => Some(extract_stb_sec_roll_l(node)),
+            "StbSecRoll-L" => Some(extract_stb_sec_roll_l(node)?),
             "StbSecLipC" => None,
             "StbSecFlatBar" => None,
             "StbSecRoundBar" => None,
@@ -726,104 +911,152 @@ fn extract_stb_sec_steel(stb_sec_steel_node: roxmltree::Node) -> StbSecSteel {
             _ => None,
         };
 
-        let stb_sec_steel_children = stb_sec_steel_children
-            .unwrap_or_else(|| panic!("Tag name {} is unimplemented.", tag_name));
+        let stb_sec_steel_children = match stb_sec_steel_children {
+            Some(stb_sec_steel_children) => stb_sec_steel_children,
+            None => return Err(element_error(node, "steel shape not supported")),
+        };
 
         stb_sec_steel
             .children_map
             .insert(stb_sec_steel_children.name(), stb_sec_steel_children);
     }
 
-    stb_sec_steel
+    Ok(stb_sec_steel)
 }
 
-fn extract_stb_sec_roll_h(node: roxmltree::Node) -> StbSecSteelChildren {
-    StbSecSteelChildren::StbSecRollH {
-        name: parse_attribute("name", node).unwrap(),
-        sec_type: parse_enum_attribute("type", node).unwrap(),
-        a: parse_attribute("A", node).unwrap(),
-        b: parse_attribute("B", node).unwrap(),
-        t1: parse_attribute("t1", node).unwrap(),
-        t2: parse_attribute("t2", node).unwrap(),
-        r: parse_attribute("r", node).unwrap(),
-    }
+fn extract_stb_sec_roll_h(node: roxmltree::Node) -> Result<StbSecSteelChildren, String> {
+    Ok(StbSecSteelChildren::StbSecRollH {
+        name: parse_attribute("name", node)?,
+        sec_type: parse_enum_attribute("type", node)?,
+        a: parse_attribute("A", node)?,
+        b: parse_attribute("B", node)?,
+        t1: parse_attribute("t1", node)?,
+        t2: parse_attribute("t2", node)?,
+        r: parse_attribute("r", node)?,
+    })
 }
 
-fn extract_stb_sec_build_h(node: roxmltree::Node) -> StbSecSteelChildren {
-    StbSecSteelChildren::StbSecBuildH {
-        name: parse_attribute("name", node).unwrap(),
-        a: parse_attribute("A", node).unwrap(),
-        b: parse_attribute("B", node).unwrap(),
-        t1: parse_attribute("t1", node).unwrap(),
-        t2: parse_attribute("t2", node).unwrap(),
-    }
+fn extract_stb_sec_build_h(node: roxmltree::Node) -> Result<StbSecSteelChildren, String> {
+    Ok(StbSecSteelChildren::StbSecBuildH {
+        name: parse_attribute("name", node)?,
+        a: parse_attribute("A", node)?,
+        b: parse_attribute("B", node)?,
+        t1: parse_attribute("t1", node)?,
+        t2: parse_attribute("t2", node)?,
+    })
 }
 
-fn extract_stb_sec_roll_box(node: roxmltree::Node) -> StbSecSteelChildren {
-    StbSecSteelChildren::StbSecRollBox {
-        name: parse_attribute("name", node).unwrap(),
-        sec_type: parse_enum_attribute("type", node).unwrap(),
-        a: parse_attribute("A", node).unwrap(),
-        b: parse_attribute("B", node).unwrap(),
-        t: parse_attribute("t", node).unwrap(),
-        r: parse_attribute("R", node).unwrap(),
-    }
+fn extract_stb_sec_roll_box(node: roxmltree::Node) -> Result<StbSecSteelChildren, String> {
+    Ok(StbSecSteelChildren::StbSecRollBox {
+        name: parse_attribute("name", node)?,
+        sec_type: parse_enum_attribute("type", node)?,
+        a: parse_attribute("A", node)?,
+        b: parse_attribute("B", node)?,
+        t: parse_attribute("t", node)?,
+        r: parse_attribute("R", node)?,
+    })
 }
 
-fn extract_stb_sec_build_box(node: roxmltree::Node) -> StbSecSteelChildren {
-    StbSecSteelChildren::StbSecBuildBox {
-        name: parse_attribute("name", node).unwrap(),
-        a: parse_attribute("A", node).unwrap(),
-        b: parse_attribute("B", node).unwrap(),
-        t1: parse_attribute("t1", node).unwrap(),
-        t2: parse_attribute("t2", node).unwrap(),
-    }
+fn extract_stb_sec_build_box(node: roxmltree::Node) -> Result<StbSecSteelChildren, String> {
+    Ok(StbSecSteelChildren::StbSecBuildBox {
+        name: parse_attribute("name", node)?,
+        a: parse_attribute("A", node)?,
+        b: parse_attribute("B", node)?,
+        t1: parse_attribute("t1", node)?,
+        t2: parse_attribute("t2", node)?,
+    })
 }
 
-fn extract_stb_sec_pipe(node: roxmltree::Node) -> StbSecSteelChildren {
-    StbSecSteelChildren::StbSecPipe {
-        name: parse_attribute("name", node).unwrap(),
-        d: parse_attribute("D", node).unwrap(),
-        t: parse_attribute("t", node).unwrap(),
-    }
+fn extract_stb_sec_pipe(node: roxmltree::Node) -> Result<StbSecSteelChildren, String> {
+    Ok(StbSecSteelChildren::StbSecPipe {
+        name: parse_attribute("name", node)?,
+        d: parse_attribute("D", node)?,
+        t: parse_attribute("t", node)?,
+    })
 }
 
-fn extract_stb_sec_roll_l(node: roxmltree::Node) -> StbSecSteelChildren {
-    StbSecSteelChildren::StbSecRollL {
-        name: parse_attribute("name", node).unwrap(),
-        sec_type: parse_enum_attribute("type", node).unwrap(),
-        a: parse_attribute("A", node).unwrap(),
-        b: parse_attribute("B", node).unwrap(),
-        t1: parse_attribute("t1", node).unwrap(),
-        t2: parse_attribute("t2", node).unwrap(),
-        r1: parse_attribute("r1", node).unwrap(),
-        r2: parse_attribute("r2", node).unwrap(),
-        side: parse_attribute("side", node).unwrap(),
-    }
+fn extract_stb_sec_roll_l(node: roxmltree::Node) -> Result<StbSecSteelChildren, String> {
+    Ok(StbSecSteelChildren::StbSecRollL {
+        name: parse_attribute("name", node)?,
+        sec_type: parse_enum_attribute("type", node)?,
+        a: parse_attribute("A", node)?,
+        b: parse_attribute("B", node)?,
+        t1: parse_attribute("t1", node)?,
+        t2: parse_attribute("t2", node)?,
+        r1: parse_attribute("r1", node)?,
+        r2: parse_attribute("r2", node)?,
+        side: parse_attribute("side", node)?,
+    })
 }
 
-pub fn extract_stb_extensions(root_node: roxmltree::Node) -> StbExtensions {
-    let stb_extensions_node = extract_node("StbExtensions", root_node).unwrap();
+pub fn extract_stb_extensions(root_node: roxmltree::Node) -> Result<StbExtensions, String> {
+    let stb_extensions_node = child_node("StbExtensions", root_node)?;
 
     let mut stb_extension_list = Vec::new();
 
     for node in stb_extensions_node.children().filter(|n| n.is_element()) {
         stb_extension_list.push(StbExtension {
-            identifier: parse_attribute("identifier", node).unwrap(),
-            description: parse_attribute("description", node).unwrap(),
+            identifier: parse_attribute("identifier", node)?,
+            description: parse_attribute("description", node)?,
         });
     }
 
-    StbExtensions { stb_extension_list }
+    Ok(StbExtensions { stb_extension_list })
 }
 
-fn parse_attribute<T: FromStr>(key: &str, node: roxmltree::Node) -> Result<T, <T as FromStr>::Err> {
-    node.attribute(key).unwrap().to_lowercase().parse::<T>()
+// The first child element with the given name, which the schema requires.
+fn child_node<'a>(
+    name: &str,
+    parent: roxmltree::Node<'a, '_>,
+) -> Result<roxmltree::Node<'a, 'a>, String> {
+    extract_node(name, parent).ok_or_else(|| element_error(parent, &format!("missing {}", name)))
 }
 
-fn parse_enum_attribute<T: FromStr>(
-    name: &str,
+fn element_error(node: roxmltree::Node, message: &str) -> String {
+    let position = node.document().text_pos_at(node.range().start);
+    format!(
+        "{} on line {}: {}",
+        node.tag_name().name(),
+        position.row,
+        message
+    )
+}
+
+fn parse_attribute<T: FromStr>(key: &str, node: roxmltree::Node) -> Result<T, String> {
+    match node.attribute(key) {
+        Some(value) => parse_value(key, value, node),
+        None => Err(element_error(node, &format!("missing attribute {}", key))),
+    }
+}
+
+fn parse_optional_attribute<T: FromStr>(
+    key: &str,
     node: roxmltree::Node,
-) -> Result<T, <T as FromStr>::Err> {
-    T::from_str(node.attribute(name).unwrap())
+) -> Result<Option<T>, String> {
+    match node.attribute(key) {
+        Some(value) => parse_value(key, value, node).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str, node: roxmltree::Node) -> Result<T, String> {
+    // Booleans are written as TRUE/FALSE, so retry lowercased instead of lowercasing names.
+    value
+        .parse::<T>()
+        .or_else(|_| value.to_lowercase().parse::<T>())
+        .map_err(|_| element_error(node, &format!("invalid {} \"{}\"", key, value)))
+}
+
+// Bar counts of optional layers are left out of the file when there are none.
+fn parse_count_attribute(key: &str, node: roxmltree::Node) -> Result<u32, String> {
+    Ok(parse_optional_attribute(key, node)?.unwrap_or(0))
+}
+
+// Girders and beams are rigidly connected unless a pinned end is given.
+fn parse_condition_attribute(key: &str, node: roxmltree::Node) -> Result<JointCondition, String> {
+    Ok(parse_optional_attribute(key, node)?.unwrap_or(JointCondition::Fix))
+}
+
+fn parse_enum_attribute<T: FromStr>(name: &str, node: roxmltree::Node) -> Result<T, String> {
+    parse_attribute(name, node)
 }
@@ -3,22 +3,494 @@
     windows_subsystem = "windows"
 )]
 
+use app::analysis::frame::{AnalysisResult, FrameModel, LoadCase};
+use app::analysis::load::{GeneratedLoads, LoadSettings};
+use app::connectivity::{ConnectivityGraph, DisconnectedPart};
+use app::diff::ModelDiff;
+use app::document::{DocumentInfo, DocumentStore};
+use app::export::MeshExportOptions;
+use app::geometry::Mesh;
+use app::import::ifc::IfcImport;
+use app::material::{MaterialDatabase, MaterialReport};
+use app::merge::{Conflict, Resolution};
+use app::node_merge::DuplicateNodeGroup;
+use app::section_property::{RcSectionProperty, SteelSectionProperty};
 use app::st_bridge::stb_model::stb_nodes::StbNode;
-use app::st_bridge::StBridge;
+use app::takeoff::concrete::ConcreteTakeoff;
+use app::takeoff::steel::SteelTakeoff;
+use app::validation::Issue;
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Serialize)]
+struct ImportedDocument {
+    handle: u32,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct MergedDocument {
+    handle: u32,
+    conflict_list: Vec<Conflict>,
+}
+
+#[tauri::command]
+fn open_st_bridge(documents: State<DocumentStore>, file_name: String) -> Result<u32, String> {
+    let st_bridge = app::read_st_bridge(&file_name)?;
+    Ok(documents.open(Some(file_name), st_bridge))
+}
+
+#[tauri::command]
+fn close_st_bridge(documents: State<DocumentStore>, handle: u32) -> Result<(), String> {
+    documents.close(handle)
+}
+
+#[tauri::command]
+fn open_documents(documents: State<DocumentStore>) -> Vec<DocumentInfo> {
+    documents.document_list()
+}
+
+#[tauri::command]
+fn members(
+    documents: State<DocumentStore>,
+    handle: u32,
+) -> Result<Vec<(StbNode, StbNode)>, String> {
+    documents.read(handle, |st_bridge| st_bridge.members())
+}
+
+#[tauri::command]
+fn slab_meshes(documents: State<DocumentStore>, handle: u32) -> Result<Vec<Mesh>, String> {
+    documents.read(handle, app::geometry::create_slab_mesh_list)
+}
+
+#[tauri::command]
+fn wall_meshes(documents: State<DocumentStore>, handle: u32) -> Result<Vec<Mesh>, String> {
+    documents.read(handle, app::geometry::create_wall_mesh_list)
+}
+
+#[tauri::command]
+fn export_glb(documents: State<DocumentStore>, handle: u32, file_name: &str) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::export::gltf::write_glb(st_bridge, file_name).map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn export_ifc(documents: State<DocumentStore>, handle: u32, file_name: &str) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::export::ifc::write_ifc(st_bridge, file_name).map_err(|e| e.to_string())
+    })?
+}
+
+fn mesh_export_options(scale: Option<f64>, story: Option<String>) -> MeshExportOptions {
+    MeshExportOptions {
+        scale: scale.unwrap_or(1.0),
+        story,
+    }
+}
+
+#[tauri::command]
+fn export_obj(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+    scale: Option<f64>,
+    story: Option<String>,
+) -> Result<(), String> {
+    let options = mesh_export_options(scale, story);
+    documents.read(handle, |st_bridge| {
+        app::export::obj::write_obj(st_bridge, file_name, &options).map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn export_stl(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+    scale: Option<f64>,
+    story: Option<String>,
+) -> Result<(), String> {
+    let options = mesh_export_options(scale, story);
+    documents.read(handle, |st_bridge| {
+        app::export::stl::write_stl(st_bridge, file_name, &options).map_err(|e| e.to_string())
+    })?
+}
+
+// The imported model is opened as a document without a file, since it is not ST-Bridge yet.
+#[tauri::command]
+fn import_ifc(
+    documents: State<DocumentStore>,
+    file_name: &str,
+) -> Result<ImportedDocument, String> {
+    let IfcImport {
+        st_bridge,
+        warnings,
+    } = app::import::ifc::read_ifc(file_name)?;
+    Ok(ImportedDocument {
+        handle: documents.open(None, st_bridge),
+        warnings,
+    })
+}
+
+#[tauri::command]
+fn steel_section_properties(
+    documents: State<DocumentStore>,
+    handle: u32,
+) -> Result<Vec<SteelSectionProperty>, String> {
+    documents.read(handle, app::section_property::steel_section_property_list)
+}
+
+#[tauri::command]
+fn rc_section_properties(
+    documents: State<DocumentStore>,
+    handle: u32,
+) -> Result<Vec<RcSectionProperty>, String> {
+    documents.read(handle, app::section_property::rc_section_property_list)
+}
+
+#[tauri::command]
+fn materials(
+    documents: State<DocumentStore>,
+    handle: u32,
+    custom_file: Option<String>,
+) -> Result<MaterialReport, String> {
+    let mut database = MaterialDatabase::new();
+    if let Some(custom_file) = custom_file {
+        database.load_custom(&custom_file)?;
+    }
+    documents.read(handle, |st_bridge| database.report(st_bridge))
+}
+
+#[tauri::command]
+fn steel_takeoff(documents: State<DocumentStore>, handle: u32) -> Result<SteelTakeoff, String> {
+    documents.read(handle, app::takeoff::steel::steel_takeoff)
+}
+
+#[tauri::command]
+fn export_steel_takeoff(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::takeoff::steel::write_steel_takeoff(st_bridge, file_name).map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn concrete_takeoff(
+    documents: State<DocumentStore>,
+    handle: u32,
+) -> Result<ConcreteTakeoff, String> {
+    documents.read(handle, app::takeoff::concrete::concrete_takeoff)
+}
+
+#[tauri::command]
+fn export_concrete_takeoff(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::takeoff::concrete::write_concrete_takeoff(st_bridge, file_name)
+            .map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn validate(documents: State<DocumentStore>, handle: u32) -> Result<Vec<Issue>, String> {
+    documents.read(handle, app::validation::validate)
+}
+
+#[tauri::command]
+fn duplicate_nodes(
+    documents: State<DocumentStore>,
+    handle: u32,
+    tolerance: f64,
+) -> Result<Vec<DuplicateNodeGroup>, String> {
+    documents.read(handle, |st_bridge| {
+        app::node_merge::find_duplicate_nodes(st_bridge, tolerance)
+    })?
+}
+
+// Merges in place and returns the merged groups.
+#[tauri::command]
+fn merge_duplicate_nodes(
+    documents: State<DocumentStore>,
+    handle: u32,
+    tolerance: f64,
+) -> Result<Vec<DuplicateNodeGroup>, String> {
+    documents.write(handle, |st_bridge| {
+        app::node_merge::merge_duplicate_nodes(st_bridge, tolerance)
+    })?
+}
+
+#[tauri::command]
+fn disconnected_parts(
+    documents: State<DocumentStore>,
+    handle: u32,
+) -> Result<Vec<DisconnectedPart>, String> {
+    documents.read(handle, app::connectivity::disconnected_parts)
+}
+
+#[tauri::command]
+fn node_path(
+    documents: State<DocumentStore>,
+    handle: u32,
+    from: u32,
+    to: u32,
+) -> Result<Option<Vec<u32>>, String> {
+    documents.read(handle, |st_bridge| {
+        ConnectivityGraph::new(st_bridge).path(from, to)
+    })
+}
+
+#[tauri::command]
+fn frame_model(documents: State<DocumentStore>, handle: u32) -> Result<FrameModel, String> {
+    documents.read(handle, |st_bridge| {
+        FrameModel::new(st_bridge, &MaterialDatabase::new())
+    })
+}
+
+#[tauri::command]
+fn analyze(
+    documents: State<DocumentStore>,
+    handle: u32,
+    load_case_list: Vec<LoadCase>,
+) -> Result<Vec<AnalysisResult>, String> {
+    let model = documents.read(handle, |st_bridge| {
+        FrameModel::new(st_bridge, &MaterialDatabase::new())
+    })?;
+    load_case_list.iter().map(|l| model.analyze(l)).collect()
+}
+
+#[tauri::command]
+fn generate_loads(
+    documents: State<DocumentStore>,
+    handle: u32,
+    settings: Option<LoadSettings>,
+) -> Result<GeneratedLoads, String> {
+    let database = MaterialDatabase::new();
+    let settings = settings.unwrap_or_else(LoadSettings::new);
+    documents.read(handle, |st_bridge| {
+        let model = FrameModel::new(st_bridge, &database);
+        app::analysis::load::generate_load_cases(st_bridge, &model, &database, &settings)
+    })
+}
+
+#[tauri::command]
+fn export_opensees(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::export::opensees::write_opensees(st_bridge, file_name).map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn export_saf(documents: State<DocumentStore>, handle: u32, file_name: &str) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::export::saf::write_saf(st_bridge, file_name).map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn framing_plan(
+    documents: State<DocumentStore>,
+    handle: u32,
+    story: String,
+    scale: Option<f64>,
+) -> Result<Option<String>, String> {
+    let scale = scale.unwrap_or(app::drawing::DEFAULT_SCALE);
+    documents.read(handle, |st_bridge| {
+        st_bridge
+            .stb_model
+            .stb_stories
+            .stb_story_list
+            .iter()
+            .find(|s| s.name == story)
+            .map(|s| app::drawing::plan::framing_plan(st_bridge, s, scale).to_svg())
+    })
+}
+
+#[tauri::command]
+fn export_framing_plans(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+    scale: Option<f64>,
+) -> Result<(), String> {
+    let scale = scale.unwrap_or(app::drawing::DEFAULT_SCALE);
+    documents.read(handle, |st_bridge| {
+        app::drawing::plan::write_framing_plans(st_bridge, file_name, scale)
+            .map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn rc_section(
+    documents: State<DocumentStore>,
+    handle: u32,
+    id_section: u32,
+    scale: Option<f64>,
+) -> Result<Option<String>, String> {
+    let scale = scale.unwrap_or(app::drawing::rc_section::DEFAULT_SECTION_SCALE);
+    documents.read(handle, |st_bridge| {
+        let stb_section = st_bridge.stb_model.stb_sections.get(id_section)?;
+        app::drawing::rc_section::rc_section_drawing(stb_section, scale).map(|d| d.to_svg())
+    })
+}
+
+#[tauri::command]
+fn export_rc_sections(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+    scale: Option<f64>,
+) -> Result<(), String> {
+    let scale = scale.unwrap_or(app::drawing::rc_section::DEFAULT_SECTION_SCALE);
+    documents.read(handle, |st_bridge| {
+        app::drawing::rc_section::write_rc_sections(st_bridge, file_name, scale)
+            .map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn section_schedules(documents: State<DocumentStore>, handle: u32) -> Result<String, String> {
+    documents.read(handle, |st_bridge| {
+        app::schedule::schedules_to_html(&app::schedule::section_schedule_list(st_bridge))
+    })
+}
+
+#[tauri::command]
+fn export_section_schedules(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+) -> Result<(), String> {
+    documents.read(handle, |st_bridge| {
+        app::schedule::write_section_schedules(st_bridge, file_name).map_err(|e| e.to_string())
+    })?
+}
+
+#[tauri::command]
+fn axis_elevation(
+    documents: State<DocumentStore>,
+    handle: u32,
+    axis: String,
+    scale: Option<f64>,
+) -> Result<Option<String>, String> {
+    let scale = scale.unwrap_or(app::drawing::DEFAULT_SCALE);
+    documents.read(handle, |st_bridge| {
+        let stb_axes = &st_bridge.stb_model.stb_axes;
+        if let Some(a) = stb_axes.stb_x_axis_list.iter().find(|a| a.name == axis) {
+            return Some(app::drawing::elevation::x_axis_elevation(st_bridge, a, scale).to_svg());
+        }
+        stb_axes
+            .stb_y_axis_list
+            .iter()
+            .find(|a| a.name == axis)
+            .map(|a| app::drawing::elevation::y_axis_elevation(st_bridge, a, scale).to_svg())
+    })
+}
+
+#[tauri::command]
+fn export_axis_elevations(
+    documents: State<DocumentStore>,
+    handle: u32,
+    file_name: &str,
+    scale: Option<f64>,
+) -> Result<(), String> {
+    let scale = scale.unwrap_or(app::drawing::DEFAULT_SCALE);
+    documents.read(handle, |st_bridge| {
+        app::drawing::elevation::write_axis_elevations(st_bridge, file_name, scale)
+            .map_err(|e| e.to_string())
+    })?
+}
 
 #[tauri::command]
-fn read_st_bridge(file_name: &str) -> StBridge {
-    app::read_st_bridge(file_name)
+fn diff(
+    documents: State<DocumentStore>,
+    old: u32,
+    new: u32,
+    tolerance: Option<f64>,
+) -> Result<ModelDiff, String> {
+    let tolerance = tolerance.unwrap_or(app::validation::NODE_TOLERANCE);
+    documents.read_list(&[old, new], |model_list| {
+        app::diff::diff_with_tolerance(model_list[0], model_list[1], tolerance)
+    })?
 }
 
+// The merged model is opened as a new document without a file.
 #[tauri::command]
-fn members(st_bridge: StBridge) -> Vec<(StbNode, StbNode)> {
-    st_bridge.members()
+fn merge(
+    documents: State<DocumentStore>,
+    base: u32,
+    ours: u32,
+    theirs: u32,
+    resolution_list: Option<Vec<Resolution>>,
+) -> Result<MergedDocument, String> {
+    let resolution_list = resolution_list.unwrap_or_default();
+    let result = documents.read_list(&[base, ours, theirs], |model_list| {
+        app::merge::merge_with_resolutions(
+            model_list[0],
+            model_list[1],
+            model_list[2],
+            &resolution_list,
+        )
+    })?;
+    Ok(MergedDocument {
+        handle: documents.open(None, result.st_bridge),
+        conflict_list: result.conflict_list,
+    })
 }
 
 fn main() {
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![read_st_bridge, members])
+        .manage(DocumentStore::new())
+        .invoke_handler(tauri::generate_handler![
+            open_st_bridge,
+            close_st_bridge,
+            open_documents,
+            members,
+            slab_meshes,
+            wall_meshes,
+            export_glb,
+            export_ifc,
+            export_obj,
+            export_stl,
+            import_ifc,
+            steel_section_properties,
+            rc_section_properties,
+            materials,
+            steel_takeoff,
+            export_steel_takeoff,
+            concrete_takeoff,
+            export_concrete_takeoff,
+            validate,
+            duplicate_nodes,
+            merge_duplicate_nodes,
+            disconnected_parts,
+            node_path,
+            frame_model,
+            analyze,
+            generate_loads,
+            export_opensees,
+            export_saf,
+            framing_plan,
+            export_framing_plans,
+            axis_elevation,
+            export_axis_elevations,
+            section_schedules,
+            export_section_schedules,
+            rc_section,
+            export_rc_sections,
+            diff,
+            merge
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,524 @@
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::stb_model::stb_sections::StbSection;
+use crate::st_bridge::StBridge;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MaterialKind {
+    Steel,
+    Concrete,
+    Rebar,
+}
+
+// Stresses in N/mm2, density in kg/m3, thermal coefficient in 1/K. f is the F value for steel,
+// the design strength Fc for concrete and the specified yield strength for rebar.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Material {
+    pub name: String,
+    pub kind: MaterialKind,
+    pub f: f64,
+    pub young_modulus: f64,
+    pub poisson_ratio: f64,
+    pub density: f64,
+    pub thermal_coefficient: f64,
+}
+
+// F values for thicknesses up to 40 mm (Notification No. 2464 of the Building Standard Law).
+const STEEL_F_LIST: [(&str, f64); 30] = [
+    ("SS400", 235.0),
+    ("SS490", 275.0),
+    ("SS540", 375.0),
+    ("SN400", 235.0),
+    ("SN400A", 235.0),
+    ("SN400B", 235.0),
+    ("SN400C", 235.0),
+    ("SN490", 325.0),
+    ("SN490B", 325.0),
+    ("SN490C", 325.0),
+    ("SM400", 235.0),
+    ("SM490", 325.0),
+    ("SM490Y", 355.0),
+    ("SM520", 355.0),
+    ("SM570", 440.0),
+    ("SA440", 440.0),
+    ("SSC400", 235.0),
+    ("SWH400", 235.0),
+    ("STK400", 235.0),
+    ("STK490", 325.0),
+    ("STKN400", 235.0),
+    ("STKN490", 325.0),
+    ("STKR400", 235.0),
+    ("STKR490", 325.0),
+    ("BCR295", 295.0),
+    ("BCP235", 235.0),
+    ("BCP325", 325.0),
+    ("BCP325T", 325.0),
+    ("TMCP325", 325.0),
+    ("TMCP355", 355.0),
+];
+
+pub fn steel(name: &str, f: f64) -> Material {
+    Material {
+        name: name.to_string(),
+        kind: MaterialKind::Steel,
+        f,
+        young_modulus: 205000.0,
+        poisson_ratio: 0.3,
+        density: 7850.0,
+        thermal_coefficient: 1.2e-5,
+    }
+}
+
+// Young's modulus from the AIJ RC standard, E = 3.35e4 * (gamma / 24)^2 * (Fc / 60)^(1/3), with
+// the unit weight gamma of plain concrete rising with strength.
+pub fn concrete(name: &str, fc: f64) -> Material {
+    let gamma = if fc <= 36.0 {
+        23.0
+    } else if fc <= 48.0 {
+        23.5
+    } else {
+        24.0
+    };
+
+    Material {
+        name: name.to_string(),
+        kind: MaterialKind::Concrete,
+        f: fc,
+        young_modulus: 3.35e4 * (gamma / 24.0_f64).powi(2) * (fc / 60.0).powf(1.0 / 3.0),
+        poisson_ratio: 0.2,
+        density: gamma * 1000.0 / 9.80665,
+        thermal_coefficient: 1.0e-5,
+    }
+}
+
+// Rebar shares the thermal coefficient of concrete in the AIJ RC standard.
+pub fn rebar(name: &str, f: f64) -> Material {
+    Material {
+        name: name.to_string(),
+        kind: MaterialKind::Rebar,
+        f,
+        young_modulus: 205000.0,
+        poisson_ratio: 0.3,
+        density: 7850.0,
+        thermal_coefficient: 1.0e-5,
+    }
+}
+
+// Resolves built-in grade names: the steel table, FC<n> for concrete and SD<n> for rebar.
+pub fn parse_material(name: &str) -> Option<Material> {
+    let key = name.trim().to_uppercase();
+    if key.is_empty() {
+        return None;
+    }
+
+    if let Some(&(_, f)) = STEEL_F_LIST.iter().find(|(grade, _)| *grade == key) {
+        return Some(steel(name.trim(), f));
+    }
+
+    if let Some(rest) = key.strip_prefix("FC") {
+        let fc: f64 = rest.trim_start_matches('=').parse().ok()?;
+        return Some(concrete(name.trim(), fc));
+    }
+
+    if let Some(rest) = key.strip_prefix("SD") {
+        let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let suffix = &rest[digits.len()..];
+        if suffix.is_empty() || suffix == "A" || suffix == "B" {
+            return Some(rebar(name.trim(), digits.parse().ok()?));
+        }
+        return None;
+    }
+
+    // Grades such as SS400A or SN490B that only differ by a toughness suffix.
+    let trimmed = key.trim_end_matches(&['A', 'B', 'C'][..]);
+    if trimmed != key {
+        if let Some(&(_, f)) = STEEL_F_LIST.iter().find(|(grade, _)| *grade == trimmed) {
+            return Some(steel(name.trim(), f));
+        }
+    }
+
+    None
+}
+
+// Custom grades only need a name, kind and f; the rest fall back to the kind's defaults.
+#[derive(Deserialize)]
+struct CustomMaterial {
+    name: String,
+    kind: MaterialKind,
+    f: f64,
+    young_modulus: Option<f64>,
+    poisson_ratio: Option<f64>,
+    density: Option<f64>,
+    thermal_coefficient: Option<f64>,
+}
+
+// A TOML file lists the grades as [[material]] tables.
+#[derive(Deserialize)]
+struct CustomMaterialFile {
+    #[serde(default)]
+    material: Vec<CustomMaterial>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MaterialReport {
+    pub material_list: Vec<Material>,
+    pub unknown_list: Vec<String>,
+}
+
+pub struct MaterialDatabase {
+    custom_map: HashMap<String, Material>,
+}
+
+impl MaterialDatabase {
+    pub fn new() -> MaterialDatabase {
+        MaterialDatabase {
+            custom_map: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, material: Material) {
+        self.custom_map
+            .insert(material.name.trim().to_uppercase(), material);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Material> {
+        match self.custom_map.get(&name.trim().to_uppercase()) {
+            Some(material) => Some(material.clone()),
+            None => parse_material(name),
+        }
+    }
+
+    // Reads custom grades from a .toml file, or from a JSON array otherwise, returning how many
+    // were added.
+    pub fn load_custom(&mut self, file_name: &str) -> Result<usize, String> {
+        let contents = std::fs::read_to_string(file_name).map_err(|e| e.to_string())?;
+        let is_toml = Path::new(file_name)
+            .extension()
+            .map(|e| e.eq_ignore_ascii_case("toml"))
+            .unwrap_or(false);
+        if is_toml {
+            self.load_custom_toml(&contents)
+        } else {
+            self.load_custom_json(&contents)
+        }
+    }
+
+    pub fn load_custom_json(&mut self, contents: &str) -> Result<usize, String> {
+        let custom_list: Vec<CustomMaterial> =
+            serde_json::from_str(contents).map_err(|e| e.to_string())?;
+        Ok(self.insert_custom(custom_list))
+    }
+
+    pub fn load_custom_toml(&mut self, contents: &str) -> Result<usize, String> {
+        let file: CustomMaterialFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+        Ok(self.insert_custom(file.material))
+    }
+
+    fn insert_custom(&mut self, custom_list: Vec<CustomMaterial>) -> usize {
+        let count = custom_list.len();
+
+        for custom in custom_list {
+            let default = match custom.kind {
+                MaterialKind::Steel => steel(&custom.name, custom.f),
+                MaterialKind::Concrete => concrete(&custom.name, custom.f),
+                MaterialKind::Rebar => rebar(&custom.name, custom.f),
+            };
+
+            self.insert(Material {
+                young_modulus: custom.young_modulus.unwrap_or(default.young_modulus),
+                poisson_ratio: custom.poisson_ratio.unwrap_or(default.poisson_ratio),
+                density: custom.density.unwrap_or(default.density),
+                thermal_coefficient: custom
+                    .thermal_coefficient
+                    .unwrap_or(default.thermal_coefficient),
+                ..default
+            });
+        }
+
+        count
+    }
+
+    pub fn report(&self, st_bridge: &StBridge) -> MaterialReport {
+        let mut material_list = Vec::new();
+        let mut unknown_list = Vec::new();
+
+        for grade in grade_list(st_bridge) {
+            match self.get(&grade) {
+                Some(material) => material_list.push(material),
+                None => unknown_list.push(grade),
+            }
+        }
+
+        MaterialReport {
+            material_list,
+            unknown_list,
+        }
+    }
+}
+
+impl Default for MaterialDatabase {
+    fn default() -> MaterialDatabase {
+        MaterialDatabase::new()
+    }
+}
+
+// Every strength name used by the model's sections, stories and reinforcement list.
+pub fn grade_list(st_bridge: &StBridge) -> Vec<String> {
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    let mut grades: BTreeSet<String> = BTreeSet::new();
+
+    for stb_section in stb_sections
+        .column_rc_map
+        .values()
+        .chain(stb_sections.column_s_map.values())
+        .chain(stb_sections.beam_rc_map.values())
+        .chain(stb_sections.beam_s_map.values())
+        .chain(stb_sections.slab_rc_map.values())
+        .chain(stb_sections.brace_s_map.values())
+        .chain(stb_sections.wall_rc_map.values())
+    {
+        grades.extend(section_grade_list(stb_section));
+    }
+
+    for stb_story in &st_bridge.stb_model.stb_stories.stb_story_list {
+        grades.insert(stb_story.concrete_strength.clone());
+    }
+
+    grades.extend(
+        st_bridge
+            .stb_common
+            .stb_reinforcement_strength_list
+            .strength_list(),
+    );
+
+    grades
+        .into_iter()
+        .filter(|g| !g.trim().is_empty())
+        .collect()
+}
+
+pub fn section_grade_list(stb_section: &StbSection) -> Vec<String> {
+    match stb_section {
+        StbSection::StbSecColumnRC {
+            strength_concrete,
+            strength_reinforcement_main,
+            strength_rein_band,
+            ..
+        } => {
+            let mut list = vec![
+                strength_reinforcement_main.clone(),
+                strength_rein_band.clone(),
+            ];
+            list.extend(strength_concrete.clone());
+            list
+        }
+        StbSection::StbSecColumnS {
+            stb_sec_steel_column,
+            ..
+        } => vec![
+            stb_sec_steel_column.strength_main.clone(),
+            stb_sec_steel_column.strength_web.clone(),
+        ],
+        StbSection::StbSecBeamRC {
+            strength_concrete,
+            strength_reinforcement_main,
+            strength_reinforcement_2nd_main,
+            strength_stirrup,
+            strength_reinforcement_web,
+            strength_bar_spacing,
+            ..
+        } => {
+            let mut list = vec![
+                strength_reinforcement_main.clone(),
+                strength_stirrup.clone(),
+                strength_reinforcement_web.clone(),
+                strength_bar_spacing.clone(),
+            ];
+            list.extend(strength_concrete.clone());
+            list.extend(strength_reinforcement_2nd_main.clone());
+            list
+        }
+        StbSection::StbSecBeamS {
+            stb_sec_steel_beam_list,
+            ..
+        } => stb_sec_steel_beam_list
+            .iter()
+            .flat_map(|b| [b.strength_main.clone(), b.strength_web.clone()])
+            .collect(),
+        StbSection::StbSecSlabRC {
+            strength_concrete,
+            stb_sec_bar_arrangement,
+            ..
+        } => {
+            let mut list = vec![strength_concrete.clone()];
+            list.extend(
+                stb_sec_bar_arrangement
+                    .stb_sec_1way_slab_1_list
+                    .iter()
+                    .map(|bar| bar.strength.clone()),
+            );
+            list
+        }
+        StbSection::StbSecBraceS {
+            stb_sec_steel_brace,
+            ..
+        } => vec![
+            stb_sec_steel_brace.strength_main.clone(),
+            stb_sec_steel_brace.strength_web.clone(),
+        ],
+        StbSection::StbSecWallRC {
+            strength_concrete, ..
+        } => vec![strength_concrete.clone()],
+    }
+}
+
+// Concrete of an RC member: the section's own strength, else that of the member's story.
+pub fn concrete_grade(
+    st_bridge: &StBridge,
+    stb_member: &StbMember,
+    stb_section: &StbSection,
+) -> String {
+    let strength_concrete = match stb_section {
+        StbSection::StbSecColumnRC {
+            strength_concrete, ..
+        }
+        | StbSection::StbSecBeamRC {
+            strength_concrete, ..
+        } => strength_concrete.clone(),
+        StbSection::StbSecSlabRC {
+            strength_concrete, ..
+        }
+        | StbSection::StbSecWallRC {
+            strength_concrete, ..
+        } => Some(strength_concrete.clone()),
+        _ => None,
+    };
+
+    strength_concrete
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            st_bridge
+                .stb_model
+                .story(stb_member)
+                .map(|s| s.concrete_strength.clone())
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_grades() {
+        let database = MaterialDatabase::new();
+
+        let sn490 = database.get("SN490B").unwrap();
+        assert_eq!(sn490.kind, MaterialKind::Steel);
+        assert_eq!(sn490.f, 325.0);
+        assert_eq!(sn490.young_modulus, 205000.0);
+        // Lower case and a toughness suffix that is not in the table.
+        assert_eq!(database.get(" ss400a ").unwrap().f, 235.0);
+
+        let fc24 = database.get("FC24").unwrap();
+        assert_eq!(fc24.kind, MaterialKind::Concrete);
+        assert_eq!(fc24.f, 24.0);
+        let e = 3.35e4 * (23.0_f64 / 24.0).powi(2) * 0.4_f64.powf(1.0 / 3.0);
+        assert!((fc24.young_modulus - e).abs() < 1e-6);
+        assert_eq!(database.get("Fc=60").unwrap().f, 60.0);
+
+        let sd345 = database.get("SD345").unwrap();
+        assert_eq!(sd345.kind, MaterialKind::Rebar);
+        assert_eq!(sd345.f, 345.0);
+        assert_eq!(database.get("SD295A").unwrap().f, 295.0);
+
+        assert!(database.get("SD345X").is_none());
+        assert!(database.get("XYZ").is_none());
+        assert!(database.get("").is_none());
+    }
+
+    #[test]
+    fn custom_grades() {
+        let mut database = MaterialDatabase::new();
+
+        let count = database
+            .load_custom_json(
+                r#"[
+                    {"name": "SN490-X", "kind": "Steel", "f": 355.0},
+                    {"name": "SS400", "kind": "Steel", "f": 245.0, "young_modulus": 200000.0}
+                ]"#,
+            )
+            .unwrap();
+
+        assert_eq!(count, 2);
+        let custom = database.get("sn490-x").unwrap();
+        assert_eq!(custom.f, 355.0);
+        assert_eq!(custom.young_modulus, 205000.0);
+        // Custom grades take precedence over the built-in table.
+        let ss400 = database.get("SS400").unwrap();
+        assert_eq!(ss400.f, 245.0);
+        assert_eq!(ss400.young_modulus, 200000.0);
+
+        assert!(database.load_custom_json(r#"[{"name": "X"}]"#).is_err());
+    }
+
+    #[test]
+    fn custom_grades_from_toml() {
+        let file_name = std::env::temp_dir().join("custom_grades_test.TOML");
+        std::fs::write(
+            &file_name,
+            r#"
+                [[material]]
+                name = "SN490-X"
+                kind = "Steel"
+                f = 355.0
+
+                [[material]]
+                name = "FC33"
+                kind = "Concrete"
+                f = 33.0
+                young_modulus = 30000.0
+            "#,
+        )
+        .unwrap();
+
+        let mut database = MaterialDatabase::default();
+        let count = database.load_custom(file_name.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&file_name).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(database.get("SN490-X").unwrap().f, 355.0);
+        let fc33 = database.get("FC33").unwrap();
+        assert_eq!(fc33.kind, MaterialKind::Concrete);
+        assert_eq!(fc33.young_modulus, 30000.0);
+
+        assert_eq!(database.load_custom_toml(""), Ok(0));
+        assert!(database
+            .load_custom_toml("[[material]]\nname = \"X\"")
+            .is_err());
+    }
+
+    #[test]
+    fn sample_grades_are_all_known() {
+        let st_bridge = crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap();
+
+        let report = MaterialDatabase::new().report(&st_bridge);
+
+        let names: Vec<&str> = report
+            .material_list
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["FC24", "SD295", "SD295A", "SD345", "SD490", "SN400", "SN490"]
+        );
+        assert!(report.unknown_list.is_empty());
+    }
+}
@@ -0,0 +1,1090 @@
+use crate::diff::{diff, ChangeKind, ModelDiff};
+use crate::st_bridge::StBridge;
+use crate::validation::NODE_TOLERANCE;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Base,
+    Ours,
+    Theirs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ConflictKind {
+    BothModified,
+    ModifiedAndRemoved,
+    BothAdded,
+    DanglingReference,
+}
+
+// A conflict on a whole element has an empty field and a null value on a side that removed it.
+// Ids are those of the merged model, and the values are translated into its numbering.
+#[derive(Debug, Serialize)]
+pub struct Conflict {
+    pub id: usize,
+    pub kind: ConflictKind,
+    pub element: String,
+    pub name: String,
+    pub element_id: Option<u32>,
+    pub field: String,
+    pub base: Value,
+    pub ours: Value,
+    pub theirs: Value,
+    pub resolution: Option<Side>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Resolution {
+    pub conflict_id: usize,
+    pub side: Side,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MergeResult {
+    pub st_bridge: StBridge,
+    pub conflict_list: Vec<Conflict>,
+}
+
+impl MergeResult {
+    pub fn is_resolved(&self) -> bool {
+        self.conflict_list.iter().all(|c| c.resolution.is_some())
+    }
+}
+
+pub fn merge(base: &StBridge, ours: &StBridge, theirs: &StBridge) -> MergeResult {
+    merge_with_resolutions(base, ours, theirs, &[])
+}
+
+// Unresolved conflicts keep the base, except elements added differently on both sides, which keep
+// ours. Conflicts are numbered in a fixed order, so a resolution list from one run applies to the
+// next run on the same three models.
+pub fn merge_with_resolutions(
+    base: &StBridge,
+    ours: &StBridge,
+    theirs: &StBridge,
+    resolution_list: &[Resolution],
+) -> MergeResult {
+    let diff_list = [diff(base, ours), diff(base, theirs)];
+    let mut root = to_value(base);
+    let side_root_list = [to_value(ours), to_value(theirs)];
+
+    let mut merger = Merger {
+        resolution_list,
+        conflict_list: Vec::new(),
+    };
+
+    let base_table_list = Category::LIST.map(|c| c.read(&root));
+    let side_table_list = [0, 1].map(|i| Category::LIST.map(|c| c.read(&side_root_list[i])));
+
+    let side_map_list = side_maps(&base_table_list, &side_table_list, &diff_list);
+
+    for (i, category) in Category::LIST.iter().enumerate() {
+        let table = merger.merge_table(
+            *category,
+            &base_table_list[i],
+            [&side_table_list[0][i], &side_table_list[1][i]],
+            &diff_list,
+            &side_map_list,
+        );
+        category.write(&mut root, table);
+    }
+
+    merger.merge_shapes(&mut root, &side_root_list);
+    merger.check_references(
+        &mut root,
+        &base_table_list,
+        &side_table_list,
+        &side_map_list,
+    );
+    let node_table = Category::Node.read(&root);
+    merge_node_id_lists(&mut root, &side_root_list, &side_map_list, &node_table);
+
+    MergeResult {
+        st_bridge: serde_json::from_value(root).unwrap(),
+        conflict_list: merger.conflict_list,
+    }
+}
+
+// Elements of one category keyed by id, each with the name of the map it is stored in.
+type Table = BTreeMap<u32, (String, Value)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Node,
+    Section,
+    Member,
+}
+
+impl Category {
+    const LIST: [Category; 3] = [Category::Node, Category::Section, Category::Member];
+
+    fn path(self) -> [&'static str; 2] {
+        match self {
+            Category::Node => ["stb_model", "stb_nodes"],
+            Category::Section => ["stb_model", "stb_sections"],
+            Category::Member => ["stb_model", "stb_members"],
+        }
+    }
+
+    fn is_table(self, container: &str) -> bool {
+        self != Category::Section || container != "stb_sec_steel"
+    }
+
+    fn read(self, root: &Value) -> Table {
+        let mut table = Table::new();
+        let [a, b] = self.path();
+        for (container, map) in root[a][b].as_object().unwrap() {
+            if !self.is_table(container) {
+                continue;
+            }
+            for (id, value) in map.as_object().unwrap() {
+                table.insert(id.parse().unwrap(), (container.clone(), value.clone()));
+            }
+        }
+        table
+    }
+
+    fn write(self, root: &mut Value, table: Table) {
+        let [a, b] = self.path();
+        let object = root[a][b].as_object_mut().unwrap();
+        for (container, map) in object.iter_mut() {
+            if self.is_table(container) {
+                *map = Value::Object(Map::new());
+            }
+        }
+        for (id, (container, value)) in table {
+            object[&container]
+                .as_object_mut()
+                .unwrap()
+                .insert(id.to_string(), value);
+        }
+    }
+
+    fn id_map(self, model_diff: &ModelDiff) -> &BTreeMap<u32, u32> {
+        match self {
+            Category::Node => &model_diff.node_id_map,
+            Category::Section => &model_diff.section_id_map,
+            Category::Member => &model_diff.member_id_map,
+        }
+    }
+
+    // Base ids of removed elements and the changed top-level fields of modified ones.
+    fn change_map(self, model_diff: &ModelDiff) -> BTreeMap<u32, Option<BTreeSet<String>>> {
+        let change_list = match self {
+            Category::Node => &model_diff.node_list,
+            Category::Section => &model_diff.section_list,
+            Category::Member => &model_diff.member_list,
+        };
+        let mut change_map = BTreeMap::new();
+        for change in change_list {
+            match change.kind {
+                ChangeKind::Removed => {
+                    change_map.insert(change.old_id.unwrap(), None);
+                }
+                ChangeKind::Modified => {
+                    let field_set = change
+                        .field_list
+                        .iter()
+                        .map(|f| f.field.split('.').next().unwrap().to_string())
+                        .collect();
+                    change_map.insert(change.old_id.unwrap(), Some(field_set));
+                }
+                ChangeKind::Added => {}
+            }
+        }
+        change_map
+    }
+
+    fn added_list(self, model_diff: &ModelDiff) -> Vec<u32> {
+        let change_list = match self {
+            Category::Node => &model_diff.node_list,
+            Category::Section => &model_diff.section_list,
+            Category::Member => &model_diff.member_list,
+        };
+        change_list
+            .iter()
+            .filter(|c| c.kind == ChangeKind::Added)
+            .map(|c| c.new_id.unwrap())
+            .collect()
+    }
+}
+
+// Maps the ids of one side to the ids of the merged model.
+#[derive(Default)]
+struct SideMap {
+    node: BTreeMap<u32, u32>,
+    section: BTreeMap<u32, u32>,
+    member: BTreeMap<u32, u32>,
+}
+
+impl SideMap {
+    fn get(&self, category: Category) -> &BTreeMap<u32, u32> {
+        match category {
+            Category::Node => &self.node,
+            Category::Section => &self.section,
+            Category::Member => &self.member,
+        }
+    }
+
+    fn get_mut(&mut self, category: Category) -> &mut BTreeMap<u32, u32> {
+        match category {
+            Category::Node => &mut self.node,
+            Category::Section => &mut self.section,
+            Category::Member => &mut self.member,
+        }
+    }
+
+    // Rewrites an element of this side into the merged numbering under the given id.
+    fn translate(&self, value: &Value, id: u32) -> Value {
+        let mut value = value.clone();
+        let object = inner_mut(&mut value);
+        let lookup = |map: &BTreeMap<u32, u32>, v: &Value| match v
+            .as_u64()
+            .and_then(|i| map.get(&(i as u32)))
+        {
+            Some(&i) => Value::from(i),
+            None => v.clone(),
+        };
+        for (field, v) in object.iter_mut() {
+            if field == "id" {
+                *v = Value::from(id);
+            } else if field.starts_with("id_node_") {
+                *v = lookup(&self.node, v);
+            } else if field == "id_section" {
+                *v = lookup(&self.section, v);
+            } else if field == "id_member" {
+                *v = lookup(&self.member, v);
+            } else if field == "stb_node_id_list" {
+                for child in v["children"].as_array_mut().unwrap() {
+                    child["id"] = lookup(&self.node, &child["id"]);
+                }
+            }
+        }
+        value
+    }
+}
+
+// Matched elements take their base id. Elements added on one side keep their id when it is free,
+// and elements added identically on both sides share one id.
+fn side_maps(
+    base_table_list: &[Table; 3],
+    side_table_list: &[[Table; 3]; 2],
+    diff_list: &[ModelDiff; 2],
+) -> [SideMap; 2] {
+    let mut side_map_list = [SideMap::default(), SideMap::default()];
+
+    for (c, category) in Category::LIST.iter().enumerate() {
+        let mut used: BTreeSet<u32> = base_table_list[c].keys().cloned().collect();
+        for (side, side_map) in side_map_list.iter_mut().enumerate() {
+            let map = side_map.get_mut(*category);
+            for (&base_id, &side_id) in category.id_map(&diff_list[side]) {
+                map.insert(side_id, base_id);
+            }
+        }
+
+        let mut ours_added = Vec::new();
+        for id in category.added_list(&diff_list[0]) {
+            let result_id = allocate(id, &mut used);
+            side_map_list[0].get_mut(*category).insert(id, result_id);
+            ours_added.push((id, result_id));
+        }
+        let mut paired: BTreeSet<u32> = BTreeSet::new();
+        for id in category.added_list(&diff_list[1]) {
+            let theirs = &side_table_list[1][c][&id].1;
+            let twin = ours_added.iter().find(|&&(ours_id, result_id)| {
+                !paired.contains(&result_id)
+                    && same_element(
+                        *category,
+                        (&side_table_list[0][c][&ours_id].1, &side_map_list[0]),
+                        (theirs, &side_map_list[1]),
+                    )
+            });
+            let result_id = match twin {
+                Some(&(_, result_id)) => {
+                    paired.insert(result_id);
+                    result_id
+                }
+                None => allocate(id, &mut used),
+            };
+            side_map_list[1].get_mut(*category).insert(id, result_id);
+        }
+    }
+
+    side_map_list
+}
+
+fn allocate(id: u32, used: &mut BTreeSet<u32>) -> u32 {
+    let id = if used.contains(&id) {
+        used.iter().next_back().unwrap() + 1
+    } else {
+        id
+    };
+    used.insert(id);
+    id
+}
+
+// Whether elements added on both sides are the same element: nodes at the same place, sections
+// with the same type, name and floor, and members of the same kind between the same nodes.
+fn same_element(category: Category, ours: (&Value, &SideMap), theirs: (&Value, &SideMap)) -> bool {
+    let (a, b) = (ours.0, theirs.0);
+    match category {
+        Category::Node => {
+            let d = ["x", "y", "z"]
+                .iter()
+                .map(|k| (a[k].as_f64().unwrap() - b[k].as_f64().unwrap()).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            d <= NODE_TOLERANCE
+        }
+        Category::Section => {
+            let key = |v: &Value| {
+                let (tag, inner) = tag(v);
+                (tag, inner.get("name").cloned(), inner.get("floor").cloned())
+            };
+            key(a) == key(b)
+        }
+        Category::Member => {
+            let key = |v: &Value, side_map: &SideMap| {
+                let translated = side_map.translate(v, 0);
+                let (tag, inner) = tag(&translated);
+                let mut node_list: Vec<Value> = inner
+                    .iter()
+                    .filter(|(k, _)| k.starts_with("id_node_"))
+                    .map(|(_, v)| v.clone())
+                    .collect();
+                if let Some(list) = inner.get("stb_node_id_list") {
+                    node_list.extend(list["children"].as_array().unwrap().iter().cloned());
+                }
+                let mut node_list: Vec<String> = node_list.iter().map(|v| v.to_string()).collect();
+                node_list.sort();
+                (tag, node_list)
+            };
+            !key(a, ours.1).1.is_empty() && key(a, ours.1) == key(b, theirs.1)
+        }
+    }
+}
+
+struct Merger<'a> {
+    resolution_list: &'a [Resolution],
+    conflict_list: Vec<Conflict>,
+}
+
+impl Merger<'_> {
+    // Records a conflict and returns the side to take.
+    #[allow(clippy::too_many_arguments)]
+    fn conflict(
+        &mut self,
+        kind: ConflictKind,
+        element: &Value,
+        element_id: Option<u32>,
+        field: &str,
+        base: Value,
+        ours: Value,
+        theirs: Value,
+        default: Side,
+    ) -> Side {
+        let id = self.conflict_list.len();
+        let resolution = self
+            .resolution_list
+            .iter()
+            .find(|r| r.conflict_id == id)
+            .map(|r| r.side);
+        let (tag, inner) = tag(element);
+        self.conflict_list.push(Conflict {
+            id,
+            kind,
+            element: if tag.is_empty() {
+                "Node".to_string()
+            } else {
+                tag
+            },
+            name: inner
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("")
+                .to_string(),
+            element_id,
+            field: field.to_string(),
+            base,
+            ours,
+            theirs,
+            resolution,
+        });
+        resolution.unwrap_or(default)
+    }
+
+    fn merge_table(
+        &mut self,
+        category: Category,
+        base: &Table,
+        side_table_list: [&Table; 2],
+        diff_list: &[ModelDiff; 2],
+        side_map_list: &[SideMap; 2],
+    ) -> Table {
+        let mut result = base.clone();
+        let change_map_list = [0, 1].map(|i| category.change_map(&diff_list[i]));
+
+        // An element of one side in the merged numbering, or null where it was removed.
+        let side_value = |side: usize, id: u32| -> Option<Value> {
+            let side_id = category.id_map(&diff_list[side]).get(&id)?;
+            let value = &side_table_list[side][side_id].1;
+            Some(side_map_list[side].translate(value, id))
+        };
+
+        for (&id, (container, base_value)) in base {
+            let state = [change_map_list[0].get(&id), change_map_list[1].get(&id)];
+            match state {
+                [None, None] => {}
+                [Some(None), Some(None)] | [Some(None), None] | [None, Some(None)] => {
+                    result.remove(&id);
+                }
+                [Some(None), Some(Some(_))] | [Some(Some(_)), Some(None)] => {
+                    let ours = side_value(0, id).unwrap_or(Value::Null);
+                    let theirs = side_value(1, id).unwrap_or(Value::Null);
+                    let side = self.conflict(
+                        ConflictKind::ModifiedAndRemoved,
+                        base_value,
+                        Some(id),
+                        "",
+                        base_value.clone(),
+                        ours.clone(),
+                        theirs.clone(),
+                        Side::Base,
+                    );
+                    let chosen = match side {
+                        Side::Base => base_value.clone(),
+                        Side::Ours => ours,
+                        Side::Theirs => theirs,
+                    };
+                    if chosen.is_null() {
+                        result.remove(&id);
+                    } else {
+                        result.insert(id, (container.clone(), chosen));
+                    }
+                }
+                [a, b] => {
+                    let ours = side_value(0, id).unwrap();
+                    let theirs = side_value(1, id).unwrap();
+                    let empty = BTreeSet::new();
+                    let (a, b) = (
+                        a.and_then(|s| s.as_ref()).unwrap_or(&empty),
+                        b.and_then(|s| s.as_ref()).unwrap_or(&empty),
+                    );
+                    let mut merged = base_value.clone();
+                    for field in a.union(b) {
+                        let base_field = tag(base_value).1.get(field).cloned();
+                        let ours_field = tag(&ours).1.get(field).cloned();
+                        let theirs_field = tag(&theirs).1.get(field).cloned();
+                        let value = if !b.contains(field) || ours_field == theirs_field {
+                            ours_field
+                        } else if !a.contains(field) {
+                            theirs_field
+                        } else {
+                            let side = self.conflict(
+                                ConflictKind::BothModified,
+                                base_value,
+                                Some(id),
+                                field,
+                                base_field.clone().unwrap_or(Value::Null),
+                                ours_field.clone().unwrap_or(Value::Null),
+                                theirs_field.clone().unwrap_or(Value::Null),
+                                Side::Base,
+                            );
+                            match side {
+                                Side::Base => base_field,
+                                Side::Ours => ours_field,
+                                Side::Theirs => theirs_field,
+                            }
+                        };
+                        let object = inner_mut(&mut merged);
+                        match value {
+                            Some(v) => {
+                                object.insert(field.clone(), v);
+                            }
+                            None => {
+                                object.remove(field);
+                            }
+                        }
+                    }
+                    result.insert(id, (container.clone(), merged));
+                }
+            }
+        }
+
+        for (side, model_diff) in diff_list.iter().enumerate() {
+            for side_id in category.added_list(model_diff) {
+                let (container, value) = &side_table_list[side][&side_id];
+                let id = side_map_list[side].get(category)[&side_id];
+                let value = side_map_list[side].translate(value, id);
+                match result.get(&id) {
+                    Some((_, ours)) if side == 1 && !base.contains_key(&id) => {
+                        // Twin nodes were paired within the tolerance, so only their kind counts.
+                        let same = match category {
+                            Category::Node => ours["kind"] == value["kind"],
+                            _ => *ours == value,
+                        };
+                        if same {
+                            continue;
+                        }
+                        let ours = ours.clone();
+                        let chosen = match self.conflict(
+                            ConflictKind::BothAdded,
+                            &value,
+                            Some(id),
+                            "",
+                            Value::Null,
+                            ours.clone(),
+                            value.clone(),
+                            Side::Ours,
+                        ) {
+                            Side::Base => None,
+                            Side::Ours => Some(ours),
+                            Side::Theirs => Some(value),
+                        };
+                        match chosen {
+                            Some(v) => {
+                                result.insert(id, (container.clone(), v));
+                            }
+                            None => {
+                                result.remove(&id);
+                            }
+                        }
+                    }
+                    _ => {
+                        result.insert(id, (container.clone(), value));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    // Members and nodes left pointing at an element the merge dropped, such as a member one side
+    // moved onto a section the other side removed. Each reference is a conflict on its field: the
+    // chosen side's value is taken, and the element is removed when that side does not have it.
+    // A node or section the chosen value refers to is brought back from that side. A member is
+    // not, as it would bring its own references along, so an opening on a missing member is
+    // removed and a node drops its id_member. Node and section references are settled first, so
+    // that member references see the members removed on the way.
+    fn check_references(
+        &mut self,
+        root: &mut Value,
+        base_table_list: &[Table; 3],
+        side_table_list: &[[Table; 3]; 2],
+        side_map_list: &[SideMap; 2],
+    ) {
+        let side_element = |c: usize, side: Side, id: u32| -> Option<(String, Value)> {
+            let side = match side {
+                Side::Base => return base_table_list[c].get(&id).cloned(),
+                Side::Ours => 0,
+                Side::Theirs => 1,
+            };
+            let category = Category::LIST[c];
+            let (&side_id, _) = side_map_list[side]
+                .get(category)
+                .iter()
+                .find(|(_, &merged_id)| merged_id == id)?;
+            let (container, value) = side_table_list[side][c].get(&side_id)?;
+            Some((container.clone(), side_map_list[side].translate(value, id)))
+        };
+
+        let pass_list = [
+            (Category::Member, false),
+            (Category::Member, true),
+            (Category::Node, true),
+        ];
+        for (category, member_reference) in pass_list {
+            let c = Category::LIST.iter().position(|&x| x == category).unwrap();
+            let mut table_list = Category::LIST.map(|x| x.read(root));
+            let mut table = table_list[c].clone();
+            let mut restored = BTreeSet::new();
+
+            let id_list: Vec<u32> = table_list[c].keys().cloned().collect();
+            for id in id_list {
+                let value = table_list[c][&id].1.clone();
+                let field_list = dangling_field_list(
+                    category,
+                    &value,
+                    &[&table_list[0], &table_list[1], &table_list[2]],
+                );
+                for field in field_list {
+                    if (field == "id_member") != member_reference {
+                        continue;
+                    }
+                    let element_list = [Side::Base, Side::Ours, Side::Theirs]
+                        .map(|side| side_element(c, side, id).map(|(_, v)| v));
+                    let [base, ours, theirs] = element_list.clone().map(|element| {
+                        element
+                            .and_then(|v| tag(&v).1.get(&field).cloned())
+                            .unwrap_or(Value::Null)
+                    });
+                    let side = self.conflict(
+                        ConflictKind::DanglingReference,
+                        &value,
+                        Some(id),
+                        &field,
+                        base,
+                        ours,
+                        theirs,
+                        Side::Base,
+                    );
+                    let chosen = match side {
+                        Side::Base => &element_list[0],
+                        Side::Ours => &element_list[1],
+                        Side::Theirs => &element_list[2],
+                    };
+                    let chosen_field = match chosen {
+                        Some(v) => tag(v).1.get(&field).cloned(),
+                        None => {
+                            table.remove(&id);
+                            break;
+                        }
+                    };
+
+                    let object = inner_mut(&mut table.get_mut(&id).unwrap().1);
+                    let chosen_field = match chosen_field {
+                        Some(f) => f,
+                        None => {
+                            object.remove(&field);
+                            continue;
+                        }
+                    };
+                    object.insert(field.clone(), chosen_field.clone());
+
+                    let (r, reference_list) = reference_list(&field, &chosen_field);
+                    let missing: Vec<u32> = reference_list
+                        .into_iter()
+                        .filter(|i| !table_list[r].contains_key(i))
+                        .collect();
+                    if Category::LIST[r] == Category::Member {
+                        if !missing.is_empty() {
+                            if tag(&value).0 == "StbOpen" {
+                                table.remove(&id);
+                                break;
+                            }
+                            object.remove(&field);
+                        }
+                        continue;
+                    }
+                    for i in missing {
+                        if let Some(element) = side_element(r, side, i) {
+                            table_list[r].insert(i, element);
+                            restored.insert(r);
+                        }
+                    }
+                }
+            }
+
+            for r in restored {
+                Category::LIST[r].write(root, table_list[r].clone());
+            }
+            category.write(root, table);
+        }
+    }
+
+    // Steel shapes are keyed by name and merged as whole entries.
+    fn merge_shapes(&mut self, root: &mut Value, side_root_list: &[Value; 2]) {
+        let children_map = |root: &Value| -> Map<String, Value> {
+            root["stb_model"]["stb_sections"]["stb_sec_steel"]["children_map"]
+                .as_object()
+                .unwrap()
+                .clone()
+        };
+        let base = children_map(root);
+        let ours = children_map(&side_root_list[0]);
+        let theirs = children_map(&side_root_list[1]);
+
+        let name_set: BTreeSet<&String> = base
+            .keys()
+            .chain(ours.keys())
+            .chain(theirs.keys())
+            .collect();
+        let mut merged = Map::new();
+        for name in name_set {
+            let (b, o, t) = (base.get(name), ours.get(name), theirs.get(name));
+            let value = if o == t || t == b {
+                o
+            } else if o == b {
+                t
+            } else {
+                let kind = match (b, o, t) {
+                    (None, _, _) => ConflictKind::BothAdded,
+                    (_, Some(_), Some(_)) => ConflictKind::BothModified,
+                    _ => ConflictKind::ModifiedAndRemoved,
+                };
+                let mut element = Map::new();
+                element.insert("name".to_string(), Value::from(name.as_str()));
+                let mut element = Value::Object(element);
+                if let Some(v) = o.or(t) {
+                    element = tag_only(v, element);
+                }
+                let side = self.conflict(
+                    kind,
+                    &element,
+                    None,
+                    "",
+                    b.cloned().unwrap_or(Value::Null),
+                    o.cloned().unwrap_or(Value::Null),
+                    t.cloned().unwrap_or(Value::Null),
+                    if b.is_none() { Side::Ours } else { Side::Base },
+                );
+                match side {
+                    Side::Base => b,
+                    Side::Ours => o,
+                    Side::Theirs => t,
+                }
+            };
+            if let Some(v) = value {
+                merged.insert(name.clone(), v.clone());
+            }
+        }
+        root["stb_model"]["stb_sections"]["stb_sec_steel"]["children_map"] = Value::Object(merged);
+    }
+}
+
+// The fields of a member or node whose ids are missing from the merged tables. Openings refer to
+// StbSecOpen sections, which are not read, so their section is not checked.
+fn dangling_field_list(category: Category, value: &Value, table_list: &[&Table; 3]) -> Vec<String> {
+    let [node_table, section_table, member_table] = table_list;
+    let missing = |table: &Table, v: &Value| match v.as_u64() {
+        Some(id) => !table.contains_key(&(id as u32)),
+        None => false,
+    };
+    let (tag, inner) = tag(value);
+    let mut field_list = Vec::new();
+    for (field, v) in &inner {
+        let dangling = if field.starts_with("id_node_") {
+            missing(node_table, v)
+        } else if field == "id_section" {
+            category == Category::Member && tag != "StbOpen" && missing(section_table, v)
+        } else if field == "id_member" {
+            missing(member_table, v)
+        } else if field == "stb_node_id_list" {
+            v["children"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|c| missing(node_table, &c["id"]))
+        } else {
+            false
+        };
+        if dangling {
+            field_list.push(field.clone());
+        }
+    }
+    field_list
+}
+
+// The category a reference field points into and the ids in its value.
+fn reference_list(field: &str, value: &Value) -> (usize, Vec<u32>) {
+    let category = if field == "id_section" {
+        Category::Section
+    } else if field == "id_member" {
+        Category::Member
+    } else {
+        Category::Node
+    };
+    let id_list = if field == "stb_node_id_list" {
+        value["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|c| c["id"].as_u64())
+            .map(|id| id as u32)
+            .collect()
+    } else {
+        value.as_u64().map(|id| id as u32).into_iter().collect()
+    };
+    let c = Category::LIST.iter().position(|&x| x == category).unwrap();
+    (c, id_list)
+}
+
+// Drops removed nodes from the axis and story node lists and adds the nodes either side put on an
+// axis or story of the same name.
+fn merge_node_id_lists(
+    root: &mut Value,
+    side_root_list: &[Value; 2],
+    side_map_list: &[SideMap; 2],
+    node_table: &Table,
+) {
+    let path_list = [
+        ["stb_axes", "stb_x_axis_list"],
+        ["stb_axes", "stb_y_axis_list"],
+        ["stb_stories", "stb_story_list"],
+    ];
+    for [a, b] in path_list {
+        for entry in root["stb_model"][a][b].as_array_mut().unwrap() {
+            let name = entry["name"].clone();
+            let children = entry["stb_node_id_list"]["children"]
+                .as_array_mut()
+                .unwrap();
+            children.retain(|c| {
+                c["id"]
+                    .as_u64()
+                    .map(|id| node_table.contains_key(&(id as u32)))
+                    .unwrap_or(false)
+            });
+            let mut id_set: BTreeSet<u64> =
+                children.iter().filter_map(|c| c["id"].as_u64()).collect();
+
+            for (side_root, side_map) in side_root_list.iter().zip(side_map_list) {
+                let side_entry = side_root["stb_model"][a][b]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .find(|e| e["name"] == name);
+                let side_children = match side_entry {
+                    Some(e) => e["stb_node_id_list"]["children"].as_array().unwrap(),
+                    None => continue,
+                };
+                for child in side_children {
+                    let id = match child["id"]
+                        .as_u64()
+                        .and_then(|id| side_map.node.get(&(id as u32)))
+                    {
+                        Some(&id) => id,
+                        None => continue,
+                    };
+                    if node_table.contains_key(&id) && id_set.insert(id as u64) {
+                        let mut child = child.clone();
+                        child["id"] = Value::from(id);
+                        children.push(child);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Splits a serialized enum such as {"StbGirder": {...}} into its tag and fields. Nodes and steel
+// shapes without a tag come back with an empty one.
+fn tag(value: &Value) -> (String, Map<String, Value>) {
+    match value.as_object() {
+        Some(map) if map.len() == 1 && map.values().all(|v| v.is_object()) => {
+            let (tag, inner) = map.iter().next().unwrap();
+            (tag.clone(), inner.as_object().unwrap().clone())
+        }
+        Some(map) => (String::new(), map.clone()),
+        None => (String::new(), Map::new()),
+    }
+}
+
+fn tag_only(value: &Value, inner: Value) -> Value {
+    let (tag, _) = tag(value);
+    if tag.is_empty() {
+        return inner;
+    }
+    let mut map = Map::new();
+    map.insert(tag, inner);
+    Value::Object(map)
+}
+
+fn inner_mut(value: &mut Value) -> &mut Map<String, Value> {
+    let is_tagged = matches!(value.as_object(), Some(map) if map.len() == 1 && map.values().all(|v| v.is_object()));
+    let object = value.as_object_mut().unwrap();
+    if is_tagged {
+        object.values_mut().next().unwrap().as_object_mut().unwrap()
+    } else {
+        object
+    }
+}
+
+fn to_value<T: Serialize>(t: &T) -> Value {
+    serde_json::to_value(t).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn portal_frame() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        ))
+        .unwrap()
+    }
+
+    fn edit(st_bridge: &StBridge, f: impl FnOnce(&mut Value)) -> StBridge {
+        let mut value = to_value(st_bridge);
+        f(&mut value["stb_model"]);
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn member(result: &MergeResult, container: &str, id: u32) -> Value {
+        let value = to_value(&result.st_bridge);
+        value["stb_model"]["stb_members"][container][id.to_string()].clone()
+    }
+
+    fn added_girder(model: &mut Value, rotate: f64) {
+        let mut girder = model["stb_members"]["stb_girders"]["3"].clone();
+        let inner = inner_mut(&mut girder);
+        inner.insert("id".to_string(), Value::from(5));
+        inner.insert("id_node_start".to_string(), Value::from(1));
+        inner.insert("id_node_end".to_string(), Value::from(2));
+        inner.insert("rotate".to_string(), Value::from(rotate));
+        model["stb_members"]["stb_girders"]["5"] = girder;
+    }
+
+    #[test]
+    fn both_modified() {
+        let base = portal_frame();
+        let ours = edit(&base, |m| {
+            m["stb_members"]["stb_girders"]["3"]["StbGirder"]["rotate"] = Value::from(10.0)
+        });
+        let theirs = edit(&base, |m| {
+            m["stb_members"]["stb_girders"]["3"]["StbGirder"]["rotate"] = Value::from(20.0)
+        });
+
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflict_list.len(), 1);
+        let conflict = &result.conflict_list[0];
+        assert_eq!(conflict.kind, ConflictKind::BothModified);
+        assert_eq!(conflict.element_id, Some(3));
+        assert_eq!(conflict.field, "rotate");
+        assert_eq!(
+            member(&result, "stb_girders", 3)["StbGirder"]["rotate"],
+            0.0
+        );
+
+        let resolution_list = [Resolution {
+            conflict_id: 0,
+            side: Side::Theirs,
+        }];
+        let result = merge_with_resolutions(&base, &ours, &theirs, &resolution_list);
+        assert!(result.is_resolved());
+        assert_eq!(
+            member(&result, "stb_girders", 3)["StbGirder"]["rotate"],
+            20.0
+        );
+    }
+
+    #[test]
+    fn modified_and_removed() {
+        let base = portal_frame();
+        let ours = edit(&base, |m| {
+            m["stb_members"]["stb_braces"]["4"]["StbBrace"]["rotate"] = Value::from(90.0)
+        });
+        let theirs = edit(&base, |m| {
+            m["stb_members"]["stb_braces"]
+                .as_object_mut()
+                .unwrap()
+                .remove("4");
+        });
+
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflict_list.len(), 1);
+        assert_eq!(
+            result.conflict_list[0].kind,
+            ConflictKind::ModifiedAndRemoved
+        );
+        assert_eq!(result.conflict_list[0].theirs, Value::Null);
+        assert_eq!(member(&result, "stb_braces", 4)["StbBrace"]["rotate"], 0.0);
+
+        let resolution_list = [Resolution {
+            conflict_id: 0,
+            side: Side::Theirs,
+        }];
+        let result = merge_with_resolutions(&base, &ours, &theirs, &resolution_list);
+        assert_eq!(member(&result, "stb_braces", 4), Value::Null);
+    }
+
+    #[test]
+    fn both_added() {
+        let base = portal_frame();
+        let ours = edit(&base, |m| added_girder(m, 10.0));
+        let theirs = edit(&base, |m| added_girder(m, 20.0));
+
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflict_list.len(), 1);
+        assert_eq!(result.conflict_list[0].kind, ConflictKind::BothAdded);
+        assert_eq!(
+            member(&result, "stb_girders", 5)["StbGirder"]["rotate"],
+            10.0
+        );
+
+        let same = merge(&base, &ours, &ours);
+        assert!(same.conflict_list.is_empty());
+        assert_eq!(member(&same, "stb_girders", 6), Value::Null);
+    }
+
+    #[test]
+    fn dangling_section() {
+        // Theirs removes the brace section and moves the brace to the girder section, while ours
+        // puts the girder on the removed section.
+        let base = portal_frame();
+        let ours = edit(&base, |m| {
+            m["stb_members"]["stb_girders"]["3"]["StbGirder"]["id_section"] = Value::from(3)
+        });
+        let theirs = edit(&base, |m| {
+            m["stb_sections"]["brace_s_map"]
+                .as_object_mut()
+                .unwrap()
+                .remove("3");
+            m["stb_members"]["stb_braces"]["4"]["StbBrace"]["id_section"] = Value::from(2);
+        });
+
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflict_list.len(), 1);
+        let conflict = &result.conflict_list[0];
+        assert_eq!(conflict.kind, ConflictKind::DanglingReference);
+        assert_eq!(conflict.element, "StbGirder");
+        assert_eq!(conflict.element_id, Some(3));
+        assert_eq!(conflict.field, "id_section");
+        assert_eq!(
+            [&conflict.base, &conflict.ours, &conflict.theirs],
+            [&Value::from(2), &Value::from(3), &Value::from(2)]
+        );
+        assert_eq!(
+            member(&result, "stb_girders", 3)["StbGirder"]["id_section"],
+            2
+        );
+        assert_eq!(
+            member(&result, "stb_braces", 4)["StbBrace"]["id_section"],
+            2
+        );
+    }
+
+    #[test]
+    fn dangling_node() {
+        // Ours adds a girder on a node theirs removes along with its column.
+        let base = portal_frame();
+        let ours = edit(&base, |m| added_girder(m, 0.0));
+        let theirs = edit(&base, |m| {
+            m["stb_nodes"]["map"].as_object_mut().unwrap().remove("2");
+            m["stb_members"]["stb_columns"]
+                .as_object_mut()
+                .unwrap()
+                .remove("2");
+        });
+
+        let result = merge(&base, &ours, &theirs);
+        assert_eq!(result.conflict_list.len(), 1);
+        let conflict = &result.conflict_list[0];
+        assert_eq!(conflict.kind, ConflictKind::DanglingReference);
+        assert_eq!(conflict.element_id, Some(5));
+        assert_eq!(conflict.field, "id_node_end");
+        assert_eq!(conflict.base, Value::Null);
+        assert_eq!(member(&result, "stb_girders", 5), Value::Null);
+
+        let resolution_list = [Resolution {
+            conflict_id: 0,
+            side: Side::Ours,
+        }];
+        let result = merge_with_resolutions(&base, &ours, &theirs, &resolution_list);
+        assert!(result.is_resolved());
+        assert_eq!(
+            member(&result, "stb_girders", 5)["StbGirder"]["id_node_end"],
+            2
+        );
+
+        // Keeping ours brings node 2 back with it, while the column theirs removed stays gone.
+        let node = result.st_bridge.stb_model.stb_nodes.get(2).unwrap();
+        assert_eq!((node.x, node.y, node.z), (6000.0, 0.0, 0.0));
+        assert_eq!(member(&result, "stb_columns", 2), Value::Null);
+    }
+}
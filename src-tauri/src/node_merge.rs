@@ -0,0 +1,337 @@
+use crate::st_bridge::stb_model::stb_axes_and_stories::StbNodeIdList;
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+// The lowest id of a group survives a merge.
+#[derive(Debug, Serialize)]
+pub struct DuplicateNodeGroup {
+    pub id: u32,
+    pub duplicate_id_list: Vec<u32>,
+}
+
+impl DuplicateNodeGroup {
+    pub fn node_id_list(&self) -> Vec<u32> {
+        let mut list = vec![self.id];
+        list.extend(self.duplicate_id_list.iter().cloned());
+        list
+    }
+}
+
+// Node matching hashes coordinates into cells of the tolerance size, which needs a positive
+// finite tolerance. Cells of far away nodes saturate and share a cell, where the distance check
+// still tells them apart.
+pub fn check_tolerance(tolerance: f64) -> Result<(), String> {
+    if tolerance.is_finite() && tolerance > 0.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "tolerance must be a positive number, got {}",
+            tolerance
+        ))
+    }
+}
+
+// Hashes nodes into cells of the tolerance size, so each node only has to be compared against
+// the nodes of its own and the 26 neighbouring cells.
+pub fn find_duplicate_nodes(
+    st_bridge: &StBridge,
+    tolerance: f64,
+) -> Result<Vec<DuplicateNodeGroup>, String> {
+    check_tolerance(tolerance)?;
+    let map = &st_bridge.stb_model.stb_nodes.map;
+
+    let cell = |v: f64| (v / tolerance).floor() as i64;
+    let mut grid: HashMap<(i64, i64, i64), Vec<u32>> = HashMap::new();
+    for (&id, node) in map {
+        grid.entry((cell(node.x), cell(node.y), cell(node.z)))
+            .or_default()
+            .push(id);
+    }
+
+    let mut id_list: Vec<u32> = map.keys().cloned().collect();
+    id_list.sort_unstable();
+
+    let mut grouped: BTreeSet<u32> = BTreeSet::new();
+    let mut group_list = Vec::new();
+
+    for id in id_list {
+        if grouped.contains(&id) {
+            continue;
+        }
+
+        let node = &map[&id];
+        let (cx, cy, cz) = (cell(node.x), cell(node.y), cell(node.z));
+        let mut duplicate_id_list = Vec::new();
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let key = (
+                        cx.saturating_add(dx),
+                        cy.saturating_add(dy),
+                        cz.saturating_add(dz),
+                    );
+                    if let Some(list) = grid.get(&key) {
+                        for &other in list {
+                            if other <= id || grouped.contains(&other) {
+                                continue;
+                            }
+                            let n = &map[&other];
+                            let distance = ((n.x - node.x).powi(2)
+                                + (n.y - node.y).powi(2)
+                                + (n.z - node.z).powi(2))
+                            .sqrt();
+                            if distance < tolerance {
+                                duplicate_id_list.push(other);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if !duplicate_id_list.is_empty() {
+            duplicate_id_list.sort_unstable();
+            grouped.insert(id);
+            grouped.extend(duplicate_id_list.iter().cloned());
+            group_list.push(DuplicateNodeGroup {
+                id,
+                duplicate_id_list,
+            });
+        }
+    }
+
+    Ok(group_list)
+}
+
+fn rewrite_id(id: &mut u32, survivor: &HashMap<u32, u32>) {
+    if let Some(&s) = survivor.get(id) {
+        *id = s;
+    }
+}
+
+// Axis and story lists keep the first occurrence of each survivor.
+fn rewrite_node_id_list(stb_node_id_list: &mut StbNodeIdList, survivor: &HashMap<u32, u32>) {
+    for n in stb_node_id_list.children.iter_mut() {
+        if let Some(&id) = survivor.get(&(n.id as u32)) {
+            n.id = id as i32;
+        }
+    }
+    let mut seen = BTreeSet::new();
+    stb_node_id_list.children.retain(|n| seen.insert(n.id));
+}
+
+// Polygon outlines only lose the repeats of merged neighbouring corners, including the pair
+// across the closing edge.
+fn rewrite_polygon(stb_node_id_list: &mut StbNodeIdList, survivor: &HashMap<u32, u32>) {
+    let children = &mut stb_node_id_list.children;
+    for n in children.iter_mut() {
+        if let Some(&id) = survivor.get(&(n.id as u32)) {
+            n.id = id as i32;
+        }
+    }
+    children.dedup_by_key(|n| n.id);
+    if children.len() > 1 && children.first().map(|n| n.id) == children.last().map(|n| n.id) {
+        children.pop();
+    }
+}
+
+// Merges every group of coincident nodes into its surviving node and returns the groups merged.
+// Members, axes and stories are rewritten to the survivor, which also takes over id_member when
+// it has none of its own.
+pub fn merge_duplicate_nodes(
+    st_bridge: &mut StBridge,
+    tolerance: f64,
+) -> Result<Vec<DuplicateNodeGroup>, String> {
+    let group_list = find_duplicate_nodes(st_bridge, tolerance)?;
+
+    let mut survivor: HashMap<u32, u32> = HashMap::new();
+    for group in &group_list {
+        for &duplicate in &group.duplicate_id_list {
+            survivor.insert(duplicate, group.id);
+        }
+    }
+    if survivor.is_empty() {
+        return Ok(group_list);
+    }
+
+    let stb_model = &mut st_bridge.stb_model;
+
+    for group in &group_list {
+        let mut id_member = None;
+        for duplicate in &group.duplicate_id_list {
+            if let Some(node) = stb_model.stb_nodes.map.remove(duplicate) {
+                id_member = id_member.or(node.id_member);
+            }
+        }
+        if let Some(node) = stb_model.stb_nodes.map.get_mut(&group.id) {
+            node.id_member = node.id_member.or(id_member);
+        }
+    }
+
+    let stb_members = &mut stb_model.stb_members;
+    for stb_member in stb_members
+        .stb_columns
+        .values_mut()
+        .chain(stb_members.stb_posts.values_mut())
+        .chain(stb_members.stb_girders.values_mut())
+        .chain(stb_members.stb_beams.values_mut())
+        .chain(stb_members.stb_braces.values_mut())
+        .chain(stb_members.stb_slabs.values_mut())
+        .chain(stb_members.stb_walls.values_mut())
+    {
+        match stb_member {
+            StbMember::StbColumn {
+                id_node_bottom,
+                id_node_top,
+                ..
+            }
+            | StbMember::StbPost {
+                id_node_bottom,
+                id_node_top,
+                ..
+            } => {
+                rewrite_id(id_node_bottom, &survivor);
+                rewrite_id(id_node_top, &survivor);
+            }
+            StbMember::StbGirder {
+                id_node_start,
+                id_node_end,
+                ..
+            }
+            | StbMember::StbBeam {
+                id_node_start,
+                id_node_end,
+                ..
+            }
+            | StbMember::StbBrace {
+                id_node_start,
+                id_node_end,
+                ..
+            } => {
+                rewrite_id(id_node_start, &survivor);
+                rewrite_id(id_node_end, &survivor);
+            }
+            StbMember::StbSlab {
+                stb_node_id_list, ..
+            }
+            | StbMember::StbWall {
+                stb_node_id_list, ..
+            } => {
+                rewrite_polygon(stb_node_id_list, &survivor);
+            }
+            StbMember::StbOpen { .. } => {}
+        }
+    }
+
+    for a in stb_model.stb_axes.stb_x_axis_list.iter_mut() {
+        rewrite_node_id_list(&mut a.stb_node_id_list, &survivor);
+    }
+    for a in stb_model.stb_axes.stb_y_axis_list.iter_mut() {
+        rewrite_node_id_list(&mut a.stb_node_id_list, &survivor);
+    }
+    for s in stb_model.stb_stories.stb_story_list.iter_mut() {
+        rewrite_node_id_list(&mut s.stb_node_id_list, &survivor);
+    }
+
+    Ok(group_list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    // Adds a copy of node 9 moved along x.
+    fn add_near_node_9(st_bridge: &mut StBridge, id: u32, dx: f64) {
+        let mut node = st_bridge.stb_model.stb_nodes.get(9).unwrap();
+        node.x += dx;
+        st_bridge.stb_model.stb_nodes.insert(id, node);
+    }
+
+    fn group_of(group_list: &[DuplicateNodeGroup], id: u32) -> Option<Vec<u32>> {
+        group_list
+            .iter()
+            .map(|g| g.node_id_list())
+            .find(|list| list.contains(&id))
+    }
+
+    #[test]
+    fn tolerance() {
+        let mut st_bridge = sample();
+        add_near_node_9(&mut st_bridge, 9000, 0.25);
+        add_near_node_9(&mut st_bridge, 9001, 0.75);
+
+        let group_list = find_duplicate_nodes(&st_bridge, 0.5).unwrap();
+        assert_eq!(group_of(&group_list, 9), Some(vec![9, 9000]));
+        assert_eq!(group_of(&group_list, 9001), None);
+
+        let group_list = find_duplicate_nodes(&st_bridge, 1.0).unwrap();
+        assert_eq!(group_of(&group_list, 9), Some(vec![9, 9000, 9001]));
+
+        // Nodes exactly the tolerance apart are not duplicates.
+        let group_list = find_duplicate_nodes(&st_bridge, 0.75).unwrap();
+        assert_eq!(group_of(&group_list, 9), Some(vec![9, 9000]));
+    }
+
+    #[test]
+    fn rejects_bad_tolerances() {
+        let mut st_bridge = sample();
+        for tolerance in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(find_duplicate_nodes(&st_bridge, tolerance).is_err());
+            assert!(merge_duplicate_nodes(&mut st_bridge, tolerance).is_err());
+        }
+    }
+
+    #[test]
+    fn neighbouring_cells() {
+        let mut st_bridge = sample();
+        let mut node = st_bridge.stb_model.stb_nodes.get(9).unwrap();
+        node.x = 99.99;
+        st_bridge.stb_model.stb_nodes.insert(9000, node.clone());
+        node.x = 100.01;
+        st_bridge.stb_model.stb_nodes.insert(9001, node);
+
+        let group_list = find_duplicate_nodes(&st_bridge, 1.0).unwrap();
+
+        assert_eq!(group_of(&group_list, 9000), Some(vec![9000, 9001]));
+    }
+
+    #[test]
+    fn merge_rewrites_references() {
+        let mut st_bridge = sample();
+        add_near_node_9(&mut st_bridge, 9000, 0.4);
+        if let Some(StbMember::StbColumn { id_node_bottom, .. }) =
+            st_bridge.stb_model.stb_members.stb_columns.get_mut(&1)
+        {
+            *id_node_bottom = 9000;
+        }
+
+        let group_list = merge_duplicate_nodes(&mut st_bridge, 1.0).unwrap();
+
+        assert_eq!(group_list.len(), 66);
+        assert!(find_duplicate_nodes(&st_bridge, 1.0).unwrap().is_empty());
+        assert!(st_bridge.stb_model.stb_nodes.get(9000).is_none());
+        assert_eq!(
+            st_bridge.stb_model.stb_members.stb_columns[&1].node_i(),
+            Some(9)
+        );
+
+        let stb_nodes = &st_bridge.stb_model.stb_nodes;
+        for stb_member in st_bridge.stb_model.stb_members.iter() {
+            for id in stb_member.node_id_list() {
+                assert!(stb_nodes.get(id).is_some(), "{} was removed", id);
+            }
+        }
+    }
+}
@@ -0,0 +1,595 @@
+use crate::drawing::{bounds, escape_xml, Drawing, Pen};
+use crate::geometry::beam_rc_size;
+use crate::st_bridge::stb_model::stb_sections::{
+    bar_text, layered_bar_text, pitched_bar_text, StbSecBarArrangementColumn, StbSection,
+};
+use crate::st_bridge::StBridge;
+use crate::takeoff::csv_line;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+// Sketches fit in a square of this size on paper (mm).
+const SKETCH_SIZE: f64 = 24.0;
+
+pub struct ScheduleRow {
+    pub cell_list: Vec<String>,
+    pub sketch: Option<Drawing>,
+}
+
+// One section list. The sketch goes in the last column of the HTML table and is left out of the
+// CSV.
+pub struct Schedule {
+    pub name: String,
+    pub title: String,
+    pub column_list: Vec<String>,
+    pub row_list: Vec<ScheduleRow>,
+}
+
+impl Schedule {
+    fn new(name: &str, title: &str, column_list: &[&str]) -> Schedule {
+        Schedule {
+            name: name.to_string(),
+            title: title.to_string(),
+            column_list: column_list.iter().map(|c| c.to_string()).collect(),
+            row_list: Vec::new(),
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_line(&self.column_list);
+        for row in &self.row_list {
+            csv += &csv_line(&row.cell_list);
+        }
+        csv
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        writeln!(html, "<h2>{}</h2>", escape_xml(&self.title)).unwrap();
+        html += "<table>\n<tr>";
+        for column in &self.column_list {
+            write!(html, "<th>{}</th>", escape_xml(column)).unwrap();
+        }
+        html += "<th>Sketch</th></tr>\n";
+
+        for row in &self.row_list {
+            html += "<tr>";
+            for cell in &row.cell_list {
+                write!(html, "<td>{}</td>", escape_xml(cell)).unwrap();
+            }
+            html += "<td>";
+            if let Some(sketch) = &row.sketch {
+                html += &sketch.to_svg();
+            }
+            html += "</td></tr>\n";
+        }
+
+        html += "</table>\n";
+        html
+    }
+}
+
+pub fn schedules_to_html(schedule_list: &[Schedule]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Section list</title>\n\
+         <style>\ntable { border-collapse: collapse; margin-bottom: 2em; }\n\
+         th, td { border: 1px solid #888; padding: 2px 6px; text-align: center; }\n</style>\n\
+         </head>\n<body>\n",
+    );
+    for schedule in schedule_list {
+        html += &schedule.to_html();
+    }
+    html += "</body>\n</html>\n";
+    html
+}
+
+// A .csv file name gets one CSV per schedule, named after the schedule. Anything else gets a
+// single HTML page.
+pub fn write_section_schedules(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let schedule_list = section_schedule_list(st_bridge);
+
+    if !file_name.to_lowercase().ends_with(".csv") {
+        let mut f = File::create(file_name)?;
+        return f.write_all(schedules_to_html(&schedule_list).as_bytes());
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    for schedule in &schedule_list {
+        let mut f = File::create(path.with_file_name(format!("{}_{}.csv", stem, schedule.name)))?;
+        f.write_all(schedule.to_csv().as_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn section_schedule_list(st_bridge: &StBridge) -> Vec<Schedule> {
+    vec![
+        steel_column_schedule(st_bridge),
+        rc_column_schedule(st_bridge),
+        steel_beam_schedule(st_bridge),
+        rc_beam_schedule(st_bridge),
+    ]
+}
+
+// Floors in story order from the bottom, unknown and empty floors last, then by name.
+fn sorted_sections<'a>(
+    st_bridge: &StBridge,
+    section_list: impl Iterator<Item = &'a StbSection>,
+) -> Vec<(&'a StbSection, String)> {
+    let mut story_list: Vec<(f64, &str)> = st_bridge
+        .stb_model
+        .stb_stories
+        .stb_story_list
+        .iter()
+        .map(|s| (s.height, s.name.as_str()))
+        .collect();
+    story_list.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut list: Vec<(&StbSection, String)> = section_list
+        .map(|s| {
+            let floor = match s {
+                StbSection::StbSecColumnRC { floor, .. }
+                | StbSection::StbSecColumnS { floor, .. }
+                | StbSection::StbSecBeamRC { floor, .. }
+                | StbSection::StbSecBeamS { floor, .. }
+                | StbSection::StbSecBraceS { floor, .. } => floor.clone(),
+                _ => String::new(),
+            };
+            (s, floor)
+        })
+        .collect();
+    list.sort_by_key(|(s, floor)| {
+        let index = story_list
+            .iter()
+            .position(|(_, name)| name == floor)
+            .unwrap_or(usize::MAX);
+        (index, floor.clone(), s.name(), s.id())
+    });
+    list
+}
+
+fn rectangle(width: f64, depth: f64) -> Vec<(f64, f64)> {
+    vec![
+        (-width / 2.0, -depth / 2.0),
+        (width / 2.0, -depth / 2.0),
+        (width / 2.0, depth / 2.0),
+        (-width / 2.0, depth / 2.0),
+    ]
+}
+
+// The section outline scaled to fit the sketch square, filled, with its holes left white.
+fn sketch(name: &str, outline: &[(f64, f64)], holes: &[Vec<(f64, f64)>]) -> Option<Drawing> {
+    let (x0, y0, x1, y1) = bounds(outline);
+    let extent = (x1 - x0).max(y1 - y0);
+    if outline.len() < 3 || extent <= 0.0 {
+        return None;
+    }
+
+    let margin = 2.0;
+    let scale = SKETCH_SIZE / extent;
+    let center = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+    let to_paper = |&(x, y): &(f64, f64)| {
+        (
+            margin + SKETCH_SIZE / 2.0 + (x - center.0) * scale,
+            margin + SKETCH_SIZE / 2.0 + (y - center.1) * scale,
+        )
+    };
+
+    let size = SKETCH_SIZE + 2.0 * margin;
+    let mut drawing = Drawing::new(name, size, size);
+    drawing.polyline(
+        outline.iter().map(to_paper).collect(),
+        true,
+        Pen::new([0, 0, 0], 0.2).filled([200, 200, 200]),
+    );
+    for hole in holes {
+        drawing.polyline(
+            hole.iter().map(to_paper).collect(),
+            true,
+            Pen::new([0, 0, 0], 0.2).filled([255, 255, 255]),
+        );
+    }
+    Some(drawing)
+}
+
+fn steel_sketch(st_bridge: &StBridge, name: &str, shape: &str) -> Option<Drawing> {
+    let children = st_bridge.stb_model.stb_sections.stb_sec_steel.get(shape)?;
+    sketch(name, &children.shape(), &children.holes())
+}
+
+pub fn steel_column_schedule(st_bridge: &StBridge) -> Schedule {
+    let mut schedule = Schedule::new(
+        "steel_columns",
+        "Steel columns",
+        &["Floor", "Name", "Shape", "Grade", "Web grade", "Base type"],
+    );
+
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    for (stb_section, floor) in sorted_sections(st_bridge, stb_sections.column_s_map.values()) {
+        if let StbSection::StbSecColumnS {
+            name,
+            base_type,
+            stb_sec_steel_column,
+            ..
+        } = stb_section
+        {
+            schedule.row_list.push(ScheduleRow {
+                cell_list: vec![
+                    floor,
+                    name.clone(),
+                    stb_sec_steel_column.shape.clone(),
+                    stb_sec_steel_column.strength_main.clone(),
+                    stb_sec_steel_column.strength_web.clone(),
+                    base_type.to_string(),
+                ],
+                sketch: steel_sketch(st_bridge, name, &stb_sec_steel_column.shape),
+            });
+        }
+    }
+
+    schedule
+}
+
+pub fn rc_column_schedule(st_bridge: &StBridge) -> Schedule {
+    let mut schedule = Schedule::new(
+        "rc_columns",
+        "RC columns",
+        &[
+            "Floor",
+            "Name",
+            "b x D",
+            "Concrete",
+            "Main bars",
+            "Hoops",
+            "Bar grade",
+        ],
+    );
+
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    for (stb_section, floor) in sorted_sections(st_bridge, stb_sections.column_rc_map.values()) {
+        if let StbSection::StbSecColumnRC {
+            name,
+            d_reinforcement_main,
+            d_rein_band,
+            strength_concrete,
+            strength_reinforcement_main,
+            strength_rein_band,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } = stb_section
+        {
+            let size = match (&stb_sec_figure.stb_sec_rect, &stb_sec_figure.stb_sec_circle) {
+                (Some(rect), _) => format!("{}x{}", rect.width_x, rect.width_y),
+                (None, Some(circle)) => format!("φ{}", circle.d),
+                (None, None) => String::new(),
+            };
+
+            let (main, hoop) = match stb_sec_bar_arrangement {
+                StbSecBarArrangementColumn {
+                    stb_sec_rect_column_same: Some(same),
+                    ..
+                } => (
+                    bar_text(same.count_main_total, d_reinforcement_main),
+                    format!(
+                        "{}x{}-{}@{}",
+                        same.count_band_dir_x, same.count_band_dir_y, d_rein_band, same.pitch_band
+                    ),
+                ),
+                StbSecBarArrangementColumn {
+                    stb_sec_circle_column_same: Some(same),
+                    ..
+                } => (
+                    bar_text(same.count_main, d_reinforcement_main),
+                    pitched_bar_text(same.count_band, d_rein_band, same.pitch_band),
+                ),
+                _ => (String::new(), String::new()),
+            };
+
+            schedule.row_list.push(ScheduleRow {
+                cell_list: vec![
+                    floor,
+                    name.clone(),
+                    size,
+                    strength_concrete.clone().unwrap_or_default(),
+                    main,
+                    hoop,
+                    format!("{} / {}", strength_reinforcement_main, strength_rein_band),
+                ],
+                sketch: stb_sec_figure
+                    .shape()
+                    .and_then(|outline| sketch(name, &outline, &[])),
+            });
+        }
+    }
+
+    schedule
+}
+
+// One row per shape: ALL, or one for each position of a haunched beam.
+pub fn steel_beam_schedule(st_bridge: &StBridge) -> Schedule {
+    let mut schedule = Schedule::new(
+        "steel_beams",
+        "Steel girders and beams",
+        &[
+            "Floor",
+            "Name",
+            "Kind",
+            "Position",
+            "Shape",
+            "Grade",
+            "Web grade",
+        ],
+    );
+
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    for (stb_section, floor) in sorted_sections(st_bridge, stb_sections.beam_s_map.values()) {
+        if let StbSection::StbSecBeamS {
+            name,
+            kind_beam,
+            stb_sec_steel_beam_list,
+            ..
+        } = stb_section
+        {
+            for steel in stb_sec_steel_beam_list {
+                schedule.row_list.push(ScheduleRow {
+                    cell_list: vec![
+                        floor.clone(),
+                        name.clone(),
+                        kind_beam.to_string(),
+                        steel.pos.to_string(),
+                        steel.shape.clone(),
+                        steel.strength_main.clone(),
+                        steel.strength_web.clone(),
+                    ],
+                    sketch: steel_sketch(st_bridge, name, &steel.shape),
+                });
+            }
+        }
+    }
+
+    schedule
+}
+
+// One row per arranged position: START, CENTER and END, or ALL when the section is the same
+// throughout.
+pub fn rc_beam_schedule(st_bridge: &StBridge) -> Schedule {
+    let mut schedule = Schedule::new(
+        "rc_beams",
+        "RC girders and beams",
+        &[
+            "Floor",
+            "Name",
+            "Kind",
+            "Position",
+            "b x D",
+            "Concrete",
+            "Top bars",
+            "Bottom bars",
+            "Stirrups",
+            "Web bars",
+            "Bar spacers",
+            "Bar grade",
+        ],
+    );
+
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+    for (stb_section, floor) in sorted_sections(st_bridge, stb_sections.beam_rc_map.values()) {
+        if let StbSection::StbSecBeamRC {
+            name,
+            kind_beam,
+            d_reinforcement_main,
+            d_stirrup,
+            d_reinforcement_web,
+            d_bar_spacing,
+            strength_concrete,
+            strength_reinforcement_main,
+            strength_stirrup,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } = stb_section
+        {
+            for a in stb_sec_bar_arrangement.beam_bars_list() {
+                let size = match &stb_sec_figure.stb_sec_haunch {
+                    Some(haunch) => Some(haunch.size(&a.position)),
+                    None => beam_rc_size(stb_sec_figure),
+                };
+
+                schedule.row_list.push(ScheduleRow {
+                    cell_list: vec![
+                        floor.clone(),
+                        name.clone(),
+                        kind_beam.to_string(),
+                        a.position.clone(),
+                        size.map(|(b, d)| format!("{}x{}", b, d))
+                            .unwrap_or_default(),
+                        strength_concrete.clone().unwrap_or_default(),
+                        layered_bar_text(a.top_1st, a.top_2nd, d_reinforcement_main),
+                        layered_bar_text(a.bottom_1st, a.bottom_2nd, d_reinforcement_main),
+                        pitched_bar_text(a.stirrup, d_stirrup, a.pitch_stirrup),
+                        bar_text(a.web, d_reinforcement_web),
+                        pitched_bar_text(a.bar_spacing, d_bar_spacing, a.pitch_bar_spacing),
+                        format!("{} / {}", strength_reinforcement_main, strength_stirrup),
+                    ],
+                    sketch: size.and_then(|(b, d)| sketch(name, &rectangle(b, d), &[])),
+                });
+            }
+        }
+    }
+
+    schedule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+    use crate::st_bridge::stb_model::stb_sections::{StbSecSteelBeam, StbSecSteelBeamPosition};
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn schedule<'a>(schedule_list: &'a [Schedule], name: &str) -> &'a Schedule {
+        schedule_list.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn steel_columns_in_story_order() {
+        let schedule_list = section_schedule_list(&sample());
+        let schedule = schedule(&schedule_list, "steel_columns");
+
+        assert_eq!(schedule.row_list.len(), 24);
+        assert!(schedule.row_list.iter().all(|r| r.sketch.is_some()));
+
+        let floor_list: Vec<&str> = schedule
+            .row_list
+            .iter()
+            .map(|r| r.cell_list[0].as_str())
+            .collect();
+        let mut sorted = floor_list.clone();
+        sorted.dedup();
+        assert_eq!(sorted, ["1", "2", "3", "R"]);
+
+        assert_eq!(
+            schedule.row_list[0].cell_list,
+            ["1", "C1", "□-400x400x22x22", "SN490", "SN490", ""]
+        );
+        assert_eq!(schedule.row_list[18].cell_list[1], "C10");
+    }
+
+    #[test]
+    fn rc_beams_by_position() {
+        let schedule_list = section_schedule_list(&sample());
+        assert!(schedule(&schedule_list, "rc_columns").row_list.is_empty());
+
+        let schedule = schedule(&schedule_list, "rc_beams");
+        let row_list: Vec<Vec<&str>> = schedule
+            .row_list
+            .iter()
+            .map(|r| r.cell_list.iter().map(|c| c.as_str()).collect())
+            .collect();
+
+        assert_eq!(row_list.len(), 7);
+        assert_eq!(row_list[0][1..4], ["FB1", "BEAM", "ALL"]);
+        assert_eq!(
+            row_list[1][1..],
+            [
+                "FG1",
+                "GIRDER",
+                "START",
+                "500x900",
+                "FC24",
+                "5-D25",
+                "4-D25",
+                "2-D13@200",
+                "2-D13",
+                "1-D10@1000",
+                "SD345 / SD295A"
+            ]
+        );
+        assert_eq!(row_list[2][3..7], ["CENTER", "500x900", "FC24", "3-D25"]);
+        assert_eq!(row_list[3][3], "END");
+    }
+
+    // G1 on the 2nd floor of the sample, deeper at its ends.
+    fn haunched_sample() -> StBridge {
+        let mut st_bridge = sample();
+        let stb_section = st_bridge
+            .stb_model
+            .stb_sections
+            .beam_s_map
+            .get_mut(&5)
+            .unwrap();
+        if let StbSection::StbSecBeamS {
+            stb_sec_steel_beam_list,
+            ..
+        } = stb_section
+        {
+            let steel = |pos: StbSecSteelBeamPosition, shape: &str| StbSecSteelBeam {
+                pos,
+                shape: shape.to_string(),
+                strength_main: "SN490".to_string(),
+                strength_web: "SN400".to_string(),
+            };
+            *stb_sec_steel_beam_list = vec![
+                steel(StbSecSteelBeamPosition::Start, "H-700x300x13x24x28"),
+                steel(StbSecSteelBeamPosition::Center, "H-588x300x12x20x28"),
+                steel(StbSecSteelBeamPosition::End, "H-700x300x13x24x28"),
+            ];
+        }
+        st_bridge
+    }
+
+    #[test]
+    fn haunched_steel_beam_by_position() {
+        let schedule_list = section_schedule_list(&haunched_sample());
+        let schedule = schedule(&schedule_list, "steel_beams");
+
+        let row_list: Vec<&ScheduleRow> = schedule
+            .row_list
+            .iter()
+            .filter(|r| r.cell_list[0] == "2" && r.cell_list[1] == "G1")
+            .collect();
+        assert!(row_list.iter().all(|r| r.sketch.is_some()));
+        let row_list: Vec<&Vec<String>> = row_list.iter().map(|r| &r.cell_list).collect();
+        assert_eq!(
+            row_list,
+            [
+                &[
+                    "2",
+                    "G1",
+                    "GIRDER",
+                    "START",
+                    "H-700x300x13x24x28",
+                    "SN490",
+                    "SN400"
+                ],
+                &[
+                    "2",
+                    "G1",
+                    "GIRDER",
+                    "CENTER",
+                    "H-588x300x12x20x28",
+                    "SN490",
+                    "SN400"
+                ],
+                &[
+                    "2",
+                    "G1",
+                    "GIRDER",
+                    "END",
+                    "H-700x300x13x24x28",
+                    "SN490",
+                    "SN400"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_and_html() {
+        let schedule_list = section_schedule_list(&sample());
+        let schedule = schedule(&schedule_list, "steel_beams");
+
+        let csv = schedule.to_csv();
+        assert_eq!(csv.lines().count(), schedule.row_list.len() + 1);
+        assert!(csv.starts_with("Floor,Name,Kind,Position,Shape,Grade,Web grade\n"));
+        assert!(csv.contains("\n2,G1,GIRDER,ALL,H-700x300x13x24x28,SN400,SN400\n"));
+
+        let html = schedules_to_html(&schedule_list);
+        assert_eq!(html.matches("<table>").count(), 4);
+        assert_eq!(html.matches("<svg").count(), 24 + 22 + 7);
+        assert!(html.contains("<h2>Steel girders and beams</h2>"));
+    }
+}
@@ -0,0 +1,1055 @@
+use crate::st_bridge::stb_model::stb_sections::*;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::f64::consts::PI;
+
+const STEEL_DENSITY: f64 = 7.85e-6; // kg/mm3
+
+// Lengths in mm, weight in kg/m. x is the horizontal axis of the section outline, so for an
+// H-shape ix/zx/zpx are about the strong axis.
+#[derive(Debug, Serialize)]
+pub struct SteelSectionProperty {
+    pub name: String,
+    pub area: f64,
+    pub unit_weight: f64,
+    pub centroid_x: f64,
+    pub centroid_y: f64,
+    pub ix: f64,
+    pub iy: f64,
+    pub zx: f64,
+    pub zy: f64,
+    pub zpx: f64,
+    pub zpy: f64,
+    pub rx: f64,
+    pub ry: f64,
+    pub j: f64,
+    pub cw: f64,
+}
+
+// Sections are built up from rectangles and corner fillets, each added or removed.
+enum Part {
+    Rectangle {
+        x0: f64,
+        x1: f64,
+        y0: f64,
+        y1: f64,
+    },
+    Fillet {
+        x: f64,
+        y: f64,
+        dx: f64,
+        dy: f64,
+        r: f64,
+    },
+}
+
+struct SignedPart {
+    sign: f64,
+    part: Part,
+}
+
+impl Part {
+    fn area(&self) -> f64 {
+        match *self {
+            Part::Rectangle { x0, x1, y0, y1 } => (x1 - x0) * (y1 - y0),
+            Part::Fillet { r, .. } => (1.0 - PI / 4.0) * r * r,
+        }
+    }
+
+    // The fillet is the spandrel between the corner (x, y) and a quarter circle of radius r,
+    // lying towards (dx, dy).
+    fn centroid(&self) -> (f64, f64) {
+        match *self {
+            Part::Rectangle { x0, x1, y0, y1 } => ((x0 + x1) / 2.0, (y0 + y1) / 2.0),
+            Part::Fillet { x, y, dx, dy, r } => {
+                let e = fillet_centroid_distance(r);
+                (x + dx * e, y + dy * e)
+            }
+        }
+    }
+
+    fn own_inertia(&self) -> (f64, f64) {
+        match *self {
+            Part::Rectangle { x0, x1, y0, y1 } => {
+                let (w, h) = (x1 - x0, y1 - y0);
+                (w * h.powi(3) / 12.0, h * w.powi(3) / 12.0)
+            }
+            Part::Fillet { r, .. } => {
+                let e = fillet_centroid_distance(r);
+                let i = r.powi(4) * (1.0 - 5.0 * PI / 16.0) - self.area() * e * e;
+                (i, i)
+            }
+        }
+    }
+
+    fn bounds(&self) -> (f64, f64, f64, f64) {
+        match *self {
+            Part::Rectangle { x0, x1, y0, y1 } => (x0, x1, y0, y1),
+            Part::Fillet { x, y, dx, dy, r } => (
+                x.min(x + dx * r),
+                x.max(x + dx * r),
+                y.min(y + dy * r),
+                y.max(y + dy * r),
+            ),
+        }
+    }
+
+    // Area on the lower side of the line y = c (or x = c when vertical is true) and its first
+    // moment about that line. Fillets are small enough to be treated as lumped at the centroid.
+    fn split(&self, c: f64, vertical: bool) -> (f64, f64) {
+        match *self {
+            Part::Rectangle { x0, x1, y0, y1 } => {
+                let (lo, hi, w) = if vertical {
+                    (x0, x1, y1 - y0)
+                } else {
+                    (y0, y1, x1 - x0)
+                };
+                let top = hi.min(c);
+                if top <= lo {
+                    (0.0, 0.0)
+                } else {
+                    let area = (top - lo) * w;
+                    (area, area * (c - (lo + top) / 2.0))
+                }
+            }
+            Part::Fillet { .. } => {
+                let (cx, cy) = self.centroid();
+                let v = if vertical { cx } else { cy };
+                if v < c {
+                    (self.area(), self.area() * (c - v))
+                } else {
+                    (0.0, 0.0)
+                }
+            }
+        }
+    }
+}
+
+fn fillet_centroid_distance(r: f64) -> f64 {
+    r * (10.0 - 3.0 * PI) / (12.0 - 3.0 * PI)
+}
+
+fn rectangle(sign: f64, x0: f64, x1: f64, y0: f64, y1: f64) -> SignedPart {
+    SignedPart {
+        sign,
+        part: Part::Rectangle { x0, x1, y0, y1 },
+    }
+}
+
+fn fillet(sign: f64, x: f64, y: f64, dx: f64, dy: f64, r: f64) -> Vec<SignedPart> {
+    if r > 0.0 {
+        vec![SignedPart {
+            sign,
+            part: Part::Fillet { x, y, dx, dy, r },
+        }]
+    } else {
+        Vec::new()
+    }
+}
+
+// A w x h rectangle centred on the origin with its four corners rounded to radius r.
+fn rounded_rectangle(sign: f64, w: f64, h: f64, r: f64) -> Vec<SignedPart> {
+    let mut parts = vec![rectangle(sign, -w / 2.0, w / 2.0, -h / 2.0, h / 2.0)];
+    for &(sx, sy) in &[(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)] {
+        parts.extend(fillet(-sign, sx * w / 2.0, sy * h / 2.0, -sx, -sy, r));
+    }
+    parts
+}
+
+struct Composite {
+    area: f64,
+    centroid_x: f64,
+    centroid_y: f64,
+    ix: f64,
+    iy: f64,
+    zx: f64,
+    zy: f64,
+    zpx: f64,
+    zpy: f64,
+}
+
+fn composite(parts: &[SignedPart]) -> Composite {
+    let area: f64 = parts.iter().map(|p| p.sign * p.part.area()).sum();
+    let (sx, sy) = parts.iter().fold((0.0, 0.0), |(sx, sy), p| {
+        let (cx, cy) = p.part.centroid();
+        let a = p.sign * p.part.area();
+        (sx + a * cx, sy + a * cy)
+    });
+    let (centroid_x, centroid_y) = (sx / area, sy / area);
+
+    let (ix, iy) = parts.iter().fold((0.0, 0.0), |(ix, iy), p| {
+        let (cx, cy) = p.part.centroid();
+        let (ox, oy) = p.part.own_inertia();
+        let a = p.part.area();
+        (
+            ix + p.sign * (ox + a * (cy - centroid_y).powi(2)),
+            iy + p.sign * (oy + a * (cx - centroid_x).powi(2)),
+        )
+    });
+
+    let (x_min, x_max, y_min, y_max) = parts
+        .iter()
+        .filter(|p| p.sign > 0.0)
+        .map(|p| p.part.bounds())
+        .fold(
+            (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+            |(a, b, c, d), (x0, x1, y0, y1)| (a.min(x0), b.max(x1), c.min(y0), d.max(y1)),
+        );
+
+    let zx = ix / (y_max - centroid_y).max(centroid_y - y_min);
+    let zy = iy / (x_max - centroid_x).max(centroid_x - x_min);
+
+    Composite {
+        area,
+        centroid_x,
+        centroid_y,
+        ix,
+        iy,
+        zx,
+        zy,
+        zpx: plastic_modulus(parts, area, centroid_y, y_min, y_max, false),
+        zpy: plastic_modulus(parts, area, centroid_x, x_min, x_max, true),
+    }
+}
+
+// Finds the equal-area axis by bisection and sums the first moments of both halves about it.
+fn plastic_modulus(
+    parts: &[SignedPart],
+    area: f64,
+    centroid: f64,
+    min: f64,
+    max: f64,
+    vertical: bool,
+) -> f64 {
+    let below = |c: f64| -> (f64, f64) {
+        parts.iter().fold((0.0, 0.0), |(a, s), p| {
+            let (pa, ps) = p.part.split(c, vertical);
+            (a + p.sign * pa, s + p.sign * ps)
+        })
+    };
+
+    let (mut lo, mut hi) = (min, max);
+    for _ in 0..100 {
+        let mid = (lo + hi) / 2.0;
+        if below(mid).0 < area / 2.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let c = (lo + hi) / 2.0;
+
+    // The first moment of the whole section about c is area * (c - centroid), so the part above
+    // follows from the part below.
+    let (_, s_below) = below(c);
+    2.0 * s_below - area * (c - centroid)
+}
+
+pub fn steel_section_property(steel: &StbSecSteelChildren) -> SteelSectionProperty {
+    let (c, j, cw) = match *steel {
+        StbSecSteelChildren::StbSecRollH {
+            a, b, t1, t2, r, ..
+        } => h_shape(a, b, t1, t2, r),
+        StbSecSteelChildren::StbSecBuildH { a, b, t1, t2, .. } => h_shape(a, b, t1, t2, 0.0),
+        StbSecSteelChildren::StbSecRollBox { a, b, t, r, .. } => {
+            let mut parts = rounded_rectangle(1.0, b, a, r);
+            parts.extend(rounded_rectangle(
+                -1.0,
+                b - 2.0 * t,
+                a - 2.0 * t,
+                (r - t).max(0.0),
+            ));
+            (composite(&parts), box_torsion(a, b, t, t), 0.0)
+        }
+        StbSecSteelChildren::StbSecBuildBox { a, b, t1, t2, .. } => {
+            let parts = vec![
+                rectangle(1.0, -b / 2.0, b / 2.0, -a / 2.0, a / 2.0),
+                rectangle(
+                    -1.0,
+                    -b / 2.0 + t1,
+                    b / 2.0 - t1,
+                    -a / 2.0 + t2,
+                    a / 2.0 - t2,
+                ),
+            ];
+            (composite(&parts), box_torsion(a, b, t1, t2), 0.0)
+        }
+        StbSecSteelChildren::StbSecPipe { d, t, .. } => (pipe(d, t), 0.0, 0.0),
+        StbSecSteelChildren::StbSecRollL {
+            a,
+            b,
+            t1,
+            t2,
+            r1,
+            r2,
+            ..
+        } => l_shape(a, b, t1, t2, r1, r2),
+    };
+
+    let (j, cw) = match *steel {
+        StbSecSteelChildren::StbSecPipe { .. } => (c.ix + c.iy, 0.0),
+        _ => (j, cw),
+    };
+
+    SteelSectionProperty {
+        name: steel.name(),
+        area: c.area,
+        unit_weight: c.area * STEEL_DENSITY * 1000.0,
+        centroid_x: c.centroid_x,
+        centroid_y: c.centroid_y,
+        ix: c.ix,
+        iy: c.iy,
+        zx: c.zx,
+        zy: c.zy,
+        zpx: c.zpx,
+        zpy: c.zpy,
+        rx: (c.ix / c.area).sqrt(),
+        ry: (c.iy / c.area).sqrt(),
+        j,
+        cw,
+    }
+}
+
+pub fn steel_section_property_list(st_bridge: &StBridge) -> Vec<SteelSectionProperty> {
+    let children_map = &st_bridge.stb_model.stb_sections.stb_sec_steel.children_map;
+
+    let mut property_list: Vec<SteelSectionProperty> =
+        children_map.values().map(steel_section_property).collect();
+    property_list.sort_by(|a, b| a.name.cmp(&b.name));
+    property_list
+}
+
+// Web thickness t1, flange thickness t2, fillet r between web and flanges.
+fn h_shape(a: f64, b: f64, t1: f64, t2: f64, r: f64) -> (Composite, f64, f64) {
+    let mut parts = vec![
+        rectangle(1.0, -b / 2.0, b / 2.0, a / 2.0 - t2, a / 2.0),
+        rectangle(1.0, -b / 2.0, b / 2.0, -a / 2.0, -a / 2.0 + t2),
+        rectangle(1.0, -t1 / 2.0, t1 / 2.0, -a / 2.0 + t2, a / 2.0 - t2),
+    ];
+    for &(sx, sy) in &[(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)] {
+        parts.extend(fillet(1.0, sx * t1 / 2.0, sy * (a / 2.0 - t2), sx, -sy, r));
+    }
+
+    let h = a - t2;
+    let j = (2.0 * b * t2.powi(3) + (a - 2.0 * t2) * t1.powi(3)) / 3.0;
+    let cw = t2 * b.powi(3) * h * h / 24.0;
+
+    (composite(&parts), j, cw)
+}
+
+// Closed thin-walled section: J = 4 Am^2 / (integral of ds / t) along the wall centre line.
+fn box_torsion(a: f64, b: f64, t1: f64, t2: f64) -> f64 {
+    let (w, h) = (b - t1, a - t2);
+    4.0 * (w * h).powi(2) / (2.0 * h / t1 + 2.0 * w / t2)
+}
+
+fn pipe(d: f64, t: f64) -> Composite {
+    let di = d - 2.0 * t;
+    let area = PI / 4.0 * (d * d - di * di);
+    let i = PI / 64.0 * (d.powi(4) - di.powi(4));
+    let zp = (d.powi(3) - di.powi(3)) / 6.0;
+
+    Composite {
+        area,
+        centroid_x: 0.0,
+        centroid_y: 0.0,
+        ix: i,
+        iy: i,
+        zx: i / (d / 2.0),
+        zy: i / (d / 2.0),
+        zpx: zp,
+        zpy: zp,
+    }
+}
+
+// Vertical leg a (thickness t1) on the left, horizontal leg b (thickness t2) at the bottom, laid
+// out as in StbSecSteelChildren::shape. r1 is the root fillet and r2 rounds the leg tips.
+fn l_shape(a: f64, b: f64, t1: f64, t2: f64, r1: f64, r2: f64) -> (Composite, f64, f64) {
+    let (x0, y0) = (-b / 2.0, -a / 2.0);
+
+    let mut parts = vec![
+        rectangle(1.0, x0, x0 + b, y0, y0 + t2),
+        rectangle(1.0, x0, x0 + t1, y0 + t2, y0 + a),
+    ];
+    parts.extend(fillet(1.0, x0 + t1, y0 + t2, 1.0, 1.0, r1));
+    parts.extend(fillet(-1.0, x0 + b, y0 + t2, -1.0, -1.0, r2));
+    parts.extend(fillet(-1.0, x0 + t1, y0 + a, -1.0, -1.0, r2));
+
+    let j = (b * t2.powi(3) + (a - t2) * t1.powi(3)) / 3.0;
+    let cw = (t2.powi(3) * (b - t1 / 2.0).powi(3) + t1.powi(3) * (a - t2 / 2.0).powi(3)) / 36.0;
+
+    (composite(&parts), j, cw)
+}
+
+// Nominal diameter and cross-sectional area of deformed bars (JIS G 3112).
+const DEFORMED_BAR_LIST: [(&str, f64, f64); 13] = [
+    ("D6", 6.35, 31.67),
+    ("D10", 9.53, 71.33),
+    ("D13", 12.7, 126.7),
+    ("D16", 15.9, 198.6),
+    ("D19", 19.1, 286.5),
+    ("D22", 22.2, 387.1),
+    ("D25", 25.4, 506.7),
+    ("D29", 28.6, 642.4),
+    ("D32", 31.8, 794.2),
+    ("D35", 34.9, 956.6),
+    ("D38", 38.1, 1140.0),
+    ("D41", 41.3, 1340.0),
+    ("D51", 50.8, 2027.0),
+];
+
+pub const DEFAULT_COVER: f64 = 50.0;
+// Centre to centre distance between the 1st and 2nd bar layers, in bar diameters.
+pub const LAYER_SPACING: f64 = 2.5;
+
+pub fn bar_diameter(d: &str) -> Option<f64> {
+    DEFORMED_BAR_LIST
+        .iter()
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(d.trim()))
+        .map(|&(_, diameter, _)| diameter)
+}
+
+pub fn bar_area(d: &str) -> Option<f64> {
+    DEFORMED_BAR_LIST
+        .iter()
+        .find(|(name, _, _)| name.eq_ignore_ascii_case(d.trim()))
+        .map(|&(_, _, area)| area)
+}
+
+// Young's modulus ratio of steel to concrete by design strength, as in the AIJ RC standard.
+pub fn modular_ratio(strength_concrete: Option<&str>) -> f64 {
+    let fc = strength_concrete.and_then(|s| {
+        s.trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .parse::<f64>()
+            .ok()
+    });
+
+    match fc {
+        Some(fc) if fc > 48.0 => 9.0,
+        Some(fc) if fc > 36.0 => 11.0,
+        Some(fc) if fc > 27.0 => 13.0,
+        _ => 15.0,
+    }
+}
+
+// Ratios are bar area over b * d, shear_ratio is stirrup (hoop) area over b * pitch. For beams
+// the tension side is the top at START and END and the bottom elsewhere; columns are reported
+// once per bending direction, "X" and "Y".
+#[derive(Debug, Serialize)]
+pub struct RcPositionProperty {
+    pub pos: String,
+    pub width: f64,
+    pub depth: f64,
+    pub effective_depth: f64,
+    pub transformed_area: f64,
+    pub transformed_i: f64,
+    pub tension_ratio: f64,
+    pub compression_ratio: f64,
+    pub shear_ratio: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RcSectionProperty {
+    pub id: u32,
+    pub name: String,
+    pub modular_ratio: f64,
+    pub gross_area: f64,
+    pub gross_i: f64,
+    pub strength_main: Option<String>,
+    pub position_list: Vec<RcPositionProperty>,
+}
+
+// The area of a face's main bars and the cover to their centroid, with the 2nd layer one layer
+// spacing further in than the 1st.
+fn bar_group(count_1st: u32, count_2nd: u32, d_main: &str, cover: f64) -> (f64, f64) {
+    let a_main = bar_area(d_main).unwrap_or(0.0);
+    let area_1st = count_1st as f64 * a_main;
+    let area_2nd = count_2nd as f64 * a_main;
+    let area = area_1st + area_2nd;
+    if area <= 0.0 {
+        return (0.0, cover);
+    }
+    let spacing = LAYER_SPACING * bar_diameter(d_main).unwrap_or(0.0);
+    (area, cover + spacing * area_2nd / area)
+}
+
+// count positions evenly spread from start to end, or the middle for one.
+pub fn spaced(start: f64, end: f64, count: u32) -> Vec<f64> {
+    match count {
+        0 => Vec::new(),
+        1 => vec![(start + end) / 2.0],
+        _ => (0..count)
+            .map(|i| start + (end - start) * i as f64 / (count - 1) as f64)
+            .collect(),
+    }
+}
+
+// The interior ones of count + 2 evenly spread positions.
+pub fn between(start: f64, end: f64, count: u32) -> Vec<f64> {
+    let list = spaced(start, end, count + 2);
+    list[1..list.len() - 1].to_vec()
+}
+
+// Main bar centres of a rect column, with the section centred on the origin and covers in the
+// order start X, end X, start Y, end Y. The 1st layer has bars at the corners and along each face,
+// count_main_x of them on the faces along X. The 2nd layer is one layer spacing further in from
+// each face and spread between the 2nd layer corners, so that it clears the 1st layer bars.
+pub fn rect_column_bar_list(
+    (width_x, width_y): (f64, f64),
+    cover: [f64; 4],
+    d_main: &str,
+    bars: &StbSecRectColumnSame,
+) -> Vec<(f64, f64)> {
+    let x0 = -width_x / 2.0 + cover[0];
+    let x1 = width_x / 2.0 - cover[1];
+    let y0 = -width_y / 2.0 + cover[2];
+    let y1 = width_y / 2.0 - cover[3];
+
+    let mut bar_list = Vec::new();
+    let y_list = spaced(y0, y1, bars.count_main_y_1st.max(2));
+    for x in spaced(x0, x1, bars.count_main_x_1st.max(2)) {
+        bar_list.push((x, y0));
+        bar_list.push((x, y1));
+    }
+    for &y in &y_list[1..y_list.len() - 1] {
+        bar_list.push((x0, y));
+        bar_list.push((x1, y));
+    }
+
+    let spacing = LAYER_SPACING * bar_diameter(d_main).unwrap_or(0.0);
+    for x in between(x0 + spacing, x1 - spacing, bars.count_main_x_2nd) {
+        bar_list.push((x, y0 + spacing));
+        bar_list.push((x, y1 - spacing));
+    }
+    for y in between(y0 + spacing, y1 - spacing, bars.count_main_y_2nd) {
+        bar_list.push((x0 + spacing, y));
+        bar_list.push((x1 - spacing, y));
+    }
+    bar_list
+}
+
+// A rect column bent along u, the bar coordinate from the section centre. Every main bar adds to
+// the transformed section, and the bars up to a layer spacing in from either face are the
+// tension and compression steel.
+fn rect_column_position(
+    pos: &str,
+    (width, depth): (f64, f64),
+    n: f64,
+    (u_list, a_main, spacing): (&[f64], f64, f64),
+    shear_ratio: f64,
+) -> RcPositionProperty {
+    let gross_area = width * depth;
+    let transformed_area = gross_area + (n - 1.0) * a_main * u_list.len() as f64;
+    let u_centroid = (n - 1.0) * a_main * u_list.iter().sum::<f64>() / transformed_area;
+    let transformed_i = width * depth.powi(3) / 12.0
+        + gross_area * u_centroid.powi(2)
+        + (n - 1.0) * a_main * u_list.iter().map(|u| (u - u_centroid).powi(2)).sum::<f64>();
+
+    let u_max = u_list.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let u_min = u_list.iter().cloned().fold(f64::INFINITY, f64::min);
+    let tension_list: Vec<f64> = u_list
+        .iter()
+        .cloned()
+        .filter(|&u| u >= u_max - spacing - 1e-6)
+        .collect();
+    let compression_count = u_list
+        .iter()
+        .filter(|&&u| u <= u_min + spacing + 1e-6)
+        .count();
+    let effective_depth =
+        depth / 2.0 + tension_list.iter().sum::<f64>() / tension_list.len() as f64;
+
+    RcPositionProperty {
+        pos: pos.to_string(),
+        width,
+        depth,
+        effective_depth,
+        transformed_area,
+        transformed_i,
+        tension_ratio: tension_list.len() as f64 * a_main / (width * effective_depth),
+        compression_ratio: compression_count as f64 * a_main / (width * effective_depth),
+        shear_ratio,
+    }
+}
+
+// Covers are distances from the concrete face to the centre of the main bars.
+fn rc_position(
+    pos: &str,
+    (width, depth): (f64, f64),
+    n: f64,
+    (area_top, cover_top): (f64, f64),
+    (area_bottom, cover_bottom): (f64, f64),
+    top_in_tension: bool,
+    shear_ratio: f64,
+) -> RcPositionProperty {
+    let gross_area = width * depth;
+    let transformed_area = gross_area + (n - 1.0) * (area_top + area_bottom);
+
+    let y_top = depth - cover_top;
+    let y_bottom = cover_bottom;
+    let y_centroid = (gross_area * depth / 2.0
+        + (n - 1.0) * (area_top * y_top + area_bottom * y_bottom))
+        / transformed_area;
+
+    let transformed_i = width * depth.powi(3) / 12.0
+        + gross_area * (y_centroid - depth / 2.0).powi(2)
+        + (n - 1.0)
+            * (area_top * (y_top - y_centroid).powi(2)
+                + area_bottom * (y_bottom - y_centroid).powi(2));
+
+    let (area_tension, area_compression, effective_depth) = if top_in_tension {
+        (area_top, area_bottom, depth - cover_top)
+    } else {
+        (area_bottom, area_top, depth - cover_bottom)
+    };
+
+    RcPositionProperty {
+        pos: pos.to_string(),
+        width,
+        depth,
+        effective_depth,
+        transformed_area,
+        transformed_i,
+        tension_ratio: area_tension / (width * effective_depth),
+        compression_ratio: area_compression / (width * effective_depth),
+        shear_ratio,
+    }
+}
+
+pub fn rc_section_property(
+    st_bridge: &StBridge,
+    stb_section: &StbSection,
+) -> Option<RcSectionProperty> {
+    let strength_list = &st_bridge.stb_common.stb_reinforcement_strength_list;
+
+    match stb_section {
+        StbSection::StbSecBeamRC {
+            id,
+            name,
+            d_reinforcement_main,
+            d_stirrup,
+            strength_concrete,
+            depth_cover_top,
+            depth_cover_bottom,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } => {
+            let n = modular_ratio(strength_concrete.as_deref());
+            let a_stirrup = bar_area(d_stirrup).unwrap_or(0.0);
+            let cover_top = depth_cover_top.unwrap_or(DEFAULT_COVER);
+            let cover_bottom = depth_cover_bottom.unwrap_or(DEFAULT_COVER);
+
+            let size = |pos: &str| -> Option<(f64, f64)> {
+                match (
+                    &stb_sec_figure.stb_sec_straight,
+                    &stb_sec_figure.stb_sec_haunch,
+                ) {
+                    (Some(straight), _) => Some((straight.width, straight.depth)),
+                    (None, Some(haunch)) => Some(match pos {
+                        "START" => (haunch.width_start, haunch.depth_start),
+                        "END" => (haunch.width_end, haunch.depth_end),
+                        _ => (haunch.width_center, haunch.depth_center),
+                    }),
+                    (None, None) => None,
+                }
+            };
+
+            let position = |pos: &str,
+                            (top_1st, top_2nd): (u32, u32),
+                            (bottom_1st, bottom_2nd): (u32, u32),
+                            count_stirrup: u32,
+                            pitch_stirrup: f64|
+             -> Option<RcPositionProperty> {
+                let (width, depth) = size(pos)?;
+                let shear_ratio = if pitch_stirrup > 0.0 {
+                    count_stirrup as f64 * a_stirrup / (width * pitch_stirrup)
+                } else {
+                    0.0
+                };
+
+                Some(rc_position(
+                    pos,
+                    (width, depth),
+                    n,
+                    bar_group(top_1st, top_2nd, d_reinforcement_main, cover_top),
+                    bar_group(bottom_1st, bottom_2nd, d_reinforcement_main, cover_bottom),
+                    pos == "START" || pos == "END",
+                    shear_ratio,
+                ))
+            };
+
+            let mut position_list = Vec::new();
+            if let Some(list) = &stb_sec_bar_arrangement.stb_sec_beam_start_center_end_section_list
+            {
+                for section in list {
+                    let pos = match section.pos {
+                        StbSecBeamSectionPosition::Start => "START",
+                        StbSecBeamSectionPosition::Center => "CENTER",
+                        StbSecBeamSectionPosition::End => "END",
+                    };
+                    position_list.extend(position(
+                        pos,
+                        (section.count_main_top_1st, section.count_main_top_2nd),
+                        (section.count_main_bottom_1st, section.count_main_bottom_2nd),
+                        section.count_stirrup,
+                        section.pitch_stirrup,
+                    ));
+                }
+            }
+            if let Some(section) = &stb_sec_bar_arrangement.stb_sec_beam_same_section {
+                position_list.extend(position(
+                    "ALL",
+                    (section.count_main_top_1st, section.count_main_top_2nd),
+                    (section.count_main_bottom_1st, section.count_main_bottom_2nd),
+                    section.count_stirrup,
+                    section.pitch_stirrup,
+                ));
+            }
+
+            let (width, depth) = size("CENTER")?;
+
+            Some(RcSectionProperty {
+                id: *id,
+                name: name.clone(),
+                modular_ratio: n,
+                gross_area: width * depth,
+                gross_i: width * depth.powi(3) / 12.0,
+                strength_main: strength_list.get(d_reinforcement_main.clone()).cloned(),
+                position_list,
+            })
+        }
+        StbSection::StbSecColumnRC {
+            id,
+            name,
+            d_reinforcement_main,
+            d_rein_band,
+            strength_concrete,
+            depth_cover_start_x,
+            depth_cover_end_x,
+            depth_cover_start_y,
+            depth_cover_end_y,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } => {
+            let n = modular_ratio(strength_concrete.as_deref());
+            let a_main = bar_area(d_reinforcement_main).unwrap_or(0.0);
+            let a_band = bar_area(d_rein_band).unwrap_or(0.0);
+
+            let mut position_list = Vec::new();
+
+            let (gross_area, gross_i) =
+                match (&stb_sec_figure.stb_sec_rect, &stb_sec_figure.stb_sec_circle) {
+                    (Some(rect), _) => {
+                        if let Some(bars) = &stb_sec_bar_arrangement.stb_sec_rect_column_same {
+                            let shear_ratio = |count: u32, width: f64| {
+                                if bars.pitch_band > 0.0 {
+                                    count as f64 * a_band / (width * bars.pitch_band)
+                                } else {
+                                    0.0
+                                }
+                            };
+                            let bar_list = rect_column_bar_list(
+                                (rect.width_x, rect.width_y),
+                                [
+                                    depth_cover_start_x.unwrap_or(DEFAULT_COVER),
+                                    depth_cover_end_x.unwrap_or(DEFAULT_COVER),
+                                    depth_cover_start_y.unwrap_or(DEFAULT_COVER),
+                                    depth_cover_end_y.unwrap_or(DEFAULT_COVER),
+                                ],
+                                d_reinforcement_main,
+                                bars,
+                            );
+                            let spacing =
+                                LAYER_SPACING * bar_diameter(d_reinforcement_main).unwrap_or(0.0);
+                            let x_list: Vec<f64> = bar_list.iter().map(|b| b.0).collect();
+                            let y_list: Vec<f64> = bar_list.iter().map(|b| b.1).collect();
+
+                            // Bending in X, where the section is width_X deep and width_Y wide.
+                            position_list.push(rect_column_position(
+                                "X",
+                                (rect.width_y, rect.width_x),
+                                n,
+                                (&x_list, a_main, spacing),
+                                shear_ratio(bars.count_band_dir_x, rect.width_y),
+                            ));
+                            position_list.push(rect_column_position(
+                                "Y",
+                                (rect.width_x, rect.width_y),
+                                n,
+                                (&y_list, a_main, spacing),
+                                shear_ratio(bars.count_band_dir_y, rect.width_x),
+                            ));
+                        }
+                        (
+                            rect.width_x * rect.width_y,
+                            rect.width_y * rect.width_x.powi(3) / 12.0,
+                        )
+                    }
+                    (None, Some(circle)) => {
+                        let gross_area = PI * circle.d * circle.d / 4.0;
+                        let gross_i = PI * circle.d.powi(4) / 64.0;
+
+                        if let Some(bars) = &stb_sec_bar_arrangement.stb_sec_circle_column_same {
+                            let area_main = bars.count_main as f64 * a_main;
+                            let cover = depth_cover_start_x.unwrap_or(DEFAULT_COVER);
+                            // Bars on a circle of radius rc contribute n * A * rc^2 / 2 about a diameter.
+                            let rc = circle.d / 2.0 - cover;
+                            let shear_ratio = if bars.pitch_band > 0.0 {
+                                bars.count_band as f64 * a_band / (circle.d * bars.pitch_band)
+                            } else {
+                                0.0
+                            };
+
+                            position_list.push(RcPositionProperty {
+                                pos: "ALL".to_string(),
+                                width: circle.d,
+                                depth: circle.d,
+                                effective_depth: circle.d - cover,
+                                transformed_area: gross_area + (n - 1.0) * area_main,
+                                transformed_i: gross_i + (n - 1.0) * area_main * rc * rc / 2.0,
+                                tension_ratio: area_main / gross_area,
+                                compression_ratio: area_main / gross_area,
+                                shear_ratio,
+                            });
+                        }
+                        (gross_area, gross_i)
+                    }
+                    (None, None) => return None,
+                };
+
+            Some(RcSectionProperty {
+                id: *id,
+                name: name.clone(),
+                modular_ratio: n,
+                gross_area,
+                gross_i,
+                strength_main: strength_list.get(d_reinforcement_main.clone()).cloned(),
+                position_list,
+            })
+        }
+        _ => None,
+    }
+}
+
+pub fn rc_section_property_list(st_bridge: &StBridge) -> Vec<RcSectionProperty> {
+    let stb_sections = &st_bridge.stb_model.stb_sections;
+
+    let mut property_list: Vec<RcSectionProperty> = stb_sections
+        .column_rc_map
+        .values()
+        .chain(stb_sections.beam_rc_map.values())
+        .filter_map(|s| rc_section_property(st_bridge, s))
+        .collect();
+    property_list.sort_by_key(|p| p.id);
+    property_list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < tolerance,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn built_up_h_shape() {
+        let p = steel_section_property(&StbSecSteelChildren::StbSecBuildH {
+            name: "BH-400x200x8x13".to_string(),
+            a: 400.0,
+            b: 200.0,
+            t1: 8.0,
+            t2: 13.0,
+        });
+
+        let hw: f64 = 400.0 - 2.0 * 13.0;
+        let ix = (200.0 * 400.0_f64.powi(3) - 192.0 * hw.powi(3)) / 12.0;
+        assert_close(p.area, 2.0 * 200.0 * 13.0 + 8.0 * hw, 1e-9);
+        assert_close(p.ix, ix, 1e-9);
+        assert_close(
+            p.iy,
+            2.0 * 13.0 * 200.0_f64.powi(3) / 12.0 + hw * 8.0_f64.powi(3) / 12.0,
+            1e-9,
+        );
+        assert_close(p.zx, ix / 200.0, 1e-9);
+        assert_close(
+            p.zpx,
+            200.0 * 13.0 * (400.0 - 13.0) + 8.0 * hw * hw / 4.0,
+            1e-6,
+        );
+        assert!(p.centroid_x.abs() < 1e-9 && p.centroid_y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn rolled_h_shape_matches_jis_table() {
+        // JIS G 3192: A = 83.37 cm2, Ix = 23500 cm4, Iy = 1740 cm4, Zx = 1170 cm3.
+        let p = steel_section_property(&StbSecSteelChildren::StbSecRollH {
+            name: "H-400x200x8x13".to_string(),
+            sec_type: StbSecRollHType::H,
+            a: 400.0,
+            b: 200.0,
+            t1: 8.0,
+            t2: 13.0,
+            r: 13.0,
+        });
+
+        assert_close(p.area, 8337.0, 1e-3);
+        assert_close(p.ix, 23500.0e4, 5e-3);
+        assert_close(p.iy, 1740.0e4, 5e-3);
+        assert_close(p.zx, 1170.0e3, 5e-3);
+        assert_close(p.unit_weight, 65.4, 5e-3);
+    }
+
+    #[test]
+    fn built_up_box() {
+        let p = steel_section_property(&StbSecSteelChildren::StbSecBuildBox {
+            name: "BX-300x300x12".to_string(),
+            a: 300.0,
+            b: 300.0,
+            t1: 12.0,
+            t2: 12.0,
+        });
+
+        let ix = (300.0_f64.powi(4) - 276.0_f64.powi(4)) / 12.0;
+        assert_close(p.area, 300.0 * 300.0 - 276.0 * 276.0, 1e-9);
+        assert_close(p.ix, ix, 1e-9);
+        assert_close(p.iy, ix, 1e-9);
+        assert_close(p.zpx, (300.0_f64.powi(3) - 276.0_f64.powi(3)) / 4.0, 1e-6);
+        // Bredt: 4 (288 x 288)^2 / (4 x 288 / 12).
+        assert_close(p.j, 12.0 * 288.0_f64.powi(3), 1e-9);
+    }
+
+    #[test]
+    fn pipe() {
+        let p = steel_section_property(&StbSecSteelChildren::StbSecPipe {
+            name: "P-100x10".to_string(),
+            d: 100.0,
+            t: 10.0,
+        });
+
+        let i = PI / 64.0 * (100.0_f64.powi(4) - 80.0_f64.powi(4));
+        assert_close(p.area, PI / 4.0 * (100.0 * 100.0 - 80.0 * 80.0), 1e-9);
+        assert_close(p.ix, i, 1e-9);
+        assert_close(p.zx, i / 50.0, 1e-9);
+        assert_close(p.j, 2.0 * i, 1e-9);
+    }
+
+    #[test]
+    fn angle_matches_jis_table() {
+        // JIS G 3192 L-100x100x10: A = 19.00 cm2, Cx = Cy = 2.82 cm, Ix = 175 cm4.
+        let p = steel_section_property(&StbSecSteelChildren::StbSecRollL {
+            name: "L-100x100x10".to_string(),
+            sec_type: StbSecRollLType::L,
+            a: 100.0,
+            b: 100.0,
+            t1: 10.0,
+            t2: 10.0,
+            r1: 10.0,
+            r2: 7.0,
+            side: false,
+        });
+
+        assert_close(p.area, 1900.0, 2e-3);
+        assert_close(p.centroid_x + 50.0, 28.2, 5e-3);
+        assert_close(p.centroid_y + 50.0, 28.2, 5e-3);
+        assert_close(p.ix, 175.0e4, 5e-3);
+        assert_close(p.ix, p.iy, 1e-9);
+    }
+
+    fn portal_frame() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        ))
+        .unwrap()
+    }
+
+    fn position<'a>(p: &'a RcSectionProperty, pos: &str) -> &'a RcPositionProperty {
+        p.position_list.iter().find(|x| x.pos == pos).unwrap()
+    }
+
+    #[test]
+    fn rc_column_with_2nd_layer_bars() {
+        let st_bridge = portal_frame();
+        let section = &st_bridge.stb_model.stb_sections.column_rc_map[&1];
+        let p = rc_section_property(&st_bridge, section).unwrap();
+
+        assert_eq!(p.name, "C1");
+        assert_eq!(p.modular_ratio, 15.0);
+        assert_close(p.gross_area, 600.0 * 600.0, 1e-9);
+        assert_close(p.gross_i, 600.0_f64.powi(4) / 12.0, 1e-9);
+        assert_eq!(p.strength_main.as_deref(), Some("SD345"));
+
+        // 4 + 2 D22 per face, the 2nd layer 2.5 diameters inside the default 50 mm cover.
+        let area = 6.0 * 387.1;
+        let spacing: f64 = 2.5 * 22.2;
+        let cover = 50.0 + spacing * 2.0 / 6.0;
+        // Along either axis, 8 of the 20 bars are at the faces and 4 a third of the way from the
+        // centre to them. Of the 2nd layer, 4 are a layer spacing in from the faces and 4 sit
+        // between its corners.
+        let second = (500.0 - 2.0 * spacing) / 6.0;
+        let bar_i = 387.1
+            * (8.0 * 250.0_f64.powi(2)
+                + 4.0 * (250.0_f64 / 3.0).powi(2)
+                + 4.0 * (250.0 - spacing).powi(2)
+                + 4.0 * second.powi(2));
+        for pos in ["X", "Y"] {
+            let x = position(&p, pos);
+            assert_close(x.effective_depth, 600.0 - cover, 1e-9);
+            assert_close(x.tension_ratio, area / (600.0 * (600.0 - cover)), 1e-9);
+            assert_close(x.compression_ratio, x.tension_ratio, 1e-9);
+            assert_close(x.shear_ratio, 2.0 * 71.33 / (600.0 * 100.0), 1e-9);
+            assert_close(x.transformed_area, 360000.0 + 14.0 * 20.0 * 387.1, 1e-9);
+            assert_close(
+                x.transformed_i,
+                600.0_f64.powi(4) / 12.0 + 14.0 * bar_i,
+                1e-9,
+            );
+        }
+    }
+
+    #[test]
+    fn rc_girder_positions() {
+        let st_bridge = portal_frame();
+        let section = &st_bridge.stb_model.stb_sections.beam_rc_map[&2];
+        let p = rc_section_property(&st_bridge, section).unwrap();
+
+        assert_close(p.gross_area, 400.0 * 700.0, 1e-9);
+        let pos_list: Vec<&str> = p.position_list.iter().map(|x| x.pos.as_str()).collect();
+        assert_eq!(pos_list, vec!["START", "CENTER", "END"]);
+
+        // The ends are in tension at the top, with 4 + 2 bars there and 3 at the bottom.
+        let top_cover = 50.0 + 2.5 * 22.2 * 2.0 / 6.0;
+        for pos in ["START", "END"] {
+            let x = position(&p, pos);
+            let d = 700.0 - top_cover;
+            assert_close(x.effective_depth, d, 1e-9);
+            assert_close(x.tension_ratio, 6.0 * 387.1 / (400.0 * d), 1e-9);
+            assert_close(x.compression_ratio, 3.0 * 387.1 / (400.0 * d), 1e-9);
+            assert_close(x.shear_ratio, 2.0 * 71.33 / (400.0 * 100.0), 1e-9);
+        }
+
+        // The centre is in tension at the bottom, with 3 + 2 bars there.
+        let x = position(&p, "CENTER");
+        let d = 700.0 - (50.0 + 2.5 * 22.2 * 2.0 / 5.0);
+        assert_close(x.effective_depth, d, 1e-9);
+        assert_close(x.tension_ratio, 5.0 * 387.1 / (400.0 * d), 1e-9);
+        assert_close(x.compression_ratio, 3.0 * 387.1 / (400.0 * d), 1e-9);
+        assert_close(x.shear_ratio, 2.0 * 71.33 / (400.0 * 200.0), 1e-9);
+    }
+
+    #[test]
+    fn rc_property_list_has_the_column_and_girder() {
+        let property_list = rc_section_property_list(&portal_frame());
+
+        let name_list: Vec<&str> = property_list.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(name_list, vec!["C1", "G1"]);
+    }
+}
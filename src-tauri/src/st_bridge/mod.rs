@@ -16,15 +16,28 @@ pub struct StBridge {
     pub stb_extensions: StbExtensions,
 }
 
+// The reader and writer follow the 1.x schema; 2.x moved sections and bar arrangements into
+// differently shaped elements and is not supported.
+pub fn check_version(version: &str) -> Result<(), String> {
+    if version.split('.').next() == Some("1") {
+        Ok(())
+    } else {
+        Err(format!(
+            "ST-Bridge version {} is not supported, only 1.x",
+            version
+        ))
+    }
+}
+
 impl StBridge {
     pub fn members(&self) -> Vec<(StbNode, StbNode)> {
         self.stb_model
             .stb_members
             .iter()
-            .map(|m| {
-                let node_i = self.stb_model.stb_nodes.get(m.node_i()).unwrap();
-                let node_j = self.stb_model.stb_nodes.get(m.node_j()).unwrap();
-                (node_i, node_j)
+            .filter_map(|m| {
+                let node_i = self.stb_model.stb_nodes.get(m.node_i()?)?;
+                let node_j = self.stb_model.stb_nodes.get(m.node_j()?)?;
+                Some((node_i, node_j))
             })
             .collect()
     }
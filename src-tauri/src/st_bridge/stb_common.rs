@@ -3,6 +3,7 @@ use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbCommon {
+    pub project_name: String,
     pub stb_reinforcement_strength_list: StbReinforcementStrengthList,
 }
 
@@ -11,6 +12,7 @@ impl StbCommon {
         let map = HashMap::new();
         let stb_reinforcement_strength_list = StbReinforcementStrengthList { map };
         StbCommon {
+            project_name: String::new(),
             stb_reinforcement_strength_list,
         }
     }
@@ -29,6 +31,14 @@ impl StbReinforcementStrengthList {
     pub fn get(&self, d: String) -> Option<&String> {
         self.map.get(&d)
     }
+
+    pub fn strength_list(&self) -> Vec<String> {
+        self.map.values().cloned().collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.map.iter()
+    }
 }
 
 /*
@@ -6,6 +6,8 @@ pub mod stb_sections;
 use serde::{Deserialize, Serialize};
 use stb_axes_and_stories::StbAxes;
 use stb_axes_and_stories::StbStories;
+use stb_axes_and_stories::StbStory;
+use stb_members::StbMember;
 use stb_members::StbMembers;
 use stb_nodes::StbNodes;
 use stb_sections::StbSections;
@@ -18,3 +20,31 @@ pub struct StbModel {
     pub stb_members: StbMembers,
     pub stb_sections: StbSections,
 }
+
+impl StbModel {
+    // A member belongs to the story of its lowest node, so that "1C1" and "1FG1" both land on 1F.
+    pub fn story(&self, stb_member: &StbMember) -> Option<&StbStory> {
+        let lowest = stb_member
+            .node_id_list()
+            .into_iter()
+            .filter_map(|id| self.stb_nodes.get(id).map(|n| (id, n.z)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())?;
+
+        let story_list = &self.stb_stories.stb_story_list;
+
+        story_list
+            .iter()
+            .find(|s| {
+                s.stb_node_id_list
+                    .children
+                    .iter()
+                    .any(|n| n.id as u32 == lowest.0)
+            })
+            .or_else(|| {
+                story_list
+                    .iter()
+                    .filter(|s| s.height <= lowest.1 + 1.0)
+                    .max_by(|a, b| a.height.partial_cmp(&b.height).unwrap())
+            })
+    }
+}
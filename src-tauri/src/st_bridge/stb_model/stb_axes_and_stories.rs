@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use strum_macros::EnumString;
+use strum_macros::{Display, EnumString};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbAxes {
@@ -65,7 +65,7 @@ pub struct StbStory {
     pub stb_node_id_list: StbNodeIdList,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum StbStoryKind {
     #[strum(serialize = "GENERAL")]
     General,
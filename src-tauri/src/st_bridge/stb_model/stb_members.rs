@@ -1,7 +1,8 @@
+use super::stb_axes_and_stories::StbNodeIdList;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::Iter;
 use std::collections::HashMap;
-use strum_macros::EnumString;
+use strum_macros::{Display, EnumString};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbMembers {
@@ -11,13 +12,13 @@ pub struct StbMembers {
     pub stb_beams: HashMap<u32, StbMember>,
     pub stb_braces: HashMap<u32, StbMember>,
     pub stb_slabs: HashMap<u32, StbMember>,
-    // TODO: implement StbWalls
+    pub stb_walls: HashMap<u32, StbMember>,
     // TODO: implement StbFootings
     // TODO: implement StbStripFootings
     // TODO: implement StbPiles
     // TODO: implement StbFoundationColumns
     // TODO: implement StbParapets
-    // TODO: implement StbOpens
+    pub stb_opens: HashMap<u32, StbMember>,
 }
 
 impl StbMembers {
@@ -29,12 +30,26 @@ impl StbMembers {
             stb_beams: HashMap::new(),
             stb_braces: HashMap::new(),
             stb_slabs: HashMap::new(),
+            stb_walls: HashMap::new(),
+            stb_opens: HashMap::new(),
         }
     }
 
     pub fn iter<'a>(&'a self) -> StbMembersIter<'a> {
         StbMembersIter::new(self)
     }
+
+    pub fn get(&self, id: u32) -> Option<&StbMember> {
+        self.stb_columns
+            .get(&id)
+            .or_else(|| self.stb_posts.get(&id))
+            .or_else(|| self.stb_girders.get(&id))
+            .or_else(|| self.stb_beams.get(&id))
+            .or_else(|| self.stb_braces.get(&id))
+            .or_else(|| self.stb_slabs.get(&id))
+            .or_else(|| self.stb_walls.get(&id))
+            .or_else(|| self.stb_opens.get(&id))
+    }
 }
 
 pub struct StbMembersIter<'a> {
@@ -44,6 +59,8 @@ pub struct StbMembersIter<'a> {
     beams_iter: Iter<'a, u32, StbMember>,
     braces_iter: Iter<'a, u32, StbMember>,
     slabs_iter: Iter<'a, u32, StbMember>,
+    walls_iter: Iter<'a, u32, StbMember>,
+    opens_iter: Iter<'a, u32, StbMember>,
 }
 
 impl StbMembersIter<'_> {
@@ -55,6 +72,8 @@ impl StbMembersIter<'_> {
             beams_iter: stb_members.stb_beams.iter(),
             braces_iter: stb_members.stb_braces.iter(),
             slabs_iter: stb_members.stb_slabs.iter(),
+            walls_iter: stb_members.stb_walls.iter(),
+            opens_iter: stb_members.stb_opens.iter(),
         }
     }
 }
@@ -74,6 +93,10 @@ impl<'a> Iterator for StbMembersIter<'a> {
             return Some(t.1);
         } else if let Some(t) = self.slabs_iter.next() {
             return Some(t.1);
+        } else if let Some(t) = self.walls_iter.next() {
+            return Some(t.1);
+        } else if let Some(t) = self.opens_iter.next() {
+            return Some(t.1);
         } else {
             return None;
         }
@@ -126,6 +149,8 @@ pub enum StbMember {
         offset: f64,
         level: f64,
         type_haunch_h: Option<HaunchType>,
+        condition_start: JointCondition,
+        condition_end: JointCondition,
     },
     StbBeam {
         id: u32,
@@ -138,6 +163,8 @@ pub enum StbMember {
         is_foundation: bool,
         offset: f64,
         level: f64,
+        condition_start: JointCondition,
+        condition_end: JointCondition,
     },
     StbBrace {
         id: u32,
@@ -164,6 +191,26 @@ pub enum StbMember {
         kind_slab: SlabKind,
         level: f64,
         is_foundation: bool,
+        stb_node_id_list: StbNodeIdList,
+    },
+    StbWall {
+        id: u32,
+        name: String,
+        id_section: u32,
+        kind_structure: WallStructureKind,
+        stb_node_id_list: StbNodeIdList,
+    },
+    StbOpen {
+        id: u32,
+        name: String,
+        id_section: u32,
+        id_member: u32,
+        kind_member: OpenMemberKind,
+        position_x: f64,
+        position_y: f64,
+        length_x: f64,
+        length_y: f64,
+        rotate: f64,
     },
 }
 
@@ -176,28 +223,111 @@ impl StbMember {
             StbMember::StbBeam { id, .. } => id,
             StbMember::StbGirder { id, .. } => id,
             StbMember::StbPost { id, .. } => id,
+            StbMember::StbWall { id, .. } => id,
+            StbMember::StbOpen { id, .. } => id,
         }
     }
 
-    pub fn node_i(&self) -> u32 {
+    pub fn name(&self) -> String {
+        match self {
+            StbMember::StbColumn { name, .. } => name.clone(),
+            StbMember::StbSlab { name, .. } => name.clone(),
+            StbMember::StbBrace { name, .. } => name.clone(),
+            StbMember::StbBeam { name, .. } => name.clone(),
+            StbMember::StbGirder { name, .. } => name.clone(),
+            StbMember::StbPost { name, .. } => name.clone(),
+            StbMember::StbWall { name, .. } => name.clone(),
+            StbMember::StbOpen { name, .. } => name.clone(),
+        }
+    }
+
+    pub fn id_section(&self) -> u32 {
         match *self {
-            StbMember::StbColumn { id_node_bottom, .. } => id_node_bottom,
-            StbMember::StbSlab { .. } => panic!(""),
-            StbMember::StbBrace { id_node_start, .. } => id_node_start,
-            StbMember::StbBeam { id_node_start, .. } => id_node_start,
-            StbMember::StbGirder { id_node_start, .. } => id_node_start,
-            StbMember::StbPost { id_node_bottom, .. } => id_node_bottom,
+            StbMember::StbColumn { id_section, .. } => id_section,
+            StbMember::StbSlab { id_section, .. } => id_section,
+            StbMember::StbBrace { id_section, .. } => id_section,
+            StbMember::StbBeam { id_section, .. } => id_section,
+            StbMember::StbGirder { id_section, .. } => id_section,
+            StbMember::StbPost { id_section, .. } => id_section,
+            StbMember::StbWall { id_section, .. } => id_section,
+            StbMember::StbOpen { id_section, .. } => id_section,
         }
     }
 
-    pub fn node_j(&self) -> u32 {
+    pub fn kind_structure(&self) -> String {
+        match self {
+            StbMember::StbColumn { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbSlab { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbBrace { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbBeam { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbGirder { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbPost { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbWall { kind_structure, .. } => kind_structure.to_string(),
+            StbMember::StbOpen { .. } => String::new(),
+        }
+    }
+
+    pub fn member_kind(&self) -> &'static str {
+        match self {
+            StbMember::StbColumn { .. } => "Column",
+            StbMember::StbPost { .. } => "Post",
+            StbMember::StbGirder { .. } => "Girder",
+            StbMember::StbBeam { .. } => "Beam",
+            StbMember::StbBrace { .. } => "Brace",
+            StbMember::StbSlab { .. } => "Slab",
+            StbMember::StbWall { .. } => "Wall",
+            StbMember::StbOpen { .. } => "Open",
+        }
+    }
+
+    pub fn is_linear(&self) -> bool {
+        !matches!(
+            self,
+            StbMember::StbSlab { .. } | StbMember::StbWall { .. } | StbMember::StbOpen { .. }
+        )
+    }
+
+    pub fn node_id_list(&self) -> Vec<u32> {
+        match self {
+            StbMember::StbSlab {
+                stb_node_id_list, ..
+            }
+            | StbMember::StbWall {
+                stb_node_id_list, ..
+            } => stb_node_id_list
+                .children
+                .iter()
+                .map(|n| n.id as u32)
+                .collect(),
+            StbMember::StbOpen { .. } => Vec::new(),
+            _ => self.node_i().into_iter().chain(self.node_j()).collect(),
+        }
+    }
+
+    // The start and end nodes of a linear member; slabs, walls and openings have none.
+    pub fn node_i(&self) -> Option<u32> {
         match *self {
-            StbMember::StbColumn { id_node_top, .. } => id_node_top,
-            StbMember::StbSlab { .. } => panic!(""),
-            StbMember::StbBrace { id_node_end, .. } => id_node_end,
-            StbMember::StbBeam { id_node_end, .. } => id_node_end,
-            StbMember::StbGirder { id_node_end, .. } => id_node_end,
-            StbMember::StbPost { id_node_top, .. } => id_node_top,
+            StbMember::StbColumn { id_node_bottom, .. } => Some(id_node_bottom),
+            StbMember::StbBrace { id_node_start, .. } => Some(id_node_start),
+            StbMember::StbBeam { id_node_start, .. } => Some(id_node_start),
+            StbMember::StbGirder { id_node_start, .. } => Some(id_node_start),
+            StbMember::StbPost { id_node_bottom, .. } => Some(id_node_bottom),
+            StbMember::StbSlab { .. } | StbMember::StbWall { .. } | StbMember::StbOpen { .. } => {
+                None
+            }
+        }
+    }
+
+    pub fn node_j(&self) -> Option<u32> {
+        match *self {
+            StbMember::StbColumn { id_node_top, .. } => Some(id_node_top),
+            StbMember::StbBrace { id_node_end, .. } => Some(id_node_end),
+            StbMember::StbBeam { id_node_end, .. } => Some(id_node_end),
+            StbMember::StbGirder { id_node_end, .. } => Some(id_node_end),
+            StbMember::StbPost { id_node_top, .. } => Some(id_node_top),
+            StbMember::StbSlab { .. } | StbMember::StbWall { .. } | StbMember::StbOpen { .. } => {
+                None
+            }
         }
     }
 }
@@ -217,7 +347,7 @@ impl StbColumns {
 }
 */
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum ColumnStructureKind {
     #[strum(serialize = "RC")]
     RC,
@@ -231,7 +361,7 @@ pub enum ColumnStructureKind {
     Undefined,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum JointCondition {
     #[strum(serialize = "FIX")]
     Fix,
@@ -254,7 +384,7 @@ impl StbGirders {
 }
 */
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum GirderStructureKind {
     #[strum(serialize = "RC")]
     RC,
@@ -266,7 +396,7 @@ pub enum GirderStructureKind {
     Undefined,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum HaunchType {
     #[strum(serialize = "BOTH")]
     Both,
@@ -321,7 +451,7 @@ impl StbSlabs {
 }
 */
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum SlabStructureKind {
     #[strum(serialize = "RC")]
     RC,
@@ -331,7 +461,7 @@ pub enum SlabStructureKind {
     Precast,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum SlabKind {
     #[strum(serialize = "NORMAL")]
     Normal,
@@ -354,7 +484,7 @@ impl StbBraces {
 }
 */
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum BraceStructureKind {
     #[strum(serialize = "RC")]
     RC,
@@ -363,3 +493,17 @@ pub enum BraceStructureKind {
     #[strum(serialize = "SRC")]
     SRC,
 }
+
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
+pub enum WallStructureKind {
+    #[strum(serialize = "RC")]
+    RC,
+}
+
+#[derive(Debug, PartialEq, Display, EnumString, Serialize, Deserialize)]
+pub enum OpenMemberKind {
+    #[strum(serialize = "SLAB")]
+    Slab,
+    #[strum(serialize = "WALL")]
+    Wall,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use strum_macros::EnumString;
+use strum_macros::{Display, EnumString};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbNodes {
@@ -32,7 +32,7 @@ pub struct StbNode {
     pub id_member: Option<u32>,
 }
 
-#[derive(Debug, Clone, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Clone, Display, EnumString, Serialize, Deserialize)]
 pub enum StbNodeKind {
     #[strum(serialize = "ON_GIRDER")]
     OnGirder,
@@ -1,33 +1,65 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Debug;
-use strum_macros::EnumString;
+use strum_macros::{Display, EnumString};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbSections {
+    pub column_rc_map: HashMap<u32, StbSection>,
     pub column_s_map: HashMap<u32, StbSection>,
     pub beam_rc_map: HashMap<u32, StbSection>,
     pub beam_s_map: HashMap<u32, StbSection>,
     pub slab_rc_map: HashMap<u32, StbSection>,
     pub brace_s_map: HashMap<u32, StbSection>,
+    pub wall_rc_map: HashMap<u32, StbSection>,
     pub stb_sec_steel: StbSecSteel,
 }
 
 impl StbSections {
     pub fn new() -> StbSections {
         StbSections {
+            column_rc_map: HashMap::new(),
             column_s_map: HashMap::new(),
             beam_rc_map: HashMap::new(),
             beam_s_map: HashMap::new(),
             slab_rc_map: HashMap::new(),
             brace_s_map: HashMap::new(),
+            wall_rc_map: HashMap::new(),
             stb_sec_steel: StbSecSteel::new(),
         }
     }
+
+    pub fn get(&self, id: u32) -> Option<&StbSection> {
+        self.column_rc_map
+            .get(&id)
+            .or_else(|| self.column_s_map.get(&id))
+            .or_else(|| self.beam_rc_map.get(&id))
+            .or_else(|| self.beam_s_map.get(&id))
+            .or_else(|| self.slab_rc_map.get(&id))
+            .or_else(|| self.brace_s_map.get(&id))
+            .or_else(|| self.wall_rc_map.get(&id))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum StbSection {
+    StbSecColumnRC {
+        id: u32,
+        name: String,
+        floor: String,
+        kind_column: ColumnKind,
+        d_reinforcement_main: String,
+        d_rein_band: String,
+        strength_concrete: Option<String>,
+        strength_reinforcement_main: String,
+        strength_rein_band: String,
+        depth_cover_start_x: Option<f64>,
+        depth_cover_end_x: Option<f64>,
+        depth_cover_start_y: Option<f64>,
+        depth_cover_end_y: Option<f64>,
+        stb_sec_figure: StbSecFigureColumn,
+        stb_sec_bar_arrangement: StbSecBarArrangementColumn,
+    },
     StbSecColumnS {
         id: u32,
         name: String,
@@ -67,7 +99,7 @@ pub enum StbSection {
         floor: String,
         kind_beam: BeamKind,
         is_canti: bool,
-        stb_sec_steel_beam: StbSecSteelBeam,
+        stb_sec_steel_beam_list: Vec<StbSecSteelBeam>,
     },
     StbSecSlabRC {
         id: u32,
@@ -85,16 +117,54 @@ pub enum StbSection {
         kind_brace: BraceKind,
         stb_sec_steel_brace: StbSecSteelBrace,
     },
+    StbSecWallRC {
+        id: u32,
+        name: String,
+        strength_concrete: String,
+        stb_sec_figure: StbSecFigureWall,
+    },
 }
 
 impl StbSection {
     pub fn id(&self) -> u32 {
         match *self {
+            StbSection::StbSecColumnRC { id, .. } => id,
             StbSection::StbSecColumnS { id, .. } => id,
             StbSection::StbSecBeamRC { id, .. } => id,
             StbSection::StbSecBeamS { id, .. } => id,
             StbSection::StbSecSlabRC { id, .. } => id,
             StbSection::StbSecBraceS { id, .. } => id,
+            StbSection::StbSecWallRC { id, .. } => id,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            StbSection::StbSecColumnRC { name, .. } => name.clone(),
+            StbSection::StbSecColumnS { name, .. } => name.clone(),
+            StbSection::StbSecBeamRC { name, .. } => name.clone(),
+            StbSection::StbSecBeamS { name, .. } => name.clone(),
+            StbSection::StbSecSlabRC { name, .. } => name.clone(),
+            StbSection::StbSecBraceS { name, .. } => name.clone(),
+            StbSection::StbSecWallRC { name, .. } => name.clone(),
+        }
+    }
+
+    pub fn steel_shape(&self) -> Option<&String> {
+        match self {
+            StbSection::StbSecColumnS {
+                stb_sec_steel_column,
+                ..
+            } => Some(&stb_sec_steel_column.shape),
+            StbSection::StbSecBeamS {
+                stb_sec_steel_beam_list,
+                ..
+            } => Some(&center_steel_beam(stb_sec_steel_beam_list).shape),
+            StbSection::StbSecBraceS {
+                stb_sec_steel_brace,
+                ..
+            } => Some(&stb_sec_steel_brace.shape),
+            _ => None,
         }
     }
 }
@@ -111,7 +181,7 @@ impl std::fmt::Debug for dyn StbSectionsChildren {
 
 //impl StbSectionsChildren for StbSecColumnS {}
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum ColumnKind {
     #[strum(serialize = "COLUMN")]
     Column,
@@ -119,7 +189,59 @@ pub enum ColumnKind {
     Post,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecFigureColumn {
+    pub stb_sec_rect: Option<StbSecRect>,
+    pub stb_sec_circle: Option<StbSecCircle>,
+}
+
+impl StbSecFigureColumn {
+    pub fn shape(&self) -> Option<Vec<(f64, f64)>> {
+        match (&self.stb_sec_rect, &self.stb_sec_circle) {
+            (Some(rect), _) => Some(rectangle(rect.width_x, rect.width_y)),
+            (None, Some(circle)) => Some(self::circle(circle.d / 2.0)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecRect {
+    pub width_x: f64,
+    pub width_y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecCircle {
+    pub d: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecBarArrangementColumn {
+    pub stb_sec_rect_column_same: Option<StbSecRectColumnSame>,
+    pub stb_sec_circle_column_same: Option<StbSecCircleColumnSame>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecRectColumnSame {
+    pub count_main_x_1st: u32,
+    pub count_main_y_1st: u32,
+    pub count_main_x_2nd: u32,
+    pub count_main_y_2nd: u32,
+    pub count_main_total: u32,
+    pub count_band_dir_x: u32,
+    pub count_band_dir_y: u32,
+    pub pitch_band: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecCircleColumnSame {
+    pub count_main: u32,
+    pub count_band: u32,
+    pub pitch_band: f64,
+}
+
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum SteelBaseType {
     #[strum(serialize = "")]
     Null,
@@ -139,7 +261,7 @@ pub struct StbSecSteelColumn {
     pub strength_web: String,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecSteelColumnPosition {
     #[strum(serialize = "ALL")]
     All,
@@ -147,7 +269,7 @@ pub enum StbSecSteelColumnPosition {
 
 //impl StbSectionsChildren for StbSecBeamRC {}
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum BeamKind {
     #[strum(serialize = "GIRDER")]
     Girder,
@@ -171,8 +293,20 @@ pub struct StbSecHaunch {
     pub depth_end: f64,
 }
 
+impl StbSecHaunch {
+    // Width and depth at a bar arrangement position; CENTER and ALL take the center.
+    pub fn size(&self, position: &str) -> (f64, f64) {
+        match position {
+            "START" => (self.width_start, self.depth_start),
+            "END" => (self.width_end, self.depth_end),
+            _ => (self.width_center, self.depth_center),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbSecStraightBeam {
+    pub width: f64,
     pub depth: f64,
 }
 
@@ -182,11 +316,97 @@ pub struct StbSecBarArrangementBeam {
     pub stb_sec_beam_same_section: Option<StbSecBeamSameSection>,
 }
 
+impl StbSecBarArrangementBeam {
+    // START, CENTER and END as given, or ALL when the section is the same throughout.
+    pub fn beam_bars_list(&self) -> Vec<BeamBars> {
+        match self {
+            StbSecBarArrangementBeam {
+                stb_sec_beam_start_center_end_section_list: Some(list),
+                ..
+            } => list
+                .iter()
+                .map(|a| BeamBars {
+                    position: a.pos.to_string(),
+                    top_1st: a.count_main_top_1st,
+                    top_2nd: a.count_main_top_2nd,
+                    bottom_1st: a.count_main_bottom_1st,
+                    bottom_2nd: a.count_main_bottom_2nd,
+                    stirrup: a.count_stirrup,
+                    pitch_stirrup: a.pitch_stirrup,
+                    web: a.count_web,
+                    bar_spacing: a.count_bar_spacing,
+                    pitch_bar_spacing: a.pitch_bar_spacing,
+                })
+                .collect(),
+            StbSecBarArrangementBeam {
+                stb_sec_beam_same_section: Some(a),
+                ..
+            } => vec![BeamBars {
+                position: "ALL".to_string(),
+                top_1st: a.count_main_top_1st,
+                top_2nd: a.count_main_top_2nd,
+                bottom_1st: a.count_main_bottom_1st,
+                bottom_2nd: a.count_main_bottom_2nd,
+                stirrup: a.count_stirrup,
+                pitch_stirrup: a.pitch_stirrup,
+                web: a.count_web,
+                bar_spacing: a.count_bar_spacing,
+                pitch_bar_spacing: a.pitch_bar_spacing,
+            }],
+            _ => Vec::new(),
+        }
+    }
+}
+
+// Bars of an RC beam at one position, whether given per position or for the whole beam.
+#[derive(Debug)]
+pub struct BeamBars {
+    pub position: String,
+    pub top_1st: u32,
+    pub top_2nd: u32,
+    pub bottom_1st: u32,
+    pub bottom_2nd: u32,
+    pub stirrup: u32,
+    pub pitch_stirrup: f64,
+    pub web: u32,
+    pub bar_spacing: u32,
+    pub pitch_bar_spacing: f64,
+}
+
+// "5-D25", or empty for no bars.
+pub fn bar_text(count: u32, d: &str) -> String {
+    if count == 0 {
+        String::new()
+    } else {
+        format!("{}-{}", count, d)
+    }
+}
+
+// Main bars in two layers as "5+2-D25", in one as "5-D25".
+pub fn layered_bar_text(count_1st: u32, count_2nd: u32, d: &str) -> String {
+    if count_2nd == 0 {
+        bar_text(count_1st, d)
+    } else {
+        format!("{}+{}-{}", count_1st, count_2nd, d)
+    }
+}
+
+// "2-D13@200", or empty for no bars.
+pub fn pitched_bar_text(count: u32, d: &str, pitch: f64) -> String {
+    if count == 0 {
+        String::new()
+    } else {
+        format!("{}-{}@{}", count, d, pitch)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbSecBeamStartCenterEndSection {
     pub pos: StbSecBeamSectionPosition,
     pub count_main_top_1st: u32,
     pub count_main_bottom_1st: u32,
+    pub count_main_top_2nd: u32,
+    pub count_main_bottom_2nd: u32,
     pub count_stirrup: u32,
     pub pitch_stirrup: f64,
     pub count_web: u32,
@@ -198,6 +418,8 @@ pub struct StbSecBeamStartCenterEndSection {
 pub struct StbSecBeamSameSection {
     pub count_main_top_1st: u32,
     pub count_main_bottom_1st: u32,
+    pub count_main_top_2nd: u32,
+    pub count_main_bottom_2nd: u32,
     pub count_stirrup: u32,
     pub pitch_stirrup: f64,
     pub count_web: u32,
@@ -205,7 +427,7 @@ pub struct StbSecBeamSameSection {
     pub pitch_bar_spacing: f64,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecBeamSectionPosition {
     #[strum(serialize = "START")]
     Start,
@@ -225,10 +447,32 @@ pub struct StbSecSteelBeam {
     pub strength_web: String,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecSteelBeamPosition {
     #[strum(serialize = "ALL")]
     All,
+    #[strum(serialize = "START")]
+    Start,
+    #[strum(serialize = "CENTER")]
+    Center,
+    #[strum(serialize = "END")]
+    End,
+    #[strum(serialize = "HAUNCH")]
+    Haunch,
+}
+
+// The shape used throughout, or the CENTER one of a beam with shapes by position. The reader
+// keeps at least one StbSecSteelBeam per section.
+pub fn center_steel_beam(stb_sec_steel_beam_list: &[StbSecSteelBeam]) -> &StbSecSteelBeam {
+    stb_sec_steel_beam_list
+        .iter()
+        .find(|b| {
+            matches!(
+                b.pos,
+                StbSecSteelBeamPosition::All | StbSecSteelBeamPosition::Center
+            )
+        })
+        .unwrap_or(&stb_sec_steel_beam_list[0])
 }
 
 //impl StbSectionsChildren for StbSecSlabRC {}
@@ -256,7 +500,7 @@ pub struct StbSec1WaySlab1 {
     pub pitch: f64,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSec1WaySlab1Position {
     #[strum(serialize = "MAIN_TOP")]
     MainTop,
@@ -270,7 +514,7 @@ pub enum StbSec1WaySlab1Position {
 
 //impl StbSectionsChildren for StbSecBraceS {}
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum BraceKind {
     #[strum(serialize = "VERTICAL")]
     Vertical,
@@ -286,12 +530,22 @@ pub struct StbSecSteelBrace {
     pub strength_web: String,
 }
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecSteelBraceSPosition {
     #[strum(serialize = "ALL")]
     All,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecFigureWall {
+    pub stb_sec_straight: StbSecStraightWall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StbSecStraightWall {
+    pub t: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StbSecSteel {
     pub children_map: HashMap<String, StbSecSteelChildren>,
@@ -315,9 +569,13 @@ impl StbSecSteel {
             //roll_l_map: HashMap::new(),
         }
     }
+
+    pub fn get(&self, shape: &str) -> Option<&StbSecSteelChildren> {
+        self.children_map.get(shape)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub enum StbSecSteelChildren {
     StbSecRollH {
         name: String,
@@ -380,43 +638,87 @@ impl StbSecSteelChildren {
         }
     }
 
+    pub fn set_name(&mut self, new_name: String) {
+        match self {
+            StbSecSteelChildren::StbSecRollH { name, .. }
+            | StbSecSteelChildren::StbSecBuildH { name, .. }
+            | StbSecSteelChildren::StbSecRollBox { name, .. }
+            | StbSecSteelChildren::StbSecBuildBox { name, .. }
+            | StbSecSteelChildren::StbSecPipe { name, .. }
+            | StbSecSteelChildren::StbSecRollL { name, .. } => *name = new_name,
+        }
+    }
+
     pub fn shape(&self) -> Vec<(f64, f64)> {
         match self {
-            StbSecSteelChildren::StbSecRollH { a, b, t1, t2, .. } => {
+            // A is the depth, B the flange width, t1 the web and t2 the flange thickness.
+            StbSecSteelChildren::StbSecRollH { a, b, t1, t2, .. }
+            | StbSecSteelChildren::StbSecBuildH { a, b, t1, t2, .. } => {
                 vec![
                     (-b / 2.0, -a / 2.0),
                     (b / 2.0, -a / 2.0),
-                    (b / 2.0, -a / 2.0 + t1),
-                    (t2 / 2.0, -a / 2.0 + t1),
-                    (t2 / 2.0, a / 2.0 - t1),
-                    (b / 2.0, a / 2.0 - t1),
+                    (b / 2.0, -a / 2.0 + t2),
+                    (t1 / 2.0, -a / 2.0 + t2),
+                    (t1 / 2.0, a / 2.0 - t2),
+                    (b / 2.0, a / 2.0 - t2),
                     (b / 2.0, a / 2.0),
                     (-b / 2.0, a / 2.0),
-                    (-b / 2.0, a / 2.0 - t1),
-                    (-t2 / 2.0, a / 2.0 - t1),
-                    (-t2 / 2.0, -a / 2.0 + t1),
-                    (-b / 2.0, -a / 2.0 + t1),
+                    (-b / 2.0, a / 2.0 - t2),
+                    (-t1 / 2.0, a / 2.0 - t2),
+                    (-t1 / 2.0, -a / 2.0 + t2),
+                    (-b / 2.0, -a / 2.0 + t2),
                 ]
             }
-            StbSecSteelChildren::StbSecBuildH { a, b, t1, t2, .. } => {
-                vec![(0.0, 0.0)]
+            StbSecSteelChildren::StbSecRollBox { a, b, .. }
+            | StbSecSteelChildren::StbSecBuildBox { a, b, .. } => rectangle(*b, *a),
+            StbSecSteelChildren::StbSecPipe { d, .. } => circle(d / 2.0),
+            StbSecSteelChildren::StbSecRollL { a, b, t1, t2, .. } => {
+                vec![
+                    (-b / 2.0, -a / 2.0),
+                    (b / 2.0, -a / 2.0),
+                    (b / 2.0, -a / 2.0 + t2),
+                    (-b / 2.0 + t1, -a / 2.0 + t2),
+                    (-b / 2.0 + t1, a / 2.0),
+                    (-b / 2.0, a / 2.0),
+                ]
             }
+        }
+    }
+
+    pub fn holes(&self) -> Vec<Vec<(f64, f64)>> {
+        match self {
             StbSecSteelChildren::StbSecRollBox { a, b, t, .. } => {
-                vec![(0.0, 0.0)]
+                vec![rectangle(b - 2.0 * t, a - 2.0 * t)]
             }
             StbSecSteelChildren::StbSecBuildBox { a, b, t1, t2, .. } => {
-                vec![(0.0, 0.0)]
-            }
-            StbSecSteelChildren::StbSecPipe { d, t, .. } => {
-                vec![(0.0, 0.0)]
-            }
-            StbSecSteelChildren::StbSecRollL { a, b, t1, t2, .. } => {
-                vec![(0.0, 0.0)]
+                vec![rectangle(b - 2.0 * t1, a - 2.0 * t2)]
             }
+            StbSecSteelChildren::StbSecPipe { d, t, .. } => vec![circle(d / 2.0 - t)],
+            _ => Vec::new(),
         }
     }
 }
 
+fn rectangle(width: f64, depth: f64) -> Vec<(f64, f64)> {
+    vec![
+        (-width / 2.0, -depth / 2.0),
+        (width / 2.0, -depth / 2.0),
+        (width / 2.0, depth / 2.0),
+        (-width / 2.0, depth / 2.0),
+    ]
+}
+
+fn circle(radius: f64) -> Vec<(f64, f64)> {
+    let division = 24;
+
+    (0..division)
+        .map(|i| {
+            let theta = 2.0 * std::f64::consts::PI * i as f64 / division as f64;
+            (radius * theta.cos(), radius * theta.sin())
+        })
+        .collect()
+}
+
 /*
 pub trait StbSecSteelChildren {}
 
@@ -429,7 +731,7 @@ impl std::fmt::Debug for dyn StbSecSteelChildren {
 
 //impl StbSecSteelChildren for StbSecRollH {}
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecRollHType {
     #[strum(serialize = "H")]
     H,
@@ -441,7 +743,7 @@ pub enum StbSecRollHType {
 
 //impl StbSecSteelChildren for StbSecRollBox {}
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecRollBoxType {
     #[strum(serialize = "BCP")]
     BCP,
@@ -459,8 +761,111 @@ pub enum StbSecRollBoxType {
 
 //impl StbSecSteelChildren for StbSecRollL {}
 
-#[derive(Debug, EnumString, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Display, EnumString, Serialize, Deserialize)]
 pub enum StbSecRollLType {
     #[strum(serialize = "L")]
     L,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same_section() -> StbSecBeamSameSection {
+        StbSecBeamSameSection {
+            count_main_top_1st: 5,
+            count_main_bottom_1st: 4,
+            count_main_top_2nd: 2,
+            count_main_bottom_2nd: 0,
+            count_stirrup: 4,
+            pitch_stirrup: 150.0,
+            count_web: 0,
+            count_bar_spacing: 0,
+            pitch_bar_spacing: 0.0,
+        }
+    }
+
+    #[test]
+    fn beam_bars_by_position() {
+        let st_bridge = crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        ))
+        .unwrap();
+        let bars_list = match st_bridge.stb_model.stb_sections.get(2) {
+            Some(StbSection::StbSecBeamRC {
+                stb_sec_bar_arrangement,
+                ..
+            }) => stb_sec_bar_arrangement.beam_bars_list(),
+            _ => panic!("G1 is not an RC beam"),
+        };
+
+        let position_list: Vec<&str> = bars_list.iter().map(|b| b.position.as_str()).collect();
+        assert_eq!(position_list, ["START", "CENTER", "END"]);
+        let start = &bars_list[0];
+        assert_eq!(
+            (
+                start.top_1st,
+                start.top_2nd,
+                start.bottom_1st,
+                start.bottom_2nd
+            ),
+            (4, 2, 3, 0)
+        );
+        assert_eq!((start.stirrup, start.pitch_stirrup), (2, 100.0));
+        assert_eq!((start.web, start.bar_spacing), (2, 1));
+        let center = &bars_list[1];
+        assert_eq!(
+            (center.top_1st, center.bottom_1st, center.bottom_2nd),
+            (3, 3, 2)
+        );
+        assert_eq!(center.pitch_stirrup, 200.0);
+    }
+
+    #[test]
+    fn beam_bars_for_the_whole_beam() {
+        let arrangement = StbSecBarArrangementBeam {
+            stb_sec_beam_start_center_end_section_list: None,
+            stb_sec_beam_same_section: Some(same_section()),
+        };
+        let bars_list = arrangement.beam_bars_list();
+        assert_eq!(bars_list.len(), 1);
+        assert_eq!(bars_list[0].position, "ALL");
+        assert_eq!((bars_list[0].top_1st, bars_list[0].top_2nd), (5, 2));
+        assert_eq!(bars_list[0].stirrup, 4);
+
+        let empty = StbSecBarArrangementBeam {
+            stb_sec_beam_start_center_end_section_list: None,
+            stb_sec_beam_same_section: None,
+        };
+        assert!(empty.beam_bars_list().is_empty());
+    }
+
+    #[test]
+    fn haunch_size_by_position() {
+        let haunch = StbSecHaunch {
+            width_start: 500.0,
+            depth_start: 900.0,
+            width_center: 400.0,
+            depth_center: 700.0,
+            width_end: 450.0,
+            depth_end: 800.0,
+        };
+        assert_eq!(haunch.size("START"), (500.0, 900.0));
+        assert_eq!(haunch.size("CENTER"), (400.0, 700.0));
+        assert_eq!(haunch.size("END"), (450.0, 800.0));
+        assert_eq!(haunch.size("ALL"), (400.0, 700.0));
+    }
+
+    #[test]
+    fn bar_texts() {
+        assert_eq!(bar_text(5, "D25"), "5-D25");
+        assert_eq!(bar_text(0, "D25"), "");
+        assert_eq!(layered_bar_text(5, 2, "D25"), "5+2-D25");
+        assert_eq!(layered_bar_text(5, 0, "D25"), "5-D25");
+        assert_eq!(layered_bar_text(0, 0, "D25"), "");
+        assert_eq!(pitched_bar_text(2, "D13", 200.0), "2-D13@200");
+        assert_eq!(pitched_bar_text(2, "D13", 150.5), "2-D13@150.5");
+        assert_eq!(pitched_bar_text(0, "D13", 200.0), "");
+    }
+}
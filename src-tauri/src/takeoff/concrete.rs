@@ -0,0 +1,792 @@
+use super::{csv_line, member_kind_order, story_key};
+use crate::geometry::{self, signed_area, Node, Plane};
+use crate::material::concrete_grade;
+use crate::section_property::{bar_area, DEFAULT_COVER};
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::stb_model::stb_sections::*;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::prelude::*;
+
+const REBAR_DENSITY: f64 = 7.85e-6; // kg/mm3
+
+// Volume in m3, formwork area in m2, rebar weight in t.
+#[derive(Debug, Serialize)]
+pub struct ConcreteTakeoffRow {
+    pub story: String,
+    pub grade: String,
+    pub member_kind: String,
+    pub count: u32,
+    pub concrete_volume: f64,
+    pub formwork_area: f64,
+    pub rebar_weight: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConcreteTakeoff {
+    pub row_list: Vec<ConcreteTakeoffRow>,
+    pub total_concrete_volume: f64,
+    pub total_formwork_area: f64,
+    pub total_rebar_weight: f64,
+    pub warning_list: Vec<String>,
+}
+
+struct Quantity {
+    concrete_volume: f64,
+    formwork_area: f64,
+    rebar_weight: f64,
+}
+
+// The bars of one position of a beam, with the share of the beam length it covers.
+struct BarArrangement {
+    weight: f64,
+    main_count: u32,
+    stirrup_count: u32,
+    stirrup_pitch: f64,
+    web_count: u32,
+    bar_spacing_count: u32,
+    bar_spacing_pitch: f64,
+}
+
+// Weight in kg of `length` mm of the named bar.
+fn bar_weight(d: &str, length: f64) -> f64 {
+    bar_area(d).unwrap_or(0.0) * length * REBAR_DENSITY
+}
+
+// Sets of stirrups or hoops along `length` at `pitch`.
+fn set_count(length: f64, pitch: f64) -> f64 {
+    if pitch > 0.0 {
+        (length / pitch).ceil() + 1.0
+    } else {
+        0.0
+    }
+}
+
+// RC column outlines and placements by the nodes at both of their ends.
+type ColumnMap = HashMap<u32, Vec<(Vec<(f64, f64)>, Plane)>>;
+
+fn column_map(st_bridge: &StBridge) -> ColumnMap {
+    let stb_model = &st_bridge.stb_model;
+    let mut column_map: ColumnMap = HashMap::new();
+
+    for stb_column in stb_model.stb_members.stb_columns.values() {
+        let stb_section = match stb_model.stb_sections.get(stb_column.id_section()) {
+            Some(s @ StbSection::StbSecColumnRC { .. }) => s,
+            _ => continue,
+        };
+        let (outline, _) = match geometry::section_outline(st_bridge, stb_section) {
+            Some(o) => o,
+            None => continue,
+        };
+        let (plane, _) = match geometry::member_placement(st_bridge, stb_column) {
+            Some(p) => p,
+            None => continue,
+        };
+        for id in stb_column.node_id_list() {
+            column_map
+                .entry(id)
+                .or_default()
+                .push((outline.clone(), plane));
+        }
+    }
+    column_map
+}
+
+// The thickness of the slab over each slab edge, by the nodes at both ends of the edge in
+// ascending order. Where slabs of different thickness share an edge the thicker one is kept.
+type SlabEdgeMap = HashMap<(u32, u32), f64>;
+
+fn slab_edge_map(st_bridge: &StBridge) -> SlabEdgeMap {
+    let mut slab_edge_map: SlabEdgeMap = HashMap::new();
+
+    for stb_slab in st_bridge.stb_model.stb_members.stb_slabs.values() {
+        let plate = match geometry::plate_outline(st_bridge, stb_slab) {
+            Some(p) => p,
+            None => continue,
+        };
+        let node_id_list = stb_slab.node_id_list();
+        for i in 0..node_id_list.len() {
+            let (a, b) = (node_id_list[i], node_id_list[(i + 1) % node_id_list.len()]);
+            let thickness = slab_edge_map.entry((a.min(b), a.max(b))).or_insert(0.0);
+            *thickness = thickness.max(plate.top - plate.bottom);
+        }
+    }
+    slab_edge_map
+}
+
+// The thickness of the slab a beam carries along its length, or zero for a beam with no slab.
+fn slab_thickness(slab_edge_map: &SlabEdgeMap, stb_member: &StbMember) -> f64 {
+    match (stb_member.node_i(), stb_member.node_j()) {
+        (Some(i), Some(j)) => slab_edge_map
+            .get(&(i.min(j), i.max(j)))
+            .copied()
+            .unwrap_or(0.0),
+        _ => 0.0,
+    }
+}
+
+// Distance from the column axis to where a ray along `direction` leaves the column outline.
+fn face_distance(outline: &[(f64, f64)], plane: &Plane, direction: Node) -> f64 {
+    let (u, v) = (direction.dot(plane.x_axis), direction.dot(plane.y_axis));
+    let mut distance: f64 = 0.0;
+
+    for i in 0..outline.len() {
+        let (ax, ay) = outline[i];
+        let (bx, by) = outline[(i + 1) % outline.len()];
+        let (ex, ey) = (bx - ax, by - ay);
+        let denominator = u * ey - v * ex;
+        if denominator.abs() < 1e-12 {
+            continue;
+        }
+        let t = (ax * ey - ay * ex) / denominator;
+        let s = (ax * v - ay * u) / denominator;
+        if t > 0.0 && (0.0..=1.0).contains(&s) {
+            distance = distance.max(t);
+        }
+    }
+    distance
+}
+
+// The length of a beam between the faces of the RC columns at its ends. The beam-column joint is
+// part of the column's volume, so the beam's concrete and formwork stop at the faces.
+fn clear_length(column_map: &ColumnMap, stb_member: &StbMember, ni: Node, nj: Node) -> f64 {
+    let length = Node::distance(ni, nj);
+    let plan = Node::new(nj.x - ni.x, nj.y - ni.y, 0.0);
+    if plan.length() < 1e-6 {
+        return length;
+    }
+    let direction = plan.normalize();
+
+    let face = |id: Option<u32>, direction: Node| {
+        id.and_then(|id| column_map.get(&id))
+            .map(|column_list| {
+                column_list
+                    .iter()
+                    .map(|(outline, plane)| face_distance(outline, plane, direction))
+                    .fold(0.0, f64::max)
+            })
+            .unwrap_or(0.0)
+    };
+    let deduction =
+        face(stb_member.node_i(), direction) + face(stb_member.node_j(), direction * -1.0);
+
+    // The deduction is measured in plan, so it is scaled back onto a sloping beam.
+    (length - deduction * length / plan.length()).max(0.0)
+}
+
+// Beams are formed on the soffit and both sides between column faces; bars run the full length
+// into the joints. Main bar counts are weighted a quarter each at the ends and a half at the
+// centre, over the positions the section lists. Under a slab the top of the beam is part of the
+// slab's volume, and the slab's soffit already spans the beam's width, so only the sides below
+// the slab are formed.
+fn beam_quantity(
+    stb_section: &StbSection,
+    length: f64,
+    clear_length: f64,
+    slab_thickness: f64,
+) -> Option<Quantity> {
+    let (
+        d_reinforcement_main,
+        d_stirrup,
+        d_reinforcement_web,
+        d_bar_spacing,
+        depth_cover_top,
+        stb_sec_figure,
+        stb_sec_bar_arrangement,
+    ) = match stb_section {
+        StbSection::StbSecBeamRC {
+            d_reinforcement_main,
+            d_stirrup,
+            d_reinforcement_web,
+            d_bar_spacing,
+            depth_cover_top,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+            ..
+        } => (
+            d_reinforcement_main,
+            d_stirrup,
+            d_reinforcement_web,
+            d_bar_spacing,
+            depth_cover_top,
+            stb_sec_figure,
+            stb_sec_bar_arrangement,
+        ),
+        _ => return None,
+    };
+
+    let (width, depth) = geometry::beam_rc_size(stb_sec_figure)?;
+    let cover = depth_cover_top.unwrap_or(DEFAULT_COVER);
+
+    let mut arrangement: Vec<BarArrangement> = Vec::new();
+    if let Some(list) = &stb_sec_bar_arrangement.stb_sec_beam_start_center_end_section_list {
+        let weight = |pos: &StbSecBeamSectionPosition| match pos {
+            StbSecBeamSectionPosition::Center => 0.5,
+            _ => 0.25,
+        };
+        let weight_total: f64 = list.iter().map(|s| weight(&s.pos)).sum();
+        for s in list {
+            arrangement.push(BarArrangement {
+                weight: weight(&s.pos) / weight_total,
+                main_count: s.count_main_top_1st
+                    + s.count_main_top_2nd
+                    + s.count_main_bottom_1st
+                    + s.count_main_bottom_2nd,
+                stirrup_count: s.count_stirrup,
+                stirrup_pitch: s.pitch_stirrup,
+                web_count: s.count_web,
+                bar_spacing_count: s.count_bar_spacing,
+                bar_spacing_pitch: s.pitch_bar_spacing,
+            });
+        }
+    }
+    if let Some(s) = &stb_sec_bar_arrangement.stb_sec_beam_same_section {
+        arrangement.push(BarArrangement {
+            weight: 1.0,
+            main_count: s.count_main_top_1st
+                + s.count_main_top_2nd
+                + s.count_main_bottom_1st
+                + s.count_main_bottom_2nd,
+            stirrup_count: s.count_stirrup,
+            stirrup_pitch: s.pitch_stirrup,
+            web_count: s.count_web,
+            bar_spacing_count: s.count_bar_spacing,
+            bar_spacing_pitch: s.pitch_bar_spacing,
+        });
+    }
+
+    let inner_width = (width - 2.0 * cover).max(0.0);
+    let inner_depth = (depth - 2.0 * cover).max(0.0);
+
+    let rebar_weight: f64 = arrangement
+        .iter()
+        .map(|a| {
+            let part = length * a.weight;
+            let stirrup_length = a.stirrup_count as f64 * inner_depth + 2.0 * inner_width;
+            bar_weight(d_reinforcement_main, a.main_count as f64 * part)
+                + bar_weight(d_stirrup, stirrup_length * set_count(part, a.stirrup_pitch))
+                + bar_weight(d_reinforcement_web, a.web_count as f64 * part)
+                + bar_weight(
+                    d_bar_spacing,
+                    a.bar_spacing_count as f64 * inner_width * set_count(part, a.bar_spacing_pitch),
+                )
+        })
+        .sum();
+
+    let (concrete_depth, formwork_width) = if slab_thickness > 0.0 {
+        ((depth - slab_thickness).max(0.0), 0.0)
+    } else {
+        (depth, width)
+    };
+
+    Some(Quantity {
+        concrete_volume: width * concrete_depth * clear_length / 1e9,
+        formwork_area: (formwork_width + 2.0 * concrete_depth) * clear_length / 1e6,
+        rebar_weight: rebar_weight / 1000.0,
+    })
+}
+
+// Columns are formed all round. Only bar arrangements that are the same over the whole height
+// are read, so a column with any other arrangement is reported and left without rebar.
+fn column_quantity(
+    stb_member: &StbMember,
+    stb_section: &StbSection,
+    length: f64,
+    warning_list: &mut Vec<String>,
+) -> Option<Quantity> {
+    let (d_reinforcement_main, d_rein_band, depth_cover_start_x, stb_sec_figure, bars) =
+        match stb_section {
+            StbSection::StbSecColumnRC {
+                d_reinforcement_main,
+                d_rein_band,
+                depth_cover_start_x,
+                stb_sec_figure,
+                stb_sec_bar_arrangement,
+                ..
+            } => (
+                d_reinforcement_main,
+                d_rein_band,
+                depth_cover_start_x,
+                stb_sec_figure,
+                stb_sec_bar_arrangement,
+            ),
+            _ => return None,
+        };
+
+    let cover = depth_cover_start_x.unwrap_or(DEFAULT_COVER);
+
+    let (area, perimeter, rebar_weight) =
+        match (&stb_sec_figure.stb_sec_rect, &stb_sec_figure.stb_sec_circle) {
+            (Some(rect), _) => {
+                let rebar_weight = bars.stb_sec_rect_column_same.as_ref().map(|b| {
+                    let hoop_length = b.count_band_dir_x as f64 * (rect.width_y - 2.0 * cover)
+                        + b.count_band_dir_y as f64 * (rect.width_x - 2.0 * cover);
+                    bar_weight(d_reinforcement_main, b.count_main_total as f64 * length)
+                        + bar_weight(d_rein_band, hoop_length * set_count(length, b.pitch_band))
+                });
+                (
+                    rect.width_x * rect.width_y,
+                    2.0 * (rect.width_x + rect.width_y),
+                    rebar_weight,
+                )
+            }
+            (None, Some(circle)) => {
+                let rebar_weight = bars.stb_sec_circle_column_same.as_ref().map(|b| {
+                    let hoop_length =
+                        b.count_band as f64 * std::f64::consts::PI * (circle.d - 2.0 * cover);
+                    bar_weight(d_reinforcement_main, b.count_main as f64 * length)
+                        + bar_weight(d_rein_band, hoop_length * set_count(length, b.pitch_band))
+                });
+                (
+                    std::f64::consts::PI * circle.d * circle.d / 4.0,
+                    std::f64::consts::PI * circle.d,
+                    rebar_weight,
+                )
+            }
+            (None, None) => return None,
+        };
+
+    let rebar_weight = rebar_weight.unwrap_or_else(|| {
+        warning_list.push(format!(
+            "{} {}: bar arrangements that vary over the height are not supported, rebar skipped",
+            stb_member.member_kind(),
+            stb_member.name()
+        ));
+        0.0
+    });
+
+    Some(Quantity {
+        concrete_volume: area * length / 1e9,
+        formwork_area: perimeter * length / 1e6,
+        rebar_weight: rebar_weight / 1000.0,
+    })
+}
+
+fn polygon_perimeter(polygon: &[(f64, f64)]) -> f64 {
+    (0..polygon.len())
+        .map(|i| {
+            let (x0, y0) = polygon[i];
+            let (x1, y1) = polygon[(i + 1) % polygon.len()];
+            ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt()
+        })
+        .sum()
+}
+
+// Slabs are formed on the soffit and edges, walls on both faces and edges. Bars run at their
+// pitch over the whole net area.
+fn plate_quantity(
+    st_bridge: &StBridge,
+    stb_member: &StbMember,
+    stb_section: &StbSection,
+) -> Option<Quantity> {
+    let plate = geometry::plate_outline(st_bridge, stb_member)?;
+
+    let net_area = signed_area(&plate.outline).abs()
+        - plate
+            .holes
+            .iter()
+            .map(|h| signed_area(h).abs())
+            .sum::<f64>();
+    let edge_length = polygon_perimeter(&plate.outline)
+        + plate
+            .holes
+            .iter()
+            .map(|h| polygon_perimeter(h))
+            .sum::<f64>();
+    let thickness = plate.top - plate.bottom;
+
+    let (formwork_area, rebar_weight) = match stb_section {
+        StbSection::StbSecSlabRC {
+            stb_sec_bar_arrangement,
+            ..
+        } => {
+            let rebar_weight: f64 = stb_sec_bar_arrangement
+                .stb_sec_1way_slab_1_list
+                .iter()
+                .filter(|bar| bar.pitch > 0.0)
+                .map(|bar| bar_weight(&bar.d, net_area / bar.pitch))
+                .sum();
+            (net_area + edge_length * thickness, rebar_weight)
+        }
+        _ => (2.0 * net_area + edge_length * thickness, 0.0),
+    };
+
+    Some(Quantity {
+        concrete_volume: net_area * thickness / 1e9,
+        formwork_area: formwork_area / 1e6,
+        rebar_weight: rebar_weight / 1000.0,
+    })
+}
+
+pub fn concrete_takeoff(st_bridge: &StBridge) -> ConcreteTakeoff {
+    let stb_model = &st_bridge.stb_model;
+    let column_map = column_map(st_bridge);
+    let slab_edge_map = slab_edge_map(st_bridge);
+
+    let mut rows: BTreeMap<(usize, String, String, usize), ConcreteTakeoffRow> = BTreeMap::new();
+    let mut warning_list = Vec::new();
+
+    for stb_member in stb_model.stb_members.iter() {
+        // SRC and CFT sections are not read, so their concrete is left out rather than guessed.
+        let kind_structure = stb_member.kind_structure();
+        if kind_structure == "SRC" || kind_structure == "CFT" {
+            warning_list.push(format!(
+                "{} {}: {} sections are not supported, skipped",
+                stb_member.member_kind(),
+                stb_member.name(),
+                kind_structure
+            ));
+            continue;
+        }
+
+        let stb_section = match stb_model.stb_sections.get(stb_member.id_section()) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let quantity = match stb_member {
+            StbMember::StbSlab { .. } | StbMember::StbWall { .. } => {
+                plate_quantity(st_bridge, stb_member, stb_section)
+            }
+            StbMember::StbOpen { .. } => None,
+            _ => geometry::member_ends(st_bridge, stb_member).and_then(|(ni, nj)| {
+                let length = Node::distance(ni, nj);
+                match stb_member {
+                    StbMember::StbColumn { .. } | StbMember::StbPost { .. } => {
+                        column_quantity(stb_member, stb_section, length, &mut warning_list)
+                    }
+                    _ => beam_quantity(
+                        stb_section,
+                        length,
+                        clear_length(&column_map, stb_member, ni, nj),
+                        slab_thickness(&slab_edge_map, stb_member),
+                    ),
+                }
+            }),
+        };
+        let quantity = match quantity {
+            Some(q) => q,
+            None => continue,
+        };
+
+        let (story_index, story) = story_key(st_bridge, stb_member);
+        let grade = concrete_grade(st_bridge, stb_member, stb_section);
+        let member_kind = stb_member.member_kind();
+
+        let row = rows
+            .entry((
+                story_index,
+                story.clone(),
+                grade.clone(),
+                member_kind_order(member_kind),
+            ))
+            .or_insert_with(|| ConcreteTakeoffRow {
+                story,
+                grade,
+                member_kind: member_kind.to_string(),
+                count: 0,
+                concrete_volume: 0.0,
+                formwork_area: 0.0,
+                rebar_weight: 0.0,
+            });
+        row.count += 1;
+        row.concrete_volume += quantity.concrete_volume;
+        row.formwork_area += quantity.formwork_area;
+        row.rebar_weight += quantity.rebar_weight;
+    }
+
+    let row_list: Vec<ConcreteTakeoffRow> = rows.into_values().collect();
+
+    ConcreteTakeoff {
+        total_concrete_volume: row_list.iter().map(|r| r.concrete_volume).sum(),
+        total_formwork_area: row_list.iter().map(|r| r.formwork_area).sum(),
+        total_rebar_weight: row_list.iter().map(|r| r.rebar_weight).sum(),
+        row_list,
+        warning_list,
+    }
+}
+
+impl ConcreteTakeoff {
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_line(&[
+            "story".to_string(),
+            "grade".to_string(),
+            "member_kind".to_string(),
+            "count".to_string(),
+            "concrete_volume_m3".to_string(),
+            "formwork_area_m2".to_string(),
+            "rebar_weight_t".to_string(),
+        ]);
+
+        for row in &self.row_list {
+            csv += &csv_line(&[
+                row.story.clone(),
+                row.grade.clone(),
+                row.member_kind.clone(),
+                row.count.to_string(),
+                format!("{:.3}", row.concrete_volume),
+                format!("{:.2}", row.formwork_area),
+                format!("{:.3}", row.rebar_weight),
+            ]);
+        }
+
+        csv += &csv_line(&[
+            "total".to_string(),
+            String::new(),
+            String::new(),
+            self.row_list
+                .iter()
+                .map(|r| r.count)
+                .sum::<u32>()
+                .to_string(),
+            format!("{:.3}", self.total_concrete_volume),
+            format!("{:.2}", self.total_formwork_area),
+            format!("{:.3}", self.total_rebar_weight),
+        ]);
+
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+// Writes CSV when the file name ends in .csv and JSON otherwise.
+pub fn write_concrete_takeoff(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let takeoff = concrete_takeoff(st_bridge);
+    let contents = if file_name.to_lowercase().ends_with(".csv") {
+        takeoff.to_csv()
+    } else {
+        takeoff.to_json()
+    };
+
+    let mut f = File::create(file_name)?;
+    f.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::st_bridge::stb_model::stb_members::ColumnStructureKind;
+
+    fn portal_frame() -> StBridge {
+        crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/portal_frame.stb"
+        ))
+        .unwrap()
+    }
+
+    fn row<'a>(
+        takeoff: &'a ConcreteTakeoff,
+        story: &str,
+        member_kind: &str,
+    ) -> &'a ConcreteTakeoffRow {
+        takeoff
+            .row_list
+            .iter()
+            .find(|r| r.story == story && r.member_kind == member_kind)
+            .unwrap()
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    // Bar areas of D22 and D10 in mm2.
+    const D22: f64 = 387.1;
+    const D10: f64 = 71.33;
+
+    #[test]
+    fn portal_frame_columns() {
+        let takeoff = concrete_takeoff(&portal_frame());
+        let row = row(&takeoff, "1", "Column");
+        assert_eq!(row.grade, "FC24");
+        assert_eq!(row.count, 2);
+
+        // Two 600x600 columns 3000 long.
+        assert_near(row.concrete_volume, 2.0 * 0.6 * 0.6 * 3.0);
+        assert_near(row.formwork_area, 2.0 * 2.4 * 3.0);
+
+        // 20 D22 main bars, and 31 sets of two 500 legs each way at a pitch of 100.
+        let main = 20.0 * 3000.0 * D22;
+        let hoop = 31.0 * 4.0 * 500.0 * D10;
+        assert_near(
+            row.rebar_weight,
+            2.0 * (main + hoop) * REBAR_DENSITY / 1000.0,
+        );
+    }
+
+    #[test]
+    fn portal_frame_girder() {
+        let takeoff = concrete_takeoff(&portal_frame());
+        let row = row(&takeoff, "2", "Girder");
+        assert_eq!(row.grade, "FC24");
+        assert_eq!(row.count, 1);
+
+        // The 6000 span less half of each 600 column is 5400 between the faces. The 150 slab
+        // over the girder takes its top, leaving 550 below the slab formed on both sides.
+        assert_near(row.concrete_volume, 0.4 * 0.55 * 5.4);
+        assert_near(row.formwork_area, 2.0 * 0.55 * 5.4);
+
+        // The ends cover 1500 each and the centre 3000 of the full 6000. Stirrups are 1800 long
+        // (two 600 legs and two 300 ties), 16 sets at each end at 100 and 16 at the centre at 200.
+        // Bar spacers are 300 long, 3 at each end and 4 at the centre.
+        let main = (9.0 * 1500.0 * 2.0 + 8.0 * 3000.0) * D22;
+        let stirrup = 3.0 * 16.0 * 1800.0 * D10;
+        let web = 2.0 * 6000.0 * D10;
+        let bar_spacing = (3.0 + 4.0 + 3.0) * 300.0 * D10;
+        assert_near(
+            row.rebar_weight,
+            (main + stirrup + web + bar_spacing) * REBAR_DENSITY / 1000.0,
+        );
+    }
+
+    #[test]
+    fn portal_frame_slab() {
+        let takeoff = concrete_takeoff(&portal_frame());
+        let row = row(&takeoff, "2", "Slab");
+        assert_eq!(row.grade, "FC24");
+        assert_eq!(row.count, 1);
+
+        // A 6000 square 150 thick, formed on the soffit and the edges, with four layers of D10
+        // at 200.
+        assert_near(row.concrete_volume, 6.0 * 6.0 * 0.15);
+        assert_near(row.formwork_area, 6.0 * 6.0 + 24.0 * 0.15);
+        let bar_length = 6000.0 * 6000.0 / 200.0;
+        assert_near(
+            row.rebar_weight,
+            4.0 * bar_length * D10 * REBAR_DENSITY / 1000.0,
+        );
+    }
+
+    #[test]
+    fn portal_frame_totals_and_order() {
+        let takeoff = concrete_takeoff(&portal_frame());
+
+        // The steel brace has no concrete.
+        let key_list: Vec<(&str, &str)> = takeoff
+            .row_list
+            .iter()
+            .map(|r| (r.story.as_str(), r.member_kind.as_str()))
+            .collect();
+        assert_eq!(
+            key_list,
+            vec![("1", "Column"), ("2", "Girder"), ("2", "Slab")]
+        );
+
+        assert_near(
+            takeoff.total_concrete_volume,
+            takeoff.row_list.iter().map(|r| r.concrete_volume).sum(),
+        );
+        assert_near(
+            takeoff.total_rebar_weight,
+            takeoff.row_list.iter().map(|r| r.rebar_weight).sum(),
+        );
+        assert!(takeoff.warning_list.is_empty());
+
+        let csv = takeoff.to_csv();
+        assert!(csv.starts_with("story,grade,member_kind,count,"));
+        assert!(csv.contains("\n1,FC24,Column,2,2.160,14.40,"));
+        assert!(csv.contains("\ntotal,,,4,"));
+    }
+
+    #[test]
+    fn src_members_are_reported() {
+        let mut st_bridge = portal_frame();
+        if let Some(StbMember::StbColumn { kind_structure, .. }) =
+            st_bridge.stb_model.stb_members.stb_columns.get_mut(&2)
+        {
+            *kind_structure = ColumnStructureKind::SRC;
+        }
+
+        let takeoff = concrete_takeoff(&st_bridge);
+        assert_eq!(row(&takeoff, "1", "Column").count, 1);
+        assert_eq!(
+            takeoff.warning_list,
+            vec!["Column C1: SRC sections are not supported, skipped".to_string()]
+        );
+    }
+
+    #[test]
+    fn girder_without_a_slab() {
+        let mut st_bridge = portal_frame();
+        st_bridge.stb_model.stb_members.stb_slabs.clear();
+
+        let takeoff = concrete_takeoff(&st_bridge);
+        let row = row(&takeoff, "2", "Girder");
+        assert_near(row.concrete_volume, 0.4 * 0.7 * 5.4);
+        assert_near(row.formwork_area, (0.4 + 2.0 * 0.7) * 5.4);
+    }
+
+    #[test]
+    fn column_without_same_bar_arrangement_is_reported() {
+        let mut st_bridge = portal_frame();
+        for stb_section in st_bridge.stb_model.stb_sections.column_rc_map.values_mut() {
+            if let StbSection::StbSecColumnRC {
+                stb_sec_bar_arrangement,
+                ..
+            } = stb_section
+            {
+                stb_sec_bar_arrangement.stb_sec_rect_column_same = None;
+            }
+        }
+
+        let takeoff = concrete_takeoff(&st_bridge);
+        let row = row(&takeoff, "1", "Column");
+        assert_eq!(row.rebar_weight, 0.0);
+        assert_near(row.concrete_volume, 2.0 * 0.6 * 0.6 * 3.0);
+        assert_eq!(takeoff.warning_list.len(), 2);
+        assert!(takeoff.warning_list[0].ends_with(
+            ": bar arrangements that vary over the height are not supported, rebar skipped"
+        ));
+    }
+
+    #[test]
+    fn face_distance_along_a_diagonal() {
+        let outline = vec![
+            (-300.0, -300.0),
+            (300.0, -300.0),
+            (300.0, 300.0),
+            (-300.0, 300.0),
+        ];
+        let plane = Plane::new(
+            Node::new(0.0, 0.0, 0.0),
+            Node::new(1.0, 0.0, 0.0),
+            Node::new(0.0, 0.0, 1.0),
+        );
+
+        assert_near(
+            face_distance(&outline, &plane, Node::new(1.0, 0.0, 0.0)),
+            300.0,
+        );
+        assert_near(
+            face_distance(&outline, &plane, Node::new(0.0, -1.0, 0.0)),
+            300.0,
+        );
+        let diagonal = Node::new(1.0, 1.0, 0.0).normalize();
+        assert_near(
+            face_distance(&outline, &plane, diagonal),
+            300.0 * 2.0_f64.sqrt(),
+        );
+    }
+
+    #[test]
+    fn bar_weight_and_set_count() {
+        // 1 m of D22 weighs about 3.04 kg.
+        assert_near(bar_weight("D22", 1000.0), 1000.0 * D22 * REBAR_DENSITY);
+        assert_eq!(bar_weight("X99", 1000.0), 0.0);
+
+        assert_eq!(set_count(1500.0, 100.0), 16.0);
+        assert_eq!(set_count(1550.0, 100.0), 17.0);
+        assert_eq!(set_count(1500.0, 0.0), 0.0);
+    }
+}
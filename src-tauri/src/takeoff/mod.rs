@@ -0,0 +1,43 @@
+pub mod concrete;
+pub mod steel;
+
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::StBridge;
+
+// Stories sort by height; members without a story go last.
+fn story_key(st_bridge: &StBridge, stb_member: &StbMember) -> (usize, String) {
+    let stb_model = &st_bridge.stb_model;
+
+    match stb_model.story(stb_member) {
+        Some(stb_story) => {
+            let index = stb_model
+                .stb_stories
+                .stb_story_list
+                .iter()
+                .filter(|s| s.height < stb_story.height)
+                .count();
+            (index, stb_story.name.clone())
+        }
+        None => (usize::MAX, String::new()),
+    }
+}
+
+fn member_kind_order(member_kind: &str) -> usize {
+    ["Column", "Post", "Girder", "Beam", "Brace", "Slab", "Wall"]
+        .iter()
+        .position(|k| *k == member_kind)
+        .unwrap_or(usize::MAX)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn csv_line(fields: &[String]) -> String {
+    let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    fields.join(",") + "\n"
+}
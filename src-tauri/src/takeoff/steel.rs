@@ -0,0 +1,224 @@
+use super::{csv_line, member_kind_order, story_key};
+use crate::geometry::{self, Node};
+use crate::section_property;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::prelude::*;
+
+// Lengths in m, unit weight in kg/m, weight in t.
+#[derive(Debug, Serialize)]
+pub struct SteelTakeoffRow {
+    pub story: String,
+    pub member_kind: String,
+    pub section: String,
+    pub shape: String,
+    pub grade: String,
+    pub count: u32,
+    pub length: f64,
+    pub unit_weight: f64,
+    pub weight: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SteelTakeoff {
+    pub row_list: Vec<SteelTakeoffRow>,
+    pub total_count: u32,
+    pub total_length: f64,
+    pub total_weight: f64,
+}
+
+pub fn steel_takeoff(st_bridge: &StBridge) -> SteelTakeoff {
+    let stb_model = &st_bridge.stb_model;
+    let stb_sections = &stb_model.stb_sections;
+
+    let mut rows: BTreeMap<(usize, String, usize, String, String, String), SteelTakeoffRow> =
+        BTreeMap::new();
+
+    for stb_member in stb_model.stb_members.iter().filter(|m| m.is_linear()) {
+        let stb_section = match stb_sections.get(stb_member.id_section()) {
+            Some(s) => s,
+            None => continue,
+        };
+        let shape = match stb_section.steel_shape() {
+            Some(s) => s,
+            None => continue,
+        };
+        let steel = match stb_sections.stb_sec_steel.get(shape) {
+            Some(s) => s,
+            None => continue,
+        };
+        let (ni, nj) = match geometry::member_ends(st_bridge, stb_member) {
+            Some(ends) => ends,
+            None => continue,
+        };
+
+        let grade = crate::material::section_grade_list(stb_section)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let (story_index, story) = story_key(st_bridge, stb_member);
+        let member_kind = stb_member.member_kind();
+        let key = (
+            story_index,
+            story.clone(),
+            member_kind_order(member_kind),
+            stb_section.name(),
+            shape.clone(),
+            grade.clone(),
+        );
+
+        let unit_weight = section_property::steel_section_property(steel).unit_weight;
+        let length = Node::distance(ni, nj) / 1000.0;
+
+        let row = rows.entry(key).or_insert_with(|| SteelTakeoffRow {
+            story,
+            member_kind: member_kind.to_string(),
+            section: stb_section.name(),
+            shape: shape.clone(),
+            grade,
+            count: 0,
+            length: 0.0,
+            unit_weight,
+            weight: 0.0,
+        });
+        row.count += 1;
+        row.length += length;
+        row.weight += length * unit_weight / 1000.0;
+    }
+
+    let row_list: Vec<SteelTakeoffRow> = rows.into_values().collect();
+
+    SteelTakeoff {
+        total_count: row_list.iter().map(|r| r.count).sum(),
+        total_length: row_list.iter().map(|r| r.length).sum(),
+        total_weight: row_list.iter().map(|r| r.weight).sum(),
+        row_list,
+    }
+}
+
+impl SteelTakeoff {
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_line(&[
+            "story".to_string(),
+            "member_kind".to_string(),
+            "section".to_string(),
+            "shape".to_string(),
+            "grade".to_string(),
+            "count".to_string(),
+            "length_m".to_string(),
+            "unit_weight_kg_per_m".to_string(),
+            "weight_t".to_string(),
+        ]);
+
+        for row in &self.row_list {
+            csv += &csv_line(&[
+                row.story.clone(),
+                row.member_kind.clone(),
+                row.section.clone(),
+                row.shape.clone(),
+                row.grade.clone(),
+                row.count.to_string(),
+                format!("{:.3}", row.length),
+                format!("{:.2}", row.unit_weight),
+                format!("{:.3}", row.weight),
+            ]);
+        }
+
+        csv += &csv_line(&[
+            "total".to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            self.total_count.to_string(),
+            format!("{:.3}", self.total_length),
+            String::new(),
+            format!("{:.3}", self.total_weight),
+        ]);
+
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+// Writes CSV when the file name ends in .csv and JSON otherwise.
+pub fn write_steel_takeoff(st_bridge: &StBridge, file_name: &str) -> std::io::Result<()> {
+    let takeoff = steel_takeoff(st_bridge);
+    let contents = if file_name.to_lowercase().ends_with(".csv") {
+        takeoff.to_csv()
+    } else {
+        takeoff.to_json()
+    };
+
+    let mut f = File::create(file_name)?;
+    f.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SteelTakeoff {
+        let st_bridge = crate::read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap();
+        steel_takeoff(&st_bridge)
+    }
+
+    #[test]
+    fn sample_totals() {
+        let takeoff = sample();
+
+        let columns: u32 = takeoff
+            .row_list
+            .iter()
+            .filter(|r| r.member_kind == "Column")
+            .map(|r| r.count)
+            .sum();
+        assert_eq!(columns, 34);
+        assert_eq!(takeoff.total_count, 209);
+        assert!((takeoff.total_length - 1019.183).abs() < 1e-3);
+        assert!((takeoff.total_weight - 93.183).abs() < 1e-3);
+
+        let weight: f64 = takeoff.row_list.iter().map(|r| r.weight).sum();
+        assert!((takeoff.total_weight - weight).abs() < 1e-9);
+    }
+
+    #[test]
+    fn built_up_column_row() {
+        let takeoff = sample();
+
+        let row = takeoff
+            .row_list
+            .iter()
+            .find(|r| r.story == "R" && r.section == "C10")
+            .unwrap();
+
+        // BH-250x250x9x14: 2 x 250 x 14 + 9 x (250 - 2 x 14) mm2 of steel at 7850 kg/m3.
+        let unit_weight = (2.0 * 250.0 * 14.0 + 9.0 * 222.0) * 7.85e-3;
+        assert_eq!(row.shape, "BH-250x250x9x14x0");
+        assert_eq!(row.grade, "SN400");
+        assert_eq!(row.count, 4);
+        assert!((row.length - 8.2).abs() < 1e-9);
+        assert!((row.unit_weight - unit_weight).abs() < 1e-9);
+        assert!((row.weight - 8.2 * unit_weight / 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn csv_ends_with_totals() {
+        let csv = sample().to_csv();
+
+        assert!(csv.starts_with("story,member_kind,section,shape,grade,count,"));
+        assert_eq!(
+            csv.lines().last().unwrap(),
+            "total,,,,,209,1019.183,,93.183"
+        );
+    }
+}
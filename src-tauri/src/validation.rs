@@ -0,0 +1,584 @@
+use crate::connectivity::disconnected_parts;
+use crate::node_merge::find_duplicate_nodes;
+use crate::st_bridge::stb_model::stb_axes_and_stories::StbNodeIdList;
+use crate::st_bridge::stb_model::stb_members::StbMember;
+use crate::st_bridge::stb_model::stb_sections::StbSection;
+use crate::st_bridge::StBridge;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Nodes closer than this are coincident and members shorter than this have zero length (mm).
+pub const NODE_TOLERANCE: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Issue {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub node_id_list: Vec<u32>,
+    pub member_id_list: Vec<u32>,
+    pub section_id_list: Vec<u32>,
+}
+
+impl Issue {
+    pub fn new(rule: &str, severity: Severity, message: String) -> Issue {
+        Issue {
+            rule: rule.to_string(),
+            severity,
+            message,
+            node_id_list: Vec::new(),
+            member_id_list: Vec::new(),
+            section_id_list: Vec::new(),
+        }
+    }
+
+    pub fn nodes(mut self, node_id_list: Vec<u32>) -> Issue {
+        self.node_id_list = node_id_list;
+        self
+    }
+
+    pub fn members(mut self, member_id_list: Vec<u32>) -> Issue {
+        self.member_id_list = member_id_list;
+        self
+    }
+
+    pub fn sections(mut self, section_id_list: Vec<u32>) -> Issue {
+        self.section_id_list = section_id_list;
+        self
+    }
+}
+
+pub trait Rule {
+    fn name(&self) -> &'static str;
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue>;
+}
+
+pub struct Validator {
+    pub rule_list: Vec<Box<dyn Rule>>,
+}
+
+impl Validator {
+    // Starts with the built-in rules; call add() to plug in more.
+    pub fn new() -> Validator {
+        Validator {
+            rule_list: vec![
+                Box::new(MissingNodeRule),
+                Box::new(SectionRule),
+                Box::new(SteelShapeRule),
+                Box::new(ZeroLengthRule),
+                Box::new(DuplicateNodeRule),
+                Box::new(UnusedNodeRule),
+                Box::new(NodeIdListRule),
+                Box::new(DisconnectedRule),
+            ],
+        }
+    }
+
+    pub fn add(&mut self, rule: Box<dyn Rule>) {
+        self.rule_list.push(rule);
+    }
+
+    // Issues come back most severe first, in rule order otherwise.
+    pub fn validate(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let mut issue_list: Vec<Issue> = self
+            .rule_list
+            .iter()
+            .flat_map(|rule| rule.check(st_bridge))
+            .collect();
+        issue_list.sort_by_key(|i| std::cmp::Reverse(i.severity));
+        issue_list
+    }
+}
+
+impl Default for Validator {
+    fn default() -> Validator {
+        Validator::new()
+    }
+}
+
+pub fn validate(st_bridge: &StBridge) -> Vec<Issue> {
+    Validator::new().validate(st_bridge)
+}
+
+// Members in kind and id order so that reports are stable.
+fn sorted_members(st_bridge: &StBridge) -> Vec<&StbMember> {
+    let mut member_list: Vec<&StbMember> = st_bridge.stb_model.stb_members.iter().collect();
+    member_list.sort_by_key(|m| (m.member_kind(), m.id()));
+    member_list
+}
+
+pub struct MissingNodeRule;
+
+impl Rule for MissingNodeRule {
+    fn name(&self) -> &'static str {
+        "missing_node"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let stb_model = &st_bridge.stb_model;
+        let mut issue_list = Vec::new();
+
+        for stb_member in sorted_members(st_bridge) {
+            let missing: Vec<u32> = stb_member
+                .node_id_list()
+                .into_iter()
+                .filter(|&id| stb_model.stb_nodes.get(id).is_none())
+                .collect();
+
+            if !missing.is_empty() {
+                issue_list.push(
+                    Issue::new(
+                        self.name(),
+                        Severity::Error,
+                        format!(
+                            "{} {} references missing nodes {:?}",
+                            stb_member.member_kind(),
+                            stb_member.name(),
+                            missing
+                        ),
+                    )
+                    .nodes(missing)
+                    .members(vec![stb_member.id()]),
+                );
+            }
+
+            if let StbMember::StbOpen { id_member, .. } = stb_member {
+                if stb_model.stb_members.get(*id_member).is_none() {
+                    issue_list.push(
+                        Issue::new(
+                            self.name(),
+                            Severity::Error,
+                            format!(
+                                "Open {} references missing member {}",
+                                stb_member.name(),
+                                id_member
+                            ),
+                        )
+                        .members(vec![stb_member.id()]),
+                    );
+                }
+            }
+        }
+
+        issue_list
+    }
+}
+
+// Openings reference StbSecOpen sections, which are not read, so they are not checked.
+pub struct SectionRule;
+
+impl Rule for SectionRule {
+    fn name(&self) -> &'static str {
+        "section"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let mut issue_list = Vec::new();
+
+        for stb_member in sorted_members(st_bridge) {
+            if let StbMember::StbOpen { .. } = stb_member {
+                continue;
+            }
+
+            let id_section = stb_member.id_section();
+            let stb_section = match st_bridge.stb_model.stb_sections.get(id_section) {
+                Some(s) => s,
+                None => {
+                    issue_list.push(
+                        Issue::new(
+                            self.name(),
+                            Severity::Error,
+                            format!(
+                                "{} {} references missing section {}",
+                                stb_member.member_kind(),
+                                stb_member.name(),
+                                id_section
+                            ),
+                        )
+                        .members(vec![stb_member.id()])
+                        .sections(vec![id_section]),
+                    );
+                    continue;
+                }
+            };
+
+            let matches = matches!(
+                (stb_member, stb_section),
+                (
+                    StbMember::StbColumn { .. } | StbMember::StbPost { .. },
+                    StbSection::StbSecColumnRC { .. } | StbSection::StbSecColumnS { .. }
+                ) | (
+                    StbMember::StbGirder { .. } | StbMember::StbBeam { .. },
+                    StbSection::StbSecBeamRC { .. } | StbSection::StbSecBeamS { .. }
+                ) | (StbMember::StbBrace { .. }, StbSection::StbSecBraceS { .. })
+                    | (StbMember::StbSlab { .. }, StbSection::StbSecSlabRC { .. })
+                    | (StbMember::StbWall { .. }, StbSection::StbSecWallRC { .. })
+            );
+
+            if !matches {
+                issue_list.push(
+                    Issue::new(
+                        self.name(),
+                        Severity::Error,
+                        format!(
+                            "{} {} uses section {} of the wrong kind",
+                            stb_member.member_kind(),
+                            stb_member.name(),
+                            stb_section.name()
+                        ),
+                    )
+                    .members(vec![stb_member.id()])
+                    .sections(vec![id_section]),
+                );
+                continue;
+            }
+
+            // SRC and CFT members may carry either kind of section.
+            let kind_structure = stb_member.kind_structure();
+            let is_steel = stb_section.steel_shape().is_some();
+            if (kind_structure == "S" && !is_steel) || (kind_structure == "RC" && is_steel) {
+                issue_list.push(
+                    Issue::new(
+                        self.name(),
+                        Severity::Warning,
+                        format!(
+                            "{} {} is {} but section {} is not",
+                            stb_member.member_kind(),
+                            stb_member.name(),
+                            kind_structure,
+                            stb_section.name()
+                        ),
+                    )
+                    .members(vec![stb_member.id()])
+                    .sections(vec![id_section]),
+                );
+            }
+        }
+
+        issue_list
+    }
+}
+
+pub struct SteelShapeRule;
+
+impl Rule for SteelShapeRule {
+    fn name(&self) -> &'static str {
+        "steel_shape"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let stb_sections = &st_bridge.stb_model.stb_sections;
+
+        let mut missing: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        for stb_section in stb_sections
+            .column_s_map
+            .values()
+            .chain(stb_sections.beam_s_map.values())
+            .chain(stb_sections.brace_s_map.values())
+        {
+            if let Some(shape) = stb_section.steel_shape() {
+                if stb_sections.stb_sec_steel.get(shape).is_none() {
+                    missing
+                        .entry(shape.clone())
+                        .or_default()
+                        .push(stb_section.id());
+                }
+            }
+        }
+
+        missing
+            .into_iter()
+            .map(|(shape, mut section_id_list)| {
+                section_id_list.sort_unstable();
+                let member_id_list: Vec<u32> = sorted_members(st_bridge)
+                    .into_iter()
+                    .filter(|m| section_id_list.contains(&m.id_section()))
+                    .map(|m| m.id())
+                    .collect();
+
+                Issue::new(
+                    self.name(),
+                    Severity::Error,
+                    format!("Steel shape {} is not defined in StbSecSteel", shape),
+                )
+                .members(member_id_list)
+                .sections(section_id_list)
+            })
+            .collect()
+    }
+}
+
+pub struct ZeroLengthRule;
+
+impl Rule for ZeroLengthRule {
+    fn name(&self) -> &'static str {
+        "zero_length"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let stb_nodes = &st_bridge.stb_model.stb_nodes;
+
+        sorted_members(st_bridge)
+            .into_iter()
+            .filter_map(|m| {
+                let (i, j) = (m.node_i()?, m.node_j()?);
+                let ni = stb_nodes.get(i)?;
+                let nj = stb_nodes.get(j)?;
+                let length =
+                    ((nj.x - ni.x).powi(2) + (nj.y - ni.y).powi(2) + (nj.z - ni.z).powi(2)).sqrt();
+
+                if length < NODE_TOLERANCE {
+                    Some(
+                        Issue::new(
+                            self.name(),
+                            Severity::Error,
+                            format!("{} {} has zero length", m.member_kind(), m.name()),
+                        )
+                        .nodes(vec![i, j])
+                        .members(vec![m.id()]),
+                    )
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct DuplicateNodeRule;
+
+impl Rule for DuplicateNodeRule {
+    fn name(&self) -> &'static str {
+        "duplicate_node"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        find_duplicate_nodes(st_bridge, NODE_TOLERANCE)
+            .unwrap()
+            .into_iter()
+            .map(|group| {
+                let node_id_list = group.node_id_list();
+                Issue::new(
+                    self.name(),
+                    Severity::Warning,
+                    format!("Nodes {:?} are coincident", node_id_list),
+                )
+                .nodes(node_id_list)
+            })
+            .collect()
+    }
+}
+
+pub struct UnusedNodeRule;
+
+impl Rule for UnusedNodeRule {
+    fn name(&self) -> &'static str {
+        "unused_node"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let used: BTreeSet<u32> = st_bridge
+            .stb_model
+            .stb_members
+            .iter()
+            .flat_map(|m| m.node_id_list())
+            .collect();
+
+        let mut unused: Vec<u32> = st_bridge
+            .stb_model
+            .stb_nodes
+            .map
+            .keys()
+            .filter(|id| !used.contains(id))
+            .cloned()
+            .collect();
+        unused.sort_unstable();
+
+        if unused.is_empty() {
+            return Vec::new();
+        }
+
+        vec![Issue::new(
+            self.name(),
+            Severity::Info,
+            format!("{} nodes are used by no member", unused.len()),
+        )
+        .nodes(unused)]
+    }
+}
+
+// Story and axis node lists that point at nodes which do not exist.
+pub struct NodeIdListRule;
+
+impl Rule for NodeIdListRule {
+    fn name(&self) -> &'static str {
+        "node_id_list"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        let stb_model = &st_bridge.stb_model;
+
+        let unknown = |list: &StbNodeIdList| -> Vec<u32> {
+            list.children
+                .iter()
+                .map(|n| n.id as u32)
+                .filter(|&id| stb_model.stb_nodes.get(id).is_none())
+                .collect()
+        };
+
+        let mut owner_list: Vec<(String, &StbNodeIdList)> = Vec::new();
+        for s in &stb_model.stb_stories.stb_story_list {
+            owner_list.push((format!("Story {}", s.name), &s.stb_node_id_list));
+        }
+        for a in &stb_model.stb_axes.stb_x_axis_list {
+            owner_list.push((format!("X axis {}", a.name), &a.stb_node_id_list));
+        }
+        for a in &stb_model.stb_axes.stb_y_axis_list {
+            owner_list.push((format!("Y axis {}", a.name), &a.stb_node_id_list));
+        }
+
+        owner_list
+            .into_iter()
+            .filter_map(|(owner, list)| {
+                let missing = unknown(list);
+                if missing.is_empty() {
+                    return None;
+                }
+                Some(
+                    Issue::new(
+                        self.name(),
+                        Severity::Warning,
+                        format!("{} references unknown nodes {:?}", owner, missing),
+                    )
+                    .nodes(missing),
+                )
+            })
+            .collect()
+    }
+}
+
+pub struct DisconnectedRule;
+
+impl Rule for DisconnectedRule {
+    fn name(&self) -> &'static str {
+        "disconnected"
+    }
+
+    fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+        disconnected_parts(st_bridge)
+            .into_iter()
+            .map(|part| {
+                Issue::new(
+                    self.name(),
+                    Severity::Warning,
+                    format!(
+                        "{} members do not connect to the foundation",
+                        part.member_id_list.len()
+                    ),
+                )
+                .nodes(part.node_id_list)
+                .members(part.member_id_list)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_st_bridge;
+
+    fn sample() -> StBridge {
+        read_st_bridge(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../dist/steel_standard_model.stb"
+        ))
+        .unwrap()
+    }
+
+    fn rule_issues<'a>(issue_list: &'a [Issue], rule: &str) -> Vec<&'a Issue> {
+        issue_list.iter().filter(|i| i.rule == rule).collect()
+    }
+
+    #[test]
+    fn sample_has_no_errors() {
+        let issue_list = validate(&sample());
+
+        assert!(issue_list.iter().all(|i| i.severity != Severity::Error));
+        assert_eq!(rule_issues(&issue_list, "duplicate_node").len(), 65);
+
+        let unused = rule_issues(&issue_list, "unused_node");
+        assert_eq!(unused.len(), 1);
+        assert_eq!(unused[0].severity, Severity::Info);
+        assert_eq!(unused[0].node_id_list.len(), 20);
+    }
+
+    #[test]
+    fn zero_length_member() {
+        let mut st_bridge = sample();
+        // Column 1C1 runs from node 9 to node 44.
+        let bottom = st_bridge.stb_model.stb_nodes.get(9).unwrap();
+        st_bridge.stb_model.stb_nodes.insert(44, bottom);
+
+        let issue_list = ZeroLengthRule.check(&st_bridge);
+
+        let issue = issue_list.iter().find(|i| i.member_id_list == [1]).unwrap();
+        assert_eq!(issue.severity, Severity::Error);
+        assert_eq!(issue.message, "Column 1C1 has zero length");
+        assert_eq!(issue.node_id_list, [9, 44]);
+    }
+
+    #[test]
+    fn missing_node() {
+        let mut st_bridge = sample();
+        st_bridge.stb_model.stb_nodes.map.remove(&9);
+
+        let issue_list = validate(&st_bridge);
+
+        assert_eq!(issue_list[0].severity, Severity::Error);
+        let missing = rule_issues(&issue_list, "missing_node");
+        assert!(!missing.is_empty());
+        assert!(missing.iter().all(|i| i.node_id_list == [9]));
+        assert!(missing.iter().any(|i| i.member_id_list == [1]));
+        assert!(!rule_issues(&issue_list, "node_id_list").is_empty());
+    }
+
+    struct NoBracesRule;
+
+    impl Rule for NoBracesRule {
+        fn name(&self) -> &'static str {
+            "no_braces"
+        }
+
+        fn check(&self, st_bridge: &StBridge) -> Vec<Issue> {
+            st_bridge
+                .stb_model
+                .stb_members
+                .stb_braces
+                .keys()
+                .map(|&id| {
+                    Issue::new(self.name(), Severity::Error, "brace".to_string()).members(vec![id])
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn custom_rules_sort_by_severity() {
+        let mut validator = Validator::new();
+        validator.add(Box::new(NoBracesRule));
+
+        let issue_list = validator.validate(&sample());
+
+        assert_eq!(rule_issues(&issue_list, "no_braces").len(), 4);
+        assert!(issue_list[..4].iter().all(|i| i.rule == "no_braces"));
+        assert_eq!(issue_list.last().unwrap().rule, "unused_node");
+    }
+}
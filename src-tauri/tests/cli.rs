@@ -0,0 +1,197 @@
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const PORTAL_FRAME: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/portal_frame.stb"
+);
+
+fn stb(arg_list: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_stb"))
+        .args(arg_list)
+        .output()
+        .unwrap()
+}
+
+fn code(output: &Output) -> i32 {
+    output.status.code().unwrap()
+}
+
+fn json(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+// A file in the temporary directory, named after the test so that tests can run in parallel.
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("stb-cli-{}-{}", std::process::id(), name))
+}
+
+// The portal frame with one edit applied to its text.
+fn edited_portal_frame(name: &str, from: &str, to: &str) -> PathBuf {
+    let text = std::fs::read_to_string(PORTAL_FRAME).unwrap();
+    assert!(text.contains(from));
+    let path = temp_path(name);
+    std::fs::write(&path, text.replace(from, to)).unwrap();
+    path
+}
+
+#[test]
+fn info_json() {
+    let output = stb(&["info", PORTAL_FRAME, "--json"]);
+
+    assert_eq!(code(&output), 0);
+    let value = json(&output);
+    assert_eq!(value["version"], "1.4.00");
+    assert_eq!(value["project_name"], "Portal frame");
+    assert_eq!(value["node_count"], 6);
+    assert_eq!(value["story_count"], 2);
+    assert_eq!(value["member_count"]["Column"], 2);
+    assert_eq!(value["member_count"]["Slab"], 1);
+}
+
+#[test]
+fn validate_json() {
+    let output = stb(&["validate", PORTAL_FRAME, "--json"]);
+
+    assert_eq!(code(&output), 0);
+    assert_eq!(json(&output), Value::Array(Vec::new()));
+}
+
+#[test]
+fn validate_fails_on_errors() {
+    let path = edited_portal_frame(
+        "missing_node.stb",
+        r#"<StbNode id="2" x="6000" y="0" z="0" kind="ON_GRID"/>"#,
+        "",
+    );
+    let output = stb(&["validate", path.to_str().unwrap(), "--json"]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(code(&output), 1);
+    let value = json(&output);
+    let issue = &value.as_array().unwrap()[0];
+    assert_eq!(issue["severity"], "Error");
+    assert_eq!(issue["rule"], "missing_node");
+}
+
+#[test]
+fn convert_to_saf() {
+    let path = temp_path("convert.json");
+    let output = stb(&["convert", PORTAL_FRAME, path.to_str().unwrap()]);
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(code(&output), 0);
+    let saf: Value = serde_json::from_str(&text).unwrap();
+    let expected = include_str!("fixtures/portal_frame_saf.json");
+    assert_eq!(saf, serde_json::from_str::<Value>(expected).unwrap());
+}
+
+#[test]
+fn takeoff_json() {
+    let output = stb(&["takeoff", PORTAL_FRAME, "--json"]);
+
+    assert_eq!(code(&output), 0);
+    let value = json(&output);
+    assert_eq!(value["steel"]["row_list"].as_array().unwrap().len(), 1);
+    let concrete = &value["concrete"];
+    assert_eq!(concrete["row_list"][0]["member_kind"], "Column");
+    assert_eq!(concrete["row_list"][0]["concrete_volume"], 2.16);
+    assert_eq!(concrete["warning_list"], Value::Array(Vec::new()));
+
+    let output = stb(&["takeoff", PORTAL_FRAME, "--steel", "--json"]);
+    assert_eq!(json(&output)["concrete"], Value::Null);
+}
+
+#[test]
+fn diff_json() {
+    let output = stb(&["diff", PORTAL_FRAME, PORTAL_FRAME, "--json"]);
+
+    assert_eq!(code(&output), 0);
+    let value = json(&output);
+    assert_eq!(value["node_list"], Value::Array(Vec::new()));
+    assert_eq!(value["member_list"], Value::Array(Vec::new()));
+    assert_eq!(value["section_list"], Value::Array(Vec::new()));
+}
+
+#[test]
+fn diff_fails_on_changes() {
+    let path = edited_portal_frame(
+        "moved_node.stb",
+        r#"<StbNode id="2" x="6000""#,
+        r#"<StbNode id="2" x="6100""#,
+    );
+    let output = stb(&["diff", PORTAL_FRAME, path.to_str().unwrap(), "--json"]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(code(&output), 1);
+    let change = &json(&output)["node_list"][0];
+    assert_eq!(change["kind"], "Modified");
+    assert_eq!(change["new_id"], 2);
+    assert_eq!(change["field_list"][0]["field"], "x");
+    assert_eq!(change["field_list"][0]["new"], 6100.0);
+}
+
+#[test]
+fn render_plan() {
+    let path = temp_path("plan.svg");
+    let output = stb(&[
+        "render-plan",
+        PORTAL_FRAME,
+        path.to_str().unwrap(),
+        "--story",
+        "2",
+    ]);
+    let text = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(code(&output), 0);
+    assert!(text.contains("<svg"));
+
+    // Without --story every story goes to the PDF as a page.
+    let path = temp_path("plan.pdf");
+    let output = stb(&["render-plan", PORTAL_FRAME, path.to_str().unwrap()]);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(code(&output), 0);
+    assert!(bytes.starts_with(b"%PDF"));
+}
+
+#[test]
+fn errors_exit_with_2() {
+    let path = temp_path("error.svg");
+    let path = path.to_str().unwrap();
+    let arg_list_list: [&[&str]; 6] = [
+        &[],
+        &["build", PORTAL_FRAME],
+        &["info", PORTAL_FRAME, "--verbose"],
+        &["info", "missing.stb"],
+        &["diff", PORTAL_FRAME, PORTAL_FRAME, "--tolerance", "near"],
+        &["render-plan", PORTAL_FRAME, path, "--story", "9"],
+    ];
+    for arg_list in arg_list_list {
+        let output = stb(arg_list);
+        assert_eq!(code(&output), 2, "{:?}", arg_list);
+    }
+}
+
+#[test]
+fn scale_must_be_positive() {
+    let path = temp_path("scale.svg");
+    let path = path.to_str().unwrap();
+    for scale in ["0", "-100", "NaN", "inf"] {
+        let output = stb(&["render-plan", PORTAL_FRAME, path, "--scale", scale]);
+        assert_eq!(code(&output), 2, "{}", scale);
+        assert!(stderr(&output).starts_with("stb: --scale must be positive"));
+
+        let output = stb(&["convert", PORTAL_FRAME, path, "--scale", scale]);
+        assert_eq!(code(&output), 2, "{}", scale);
+    }
+    assert!(!std::path::Path::new(path).exists());
+}